@@ -1,24 +1,446 @@
+DROP TABLE IF EXISTS calendar_feeds;
+
+DROP TABLE IF EXISTS audit_events;
+
+DROP TABLE IF EXISTS goal_revisions;
+
+DROP TABLE IF EXISTS goal_tombstones;
+
+DROP TABLE IF EXISTS channel_presence;
+
+DROP TABLE IF EXISTS goal_events;
+
+DROP TABLE IF EXISTS webhook_deliveries;
+
+DROP TABLE IF EXISTS webhooks;
+
+DROP TABLE IF EXISTS notifications;
+
+DROP TABLE IF EXISTS goal_reminders;
+
+DROP TABLE IF EXISTS goal_check_ins;
+
+DROP TABLE IF EXISTS goal_habits;
+
+DROP TABLE IF EXISTS goal_notes;
+
+DROP TABLE IF EXISTS goal_comment_edits;
+
+DROP TABLE IF EXISTS goal_comment_mentions;
+
+DROP TABLE IF EXISTS goal_comments;
+
+DROP TABLE IF EXISTS share_links;
+
+DROP TABLE IF EXISTS goal_shares;
+
+DROP TABLE IF EXISTS organization_invitations;
+
+DROP TABLE IF EXISTS organization_members;
+
+DROP TABLE IF EXISTS api_keys;
+
 DROP TABLE IF EXISTS users;
 
 DROP TABLE IF EXISTS goals;
 
+DROP TABLE IF EXISTS organizations;
+
 CREATE EXTENSION IF NOT EXISTS "uuid-ossp";
 
+DROP SEQUENCE IF EXISTS goals_version_seq;
+
+CREATE SEQUENCE goals_version_seq;
+
+DROP SEQUENCE IF EXISTS goal_events_id_seq;
+
+CREATE SEQUENCE goal_events_id_seq;
+
+-- Values of a sequence are drawn when the rows are written, not when the transactions commit, so a
+-- value can become visible after a greater one. The first time a transaction draws from a tracked
+-- sequence it takes a shared advisory lock keyed by the sequence and the lowest value it can draw,
+-- kept until it ends. Nobody waits for these locks, they are read from pg_locks
+CREATE OR REPLACE FUNCTION next_tracked_value(sequence_name TEXT) RETURNS BIGINT AS $$
+DECLARE
+    setting_name TEXT := 'goals.tracked_' || sequence_name;
+    lowest_value BIGINT;
+BEGIN
+    IF coalesce(current_setting(setting_name, true), '') = '' THEN
+        EXECUTE format(
+            'SELECT CASE WHEN is_called THEN last_value + 1 ELSE last_value END FROM %I',
+            sequence_name
+        ) INTO lowest_value;
+        PERFORM pg_advisory_xact_lock_shared(hashtext(sequence_name), lowest_value::bit(32)::int);
+        PERFORM set_config(setting_name, 'on', true);
+    END IF;
+    RETURN nextval(sequence_name);
+END;
+$$ LANGUAGE plpgsql;
+
+-- The greatest value of the sequence up to which every transaction that drew values has ended,
+-- without waiting for the others. The sequence is read before the locks: a value it returns was
+-- drawn after the lock of its transaction was taken. The locks only keep the low 32 bits of the
+-- values, the values of the running transactions are the last ones drawn
+CREATE OR REPLACE FUNCTION stable_tracked_value(sequence_name TEXT) RETURNS BIGINT AS $$
+DECLARE
+    last_drawn BIGINT;
+    lowest_running BIGINT;
+BEGIN
+    EXECUTE format(
+        'SELECT CASE WHEN is_called THEN last_value ELSE 0 END FROM %I',
+        sequence_name
+    ) INTO last_drawn;
+
+    SELECT min(last_drawn + 1 - ((last_drawn + 1 - objid::bigint) % 4294967296 + 4294967296) % 4294967296)
+    INTO lowest_running
+    FROM pg_locks
+    WHERE locktype = 'advisory' AND objsubid = 2
+        AND database = (SELECT oid FROM pg_database WHERE datname = current_database())
+        AND classid::bigint = (hashtext(sequence_name)::bigint + 4294967296) % 4294967296;
+
+    RETURN least(last_drawn, lowest_running - 1);
+END;
+$$ LANGUAGE plpgsql;
+
+-- The versions of the goals and of the tombstones are the sync cursors, they never go past the
+-- stable version
+CREATE OR REPLACE FUNCTION next_goal_version() RETURNS BIGINT AS $$
+BEGIN
+    RETURN next_tracked_value('goals_version_seq');
+END;
+$$ LANGUAGE plpgsql;
+
 CREATE TABLE users (
     id UUID DEFAULT uuid_generate_v4(),
     name TEXT NOT NULL,
     email TEXT NOT NULL,
     password_hash TEXT NOT NULL,
     phone TEXT NOT NULL,
+    role TEXT NOT NULL DEFAULT 'user',
+    disabled_at TIMESTAMPTZ,
+    password_reset_required BOOLEAN NOT NULL DEFAULT false,
+    password_reset_token_hash TEXT,
+    password_reset_expires_at TIMESTAMPTZ,
+    password_changed_at TIMESTAMPTZ,
+    created_at TIMESTAMP DEFAULT now(),
+    PRIMARY KEY(id)
+);
+
+CREATE TABLE organizations (
+    id UUID DEFAULT uuid_generate_v4(),
+    name TEXT NOT NULL,
     created_at TIMESTAMP DEFAULT now(),
     PRIMARY KEY(id)
 );
 
+CREATE TABLE organization_members (
+    organization_id UUID NOT NULL,
+    user_id UUID NOT NULL,
+    role TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT now(),
+    PRIMARY KEY(organization_id, user_id),
+    CONSTRAINT fk_organization_members_organization FOREIGN KEY (organization_id) REFERENCES organizations(id) ON DELETE CASCADE,
+    CONSTRAINT fk_organization_members_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE TABLE organization_invitations (
+    id UUID DEFAULT uuid_generate_v4(),
+    organization_id UUID NOT NULL,
+    email TEXT NOT NULL,
+    role TEXT NOT NULL,
+    token_hash TEXT NOT NULL,
+    status TEXT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    invited_by UUID,
+    responded_at TIMESTAMPTZ,
+    created_at TIMESTAMP DEFAULT now(),
+    PRIMARY KEY(id),
+    CONSTRAINT uq_organization_invitations_token_hash UNIQUE (token_hash),
+    CONSTRAINT fk_organization_invitations_organization FOREIGN KEY (organization_id) REFERENCES organizations(id) ON DELETE CASCADE,
+    CONSTRAINT fk_organization_invitations_user FOREIGN KEY (invited_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
 CREATE TABLE goals (
     id UUID DEFAULT uuid_generate_v4(),
     text TEXT NOT NULL,
     user_id UUID NOT NULL,
+    organization_id UUID,
+    created_at TIMESTAMP DEFAULT now(),
+    version BIGINT NOT NULL DEFAULT next_goal_version(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    text_updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    due_at TIMESTAMPTZ,
+    -- The due date the approaching deadline notification was sent for, a new due date notifies again
+    deadline_notified_due_at TIMESTAMPTZ,
+    completed_at TIMESTAMPTZ,
+    completion_updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    archived_at TIMESTAMPTZ,
+    deleted_at TIMESTAMPTZ,
+    PRIMARY KEY(id),
+    CONSTRAINT fk_goals_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    CONSTRAINT fk_goals_organization FOREIGN KEY (organization_id) REFERENCES organizations(id) ON DELETE CASCADE
+);
+
+CREATE TABLE api_keys (
+    id UUID DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL,
+    name TEXT NOT NULL,
+    prefix TEXT NOT NULL,
+    key_hash TEXT NOT NULL,
+    scope TEXT NOT NULL,
+    expires_at TIMESTAMPTZ,
+    last_used_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMP DEFAULT now(),
+    PRIMARY KEY(id),
+    CONSTRAINT uq_api_keys_prefix UNIQUE (prefix),
+    CONSTRAINT fk_api_keys_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE TABLE goal_shares (
+    goal_id UUID NOT NULL,
+    user_id UUID NOT NULL,
+    permission TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT now(),
+    PRIMARY KEY(goal_id, user_id),
+    CONSTRAINT fk_goal_shares_goal FOREIGN KEY (goal_id) REFERENCES goals(id) ON DELETE CASCADE,
+    CONSTRAINT fk_goal_shares_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE TABLE share_links (
+    id UUID DEFAULT uuid_generate_v4(),
+    goal_id UUID NOT NULL,
+    created_by UUID NOT NULL,
+    token_hash TEXT NOT NULL,
+    expires_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
     created_at TIMESTAMP DEFAULT now(),
     PRIMARY KEY(id),
-    CONSTRAINT fk_goals_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+    CONSTRAINT uq_share_links_token_hash UNIQUE (token_hash),
+    CONSTRAINT fk_share_links_goal FOREIGN KEY (goal_id) REFERENCES goals(id) ON DELETE CASCADE,
+    CONSTRAINT fk_share_links_user FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE TABLE goal_comments (
+    id UUID DEFAULT uuid_generate_v4(),
+    goal_id UUID NOT NULL,
+    user_id UUID NOT NULL,
+    text TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ,
+    PRIMARY KEY(id),
+    CONSTRAINT fk_goal_comments_goal FOREIGN KEY (goal_id) REFERENCES goals(id) ON DELETE CASCADE,
+    CONSTRAINT fk_goal_comments_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_goal_comments_goal ON goal_comments (goal_id, created_at);
+
+CREATE TABLE goal_comment_mentions (
+    comment_id UUID NOT NULL,
+    user_id UUID NOT NULL,
+    PRIMARY KEY(comment_id, user_id),
+    CONSTRAINT fk_goal_comment_mentions_comment FOREIGN KEY (comment_id) REFERENCES goal_comments(id) ON DELETE CASCADE,
+    CONSTRAINT fk_goal_comment_mentions_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE TABLE goal_comment_edits (
+    id UUID DEFAULT uuid_generate_v4(),
+    comment_id UUID NOT NULL,
+    text TEXT NOT NULL,
+    edited_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY(id),
+    CONSTRAINT fk_goal_comment_edits_comment FOREIGN KEY (comment_id) REFERENCES goal_comments(id) ON DELETE CASCADE
+);
+
+CREATE TABLE goal_notes (
+    id UUID DEFAULT uuid_generate_v4(),
+    goal_id UUID NOT NULL,
+    user_id UUID NOT NULL,
+    text TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ,
+    PRIMARY KEY(id),
+    CONSTRAINT fk_goal_notes_goal FOREIGN KEY (goal_id) REFERENCES goals(id) ON DELETE CASCADE,
+    CONSTRAINT fk_goal_notes_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_goal_notes_goal ON goal_notes (goal_id, created_at);
+
+CREATE TABLE goal_habits (
+    goal_id UUID NOT NULL,
+    recurrence TEXT NOT NULL,
+    interval_days INTEGER,
+    times_per_week INTEGER,
+    start_date DATE NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY(goal_id),
+    CONSTRAINT fk_goal_habits_goal FOREIGN KEY (goal_id) REFERENCES goals(id) ON DELETE CASCADE
+);
+
+CREATE TABLE goal_check_ins (
+    goal_id UUID NOT NULL,
+    check_date DATE NOT NULL,
+    user_id UUID NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY(goal_id, check_date),
+    CONSTRAINT fk_goal_check_ins_habit FOREIGN KEY (goal_id) REFERENCES goal_habits(goal_id) ON DELETE CASCADE,
+    CONSTRAINT fk_goal_check_ins_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE TABLE goal_reminders (
+    id UUID DEFAULT uuid_generate_v4(),
+    goal_id UUID NOT NULL,
+    user_id UUID NOT NULL,
+    remind_at TIMESTAMPTZ NOT NULL,
+    minutes_before_due INTEGER,
+    timezone TEXT NOT NULL,
+    channel TEXT NOT NULL,
+    webhook_url TEXT,
+    status TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    locked_until TIMESTAMPTZ,
+    sent_at TIMESTAMPTZ,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY(id),
+    CONSTRAINT fk_goal_reminders_goal FOREIGN KEY (goal_id) REFERENCES goals(id) ON DELETE CASCADE,
+    CONSTRAINT fk_goal_reminders_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_goal_reminders_due ON goal_reminders (remind_at) WHERE status = 'pending';
+
+CREATE TABLE notifications (
+    id UUID DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL,
+    kind TEXT NOT NULL,
+    goal_id UUID,
+    actor_id UUID,
+    text TEXT NOT NULL,
+    read_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY(id),
+    CONSTRAINT fk_notifications_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    CONSTRAINT fk_notifications_goal FOREIGN KEY (goal_id) REFERENCES goals(id) ON DELETE CASCADE,
+    CONSTRAINT fk_notifications_actor FOREIGN KEY (actor_id) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_notifications_user ON notifications (user_id, created_at DESC, id DESC);
+
+CREATE TABLE webhooks (
+    id UUID DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    event_types TEXT[] NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY(id),
+    CONSTRAINT fk_webhooks_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE TABLE webhook_deliveries (
+    id UUID DEFAULT uuid_generate_v4(),
+    webhook_id UUID NOT NULL,
+    event_id UUID NOT NULL,
+    event_type TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    status TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    response_status INTEGER,
+    last_error TEXT,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    delivered_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY(id),
+    CONSTRAINT fk_webhook_deliveries_webhook FOREIGN KEY (webhook_id) REFERENCES webhooks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_webhook_deliveries_due ON webhook_deliveries (next_attempt_at) WHERE status = 'pending';
+
+CREATE INDEX idx_webhook_deliveries_webhook ON webhook_deliveries (webhook_id, created_at DESC);
+
+-- The ids are the cursors of the streams, they never go past the stable id
+CREATE TABLE goal_events (
+    id BIGINT NOT NULL DEFAULT next_tracked_value('goal_events_id_seq'),
+    user_id UUID NOT NULL,
+    goal_id UUID NOT NULL,
+    event_type TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY(id),
+    CONSTRAINT fk_goal_events_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_goal_events_user ON goal_events (user_id, id);
+
+CREATE INDEX idx_goal_events_created_at ON goal_events (created_at);
+
+CREATE TABLE channel_presence (
+    connection_id UUID NOT NULL,
+    channel TEXT NOT NULL,
+    user_id UUID NOT NULL,
+    last_seen TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY(connection_id, channel),
+    CONSTRAINT fk_channel_presence_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_channel_presence_channel ON channel_presence (channel, last_seen);
+
+CREATE INDEX idx_goals_version ON goals (version);
+
+CREATE TABLE goal_tombstones (
+    goal_id UUID NOT NULL,
+    user_id UUID NOT NULL,
+    version BIGINT NOT NULL,
+    deleted_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY(goal_id, user_id),
+    CONSTRAINT fk_goal_tombstones_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_goal_tombstones_user ON goal_tombstones (user_id, version);
+
+CREATE INDEX idx_goal_tombstones_deleted_at ON goal_tombstones (deleted_at);
+
+CREATE INDEX idx_goals_deleted_at ON goals (deleted_at) WHERE deleted_at IS NOT NULL;
+
+CREATE INDEX idx_goals_completed_at ON goals (completed_at) WHERE archived_at IS NULL;
+
+CREATE TABLE goal_revisions (
+    id BIGSERIAL,
+    goal_id UUID NOT NULL,
+    actor_id UUID,
+    action TEXT NOT NULL,
+    before TEXT,
+    after TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY(id),
+    CONSTRAINT fk_goal_revisions_goal FOREIGN KEY (goal_id) REFERENCES goals(id) ON DELETE CASCADE,
+    CONSTRAINT fk_goal_revisions_actor FOREIGN KEY (actor_id) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_goal_revisions_goal ON goal_revisions (goal_id, id);
+
+CREATE TABLE audit_events (
+    id BIGSERIAL,
+    event_type TEXT NOT NULL,
+    user_id UUID,
+    actor_id UUID,
+    email TEXT,
+    ip TEXT,
+    user_agent TEXT,
+    details TEXT NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY(id)
+);
+
+CREATE INDEX idx_audit_events_user ON audit_events (user_id, id);
+
+CREATE INDEX idx_audit_events_type ON audit_events (event_type, id);
+
+CREATE TABLE calendar_feeds (
+    user_id UUID NOT NULL,
+    token_hash TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY(user_id),
+    CONSTRAINT uq_calendar_feeds_token_hash UNIQUE (token_hash),
+    CONSTRAINT fk_calendar_feeds_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
 );
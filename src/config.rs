@@ -0,0 +1,39 @@
+use std::{env, str::FromStr};
+
+// Reads an environment variable, empty values are treated as not set
+pub fn get_env(name: &str) -> Option<String> {
+    match env::var(name) {
+        Err(_) => None,
+        Ok(value) if value.trim().is_empty() => None,
+        Ok(value) => Some(value),
+    }
+}
+
+// Falls back to the default when the variable is not set or cannot be parsed
+pub fn get_env_parsed<T: FromStr>(name: &str, default: T) -> T {
+    match get_env(name) {
+        None => default,
+        Some(value) => match value.trim().parse::<T>() {
+            Err(_) => {
+                eprintln!(
+                    "Config error: invalid value for {}, using the default",
+                    name
+                );
+                default
+            }
+            Ok(parsed) => parsed,
+        },
+    }
+}
+
+// For the security settings: a value that cannot be parsed is an error, the server does not start
+// with a weaker default than the one configured
+pub fn get_env_parsed_strict<T: FromStr>(name: &str, default: T) -> Result<T, String> {
+    match get_env(name) {
+        None => Ok(default),
+        Some(value) => value
+            .trim()
+            .parse::<T>()
+            .map_err(|_| format!("Invalid value for {}: {}", name, value)),
+    }
+}
@@ -0,0 +1,221 @@
+use std::fmt::{self, Display};
+
+use chrono::{DateTime, Utc};
+use tokio_postgres::{Client, Row};
+use uuid::Uuid;
+
+use crate::entities::api_key::ApiKey;
+
+pub enum ApiKeyDataAccessError {
+    DatabaseError(String),
+    MappingError(String),
+    ParameterError(String),
+}
+
+impl Display for ApiKeyDataAccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiKeyDataAccessError::DatabaseError(err) => write!(f, "{}", err),
+            ApiKeyDataAccessError::MappingError(err) => write!(f, "{}", err),
+            ApiKeyDataAccessError::ParameterError(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+fn row_to_api_key(row: &Row) -> Result<ApiKey, ApiKeyDataAccessError> {
+    let id = row.try_get::<_, Uuid>("id").unwrap_or_default().to_string();
+    let user_id = row
+        .try_get::<_, Uuid>("user_id")
+        .unwrap_or_default()
+        .to_string();
+    let name = row.try_get::<_, String>("name").unwrap_or_default();
+    let prefix = row.try_get::<_, String>("prefix").unwrap_or_default();
+    let key_hash = row.try_get::<_, String>("key_hash").unwrap_or_default();
+    let scope = row.try_get::<_, String>("scope").unwrap_or_default();
+    let expires_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("expires_at")
+        .unwrap_or_default();
+    let last_used_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("last_used_at")
+        .unwrap_or_default();
+
+    ApiKey::from_db_fields(
+        &id,
+        &user_id,
+        &name,
+        &prefix,
+        &key_hash,
+        &scope,
+        expires_at,
+        last_used_at,
+    )
+    .map_err(|err| ApiKeyDataAccessError::MappingError(err.to_string()))
+}
+
+// Returns the id generated by the database
+pub async fn add_api_key(
+    client: &Client,
+    api_key: &ApiKey,
+) -> Result<String, ApiKeyDataAccessError> {
+    let sql = "
+        INSERT INTO api_keys
+            (user_id, name, prefix, key_hash, scope, expires_at)
+        VALUES
+            ($1, $2, $3, $4, $5, $6)
+        RETURNING id";
+
+    let user_id = Uuid::parse_str(&api_key.get_user_id())
+        .map_err(|err| ApiKeyDataAccessError::ParameterError(err.to_string()))?;
+    let name = api_key.get_name();
+    let prefix = api_key.get_prefix();
+    let key_hash = api_key.get_key_hash();
+    let scope = api_key.get_scope();
+    let expires_at = api_key.get_expires_at();
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| ApiKeyDataAccessError::DatabaseError(err.to_string()))?;
+
+    let row = client
+        .query_one(
+            &stm,
+            &[&user_id, &name, &prefix, &key_hash, &scope, &expires_at],
+        )
+        .await
+        .map_err(|err| ApiKeyDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = row.try_get::<_, Uuid>("id").unwrap_or_default().to_string();
+
+    Ok(id)
+}
+
+// Revoked keys are not returned
+pub async fn find_all_api_keys(
+    client: &Client,
+    user_id: &str,
+) -> Result<Vec<ApiKey>, ApiKeyDataAccessError> {
+    let sql = "
+        SELECT * FROM api_keys
+        WHERE user_id = $1 AND revoked_at IS NULL
+        ORDER BY created_at";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| ApiKeyDataAccessError::DatabaseError(err.to_string()))?;
+
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| ApiKeyDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&user_id])
+        .await
+        .map_err(|err| ApiKeyDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut api_keys = Vec::new();
+    for row in rows.iter() {
+        api_keys.push(row_to_api_key(row)?);
+    }
+
+    Ok(api_keys)
+}
+
+// Revoked keys are not returned
+pub async fn find_api_key_by_prefix(
+    client: &Client,
+    prefix: &str,
+) -> Result<Option<ApiKey>, ApiKeyDataAccessError> {
+    let sql = "SELECT * FROM api_keys WHERE prefix = $1 AND revoked_at IS NULL";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| ApiKeyDataAccessError::DatabaseError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&prefix])
+        .await
+        .map_err(|err| ApiKeyDataAccessError::DatabaseError(err.to_string()))?;
+
+    if rows.is_empty() {
+        return Ok(None);
+    }
+
+    let api_key = row_to_api_key(&rows[0])?;
+
+    Ok(Some(api_key))
+}
+
+pub async fn update_api_key_last_used(
+    client: &Client,
+    id: &str,
+) -> Result<(), ApiKeyDataAccessError> {
+    let sql = "UPDATE api_keys SET last_used_at = now() WHERE id = $1";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| ApiKeyDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = Uuid::parse_str(id)
+        .map_err(|err| ApiKeyDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&id])
+        .await
+        .map_err(|err| ApiKeyDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+// Returns the number of revoked keys, 0 when the key is not found for the user
+pub async fn revoke_api_key(
+    client: &Client,
+    id: &str,
+    user_id: &str,
+) -> Result<u64, ApiKeyDataAccessError> {
+    let sql = "
+        UPDATE api_keys SET revoked_at = now()
+        WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| ApiKeyDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = Uuid::parse_str(id)
+        .map_err(|err| ApiKeyDataAccessError::ParameterError(err.to_string()))?;
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| ApiKeyDataAccessError::ParameterError(err.to_string()))?;
+
+    let revoked = client
+        .execute(&stm, &[&id, &user_id])
+        .await
+        .map_err(|err| ApiKeyDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(revoked)
+}
+
+// Returns the number of revoked keys
+pub async fn revoke_all_api_keys(
+    client: &Client,
+    user_id: &str,
+) -> Result<u64, ApiKeyDataAccessError> {
+    let sql = "UPDATE api_keys SET revoked_at = now() WHERE user_id = $1 AND revoked_at IS NULL";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| ApiKeyDataAccessError::DatabaseError(err.to_string()))?;
+
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| ApiKeyDataAccessError::ParameterError(err.to_string()))?;
+
+    let revoked = client
+        .execute(&stm, &[&user_id])
+        .await
+        .map_err(|err| ApiKeyDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(revoked)
+}
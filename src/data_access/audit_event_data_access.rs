@@ -0,0 +1,180 @@
+use std::fmt::{self, Display};
+
+use chrono::{DateTime, Utc};
+use tokio_postgres::{Client, Row};
+use uuid::Uuid;
+
+use crate::entities::audit_event::AuditEvent;
+
+pub enum AuditEventDataAccessError {
+    DatabaseError(String),
+    MappingError(String),
+    ParameterError(String),
+}
+
+impl Display for AuditEventDataAccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuditEventDataAccessError::DatabaseError(err) => write!(f, "{}", err),
+            AuditEventDataAccessError::MappingError(err) => write!(f, "{}", err),
+            AuditEventDataAccessError::ParameterError(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+// A missing filter matches every event
+const AUDIT_EVENTS_FILTER: &str = "
+    ($1::TEXT IS NULL OR event_type = $1)
+    AND ($2::UUID IS NULL OR user_id = $2)
+    AND ($3::TEXT IS NULL OR email = $3)";
+
+fn row_to_audit_event(row: &Row) -> Result<AuditEvent, AuditEventDataAccessError> {
+    let id = row.try_get::<_, i64>("id").unwrap_or_default();
+    let event_type = row.try_get::<_, String>("event_type").unwrap_or_default();
+    let user_id = row
+        .try_get::<_, Option<Uuid>>("user_id")
+        .unwrap_or_default()
+        .map(|id| id.to_string());
+    let actor_id = row
+        .try_get::<_, Option<Uuid>>("actor_id")
+        .unwrap_or_default()
+        .map(|id| id.to_string());
+    let email = row
+        .try_get::<_, Option<String>>("email")
+        .unwrap_or_default();
+    let ip = row.try_get::<_, Option<String>>("ip").unwrap_or_default();
+    let user_agent = row
+        .try_get::<_, Option<String>>("user_agent")
+        .unwrap_or_default();
+    let details = row.try_get::<_, String>("details").unwrap_or_default();
+    let created_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("created_at")
+        .unwrap_or_default();
+
+    AuditEvent::from_db_fields(
+        id,
+        &event_type,
+        user_id,
+        actor_id,
+        email,
+        ip,
+        user_agent,
+        &details,
+        created_at,
+    )
+    .map_err(|err| AuditEventDataAccessError::MappingError(err.to_string()))
+}
+
+fn parse_optional_id(id: Option<&str>) -> Result<Option<Uuid>, AuditEventDataAccessError> {
+    match id {
+        None => Ok(None),
+        Some(id) => Uuid::parse_str(id)
+            .map(Some)
+            .map_err(|err| AuditEventDataAccessError::ParameterError(err.to_string())),
+    }
+}
+
+pub async fn add_audit_event(
+    client: &Client,
+    event: &AuditEvent,
+) -> Result<(), AuditEventDataAccessError> {
+    let sql = "
+        INSERT INTO audit_events
+            (event_type, user_id, actor_id, email, ip, user_agent, details)
+        VALUES
+            ($1, $2, $3, $4, $5, $6, $7)";
+
+    let user_id = parse_optional_id(event.get_user_id().as_deref())?;
+    let actor_id = parse_optional_id(event.get_actor_id().as_deref())?;
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| AuditEventDataAccessError::DatabaseError(err.to_string()))?;
+
+    client
+        .execute(
+            &stm,
+            &[
+                &event.get_event_type(),
+                &user_id,
+                &actor_id,
+                &event.get_email(),
+                &event.get_ip(),
+                &event.get_user_agent(),
+                &event.get_details(),
+            ],
+        )
+        .await
+        .map_err(|err| AuditEventDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+// Newest first
+pub async fn find_audit_events(
+    client: &Client,
+    event_type: Option<&str>,
+    user_id: Option<&str>,
+    email: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<AuditEvent>, AuditEventDataAccessError> {
+    let sql = format!(
+        "SELECT * FROM audit_events WHERE {} ORDER BY id DESC LIMIT $4 OFFSET $5",
+        AUDIT_EVENTS_FILTER
+    );
+
+    let stm = client
+        .prepare(&sql)
+        .await
+        .map_err(|err| AuditEventDataAccessError::DatabaseError(err.to_string()))?;
+
+    let user_id = parse_optional_id(user_id)?;
+
+    let rows = client
+        .query(&stm, &[&event_type, &user_id, &email, &limit, &offset])
+        .await
+        .map_err(|err| AuditEventDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut events = Vec::new();
+    for row in rows.iter() {
+        events.push(row_to_audit_event(row)?);
+    }
+
+    Ok(events)
+}
+
+// Oldest first, the events with an id greater than after_id. Used to read the whole log in batches
+pub async fn find_audit_events_after(
+    client: &Client,
+    event_type: Option<&str>,
+    user_id: Option<&str>,
+    email: Option<&str>,
+    after_id: i64,
+    limit: i64,
+) -> Result<Vec<AuditEvent>, AuditEventDataAccessError> {
+    let sql = format!(
+        "SELECT * FROM audit_events WHERE {} AND id > $4 ORDER BY id LIMIT $5",
+        AUDIT_EVENTS_FILTER
+    );
+
+    let stm = client
+        .prepare(&sql)
+        .await
+        .map_err(|err| AuditEventDataAccessError::DatabaseError(err.to_string()))?;
+
+    let user_id = parse_optional_id(user_id)?;
+
+    let rows = client
+        .query(&stm, &[&event_type, &user_id, &email, &after_id, &limit])
+        .await
+        .map_err(|err| AuditEventDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut events = Vec::new();
+    for row in rows.iter() {
+        events.push(row_to_audit_event(row)?);
+    }
+
+    Ok(events)
+}
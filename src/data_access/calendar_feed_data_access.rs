@@ -0,0 +1,109 @@
+use std::fmt::{self, Display};
+
+use chrono::{DateTime, Utc};
+use tokio_postgres::{Client, Row};
+use uuid::Uuid;
+
+use crate::entities::calendar_feed::CalendarFeed;
+
+pub enum CalendarFeedDataAccessError {
+    DatabaseError(String),
+    MappingError(String),
+    ParameterError(String),
+}
+
+impl Display for CalendarFeedDataAccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalendarFeedDataAccessError::DatabaseError(err) => write!(f, "{}", err),
+            CalendarFeedDataAccessError::MappingError(err) => write!(f, "{}", err),
+            CalendarFeedDataAccessError::ParameterError(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+fn row_to_calendar_feed(row: &Row) -> Result<CalendarFeed, CalendarFeedDataAccessError> {
+    let user_id = row
+        .try_get::<_, Uuid>("user_id")
+        .unwrap_or_default()
+        .to_string();
+    let token_hash = row.try_get::<_, String>("token_hash").unwrap_or_default();
+    let created_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("created_at")
+        .unwrap_or_default();
+
+    CalendarFeed::from_db_fields(&user_id, &token_hash, created_at)
+        .map_err(|err| CalendarFeedDataAccessError::MappingError(err.to_string()))
+}
+
+// Replaces the token of the feed of the user, the old feed URL stops working. Returns the feed
+// with the creation date set by the database
+pub async fn set_calendar_feed(
+    client: &Client,
+    calendar_feed: &CalendarFeed,
+) -> Result<CalendarFeed, CalendarFeedDataAccessError> {
+    let sql = "
+        INSERT INTO calendar_feeds (user_id, token_hash)
+        VALUES ($1, $2)
+        ON CONFLICT (user_id) DO UPDATE SET token_hash = EXCLUDED.token_hash, created_at = now()
+        RETURNING *";
+
+    let user_id = Uuid::parse_str(&calendar_feed.get_user_id())
+        .map_err(|err| CalendarFeedDataAccessError::ParameterError(err.to_string()))?;
+    let token_hash = calendar_feed.get_token_hash();
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| CalendarFeedDataAccessError::DatabaseError(err.to_string()))?;
+
+    let row = client
+        .query_one(&stm, &[&user_id, &token_hash])
+        .await
+        .map_err(|err| CalendarFeedDataAccessError::DatabaseError(err.to_string()))?;
+
+    row_to_calendar_feed(&row)
+}
+
+pub async fn find_calendar_feed_by_token_hash(
+    client: &Client,
+    token_hash: &str,
+) -> Result<Option<CalendarFeed>, CalendarFeedDataAccessError> {
+    let sql = "SELECT * FROM calendar_feeds WHERE token_hash = $1";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| CalendarFeedDataAccessError::DatabaseError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&token_hash])
+        .await
+        .map_err(|err| CalendarFeedDataAccessError::DatabaseError(err.to_string()))?;
+
+    match rows.first() {
+        None => Ok(None),
+        Some(row) => Ok(Some(row_to_calendar_feed(row)?)),
+    }
+}
+
+// Returns the number of deleted feeds, 0 when the user had none
+pub async fn delete_calendar_feed(
+    client: &Client,
+    user_id: &str,
+) -> Result<u64, CalendarFeedDataAccessError> {
+    let sql = "DELETE FROM calendar_feeds WHERE user_id = $1";
+
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| CalendarFeedDataAccessError::ParameterError(err.to_string()))?;
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| CalendarFeedDataAccessError::DatabaseError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&user_id])
+        .await
+        .map_err(|err| CalendarFeedDataAccessError::DatabaseError(err.to_string()))
+}
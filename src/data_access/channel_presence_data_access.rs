@@ -0,0 +1,212 @@
+use std::fmt::{self, Display};
+
+use tokio_postgres::{Client, Row};
+use uuid::Uuid;
+
+use crate::entities::channel::ChannelViewer;
+
+// Postgres channel of the presence changes. The payload of a notification is the name of the
+// channel whose viewers changed
+pub const PRESENCE_CHANNEL: &str = "channel_presence";
+
+pub enum ChannelPresenceDataAccessError {
+    DatabaseError(String),
+    MappingError(String),
+    ParameterError(String),
+}
+
+impl Display for ChannelPresenceDataAccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChannelPresenceDataAccessError::DatabaseError(err) => write!(f, "{}", err),
+            ChannelPresenceDataAccessError::MappingError(err) => write!(f, "{}", err),
+            ChannelPresenceDataAccessError::ParameterError(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+fn row_to_channel_viewer(row: &Row) -> Result<ChannelViewer, ChannelPresenceDataAccessError> {
+    let user_id = row
+        .try_get::<_, Uuid>("user_id")
+        .unwrap_or_default()
+        .to_string();
+    let name = row.try_get::<_, String>("name").unwrap_or_default();
+
+    ChannelViewer::from_db_fields(&user_id, &name)
+        .map_err(|err| ChannelPresenceDataAccessError::MappingError(err.to_string()))
+}
+
+fn parse_uuid(id: &str) -> Result<Uuid, ChannelPresenceDataAccessError> {
+    Uuid::parse_str(id)
+        .map_err(|err| ChannelPresenceDataAccessError::ParameterError(err.to_string()))
+}
+
+// Subscribing twice from the same connection only refreshes the presence
+pub async fn add_presence(
+    client: &Client,
+    connection_id: &str,
+    channel: &str,
+    user_id: &str,
+) -> Result<(), ChannelPresenceDataAccessError> {
+    let sql = "
+        WITH upserted AS (
+            INSERT INTO channel_presence (connection_id, channel, user_id)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (connection_id, channel) DO UPDATE SET last_seen = now()
+            RETURNING channel
+        )
+        SELECT pg_notify($4, channel) FROM upserted";
+
+    let connection_id = parse_uuid(connection_id)?;
+    let user_id = parse_uuid(user_id)?;
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| ChannelPresenceDataAccessError::DatabaseError(err.to_string()))?;
+
+    client
+        .query(
+            &stm,
+            &[&connection_id, &channel, &user_id, &PRESENCE_CHANNEL],
+        )
+        .await
+        .map_err(|err| ChannelPresenceDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+// Returns the number of channels left
+pub async fn delete_presence(
+    client: &Client,
+    connection_id: &str,
+    channel: &str,
+) -> Result<u64, ChannelPresenceDataAccessError> {
+    let sql = "
+        WITH deleted AS (
+            DELETE FROM channel_presence WHERE connection_id = $1 AND channel = $2
+            RETURNING channel
+        )
+        SELECT pg_notify($3, channel) FROM deleted";
+
+    let connection_id = parse_uuid(connection_id)?;
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| ChannelPresenceDataAccessError::DatabaseError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&connection_id, &channel, &PRESENCE_CHANNEL])
+        .await
+        .map_err(|err| ChannelPresenceDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(rows.len() as u64)
+}
+
+// Every channel of a closed connection
+pub async fn delete_connection_presence(
+    client: &Client,
+    connection_id: &str,
+) -> Result<u64, ChannelPresenceDataAccessError> {
+    let sql = "
+        WITH deleted AS (
+            DELETE FROM channel_presence WHERE connection_id = $1
+            RETURNING channel
+        )
+        SELECT pg_notify($2, channel) FROM deleted";
+
+    let connection_id = parse_uuid(connection_id)?;
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| ChannelPresenceDataAccessError::DatabaseError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&connection_id, &PRESENCE_CHANNEL])
+        .await
+        .map_err(|err| ChannelPresenceDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(rows.len() as u64)
+}
+
+// Open connections refresh their presence, the rows of the connections of a stopped instance expire
+pub async fn touch_connection_presence(
+    client: &Client,
+    connection_id: &str,
+) -> Result<u64, ChannelPresenceDataAccessError> {
+    let sql = "UPDATE channel_presence SET last_seen = now() WHERE connection_id = $1";
+
+    let connection_id = parse_uuid(connection_id)?;
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| ChannelPresenceDataAccessError::DatabaseError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&connection_id])
+        .await
+        .map_err(|err| ChannelPresenceDataAccessError::DatabaseError(err.to_string()))
+}
+
+// Users with the channel open in a connection seen in the last ttl_seconds, by name
+pub async fn find_channel_viewers(
+    client: &Client,
+    channel: &str,
+    ttl_seconds: f64,
+) -> Result<Vec<ChannelViewer>, ChannelPresenceDataAccessError> {
+    let sql = "
+        SELECT DISTINCT u.id AS user_id, u.name FROM channel_presence p
+        INNER JOIN users u ON u.id = p.user_id
+        WHERE p.channel = $1 AND p.last_seen > now() - make_interval(secs => $2)
+        ORDER BY u.name, u.id";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| ChannelPresenceDataAccessError::DatabaseError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&channel, &ttl_seconds])
+        .await
+        .map_err(|err| ChannelPresenceDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut viewers = Vec::new();
+    for row in rows.iter() {
+        viewers.push(row_to_channel_viewer(row)?);
+    }
+
+    Ok(viewers)
+}
+
+// Returns the number of presences deleted, their channels are notified
+pub async fn delete_stale_presence(
+    client: &Client,
+    ttl_seconds: f64,
+) -> Result<u64, ChannelPresenceDataAccessError> {
+    let sql = "
+        WITH deleted AS (
+            DELETE FROM channel_presence WHERE last_seen < now() - make_interval(secs => $1)
+            RETURNING channel
+        ), channels AS (
+            SELECT DISTINCT channel FROM deleted
+        )
+        SELECT pg_notify($2, channel), (SELECT COUNT(*) FROM deleted) AS deleted FROM channels";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| ChannelPresenceDataAccessError::DatabaseError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&ttl_seconds, &PRESENCE_CHANNEL])
+        .await
+        .map_err(|err| ChannelPresenceDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(rows
+        .first()
+        .map(|row| row.try_get::<_, i64>("deleted").unwrap_or_default() as u64)
+        .unwrap_or_default())
+}
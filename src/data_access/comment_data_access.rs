@@ -0,0 +1,283 @@
+use std::fmt::{self, Display};
+
+use chrono::{DateTime, Utc};
+use tokio_postgres::{Client, Row};
+use uuid::Uuid;
+
+use crate::entities::comment::{Comment, CommentEdit};
+
+pub enum CommentDataAccessError {
+    DatabaseError(String),
+    MappingError(String),
+    ParameterError(String),
+}
+
+impl Display for CommentDataAccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommentDataAccessError::DatabaseError(err) => write!(f, "{}", err),
+            CommentDataAccessError::MappingError(err) => write!(f, "{}", err),
+            CommentDataAccessError::ParameterError(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+// Comments are always selected with the ids of the mentioned users
+const SELECT_COMMENTS: &str = "
+    SELECT c.*,
+        COALESCE(array_agg(m.user_id) FILTER (WHERE m.user_id IS NOT NULL), '{}') AS mentions
+    FROM goal_comments c
+    LEFT JOIN goal_comment_mentions m ON m.comment_id = c.id";
+
+fn row_to_comment(row: &Row) -> Result<Comment, CommentDataAccessError> {
+    let id = row.try_get::<_, Uuid>("id").unwrap_or_default().to_string();
+    let goal_id = row
+        .try_get::<_, Uuid>("goal_id")
+        .unwrap_or_default()
+        .to_string();
+    let user_id = row
+        .try_get::<_, Uuid>("user_id")
+        .unwrap_or_default()
+        .to_string();
+    let text = row.try_get::<_, String>("text").unwrap_or_default();
+    let mentions = row
+        .try_get::<_, Vec<Uuid>>("mentions")
+        .unwrap_or_default()
+        .iter()
+        .map(|id| id.to_string())
+        .collect();
+    let created_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("created_at")
+        .unwrap_or_default();
+    let updated_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("updated_at")
+        .unwrap_or_default();
+
+    Comment::from_db_fields(
+        &id, &goal_id, &user_id, &text, mentions, created_at, updated_at,
+    )
+    .map_err(|err| CommentDataAccessError::MappingError(err.to_string()))
+}
+
+// Returns the id generated by the database
+pub async fn add_comment(
+    client: &Client,
+    comment: &Comment,
+) -> Result<String, CommentDataAccessError> {
+    let sql = "
+        INSERT INTO goal_comments
+            (goal_id, user_id, text)
+        VALUES
+            ($1, $2, $3)
+        RETURNING id";
+
+    let goal_id = Uuid::parse_str(&comment.get_goal_id())
+        .map_err(|err| CommentDataAccessError::ParameterError(err.to_string()))?;
+    let user_id = Uuid::parse_str(&comment.get_user_id())
+        .map_err(|err| CommentDataAccessError::ParameterError(err.to_string()))?;
+    let text = comment.get_text();
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| CommentDataAccessError::DatabaseError(err.to_string()))?;
+
+    let row = client
+        .query_one(&stm, &[&goal_id, &user_id, &text])
+        .await
+        .map_err(|err| CommentDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = row.try_get::<_, Uuid>("id").unwrap_or_default().to_string();
+
+    Ok(id)
+}
+
+pub async fn find_comment_by_id(
+    client: &Client,
+    id: &str,
+) -> Result<Option<Comment>, CommentDataAccessError> {
+    let sql = format!("{} WHERE c.id = $1 GROUP BY c.id", SELECT_COMMENTS);
+
+    let stm = client
+        .prepare(&sql)
+        .await
+        .map_err(|err| CommentDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = Uuid::parse_str(id)
+        .map_err(|err| CommentDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&id])
+        .await
+        .map_err(|err| CommentDataAccessError::DatabaseError(err.to_string()))?;
+
+    if rows.is_empty() {
+        return Ok(None);
+    }
+
+    let comment = row_to_comment(&rows[0])?;
+
+    Ok(Some(comment))
+}
+
+// Oldest first
+pub async fn find_comments(
+    client: &Client,
+    goal_id: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<Comment>, CommentDataAccessError> {
+    let sql = format!(
+        "{} WHERE c.goal_id = $1 GROUP BY c.id ORDER BY c.created_at, c.id LIMIT $2 OFFSET $3",
+        SELECT_COMMENTS
+    );
+
+    let stm = client
+        .prepare(&sql)
+        .await
+        .map_err(|err| CommentDataAccessError::DatabaseError(err.to_string()))?;
+
+    let goal_id = Uuid::parse_str(goal_id)
+        .map_err(|err| CommentDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&goal_id, &limit, &offset])
+        .await
+        .map_err(|err| CommentDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut comments = Vec::new();
+    for row in rows.iter() {
+        comments.push(row_to_comment(row)?);
+    }
+
+    Ok(comments)
+}
+
+// The previous text is kept in the edit history in the same statement
+pub async fn update_comment_text(
+    client: &Client,
+    id: &str,
+    text: &str,
+) -> Result<(), CommentDataAccessError> {
+    let sql = "
+        WITH previous AS (
+            INSERT INTO goal_comment_edits (comment_id, text)
+            SELECT id, text FROM goal_comments WHERE id = $1
+        )
+        UPDATE goal_comments SET text = $2, updated_at = now()
+        WHERE id = $1";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| CommentDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = Uuid::parse_str(id)
+        .map_err(|err| CommentDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&id, &text])
+        .await
+        .map_err(|err| CommentDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+// Replaces the mentions of the comment. The delete and the insert are two statements, in one
+// statement the insert does not see the delete and kept mentions break the primary key
+pub async fn set_comment_mentions(
+    client: &Client,
+    id: &str,
+    user_ids: &[String],
+) -> Result<(), CommentDataAccessError> {
+    let delete_sql = "
+        DELETE FROM goal_comment_mentions
+        WHERE comment_id = $1 AND user_id <> ALL($2::uuid[])";
+    let insert_sql = "
+        INSERT INTO goal_comment_mentions (comment_id, user_id)
+        SELECT $1, unnest($2::uuid[])
+        ON CONFLICT DO NOTHING";
+
+    let delete_stm = client
+        .prepare(delete_sql)
+        .await
+        .map_err(|err| CommentDataAccessError::DatabaseError(err.to_string()))?;
+    let insert_stm = client
+        .prepare(insert_sql)
+        .await
+        .map_err(|err| CommentDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = Uuid::parse_str(id)
+        .map_err(|err| CommentDataAccessError::ParameterError(err.to_string()))?;
+    let mut user_uuids = Vec::new();
+    for user_id in user_ids {
+        let user_uuid = Uuid::parse_str(user_id)
+            .map_err(|err| CommentDataAccessError::ParameterError(err.to_string()))?;
+        user_uuids.push(user_uuid);
+    }
+
+    client
+        .execute(&delete_stm, &[&id, &user_uuids])
+        .await
+        .map_err(|err| CommentDataAccessError::DatabaseError(err.to_string()))?;
+    client
+        .execute(&insert_stm, &[&id, &user_uuids])
+        .await
+        .map_err(|err| CommentDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+pub async fn delete_comment(client: &Client, id: &str) -> Result<(), CommentDataAccessError> {
+    let sql = "DELETE FROM goal_comments WHERE id = $1";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| CommentDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = Uuid::parse_str(id)
+        .map_err(|err| CommentDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&id])
+        .await
+        .map_err(|err| CommentDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+// Oldest first
+pub async fn find_comment_edits(
+    client: &Client,
+    comment_id: &str,
+) -> Result<Vec<CommentEdit>, CommentDataAccessError> {
+    let sql = "
+        SELECT text, edited_at FROM goal_comment_edits
+        WHERE comment_id = $1
+        ORDER BY edited_at";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| CommentDataAccessError::DatabaseError(err.to_string()))?;
+
+    let comment_id = Uuid::parse_str(comment_id)
+        .map_err(|err| CommentDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&comment_id])
+        .await
+        .map_err(|err| CommentDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut edits = Vec::new();
+    for row in rows.iter() {
+        let text = row.try_get::<_, String>("text").unwrap_or_default();
+        let edited_at = row
+            .try_get::<_, Option<DateTime<Utc>>>("edited_at")
+            .unwrap_or_default();
+        edits.push(CommentEdit::from_db_fields(&text, edited_at));
+    }
+
+    Ok(edits)
+}
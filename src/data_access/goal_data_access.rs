@@ -1,9 +1,10 @@
 use std::fmt::{self, Display};
 
-use tokio_postgres::Client;
+use chrono::{DateTime, Utc};
+use tokio_postgres::{Client, Row};
 use uuid::Uuid;
 
-use crate::entities::goal::Goal;
+use crate::entities::{goal::Goal, goal_share::EDIT_PERMISSION, organization::VIEWER_ROLE};
 
 pub enum GoalDataAccessError {
     DatabaseError(String),
@@ -21,38 +22,121 @@ impl Display for GoalDataAccessError {
     }
 }
 
-pub async fn add_goal(client: &Client, goal: &Goal) -> Result<(), GoalDataAccessError> {
+// Goals are always selected with the number of notes of each goal
+const SELECT_GOALS: &str = "
+    SELECT g.*,
+        (SELECT COUNT(*) FROM goal_notes n WHERE n.goal_id = g.id) AS note_count
+    FROM goals g";
+
+fn row_to_goal(row: &Row) -> Result<Goal, GoalDataAccessError> {
+    let id = row.try_get::<_, Uuid>("id").unwrap_or_default().to_string();
+    let text = row.try_get::<_, String>("text").unwrap_or_default();
+    let user_id = row
+        .try_get::<_, Uuid>("user_id")
+        .unwrap_or_default()
+        .to_string();
+    let organization_id = row
+        .try_get::<_, Option<Uuid>>("organization_id")
+        .unwrap_or_default()
+        .map(|id| id.to_string());
+    let note_count = row.try_get::<_, i64>("note_count").unwrap_or_default();
+    let version = row.try_get::<_, i64>("version").unwrap_or_default();
+    let updated_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("updated_at")
+        .unwrap_or_default();
+    let text_updated_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("text_updated_at")
+        .unwrap_or_default();
+    let due_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("due_at")
+        .unwrap_or_default();
+    let completed_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("completed_at")
+        .unwrap_or_default();
+    let completion_updated_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("completion_updated_at")
+        .unwrap_or_default();
+    let archived_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("archived_at")
+        .unwrap_or_default();
+    let deleted_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("deleted_at")
+        .unwrap_or_default();
+
+    Goal::from_db_fields(
+        &id,
+        &text,
+        &user_id,
+        organization_id,
+        note_count,
+        version,
+        updated_at,
+        text_updated_at,
+        due_at,
+        completed_at,
+        completion_updated_at,
+        archived_at,
+        deleted_at,
+    )
+    .map_err(|err| GoalDataAccessError::MappingError(err.to_string()))
+}
+
+// Returns the id generated by the database. Goals can be created completed by the imports
+pub async fn add_goal(client: &Client, goal: &Goal) -> Result<String, GoalDataAccessError> {
     let sql = "
         INSERT INTO goals
-            (text, user_id)
+            (text, user_id, organization_id, due_at, completed_at)
         VALUES
-            ($1, $2)";
+            ($1, $2, $3, $4, $5)
+        RETURNING id";
 
     let user_id = Uuid::parse_str(&goal.get_user_id())
         .map_err(|err| GoalDataAccessError::ParameterError(err.to_string()))?;
+    let organization_id = match goal.get_organization_id() {
+        None => None,
+        Some(id) => Some(
+            Uuid::parse_str(&id)
+                .map_err(|err| GoalDataAccessError::ParameterError(err.to_string()))?,
+        ),
+    };
     let text = goal.get_text();
+    let due_at = goal.get_due_at();
+    let completed_at = goal.get_completed_at();
 
     let stm = client
         .prepare(sql)
         .await
         .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
 
-    client
-        .execute(&stm, &[&text, &user_id])
+    let row = client
+        .query_one(
+            &stm,
+            &[&text, &user_id, &organization_id, &due_at, &completed_at],
+        )
         .await
         .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
 
-    Ok(())
+    let id = row.try_get::<_, Uuid>("id").unwrap_or_default().to_string();
+
+    Ok(id)
 }
 
+// Personal goals only, the goals of the organizations are found with find_organization_goals. The
+// archived goals when archived is true, the active ones otherwise. Goals in the trash are only found
+// by the trash functions
 pub async fn find_all_goals(
     client: &Client,
     user_id: &str,
+    archived: bool,
 ) -> Result<Vec<Goal>, GoalDataAccessError> {
-    let sql = "SELECT * FROM goals WHERE user_id = $1";
+    let sql = format!(
+        "{} WHERE g.user_id = $1 AND g.organization_id IS NULL AND g.deleted_at IS NULL
+            AND (g.archived_at IS NOT NULL) = $2",
+        SELECT_GOALS
+    );
 
     let stm = client
-        .prepare(sql)
+        .prepare(&sql)
         .await
         .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
 
@@ -60,32 +144,160 @@ pub async fn find_all_goals(
         .map_err(|err| GoalDataAccessError::ParameterError(err.to_string()))?;
 
     let rows = client
-        .query(&stm, &[&user_id])
+        .query(&stm, &[&user_id, &archived])
         .await
         .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
 
-    if rows.len() == 0 {
-        return Ok(Vec::new());
+    let mut goals = Vec::new();
+    for row in rows.iter() {
+        goals.push(row_to_goal(row)?);
     }
 
-    // Map Vec<Row> to Vec<entities::Goal>
-    let user_id = user_id.to_string();
+    Ok(goals)
+}
+
+pub async fn find_organization_goals(
+    client: &Client,
+    organization_id: &str,
+    archived: bool,
+) -> Result<Vec<Goal>, GoalDataAccessError> {
+    let sql = format!(
+        "{} WHERE g.organization_id = $1 AND g.deleted_at IS NULL
+            AND (g.archived_at IS NOT NULL) = $2",
+        SELECT_GOALS
+    );
+
+    let stm = client
+        .prepare(&sql)
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    let organization_id = Uuid::parse_str(organization_id)
+        .map_err(|err| GoalDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&organization_id, &archived])
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
     let mut goals = Vec::new();
     for row in rows.iter() {
-        let id = row.try_get::<_, Uuid>("id").unwrap_or_default().to_string();
-        let text = row.try_get::<_, String>("text").unwrap_or_default();
+        goals.push(row_to_goal(row)?);
+    }
+
+    Ok(goals)
+}
 
-        let goal = Goal::from_db_fields(&id, &text, &user_id)
-            .map_err(|err| GoalDataAccessError::MappingError(err.to_string()))?;
+pub async fn find_goal_by_id(
+    client: &Client,
+    id: &str,
+) -> Result<Option<Goal>, GoalDataAccessError> {
+    let sql = format!("{} WHERE g.id = $1 AND g.deleted_at IS NULL", SELECT_GOALS);
 
-        goals.push(goal);
+    let stm = client
+        .prepare(&sql)
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    let goal_id =
+        Uuid::parse_str(id).map_err(|err| GoalDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&goal_id])
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    if rows.is_empty() {
+        return Ok(None);
     }
 
-    Ok(goals)
+    let goal = row_to_goal(&rows[0])?;
+
+    Ok(Some(goal))
 }
 
+// Deletes the goal for good, in the trash or not. Leaves a tombstone for the owner, the users the goal is shared with and the members of its
+// organization, so the clients that sync find out about the deletion
 pub async fn delete_goal(client: &Client, id: &str) -> Result<(), GoalDataAccessError> {
-    let sql = "DELETE FROM goals WHERE id = $1";
+    let sql = "
+        WITH recipients AS (
+            SELECT user_id FROM goals WHERE id = $1
+            UNION SELECT user_id FROM goal_shares WHERE goal_id = $1
+            UNION SELECT m.user_id FROM organization_members m
+                INNER JOIN goals g ON g.organization_id = m.organization_id
+                WHERE g.id = $1
+        ), tombstones AS (
+            INSERT INTO goal_tombstones (goal_id, user_id, version)
+            SELECT $1, user_id, next_goal_version() FROM recipients
+            ON CONFLICT (goal_id, user_id) DO UPDATE
+                SET version = EXCLUDED.version, deleted_at = now()
+        )
+        DELETE FROM goals WHERE id = $1";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    let goal_id =
+        Uuid::parse_str(id).map_err(|err| GoalDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&goal_id])
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+// Moves the goal to the trash. Returns 0 when the goal is missing or already in the trash
+pub async fn trash_goal(client: &Client, id: &str) -> Result<u64, GoalDataAccessError> {
+    let sql = "
+        UPDATE goals
+        SET deleted_at = now(), updated_at = now(), version = next_goal_version()
+        WHERE id = $1 AND deleted_at IS NULL";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    let goal_id =
+        Uuid::parse_str(id).map_err(|err| GoalDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&goal_id])
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))
+}
+
+// Returns 0 when the goal is not in the trash
+pub async fn restore_goal(client: &Client, id: &str) -> Result<u64, GoalDataAccessError> {
+    let sql = "
+        UPDATE goals
+        SET deleted_at = NULL, updated_at = now(), version = next_goal_version()
+        WHERE id = $1 AND deleted_at IS NOT NULL";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    let goal_id =
+        Uuid::parse_str(id).map_err(|err| GoalDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&goal_id])
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))
+}
+
+// Returns 0 when the goal is missing or already archived
+pub async fn archive_goal(client: &Client, id: &str) -> Result<u64, GoalDataAccessError> {
+    let sql = "
+        UPDATE goals
+        SET archived_at = now(), updated_at = now(), version = next_goal_version()
+        WHERE id = $1 AND archived_at IS NULL AND deleted_at IS NULL";
 
     let stm = client
         .prepare(sql)
@@ -98,7 +310,554 @@ pub async fn delete_goal(client: &Client, id: &str) -> Result<(), GoalDataAccess
     client
         .execute(&stm, &[&goal_id])
         .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))
+}
+
+// Returns 0 when the goal is missing or not archived
+pub async fn unarchive_goal(client: &Client, id: &str) -> Result<u64, GoalDataAccessError> {
+    let sql = "
+        UPDATE goals
+        SET archived_at = NULL, updated_at = now(), version = next_goal_version()
+        WHERE id = $1 AND archived_at IS NOT NULL AND deleted_at IS NULL";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    let goal_id =
+        Uuid::parse_str(id).map_err(|err| GoalDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&goal_id])
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))
+}
+
+// Archives the goals completed more than completed_seconds ago and returns them
+pub async fn archive_completed_goals_before(
+    client: &Client,
+    completed_seconds: f64,
+) -> Result<Vec<Goal>, GoalDataAccessError> {
+    let sql = "
+        UPDATE goals g
+        SET archived_at = now(), updated_at = now(), version = next_goal_version()
+        WHERE g.archived_at IS NULL AND g.deleted_at IS NULL
+            AND g.completed_at < now() - make_interval(secs => $1)
+        RETURNING g.*,
+            (SELECT COUNT(*) FROM goal_notes n WHERE n.goal_id = g.id) AS note_count";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&completed_seconds])
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut goals = Vec::new();
+    for row in rows.iter() {
+        goals.push(row_to_goal(row)?);
+    }
+
+    Ok(goals)
+}
+
+// Marks the active goals due within notice_seconds that were not notified for their due date yet
+// and returns them, the goals locked by another instance are skipped
+pub async fn claim_approaching_deadlines(
+    client: &Client,
+    notice_seconds: f64,
+    limit: i64,
+) -> Result<Vec<Goal>, GoalDataAccessError> {
+    let sql = "
+        WITH approaching AS (
+            SELECT id FROM goals
+            WHERE due_at > now() AND due_at <= now() + make_interval(secs => $1)
+                AND deadline_notified_due_at IS DISTINCT FROM due_at
+                AND completed_at IS NULL AND archived_at IS NULL AND deleted_at IS NULL
+            ORDER BY due_at
+            LIMIT $2
+            FOR UPDATE SKIP LOCKED
+        )
+        UPDATE goals g
+        SET deadline_notified_due_at = g.due_at
+        FROM approaching a
+        WHERE g.id = a.id
+        RETURNING g.*,
+            (SELECT COUNT(*) FROM goal_notes n WHERE n.goal_id = g.id) AS note_count";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&notice_seconds, &limit])
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut goals = Vec::new();
+    for row in rows.iter() {
+        goals.push(row_to_goal(row)?);
+    }
+
+    Ok(goals)
+}
+
+pub async fn find_trashed_goal_by_id(
+    client: &Client,
+    id: &str,
+) -> Result<Option<Goal>, GoalDataAccessError> {
+    let sql = format!(
+        "{} WHERE g.id = $1 AND g.deleted_at IS NOT NULL",
+        SELECT_GOALS
+    );
+
+    let stm = client
+        .prepare(&sql)
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    let goal_id =
+        Uuid::parse_str(id).map_err(|err| GoalDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&goal_id])
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    if rows.is_empty() {
+        return Ok(None);
+    }
+
+    let goal = row_to_goal(&rows[0])?;
+
+    Ok(Some(goal))
+}
+
+// Personal goals of the user in the trash, the last deleted first
+pub async fn find_trashed_goals(
+    client: &Client,
+    user_id: &str,
+) -> Result<Vec<Goal>, GoalDataAccessError> {
+    let sql = format!(
+        "{} WHERE g.user_id = $1 AND g.organization_id IS NULL AND g.deleted_at IS NOT NULL
+        ORDER BY g.deleted_at DESC",
+        SELECT_GOALS
+    );
+
+    let stm = client
+        .prepare(&sql)
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| GoalDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&user_id])
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut goals = Vec::new();
+    for row in rows.iter() {
+        goals.push(row_to_goal(row)?);
+    }
+
+    Ok(goals)
+}
+
+pub async fn find_trashed_organization_goals(
+    client: &Client,
+    organization_id: &str,
+) -> Result<Vec<Goal>, GoalDataAccessError> {
+    let sql = format!(
+        "{} WHERE g.organization_id = $1 AND g.deleted_at IS NOT NULL ORDER BY g.deleted_at DESC",
+        SELECT_GOALS
+    );
+
+    let stm = client
+        .prepare(&sql)
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    let organization_id = Uuid::parse_str(organization_id)
+        .map_err(|err| GoalDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&organization_id])
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut goals = Vec::new();
+    for row in rows.iter() {
+        goals.push(row_to_goal(row)?);
+    }
+
+    Ok(goals)
+}
+
+// Deletes for good the goals in the trash for more than retention_seconds, with the tombstones of
+// delete_goal. Returns the number of goals deleted
+pub async fn delete_trashed_goals_before(
+    client: &Client,
+    retention_seconds: f64,
+) -> Result<u64, GoalDataAccessError> {
+    let sql = "
+        WITH expired AS (
+            SELECT id, user_id, organization_id FROM goals
+            WHERE deleted_at < now() - make_interval(secs => $1)
+        ), recipients AS (
+            SELECT id AS goal_id, user_id FROM expired
+            UNION SELECT s.goal_id, s.user_id FROM goal_shares s
+                INNER JOIN expired e ON e.id = s.goal_id
+            UNION SELECT e.id, m.user_id FROM organization_members m
+                INNER JOIN expired e ON e.organization_id = m.organization_id
+        ), tombstones AS (
+            INSERT INTO goal_tombstones (goal_id, user_id, version)
+            SELECT goal_id, user_id, next_goal_version() FROM recipients
+            ON CONFLICT (goal_id, user_id) DO UPDATE
+                SET version = EXCLUDED.version, deleted_at = now()
+        )
+        DELETE FROM goals WHERE id IN (SELECT id FROM expired)";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&retention_seconds])
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))
+}
+
+// The dates kept for the sync only move for the fields that changed, a completion does not make a
+// text edited offline lose against the text kept
+pub async fn update_goal(client: &Client, goal: &Goal) -> Result<(), GoalDataAccessError> {
+    let sql = "
+        UPDATE goals
+        SET text = $2, completed_at = $3, due_at = $4,
+            text_updated_at = CASE WHEN text <> $2 THEN now() ELSE text_updated_at END,
+            completion_updated_at = CASE
+                WHEN completed_at IS DISTINCT FROM $3 THEN now()
+                ELSE completion_updated_at
+            END,
+            updated_at = now(), version = next_goal_version()
+        WHERE id = $1";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    let goal_id = Uuid::parse_str(&goal.get_id())
+        .map_err(|err| GoalDataAccessError::ParameterError(err.to_string()))?;
+    let text = goal.get_text();
+    let completed_at = goal.get_completed_at();
+    let due_at = goal.get_due_at();
+
+    client
+        .execute(&stm, &[&goal_id, &text, &completed_at, &due_at])
+        .await
         .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
 
     Ok(())
 }
+
+// Goals created offline keep the id generated by the client. Returns false when the id is already
+// used or was used by a deleted goal
+pub async fn add_goal_with_id(client: &Client, goal: &Goal) -> Result<bool, GoalDataAccessError> {
+    let sql = "
+        INSERT INTO goals
+            (id, text, user_id, organization_id, text_updated_at, completed_at,
+                completion_updated_at)
+        SELECT $1, $2, $3, $4, $5, $6, $7
+        WHERE NOT EXISTS (SELECT 1 FROM goal_tombstones WHERE goal_id = $1)
+        ON CONFLICT (id) DO NOTHING";
+
+    let goal_id = Uuid::parse_str(&goal.get_id())
+        .map_err(|err| GoalDataAccessError::ParameterError(err.to_string()))?;
+    let user_id = Uuid::parse_str(&goal.get_user_id())
+        .map_err(|err| GoalDataAccessError::ParameterError(err.to_string()))?;
+    let organization_id = match goal.get_organization_id() {
+        None => None,
+        Some(id) => Some(
+            Uuid::parse_str(&id)
+                .map_err(|err| GoalDataAccessError::ParameterError(err.to_string()))?,
+        ),
+    };
+    let text = goal.get_text();
+    let text_updated_at = goal.get_text_updated_at().unwrap_or_else(Utc::now);
+    let completed_at = goal.get_completed_at();
+    let completion_updated_at = goal.get_completion_updated_at().unwrap_or(text_updated_at);
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    let inserted = client
+        .execute(
+            &stm,
+            &[
+                &goal_id,
+                &text,
+                &user_id,
+                &organization_id,
+                &text_updated_at,
+                &completed_at,
+                &completion_updated_at,
+            ],
+        )
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(inserted == 1)
+}
+
+// Last writer wins: the text is only changed when it was edited after the text kept. Returns 0 when
+// the change is older
+pub async fn update_goal_text_if_newer(
+    client: &Client,
+    goal: &Goal,
+) -> Result<u64, GoalDataAccessError> {
+    let sql = "
+        UPDATE goals
+        SET text = $2, text_updated_at = $3, updated_at = now(),
+            version = next_goal_version()
+        WHERE id = $1 AND text_updated_at < $3";
+
+    let goal_id = Uuid::parse_str(&goal.get_id())
+        .map_err(|err| GoalDataAccessError::ParameterError(err.to_string()))?;
+    let text = goal.get_text();
+    let text_updated_at = goal.get_text_updated_at().unwrap_or_else(Utc::now);
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&goal_id, &text, &text_updated_at])
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))
+}
+
+// Last writer wins, like the text: the completion is only changed when it was changed after the
+// completion kept. Returns 0 when the change is older
+pub async fn update_goal_completion_if_newer(
+    client: &Client,
+    goal: &Goal,
+) -> Result<u64, GoalDataAccessError> {
+    let sql = "
+        UPDATE goals
+        SET completed_at = $2, completion_updated_at = $3, updated_at = now(),
+            version = next_goal_version()
+        WHERE id = $1 AND completion_updated_at < $3";
+
+    let goal_id = Uuid::parse_str(&goal.get_id())
+        .map_err(|err| GoalDataAccessError::ParameterError(err.to_string()))?;
+    let completed_at = goal.get_completed_at();
+    let completion_updated_at = goal.get_completion_updated_at().unwrap_or_else(Utc::now);
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&goal_id, &completed_at, &completion_updated_at])
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))
+}
+
+// Versions are drawn when the goals are written, not when the transactions commit, so a version
+// can become visible after a greater one. Returns the greatest version up to which every change is
+// committed, without waiting for the transactions still running. Sync cursors never go past it
+pub async fn find_stable_goal_version(client: &Client) -> Result<i64, GoalDataAccessError> {
+    let stm = client
+        .prepare("SELECT stable_tracked_value('goals_version_seq') AS version")
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    let row = client
+        .query_one(&stm, &[])
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    row.try_get::<_, i64>("version")
+        .map_err(|err| GoalDataAccessError::MappingError(err.to_string()))
+}
+
+// Goals the user can read that changed after the version and up to a stable version, in the order
+// of the changes
+pub async fn find_goal_changes(
+    client: &Client,
+    user_id: &str,
+    after_version: i64,
+    up_to_version: i64,
+    limit: i64,
+) -> Result<Vec<Goal>, GoalDataAccessError> {
+    let sql = format!(
+        "{} WHERE g.version > $2 AND g.version <= $3 AND (
+            (g.organization_id IS NULL AND g.user_id = $1)
+            OR EXISTS (
+                SELECT 1 FROM organization_members m
+                WHERE m.organization_id = g.organization_id AND m.user_id = $1
+            )
+            OR EXISTS (
+                SELECT 1 FROM goal_shares s
+                WHERE s.goal_id = g.id AND s.user_id = $1
+            )
+        )
+        ORDER BY g.version
+        LIMIT $4",
+        SELECT_GOALS
+    );
+
+    let stm = client
+        .prepare(&sql)
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| GoalDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&user_id, &after_version, &up_to_version, &limit])
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut goals = Vec::new();
+    for row in rows.iter() {
+        goals.push(row_to_goal(row)?);
+    }
+
+    Ok(goals)
+}
+
+// A user can read a goal when it is a personal goal of the user, a goal of an organization the user
+// is a member of or a goal shared with the user
+pub async fn has_goal_access(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<bool, GoalDataAccessError> {
+    let sql = "
+        SELECT EXISTS (
+            SELECT 1 FROM goals g
+            WHERE g.id = $1 AND g.deleted_at IS NULL AND (
+                (g.organization_id IS NULL AND g.user_id = $2)
+                OR EXISTS (
+                    SELECT 1 FROM organization_members m
+                    WHERE m.organization_id = g.organization_id AND m.user_id = $2
+                )
+                OR EXISTS (
+                    SELECT 1 FROM goal_shares s
+                    WHERE s.goal_id = g.id AND s.user_id = $2
+                )
+            )
+        ) AS has_access";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    let goal_id = Uuid::parse_str(goal_id)
+        .map_err(|err| GoalDataAccessError::ParameterError(err.to_string()))?;
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| GoalDataAccessError::ParameterError(err.to_string()))?;
+
+    let row = client
+        .query_one(&stm, &[&goal_id, &user_id])
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(row.try_get::<_, bool>("has_access").unwrap_or_default())
+}
+
+// A user can write a goal when it is a personal goal of the user, a goal of an organization the user
+// is a member of without the viewer role or a goal shared with the user for edit
+pub async fn has_goal_write_access(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<bool, GoalDataAccessError> {
+    let sql = "
+        SELECT EXISTS (
+            SELECT 1 FROM goals g
+            WHERE g.id = $1 AND g.deleted_at IS NULL AND (
+                (g.organization_id IS NULL AND g.user_id = $2)
+                OR EXISTS (
+                    SELECT 1 FROM organization_members m
+                    WHERE m.organization_id = g.organization_id AND m.user_id = $2
+                        AND m.role <> $3
+                )
+                OR EXISTS (
+                    SELECT 1 FROM goal_shares s
+                    WHERE s.goal_id = g.id AND s.user_id = $2 AND s.permission = $4
+                )
+            )
+        ) AS has_access";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    let goal_id = Uuid::parse_str(goal_id)
+        .map_err(|err| GoalDataAccessError::ParameterError(err.to_string()))?;
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| GoalDataAccessError::ParameterError(err.to_string()))?;
+
+    let row = client
+        .query_one(&stm, &[&goal_id, &user_id, &VIEWER_ROLE, &EDIT_PERMISSION])
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(row.try_get::<_, bool>("has_access").unwrap_or_default())
+}
+
+// Stricter than the write access: the goal is deleted by its owner when it is a personal goal, by
+// the members of the organization without the viewer role otherwise. Users the goal is shared with
+// cannot delete it, whatever the permission
+pub async fn has_goal_delete_access(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<bool, GoalDataAccessError> {
+    let sql = "
+        SELECT EXISTS (
+            SELECT 1 FROM goals g
+            WHERE g.id = $1 AND g.deleted_at IS NULL AND (
+                (g.organization_id IS NULL AND g.user_id = $2)
+                OR EXISTS (
+                    SELECT 1 FROM organization_members m
+                    WHERE m.organization_id = g.organization_id AND m.user_id = $2
+                        AND m.role <> $3
+                )
+            )
+        ) AS has_access";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    let goal_id = Uuid::parse_str(goal_id)
+        .map_err(|err| GoalDataAccessError::ParameterError(err.to_string()))?;
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| GoalDataAccessError::ParameterError(err.to_string()))?;
+
+    let row = client
+        .query_one(&stm, &[&goal_id, &user_id, &VIEWER_ROLE])
+        .await
+        .map_err(|err| GoalDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(row.try_get::<_, bool>("has_access").unwrap_or_default())
+}
@@ -0,0 +1,201 @@
+use std::fmt::{self, Display};
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use tokio_postgres::{Client, Row};
+use uuid::Uuid;
+
+use crate::entities::goal_event::GoalEvent;
+
+// Postgres channel of the goal events. The payload of a notification is "{event id}:{user id}"
+pub const GOAL_EVENTS_CHANNEL: &str = "goal_events";
+
+pub enum GoalEventDataAccessError {
+    DatabaseError(String),
+    MappingError(String),
+    ParameterError(String),
+}
+
+impl Display for GoalEventDataAccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoalEventDataAccessError::DatabaseError(err) => write!(f, "{}", err),
+            GoalEventDataAccessError::MappingError(err) => write!(f, "{}", err),
+            GoalEventDataAccessError::ParameterError(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+fn row_to_goal_event(row: &Row) -> Result<GoalEvent, GoalEventDataAccessError> {
+    let id = row.try_get::<_, i64>("id").unwrap_or_default();
+    let user_id = row
+        .try_get::<_, Uuid>("user_id")
+        .unwrap_or_default()
+        .to_string();
+    let goal_id = row
+        .try_get::<_, Uuid>("goal_id")
+        .unwrap_or_default()
+        .to_string();
+    let event_type = row.try_get::<_, String>("event_type").unwrap_or_default();
+    let payload = row.try_get::<_, String>("payload").unwrap_or_default();
+    let created_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("created_at")
+        .unwrap_or_default();
+
+    GoalEvent::from_db_fields(id, &user_id, &goal_id, &event_type, &payload, created_at)
+        .map_err(|err| GoalEventDataAccessError::MappingError(err.to_string()))
+}
+
+// Stores the event for the owner, the users the goal is shared with and the members of its
+// organization, and notifies the listening instances. Called before a goal is deleted, while its
+// shares still exist. Returns the number of users the event is stored for
+pub async fn add_goal_event(
+    client: &Client,
+    goal_id: &str,
+    owner_id: &str,
+    organization_id: Option<String>,
+    event_type: &str,
+    data: &Value,
+) -> Result<usize, GoalEventDataAccessError> {
+    let sql = "
+        WITH recipients AS (
+            SELECT $2::uuid AS user_id
+            UNION SELECT user_id FROM goal_shares WHERE goal_id = $1
+            UNION SELECT user_id FROM organization_members WHERE organization_id = $3
+        ), inserted AS (
+            INSERT INTO goal_events (user_id, goal_id, event_type, payload)
+            SELECT user_id, $1, $4, $5 FROM recipients
+            RETURNING id, user_id
+        )
+        SELECT pg_notify($6, id::text || ':' || user_id::text) FROM inserted";
+
+    let goal_id = Uuid::parse_str(goal_id)
+        .map_err(|err| GoalEventDataAccessError::ParameterError(err.to_string()))?;
+    let owner_id = Uuid::parse_str(owner_id)
+        .map_err(|err| GoalEventDataAccessError::ParameterError(err.to_string()))?;
+    let organization_id = match organization_id {
+        None => None,
+        Some(id) => Some(
+            Uuid::parse_str(&id)
+                .map_err(|err| GoalEventDataAccessError::ParameterError(err.to_string()))?,
+        ),
+    };
+    let payload = data.to_string();
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| GoalEventDataAccessError::DatabaseError(err.to_string()))?;
+
+    let rows = client
+        .query(
+            &stm,
+            &[
+                &goal_id,
+                &owner_id,
+                &organization_id,
+                &event_type,
+                &payload,
+                &GOAL_EVENTS_CHANNEL,
+            ],
+        )
+        .await
+        .map_err(|err| GoalEventDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(rows.len())
+}
+
+// Ids are drawn when the events are written, not when the transactions commit, so an id can become
+// visible after a greater one. Returns the greatest id up to which every event is committed,
+// without waiting for the transactions still running. Streams never go past it
+pub async fn find_stable_goal_event_id(client: &Client) -> Result<i64, GoalEventDataAccessError> {
+    let stm = client
+        .prepare("SELECT stable_tracked_value('goal_events_id_seq') AS id")
+        .await
+        .map_err(|err| GoalEventDataAccessError::DatabaseError(err.to_string()))?;
+
+    let row = client
+        .query_one(&stm, &[])
+        .await
+        .map_err(|err| GoalEventDataAccessError::DatabaseError(err.to_string()))?;
+
+    row.try_get::<_, i64>("id")
+        .map_err(|err| GoalEventDataAccessError::MappingError(err.to_string()))
+}
+
+// Oldest first, up to a stable id
+pub async fn find_goal_events(
+    client: &Client,
+    user_id: &str,
+    after_id: i64,
+    up_to_id: i64,
+    limit: i64,
+) -> Result<Vec<GoalEvent>, GoalEventDataAccessError> {
+    let sql = "
+        SELECT * FROM goal_events
+        WHERE user_id = $1 AND id > $2 AND id <= $3
+        ORDER BY id
+        LIMIT $4";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| GoalEventDataAccessError::DatabaseError(err.to_string()))?;
+
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| GoalEventDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&user_id, &after_id, &up_to_id, &limit])
+        .await
+        .map_err(|err| GoalEventDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut goal_events = Vec::new();
+    for row in rows.iter() {
+        goal_events.push(row_to_goal_event(row)?);
+    }
+
+    Ok(goal_events)
+}
+
+// Committed events of the user after the id, stable or not
+pub async fn has_goal_events_after(
+    client: &Client,
+    user_id: &str,
+    after_id: i64,
+) -> Result<bool, GoalEventDataAccessError> {
+    let sql = "SELECT EXISTS (SELECT 1 FROM goal_events WHERE user_id = $1 AND id > $2) AS found";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| GoalEventDataAccessError::DatabaseError(err.to_string()))?;
+
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| GoalEventDataAccessError::ParameterError(err.to_string()))?;
+
+    let row = client
+        .query_one(&stm, &[&user_id, &after_id])
+        .await
+        .map_err(|err| GoalEventDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(row.try_get::<_, bool>("found").unwrap_or_default())
+}
+
+// Returns the number of events deleted
+pub async fn delete_goal_events_before(
+    client: &Client,
+    retention_seconds: f64,
+) -> Result<u64, GoalEventDataAccessError> {
+    let sql = "DELETE FROM goal_events WHERE created_at < now() - make_interval(secs => $1)";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| GoalEventDataAccessError::DatabaseError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&retention_seconds])
+        .await
+        .map_err(|err| GoalEventDataAccessError::DatabaseError(err.to_string()))
+}
@@ -0,0 +1,147 @@
+use std::fmt::{self, Display};
+
+use chrono::{DateTime, Utc};
+use tokio_postgres::{Client, Row};
+use uuid::Uuid;
+
+use crate::entities::goal_revision::GoalRevision;
+
+pub enum GoalRevisionDataAccessError {
+    DatabaseError(String),
+    MappingError(String),
+    ParameterError(String),
+}
+
+impl Display for GoalRevisionDataAccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoalRevisionDataAccessError::DatabaseError(err) => write!(f, "{}", err),
+            GoalRevisionDataAccessError::MappingError(err) => write!(f, "{}", err),
+            GoalRevisionDataAccessError::ParameterError(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+// Revisions are always selected with the name of their actor
+const SELECT_GOAL_REVISIONS: &str = "
+    SELECT r.*, u.name AS actor_name FROM goal_revisions r
+    LEFT JOIN users u ON u.id = r.actor_id";
+
+fn row_to_goal_revision(row: &Row) -> Result<GoalRevision, GoalRevisionDataAccessError> {
+    let id = row.try_get::<_, i64>("id").unwrap_or_default();
+    let goal_id = row
+        .try_get::<_, Uuid>("goal_id")
+        .unwrap_or_default()
+        .to_string();
+    let actor_id = row
+        .try_get::<_, Option<Uuid>>("actor_id")
+        .unwrap_or_default()
+        .map(|id| id.to_string());
+    let actor_name = row
+        .try_get::<_, Option<String>>("actor_name")
+        .unwrap_or_default();
+    let action = row.try_get::<_, String>("action").unwrap_or_default();
+    let before = row
+        .try_get::<_, Option<String>>("before")
+        .unwrap_or_default();
+    let after = row.try_get::<_, String>("after").unwrap_or_default();
+    let created_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("created_at")
+        .unwrap_or_default();
+
+    GoalRevision::from_db_fields(
+        id, &goal_id, actor_id, actor_name, &action, before, &after, created_at,
+    )
+    .map_err(|err| GoalRevisionDataAccessError::MappingError(err.to_string()))
+}
+
+// Revisions are only added, never changed. before and after are snapshots of GoalRevision::snapshot
+pub async fn add_goal_revision(
+    client: &Client,
+    goal_id: &str,
+    actor_id: Option<&str>,
+    action: &str,
+    before: Option<&str>,
+    after: &str,
+) -> Result<(), GoalRevisionDataAccessError> {
+    let sql = "
+        INSERT INTO goal_revisions (goal_id, actor_id, action, before, after)
+        VALUES ($1, $2, $3, $4, $5)";
+
+    let goal_id = Uuid::parse_str(goal_id)
+        .map_err(|err| GoalRevisionDataAccessError::ParameterError(err.to_string()))?;
+    let actor_id = match actor_id {
+        None => None,
+        Some(id) => Some(
+            Uuid::parse_str(id)
+                .map_err(|err| GoalRevisionDataAccessError::ParameterError(err.to_string()))?,
+        ),
+    };
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| GoalRevisionDataAccessError::DatabaseError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&goal_id, &actor_id, &action, &before, &after])
+        .await
+        .map_err(|err| GoalRevisionDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+// Newest first
+pub async fn find_goal_revisions(
+    client: &Client,
+    goal_id: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<GoalRevision>, GoalRevisionDataAccessError> {
+    let sql = format!(
+        "{} WHERE r.goal_id = $1 ORDER BY r.id DESC LIMIT $2 OFFSET $3",
+        SELECT_GOAL_REVISIONS
+    );
+
+    let stm = client
+        .prepare(&sql)
+        .await
+        .map_err(|err| GoalRevisionDataAccessError::DatabaseError(err.to_string()))?;
+
+    let goal_id = Uuid::parse_str(goal_id)
+        .map_err(|err| GoalRevisionDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&goal_id, &limit, &offset])
+        .await
+        .map_err(|err| GoalRevisionDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut revisions = Vec::new();
+    for row in rows.iter() {
+        revisions.push(row_to_goal_revision(row)?);
+    }
+
+    Ok(revisions)
+}
+
+pub async fn find_goal_revision_by_id(
+    client: &Client,
+    id: i64,
+) -> Result<Option<GoalRevision>, GoalRevisionDataAccessError> {
+    let sql = format!("{} WHERE r.id = $1", SELECT_GOAL_REVISIONS);
+
+    let stm = client
+        .prepare(&sql)
+        .await
+        .map_err(|err| GoalRevisionDataAccessError::DatabaseError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&id])
+        .await
+        .map_err(|err| GoalRevisionDataAccessError::DatabaseError(err.to_string()))?;
+
+    match rows.first() {
+        None => Ok(None),
+        Some(row) => Ok(Some(row_to_goal_revision(row)?)),
+    }
+}
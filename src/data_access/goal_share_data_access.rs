@@ -0,0 +1,262 @@
+use std::fmt::{self, Display};
+
+use chrono::{DateTime, Utc};
+use tokio_postgres::{Client, Row};
+use uuid::Uuid;
+
+use crate::entities::{goal::Goal, goal_share::GoalShare};
+
+pub enum GoalShareDataAccessError {
+    DatabaseError(String),
+    MappingError(String),
+    ParameterError(String),
+}
+
+impl Display for GoalShareDataAccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoalShareDataAccessError::DatabaseError(err) => write!(f, "{}", err),
+            GoalShareDataAccessError::MappingError(err) => write!(f, "{}", err),
+            GoalShareDataAccessError::ParameterError(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+fn row_to_goal_share(row: &Row) -> Result<GoalShare, GoalShareDataAccessError> {
+    let goal_id = row
+        .try_get::<_, Uuid>("goal_id")
+        .unwrap_or_default()
+        .to_string();
+    let user_id = row
+        .try_get::<_, Uuid>("user_id")
+        .unwrap_or_default()
+        .to_string();
+    let permission = row.try_get::<_, String>("permission").unwrap_or_default();
+
+    GoalShare::from_db_fields(&goal_id, &user_id, &permission)
+        .map_err(|err| GoalShareDataAccessError::MappingError(err.to_string()))
+}
+
+// Sharing the goal again with the same user replaces the permission. The version of the goal
+// changes, so the user receives it on the next sync, and a tombstone of a previous share is removed
+pub async fn add_goal_share(
+    client: &Client,
+    goal_share: &GoalShare,
+) -> Result<(), GoalShareDataAccessError> {
+    let sql = "
+        WITH shared AS (
+            INSERT INTO goal_shares
+                (goal_id, user_id, permission)
+            VALUES
+                ($1, $2, $3)
+            ON CONFLICT (goal_id, user_id) DO UPDATE SET permission = EXCLUDED.permission
+            RETURNING goal_id, user_id
+        ), tombstones AS (
+            DELETE FROM goal_tombstones t USING shared
+            WHERE t.goal_id = shared.goal_id AND t.user_id = shared.user_id
+        )
+        UPDATE goals SET version = next_goal_version()
+        WHERE id IN (SELECT goal_id FROM shared)";
+
+    let goal_id = Uuid::parse_str(&goal_share.get_goal_id())
+        .map_err(|err| GoalShareDataAccessError::ParameterError(err.to_string()))?;
+    let user_id = Uuid::parse_str(&goal_share.get_user_id())
+        .map_err(|err| GoalShareDataAccessError::ParameterError(err.to_string()))?;
+    let permission = goal_share.get_permission();
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| GoalShareDataAccessError::DatabaseError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&goal_id, &user_id, &permission])
+        .await
+        .map_err(|err| GoalShareDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+pub async fn find_goal_share(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<Option<GoalShare>, GoalShareDataAccessError> {
+    let sql = "SELECT * FROM goal_shares WHERE goal_id = $1 AND user_id = $2";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| GoalShareDataAccessError::DatabaseError(err.to_string()))?;
+
+    let goal_id = Uuid::parse_str(goal_id)
+        .map_err(|err| GoalShareDataAccessError::ParameterError(err.to_string()))?;
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| GoalShareDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&goal_id, &user_id])
+        .await
+        .map_err(|err| GoalShareDataAccessError::DatabaseError(err.to_string()))?;
+
+    if rows.is_empty() {
+        return Ok(None);
+    }
+
+    let goal_share = row_to_goal_share(&rows[0])?;
+
+    Ok(Some(goal_share))
+}
+
+pub async fn find_goal_shares(
+    client: &Client,
+    goal_id: &str,
+) -> Result<Vec<GoalShare>, GoalShareDataAccessError> {
+    let sql = "SELECT * FROM goal_shares WHERE goal_id = $1 ORDER BY created_at";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| GoalShareDataAccessError::DatabaseError(err.to_string()))?;
+
+    let goal_id = Uuid::parse_str(goal_id)
+        .map_err(|err| GoalShareDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&goal_id])
+        .await
+        .map_err(|err| GoalShareDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut goal_shares = Vec::new();
+    for row in rows.iter() {
+        goal_shares.push(row_to_goal_share(row)?);
+    }
+
+    Ok(goal_shares)
+}
+
+// Goals shared with the user, with the share that gives the access. The archived goals when archived
+// is true, the active ones otherwise
+pub async fn find_shared_goals(
+    client: &Client,
+    user_id: &str,
+    archived: bool,
+) -> Result<Vec<(Goal, GoalShare)>, GoalShareDataAccessError> {
+    let sql = "
+        SELECT g.id, g.text, g.user_id AS owner_id, g.organization_id,
+            (SELECT COUNT(*) FROM goal_notes n WHERE n.goal_id = g.id) AS note_count,
+            g.version, g.updated_at, g.text_updated_at, g.due_at, g.completed_at,
+            g.completion_updated_at, g.archived_at, g.deleted_at, s.goal_id, s.user_id, s.permission
+        FROM goals g
+        INNER JOIN goal_shares s ON s.goal_id = g.id
+        WHERE s.user_id = $1 AND g.deleted_at IS NULL AND (g.archived_at IS NOT NULL) = $2
+        ORDER BY s.created_at";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| GoalShareDataAccessError::DatabaseError(err.to_string()))?;
+
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| GoalShareDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&user_id, &archived])
+        .await
+        .map_err(|err| GoalShareDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut shared_goals = Vec::new();
+    for row in rows.iter() {
+        let id = row.try_get::<_, Uuid>("id").unwrap_or_default().to_string();
+        let text = row.try_get::<_, String>("text").unwrap_or_default();
+        let owner_id = row
+            .try_get::<_, Uuid>("owner_id")
+            .unwrap_or_default()
+            .to_string();
+        let organization_id = row
+            .try_get::<_, Option<Uuid>>("organization_id")
+            .unwrap_or_default()
+            .map(|id| id.to_string());
+        let note_count = row.try_get::<_, i64>("note_count").unwrap_or_default();
+        let version = row.try_get::<_, i64>("version").unwrap_or_default();
+        let updated_at = row
+            .try_get::<_, Option<DateTime<Utc>>>("updated_at")
+            .unwrap_or_default();
+        let text_updated_at = row
+            .try_get::<_, Option<DateTime<Utc>>>("text_updated_at")
+            .unwrap_or_default();
+        let due_at = row
+            .try_get::<_, Option<DateTime<Utc>>>("due_at")
+            .unwrap_or_default();
+        let completed_at = row
+            .try_get::<_, Option<DateTime<Utc>>>("completed_at")
+            .unwrap_or_default();
+        let completion_updated_at = row
+            .try_get::<_, Option<DateTime<Utc>>>("completion_updated_at")
+            .unwrap_or_default();
+        let archived_at = row
+            .try_get::<_, Option<DateTime<Utc>>>("archived_at")
+            .unwrap_or_default();
+        let deleted_at = row
+            .try_get::<_, Option<DateTime<Utc>>>("deleted_at")
+            .unwrap_or_default();
+
+        let goal = Goal::from_db_fields(
+            &id,
+            &text,
+            &owner_id,
+            organization_id,
+            note_count,
+            version,
+            updated_at,
+            text_updated_at,
+            due_at,
+            completed_at,
+            completion_updated_at,
+            archived_at,
+            deleted_at,
+        )
+        .map_err(|err| GoalShareDataAccessError::MappingError(err.to_string()))?;
+
+        shared_goals.push((goal, row_to_goal_share(row)?));
+    }
+
+    Ok(shared_goals)
+}
+
+// Returns the number of removed shares, 0 when the goal was not shared with the user. The user gets
+// a tombstone of the goal, so synced clients remove it
+pub async fn delete_goal_share(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<u64, GoalShareDataAccessError> {
+    let sql = "
+        WITH deleted AS (
+            DELETE FROM goal_shares WHERE goal_id = $1 AND user_id = $2
+            RETURNING goal_id, user_id
+        ), tombstones AS (
+            INSERT INTO goal_tombstones (goal_id, user_id, version)
+            SELECT goal_id, user_id, next_goal_version() FROM deleted
+            ON CONFLICT (goal_id, user_id) DO UPDATE
+                SET version = EXCLUDED.version, deleted_at = now()
+        )
+        SELECT COUNT(*) AS deleted FROM deleted";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| GoalShareDataAccessError::DatabaseError(err.to_string()))?;
+
+    let goal_id = Uuid::parse_str(goal_id)
+        .map_err(|err| GoalShareDataAccessError::ParameterError(err.to_string()))?;
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| GoalShareDataAccessError::ParameterError(err.to_string()))?;
+
+    let row = client
+        .query_one(&stm, &[&goal_id, &user_id])
+        .await
+        .map_err(|err| GoalShareDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(row.try_get::<_, i64>("deleted").unwrap_or_default() as u64)
+}
@@ -0,0 +1,95 @@
+use std::fmt::{self, Display};
+
+use chrono::{DateTime, Utc};
+use tokio_postgres::{Client, Row};
+use uuid::Uuid;
+
+use crate::entities::sync::GoalTombstone;
+
+pub enum GoalTombstoneDataAccessError {
+    DatabaseError(String),
+    MappingError(String),
+    ParameterError(String),
+}
+
+impl Display for GoalTombstoneDataAccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoalTombstoneDataAccessError::DatabaseError(err) => write!(f, "{}", err),
+            GoalTombstoneDataAccessError::MappingError(err) => write!(f, "{}", err),
+            GoalTombstoneDataAccessError::ParameterError(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+fn row_to_goal_tombstone(row: &Row) -> Result<GoalTombstone, GoalTombstoneDataAccessError> {
+    let goal_id = row
+        .try_get::<_, Uuid>("goal_id")
+        .unwrap_or_default()
+        .to_string();
+    let user_id = row
+        .try_get::<_, Uuid>("user_id")
+        .unwrap_or_default()
+        .to_string();
+    let version = row.try_get::<_, i64>("version").unwrap_or_default();
+    let deleted_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("deleted_at")
+        .unwrap_or_default();
+
+    GoalTombstone::from_db_fields(&goal_id, &user_id, version, deleted_at)
+        .map_err(|err| GoalTombstoneDataAccessError::MappingError(err.to_string()))
+}
+
+// Tombstones are added with the deletions of goals, shares and memberships. Oldest first, up to
+// a stable version (see find_stable_goal_version)
+pub async fn find_goal_tombstones(
+    client: &Client,
+    user_id: &str,
+    after_version: i64,
+    up_to_version: i64,
+    limit: i64,
+) -> Result<Vec<GoalTombstone>, GoalTombstoneDataAccessError> {
+    let sql = "
+        SELECT * FROM goal_tombstones
+        WHERE user_id = $1 AND version > $2 AND version <= $3
+        ORDER BY version
+        LIMIT $4";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| GoalTombstoneDataAccessError::DatabaseError(err.to_string()))?;
+
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| GoalTombstoneDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&user_id, &after_version, &up_to_version, &limit])
+        .await
+        .map_err(|err| GoalTombstoneDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut tombstones = Vec::new();
+    for row in rows.iter() {
+        tombstones.push(row_to_goal_tombstone(row)?);
+    }
+
+    Ok(tombstones)
+}
+
+// Returns the number of tombstones deleted
+pub async fn delete_goal_tombstones_before(
+    client: &Client,
+    retention_seconds: f64,
+) -> Result<u64, GoalTombstoneDataAccessError> {
+    let sql = "DELETE FROM goal_tombstones WHERE deleted_at < now() - make_interval(secs => $1)";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| GoalTombstoneDataAccessError::DatabaseError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&retention_seconds])
+        .await
+        .map_err(|err| GoalTombstoneDataAccessError::DatabaseError(err.to_string()))
+}
@@ -0,0 +1,294 @@
+use std::fmt::{self, Display};
+
+use chrono::NaiveDate;
+use tokio_postgres::{Client, Row};
+use uuid::Uuid;
+
+use crate::entities::habit::Habit;
+
+pub enum HabitDataAccessError {
+    DatabaseError(String),
+    MappingError(String),
+    ParameterError(String),
+}
+
+impl Display for HabitDataAccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HabitDataAccessError::DatabaseError(err) => write!(f, "{}", err),
+            HabitDataAccessError::MappingError(err) => write!(f, "{}", err),
+            HabitDataAccessError::ParameterError(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+fn row_to_habit(row: &Row) -> Result<Habit, HabitDataAccessError> {
+    let goal_id = row
+        .try_get::<_, Uuid>("goal_id")
+        .unwrap_or_default()
+        .to_string();
+    let recurrence = row.try_get::<_, String>("recurrence").unwrap_or_default();
+    let interval_days = row
+        .try_get::<_, Option<i32>>("interval_days")
+        .unwrap_or_default();
+    let times_per_week = row
+        .try_get::<_, Option<i32>>("times_per_week")
+        .unwrap_or_default();
+    let start_date = row
+        .try_get::<_, NaiveDate>("start_date")
+        .unwrap_or_default();
+
+    Habit::from_db_fields(
+        &goal_id,
+        &recurrence,
+        interval_days,
+        times_per_week,
+        start_date,
+    )
+    .map_err(|err| HabitDataAccessError::MappingError(err.to_string()))
+}
+
+// Creates the habit of the goal or replaces its recurrence rule
+pub async fn set_habit(client: &Client, habit: &Habit) -> Result<(), HabitDataAccessError> {
+    let sql = "
+        INSERT INTO goal_habits
+            (goal_id, recurrence, interval_days, times_per_week, start_date)
+        VALUES
+            ($1, $2, $3, $4, $5)
+        ON CONFLICT (goal_id) DO UPDATE SET
+            recurrence = EXCLUDED.recurrence,
+            interval_days = EXCLUDED.interval_days,
+            times_per_week = EXCLUDED.times_per_week,
+            start_date = EXCLUDED.start_date";
+
+    let goal_id = Uuid::parse_str(&habit.get_goal_id())
+        .map_err(|err| HabitDataAccessError::ParameterError(err.to_string()))?;
+    let recurrence = habit.get_recurrence();
+    let interval_days = habit.get_interval_days();
+    let times_per_week = habit.get_times_per_week();
+    let start_date = habit.get_start_date();
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| HabitDataAccessError::DatabaseError(err.to_string()))?;
+
+    client
+        .execute(
+            &stm,
+            &[
+                &goal_id,
+                &recurrence,
+                &interval_days,
+                &times_per_week,
+                &start_date,
+            ],
+        )
+        .await
+        .map_err(|err| HabitDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+pub async fn find_habit_by_goal_id(
+    client: &Client,
+    goal_id: &str,
+) -> Result<Option<Habit>, HabitDataAccessError> {
+    let sql = "SELECT * FROM goal_habits WHERE goal_id = $1";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| HabitDataAccessError::DatabaseError(err.to_string()))?;
+
+    let goal_id = Uuid::parse_str(goal_id)
+        .map_err(|err| HabitDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&goal_id])
+        .await
+        .map_err(|err| HabitDataAccessError::DatabaseError(err.to_string()))?;
+
+    if rows.is_empty() {
+        return Ok(None);
+    }
+
+    let habit = row_to_habit(&rows[0])?;
+
+    Ok(Some(habit))
+}
+
+// The check-ins of the goal are deleted with the habit
+pub async fn delete_habit(client: &Client, goal_id: &str) -> Result<u64, HabitDataAccessError> {
+    let sql = "DELETE FROM goal_habits WHERE goal_id = $1";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| HabitDataAccessError::DatabaseError(err.to_string()))?;
+
+    let goal_id = Uuid::parse_str(goal_id)
+        .map_err(|err| HabitDataAccessError::ParameterError(err.to_string()))?;
+
+    let count = client
+        .execute(&stm, &[&goal_id])
+        .await
+        .map_err(|err| HabitDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(count)
+}
+
+// A single check-in per day, checking in twice the same day changes nothing
+pub async fn add_check_in(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+    date: &NaiveDate,
+) -> Result<(), HabitDataAccessError> {
+    let sql = "
+        INSERT INTO goal_check_ins
+            (goal_id, check_date, user_id)
+        VALUES
+            ($1, $2, $3)
+        ON CONFLICT (goal_id, check_date) DO NOTHING";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| HabitDataAccessError::DatabaseError(err.to_string()))?;
+
+    let goal_id = Uuid::parse_str(goal_id)
+        .map_err(|err| HabitDataAccessError::ParameterError(err.to_string()))?;
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| HabitDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&goal_id, date, &user_id])
+        .await
+        .map_err(|err| HabitDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+pub async fn delete_check_in(
+    client: &Client,
+    goal_id: &str,
+    date: &NaiveDate,
+) -> Result<u64, HabitDataAccessError> {
+    let sql = "DELETE FROM goal_check_ins WHERE goal_id = $1 AND check_date = $2";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| HabitDataAccessError::DatabaseError(err.to_string()))?;
+
+    let goal_id = Uuid::parse_str(goal_id)
+        .map_err(|err| HabitDataAccessError::ParameterError(err.to_string()))?;
+
+    let count = client
+        .execute(&stm, &[&goal_id, date])
+        .await
+        .map_err(|err| HabitDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(count)
+}
+
+// Oldest first
+pub async fn find_check_in_dates(
+    client: &Client,
+    goal_id: &str,
+) -> Result<Vec<NaiveDate>, HabitDataAccessError> {
+    let sql = "SELECT check_date FROM goal_check_ins WHERE goal_id = $1 ORDER BY check_date";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| HabitDataAccessError::DatabaseError(err.to_string()))?;
+
+    let goal_id = Uuid::parse_str(goal_id)
+        .map_err(|err| HabitDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&goal_id])
+        .await
+        .map_err(|err| HabitDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut dates = Vec::new();
+    for row in rows.iter() {
+        let date = row
+            .try_get::<_, NaiveDate>("check_date")
+            .map_err(|err| HabitDataAccessError::MappingError(err.to_string()))?;
+        dates.push(date);
+    }
+
+    Ok(dates)
+}
+
+// Habits of every personal goal of the user, archived ones included, for the export
+pub async fn find_habits_of_personal_goals(
+    client: &Client,
+    user_id: &str,
+) -> Result<Vec<Habit>, HabitDataAccessError> {
+    let sql = "
+        SELECT h.* FROM goal_habits h
+        INNER JOIN goals g ON g.id = h.goal_id
+        WHERE g.user_id = $1 AND g.organization_id IS NULL AND g.deleted_at IS NULL";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| HabitDataAccessError::DatabaseError(err.to_string()))?;
+
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| HabitDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&user_id])
+        .await
+        .map_err(|err| HabitDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut habits = Vec::new();
+    for row in rows.iter() {
+        habits.push(row_to_habit(row)?);
+    }
+
+    Ok(habits)
+}
+
+// Check-ins of the habits of every personal goal of the user with the id of their goal, in date order
+pub async fn find_check_ins_of_personal_goals(
+    client: &Client,
+    user_id: &str,
+) -> Result<Vec<(String, NaiveDate)>, HabitDataAccessError> {
+    let sql = "
+        SELECT c.goal_id, c.check_date FROM goal_check_ins c
+        INNER JOIN goals g ON g.id = c.goal_id
+        WHERE g.user_id = $1 AND g.organization_id IS NULL AND g.deleted_at IS NULL
+        ORDER BY c.goal_id, c.check_date";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| HabitDataAccessError::DatabaseError(err.to_string()))?;
+
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| HabitDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&user_id])
+        .await
+        .map_err(|err| HabitDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut check_ins = Vec::new();
+    for row in rows.iter() {
+        let goal_id = row
+            .try_get::<_, Uuid>("goal_id")
+            .map_err(|err| HabitDataAccessError::MappingError(err.to_string()))?;
+        let date = row
+            .try_get::<_, NaiveDate>("check_date")
+            .map_err(|err| HabitDataAccessError::MappingError(err.to_string()))?;
+        check_ins.push((goal_id.to_string(), date));
+    }
+
+    Ok(check_ins)
+}
@@ -0,0 +1,152 @@
+use std::fmt::{self, Display};
+
+use chrono::{DateTime, Utc};
+use tokio_postgres::{Client, Row};
+use uuid::Uuid;
+
+use crate::entities::invitation::Invitation;
+
+pub const PENDING_STATUS: &str = "pending";
+pub const ACCEPTED_STATUS: &str = "accepted";
+pub const DECLINED_STATUS: &str = "declined";
+
+pub enum InvitationDataAccessError {
+    DatabaseError(String),
+    MappingError(String),
+    ParameterError(String),
+}
+
+impl Display for InvitationDataAccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvitationDataAccessError::DatabaseError(err) => write!(f, "{}", err),
+            InvitationDataAccessError::MappingError(err) => write!(f, "{}", err),
+            InvitationDataAccessError::ParameterError(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+fn row_to_invitation(row: &Row) -> Result<Invitation, InvitationDataAccessError> {
+    let id = row.try_get::<_, Uuid>("id").unwrap_or_default().to_string();
+    let organization_id = row
+        .try_get::<_, Uuid>("organization_id")
+        .unwrap_or_default()
+        .to_string();
+    let email = row.try_get::<_, String>("email").unwrap_or_default();
+    let role = row.try_get::<_, String>("role").unwrap_or_default();
+    let token_hash = row.try_get::<_, String>("token_hash").unwrap_or_default();
+    let expires_at = row
+        .try_get::<_, DateTime<Utc>>("expires_at")
+        .unwrap_or_default();
+
+    Invitation::from_db_fields(
+        &id,
+        &organization_id,
+        &email,
+        &role,
+        &token_hash,
+        expires_at,
+    )
+    .map_err(|err| InvitationDataAccessError::MappingError(err.to_string()))
+}
+
+// Returns the id generated by the database
+pub async fn add_invitation(
+    client: &Client,
+    invitation: &Invitation,
+    invited_by: &str,
+) -> Result<String, InvitationDataAccessError> {
+    let sql = "
+        INSERT INTO organization_invitations
+            (organization_id, email, role, token_hash, expires_at, invited_by, status)
+        VALUES
+            ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING id";
+
+    let organization_id = Uuid::parse_str(&invitation.get_organization_id())
+        .map_err(|err| InvitationDataAccessError::ParameterError(err.to_string()))?;
+    let invited_by = Uuid::parse_str(invited_by)
+        .map_err(|err| InvitationDataAccessError::ParameterError(err.to_string()))?;
+    let email = invitation.get_email();
+    let role = invitation.get_role();
+    let token_hash = invitation.get_token_hash();
+    let expires_at = invitation.get_expires_at();
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| InvitationDataAccessError::DatabaseError(err.to_string()))?;
+
+    let row = client
+        .query_one(
+            &stm,
+            &[
+                &organization_id,
+                &email,
+                &role,
+                &token_hash,
+                &expires_at,
+                &invited_by,
+                &PENDING_STATUS,
+            ],
+        )
+        .await
+        .map_err(|err| InvitationDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = row.try_get::<_, Uuid>("id").unwrap_or_default().to_string();
+
+    Ok(id)
+}
+
+// Only pending invitations are returned, accepted and declined ones cannot be used again
+pub async fn find_pending_invitation_by_token_hash(
+    client: &Client,
+    token_hash: &str,
+) -> Result<Option<Invitation>, InvitationDataAccessError> {
+    let sql = "
+        SELECT * FROM organization_invitations
+        WHERE token_hash = $1 AND status = $2";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| InvitationDataAccessError::DatabaseError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&token_hash, &PENDING_STATUS])
+        .await
+        .map_err(|err| InvitationDataAccessError::DatabaseError(err.to_string()))?;
+
+    if rows.is_empty() {
+        return Ok(None);
+    }
+
+    let invitation = row_to_invitation(&rows[0])?;
+
+    Ok(Some(invitation))
+}
+
+pub async fn update_invitation_status(
+    client: &Client,
+    id: &str,
+    status: &str,
+) -> Result<(), InvitationDataAccessError> {
+    let sql = "
+        UPDATE organization_invitations SET status = $2, responded_at = now()
+        WHERE id = $1";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| InvitationDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = Uuid::parse_str(id)
+        .map_err(|err| InvitationDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&id, &status])
+        .await
+        .map_err(|err| InvitationDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
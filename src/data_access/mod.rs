@@ -1,2 +1,19 @@
 pub mod user_data_access;
 pub mod goal_data_access;
+pub mod api_key_data_access;
+pub mod organization_data_access;
+pub mod invitation_data_access;
+pub mod goal_share_data_access;
+pub mod share_link_data_access;
+pub mod comment_data_access;
+pub mod note_data_access;
+pub mod habit_data_access;
+pub mod reminder_data_access;
+pub mod notification_data_access;
+pub mod webhook_data_access;
+pub mod goal_event_data_access;
+pub mod channel_presence_data_access;
+pub mod goal_tombstone_data_access;
+pub mod goal_revision_data_access;
+pub mod audit_event_data_access;
+pub mod calendar_feed_data_access;
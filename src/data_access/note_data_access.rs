@@ -0,0 +1,211 @@
+use std::fmt::{self, Display};
+
+use chrono::{DateTime, Utc};
+use tokio_postgres::{Client, Row};
+use uuid::Uuid;
+
+use crate::entities::note::Note;
+
+pub enum NoteDataAccessError {
+    DatabaseError(String),
+    MappingError(String),
+    ParameterError(String),
+}
+
+impl Display for NoteDataAccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NoteDataAccessError::DatabaseError(err) => write!(f, "{}", err),
+            NoteDataAccessError::MappingError(err) => write!(f, "{}", err),
+            NoteDataAccessError::ParameterError(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+fn row_to_note(row: &Row) -> Result<Note, NoteDataAccessError> {
+    let id = row.try_get::<_, Uuid>("id").unwrap_or_default().to_string();
+    let goal_id = row
+        .try_get::<_, Uuid>("goal_id")
+        .unwrap_or_default()
+        .to_string();
+    let user_id = row
+        .try_get::<_, Uuid>("user_id")
+        .unwrap_or_default()
+        .to_string();
+    let text = row.try_get::<_, String>("text").unwrap_or_default();
+    let created_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("created_at")
+        .unwrap_or_default();
+    let updated_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("updated_at")
+        .unwrap_or_default();
+
+    Note::from_db_fields(&id, &goal_id, &user_id, &text, created_at, updated_at)
+        .map_err(|err| NoteDataAccessError::MappingError(err.to_string()))
+}
+
+// Returns the id generated by the database
+pub async fn add_note(client: &Client, note: &Note) -> Result<String, NoteDataAccessError> {
+    let sql = "
+        INSERT INTO goal_notes
+            (goal_id, user_id, text)
+        VALUES
+            ($1, $2, $3)
+        RETURNING id";
+
+    let goal_id = Uuid::parse_str(&note.get_goal_id())
+        .map_err(|err| NoteDataAccessError::ParameterError(err.to_string()))?;
+    let user_id = Uuid::parse_str(&note.get_user_id())
+        .map_err(|err| NoteDataAccessError::ParameterError(err.to_string()))?;
+    let text = note.get_text();
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| NoteDataAccessError::DatabaseError(err.to_string()))?;
+
+    let row = client
+        .query_one(&stm, &[&goal_id, &user_id, &text])
+        .await
+        .map_err(|err| NoteDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = row.try_get::<_, Uuid>("id").unwrap_or_default().to_string();
+
+    Ok(id)
+}
+
+pub async fn find_note_by_id(
+    client: &Client,
+    id: &str,
+) -> Result<Option<Note>, NoteDataAccessError> {
+    let sql = "SELECT * FROM goal_notes WHERE id = $1";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| NoteDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id =
+        Uuid::parse_str(id).map_err(|err| NoteDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&id])
+        .await
+        .map_err(|err| NoteDataAccessError::DatabaseError(err.to_string()))?;
+
+    if rows.is_empty() {
+        return Ok(None);
+    }
+
+    let note = row_to_note(&rows[0])?;
+
+    Ok(Some(note))
+}
+
+// Oldest first, so the notes read as a journal
+pub async fn find_notes(
+    client: &Client,
+    goal_id: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<Note>, NoteDataAccessError> {
+    let sql = "
+        SELECT * FROM goal_notes
+        WHERE goal_id = $1
+        ORDER BY created_at, id
+        LIMIT $2 OFFSET $3";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| NoteDataAccessError::DatabaseError(err.to_string()))?;
+
+    let goal_id = Uuid::parse_str(goal_id)
+        .map_err(|err| NoteDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&goal_id, &limit, &offset])
+        .await
+        .map_err(|err| NoteDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut notes = Vec::new();
+    for row in rows.iter() {
+        notes.push(row_to_note(row)?);
+    }
+
+    Ok(notes)
+}
+
+// Notes of every personal goal of the user, archived ones included, for the export
+pub async fn find_notes_of_personal_goals(
+    client: &Client,
+    user_id: &str,
+) -> Result<Vec<Note>, NoteDataAccessError> {
+    let sql = "
+        SELECT n.* FROM goal_notes n
+        INNER JOIN goals g ON g.id = n.goal_id
+        WHERE g.user_id = $1 AND g.organization_id IS NULL AND g.deleted_at IS NULL
+        ORDER BY n.goal_id, n.created_at, n.id";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| NoteDataAccessError::DatabaseError(err.to_string()))?;
+
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| NoteDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&user_id])
+        .await
+        .map_err(|err| NoteDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut notes = Vec::new();
+    for row in rows.iter() {
+        notes.push(row_to_note(row)?);
+    }
+
+    Ok(notes)
+}
+
+pub async fn update_note_text(
+    client: &Client,
+    id: &str,
+    text: &str,
+) -> Result<(), NoteDataAccessError> {
+    let sql = "UPDATE goal_notes SET text = $2, updated_at = now() WHERE id = $1";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| NoteDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id =
+        Uuid::parse_str(id).map_err(|err| NoteDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&id, &text])
+        .await
+        .map_err(|err| NoteDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+pub async fn delete_note(client: &Client, id: &str) -> Result<(), NoteDataAccessError> {
+    let sql = "DELETE FROM goal_notes WHERE id = $1";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| NoteDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id =
+        Uuid::parse_str(id).map_err(|err| NoteDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&id])
+        .await
+        .map_err(|err| NoteDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
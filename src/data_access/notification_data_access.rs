@@ -0,0 +1,242 @@
+use std::fmt::{self, Display};
+
+use chrono::{DateTime, Utc};
+use tokio_postgres::{Client, Row};
+use uuid::Uuid;
+
+use crate::entities::notification::Notification;
+
+pub enum NotificationDataAccessError {
+    DatabaseError(String),
+    MappingError(String),
+    ParameterError(String),
+}
+
+impl Display for NotificationDataAccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotificationDataAccessError::DatabaseError(err) => write!(f, "{}", err),
+            NotificationDataAccessError::MappingError(err) => write!(f, "{}", err),
+            NotificationDataAccessError::ParameterError(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+fn row_to_notification(row: &Row) -> Result<Notification, NotificationDataAccessError> {
+    let id = row.try_get::<_, Uuid>("id").unwrap_or_default().to_string();
+    let user_id = row
+        .try_get::<_, Uuid>("user_id")
+        .unwrap_or_default()
+        .to_string();
+    let kind = row.try_get::<_, String>("kind").unwrap_or_default();
+    let goal_id = row
+        .try_get::<_, Option<Uuid>>("goal_id")
+        .unwrap_or_default()
+        .map(|id| id.to_string());
+    let actor_id = row
+        .try_get::<_, Option<Uuid>>("actor_id")
+        .unwrap_or_default()
+        .map(|id| id.to_string());
+    let text = row.try_get::<_, String>("text").unwrap_or_default();
+    let read_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("read_at")
+        .unwrap_or_default();
+    let created_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("created_at")
+        .unwrap_or_default();
+
+    Notification::from_db_fields(
+        &id, &user_id, &kind, goal_id, actor_id, &text, read_at, created_at,
+    )
+    .map_err(|err| NotificationDataAccessError::MappingError(err.to_string()))
+}
+
+fn parse_optional_uuid(id: Option<String>) -> Result<Option<Uuid>, NotificationDataAccessError> {
+    match id {
+        None => Ok(None),
+        Some(id) => Uuid::parse_str(&id)
+            .map(Some)
+            .map_err(|err| NotificationDataAccessError::ParameterError(err.to_string())),
+    }
+}
+
+// Returns the id generated by the database
+pub async fn add_notification(
+    client: &Client,
+    notification: &Notification,
+) -> Result<String, NotificationDataAccessError> {
+    let sql = "
+        INSERT INTO notifications
+            (user_id, kind, goal_id, actor_id, text)
+        VALUES
+            ($1, $2, $3, $4, $5)
+        RETURNING id";
+
+    let user_id = Uuid::parse_str(&notification.get_user_id())
+        .map_err(|err| NotificationDataAccessError::ParameterError(err.to_string()))?;
+    let kind = notification.get_kind();
+    let goal_id = parse_optional_uuid(notification.get_goal_id())?;
+    let actor_id = parse_optional_uuid(notification.get_actor_id())?;
+    let text = notification.get_text();
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| NotificationDataAccessError::DatabaseError(err.to_string()))?;
+
+    let row = client
+        .query_one(&stm, &[&user_id, &kind, &goal_id, &actor_id, &text])
+        .await
+        .map_err(|err| NotificationDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = row.try_get::<_, Uuid>("id").unwrap_or_default().to_string();
+
+    Ok(id)
+}
+
+// Newest first, starting after the cursor (the created_at and id of the last notification of the
+// previous page)
+pub async fn find_notifications(
+    client: &Client,
+    user_id: &str,
+    unread_only: bool,
+    cursor: Option<(DateTime<Utc>, String)>,
+    limit: i64,
+) -> Result<Vec<Notification>, NotificationDataAccessError> {
+    let sql = "
+        SELECT * FROM notifications
+        WHERE user_id = $1
+            AND (NOT $2 OR read_at IS NULL)
+            AND ($3::timestamptz IS NULL OR (created_at, id) < ($3, $4))
+        ORDER BY created_at DESC, id DESC
+        LIMIT $5";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| NotificationDataAccessError::DatabaseError(err.to_string()))?;
+
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| NotificationDataAccessError::ParameterError(err.to_string()))?;
+    let (cursor_created_at, cursor_id) = match cursor {
+        None => (None, None),
+        Some((created_at, id)) => (Some(created_at), parse_optional_uuid(Some(id))?),
+    };
+
+    let rows = client
+        .query(
+            &stm,
+            &[
+                &user_id,
+                &unread_only,
+                &cursor_created_at,
+                &cursor_id,
+                &limit,
+            ],
+        )
+        .await
+        .map_err(|err| NotificationDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut notifications = Vec::new();
+    for row in rows.iter() {
+        notifications.push(row_to_notification(row)?);
+    }
+
+    Ok(notifications)
+}
+
+pub async fn count_unread_notifications(
+    client: &Client,
+    user_id: &str,
+) -> Result<i64, NotificationDataAccessError> {
+    let sql = "SELECT COUNT(*) AS count FROM notifications WHERE user_id = $1 AND read_at IS NULL";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| NotificationDataAccessError::DatabaseError(err.to_string()))?;
+
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| NotificationDataAccessError::ParameterError(err.to_string()))?;
+
+    let row = client
+        .query_one(&stm, &[&user_id])
+        .await
+        .map_err(|err| NotificationDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(row.try_get::<_, i64>("count").unwrap_or_default())
+}
+
+// Returns the number of notifications updated, 0 when the notification is not found for the user.
+// A notification already read keeps its read date
+pub async fn set_notification_read(
+    client: &Client,
+    id: &str,
+    user_id: &str,
+    read: bool,
+) -> Result<u64, NotificationDataAccessError> {
+    let sql = "
+        UPDATE notifications
+        SET read_at = CASE WHEN $3 THEN COALESCE(read_at, now()) ELSE NULL END
+        WHERE id = $1 AND user_id = $2";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| NotificationDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = Uuid::parse_str(id)
+        .map_err(|err| NotificationDataAccessError::ParameterError(err.to_string()))?;
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| NotificationDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&id, &user_id, &read])
+        .await
+        .map_err(|err| NotificationDataAccessError::DatabaseError(err.to_string()))
+}
+
+// Returns the number of notifications marked as read
+pub async fn mark_all_notifications_read(
+    client: &Client,
+    user_id: &str,
+) -> Result<u64, NotificationDataAccessError> {
+    let sql = "UPDATE notifications SET read_at = now() WHERE user_id = $1 AND read_at IS NULL";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| NotificationDataAccessError::DatabaseError(err.to_string()))?;
+
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| NotificationDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&user_id])
+        .await
+        .map_err(|err| NotificationDataAccessError::DatabaseError(err.to_string()))
+}
+
+// Returns the number of notifications deleted, 0 when the notification is not found for the user
+pub async fn delete_notification(
+    client: &Client,
+    id: &str,
+    user_id: &str,
+) -> Result<u64, NotificationDataAccessError> {
+    let sql = "DELETE FROM notifications WHERE id = $1 AND user_id = $2";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| NotificationDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = Uuid::parse_str(id)
+        .map_err(|err| NotificationDataAccessError::ParameterError(err.to_string()))?;
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| NotificationDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&id, &user_id])
+        .await
+        .map_err(|err| NotificationDataAccessError::DatabaseError(err.to_string()))
+}
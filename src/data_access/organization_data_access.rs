@@ -0,0 +1,296 @@
+use std::fmt::{self, Display};
+
+use tokio_postgres::{Client, Row};
+use uuid::Uuid;
+
+use crate::entities::organization::{Organization, OrganizationMember, OWNER_ROLE};
+
+pub enum OrganizationDataAccessError {
+    DatabaseError(String),
+    MappingError(String),
+    ParameterError(String),
+}
+
+impl Display for OrganizationDataAccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrganizationDataAccessError::DatabaseError(err) => write!(f, "{}", err),
+            OrganizationDataAccessError::MappingError(err) => write!(f, "{}", err),
+            OrganizationDataAccessError::ParameterError(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+fn row_to_member(row: &Row) -> Result<OrganizationMember, OrganizationDataAccessError> {
+    let organization_id = row
+        .try_get::<_, Uuid>("organization_id")
+        .unwrap_or_default()
+        .to_string();
+    let user_id = row
+        .try_get::<_, Uuid>("user_id")
+        .unwrap_or_default()
+        .to_string();
+    let role = row.try_get::<_, String>("role").unwrap_or_default();
+
+    OrganizationMember::from_db_fields(&organization_id, &user_id, &role)
+        .map_err(|err| OrganizationDataAccessError::MappingError(err.to_string()))
+}
+
+fn parse_id(id: &str) -> Result<Uuid, OrganizationDataAccessError> {
+    Uuid::parse_str(id).map_err(|err| OrganizationDataAccessError::ParameterError(err.to_string()))
+}
+
+// Creates the organization and makes the user its owner in the same statement. Returns the id
+// generated by the database
+pub async fn add_organization(
+    client: &Client,
+    organization: &Organization,
+    owner_id: &str,
+) -> Result<String, OrganizationDataAccessError> {
+    let sql = "
+        WITH new_organization AS (
+            INSERT INTO organizations (name) VALUES ($1) RETURNING id
+        )
+        INSERT INTO organization_members
+            (organization_id, user_id, role)
+        SELECT id, $2, $3 FROM new_organization
+        RETURNING organization_id";
+
+    let name = organization.get_name();
+    let owner_id = parse_id(owner_id)?;
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| OrganizationDataAccessError::DatabaseError(err.to_string()))?;
+
+    let row = client
+        .query_one(&stm, &[&name, &owner_id, &OWNER_ROLE])
+        .await
+        .map_err(|err| OrganizationDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = row
+        .try_get::<_, Uuid>("organization_id")
+        .unwrap_or_default()
+        .to_string();
+
+    Ok(id)
+}
+
+// Organizations the user is a member of, with the membership of the user in each of them
+pub async fn find_organizations_by_user(
+    client: &Client,
+    user_id: &str,
+) -> Result<Vec<(Organization, OrganizationMember)>, OrganizationDataAccessError> {
+    let sql = "
+        SELECT o.id, o.name, m.organization_id, m.user_id, m.role
+        FROM organizations o
+        INNER JOIN organization_members m ON m.organization_id = o.id
+        WHERE m.user_id = $1
+        ORDER BY o.name";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| OrganizationDataAccessError::DatabaseError(err.to_string()))?;
+
+    let user_id = parse_id(user_id)?;
+
+    let rows = client
+        .query(&stm, &[&user_id])
+        .await
+        .map_err(|err| OrganizationDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut organizations = Vec::new();
+    for row in rows.iter() {
+        let id = row.try_get::<_, Uuid>("id").unwrap_or_default().to_string();
+        let name = row.try_get::<_, String>("name").unwrap_or_default();
+        let organization = Organization::from_db_fields(&id, &name)
+            .map_err(|err| OrganizationDataAccessError::MappingError(err.to_string()))?;
+        organizations.push((organization, row_to_member(row)?));
+    }
+
+    Ok(organizations)
+}
+
+pub async fn find_organization_member(
+    client: &Client,
+    organization_id: &str,
+    user_id: &str,
+) -> Result<Option<OrganizationMember>, OrganizationDataAccessError> {
+    let sql = "
+        SELECT * FROM organization_members
+        WHERE organization_id = $1 AND user_id = $2";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| OrganizationDataAccessError::DatabaseError(err.to_string()))?;
+
+    let organization_id = parse_id(organization_id)?;
+    let user_id = parse_id(user_id)?;
+
+    let rows = client
+        .query(&stm, &[&organization_id, &user_id])
+        .await
+        .map_err(|err| OrganizationDataAccessError::DatabaseError(err.to_string()))?;
+
+    if rows.is_empty() {
+        return Ok(None);
+    }
+
+    let member = row_to_member(&rows[0])?;
+
+    Ok(Some(member))
+}
+
+pub async fn find_organization_members(
+    client: &Client,
+    organization_id: &str,
+) -> Result<Vec<OrganizationMember>, OrganizationDataAccessError> {
+    let sql = "
+        SELECT * FROM organization_members
+        WHERE organization_id = $1
+        ORDER BY created_at";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| OrganizationDataAccessError::DatabaseError(err.to_string()))?;
+
+    let organization_id = parse_id(organization_id)?;
+
+    let rows = client
+        .query(&stm, &[&organization_id])
+        .await
+        .map_err(|err| OrganizationDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut members = Vec::new();
+    for row in rows.iter() {
+        members.push(row_to_member(row)?);
+    }
+
+    Ok(members)
+}
+
+// Does nothing when the user is already a member of the organization. The versions of the goals of
+// the organization change, so the new member receives them on the next sync
+pub async fn add_organization_member(
+    client: &Client,
+    member: &OrganizationMember,
+) -> Result<(), OrganizationDataAccessError> {
+    let sql = "
+        WITH added AS (
+            INSERT INTO organization_members
+                (organization_id, user_id, role)
+            VALUES
+                ($1, $2, $3)
+            ON CONFLICT (organization_id, user_id) DO NOTHING
+            RETURNING organization_id, user_id
+        ), tombstones AS (
+            DELETE FROM goal_tombstones t USING added, goals g
+            WHERE g.organization_id = added.organization_id
+                AND t.goal_id = g.id AND t.user_id = added.user_id
+        )
+        UPDATE goals SET version = next_goal_version()
+        WHERE organization_id IN (SELECT organization_id FROM added)";
+
+    let organization_id = parse_id(&member.get_organization_id())?;
+    let user_id = parse_id(&member.get_user_id())?;
+    let role = member.get_role();
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| OrganizationDataAccessError::DatabaseError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&organization_id, &user_id, &role])
+        .await
+        .map_err(|err| OrganizationDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+pub async fn update_organization_member_role(
+    client: &Client,
+    organization_id: &str,
+    user_id: &str,
+    role: &str,
+) -> Result<(), OrganizationDataAccessError> {
+    let sql = "
+        UPDATE organization_members SET role = $3
+        WHERE organization_id = $1 AND user_id = $2";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| OrganizationDataAccessError::DatabaseError(err.to_string()))?;
+
+    let organization_id = parse_id(organization_id)?;
+    let user_id = parse_id(user_id)?;
+
+    client
+        .execute(&stm, &[&organization_id, &user_id, &role])
+        .await
+        .map_err(|err| OrganizationDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+// The member gets tombstones of the goals of the organization, so synced clients remove them
+pub async fn delete_organization_member(
+    client: &Client,
+    organization_id: &str,
+    user_id: &str,
+) -> Result<(), OrganizationDataAccessError> {
+    let sql = "
+        WITH deleted AS (
+            DELETE FROM organization_members
+            WHERE organization_id = $1 AND user_id = $2
+            RETURNING organization_id, user_id
+        )
+        INSERT INTO goal_tombstones (goal_id, user_id, version)
+        SELECT g.id, deleted.user_id, next_goal_version()
+        FROM deleted INNER JOIN goals g ON g.organization_id = deleted.organization_id
+        ON CONFLICT (goal_id, user_id) DO UPDATE
+            SET version = EXCLUDED.version, deleted_at = now()";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| OrganizationDataAccessError::DatabaseError(err.to_string()))?;
+
+    let organization_id = parse_id(organization_id)?;
+    let user_id = parse_id(user_id)?;
+
+    client
+        .execute(&stm, &[&organization_id, &user_id])
+        .await
+        .map_err(|err| OrganizationDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+pub async fn count_organization_owners(
+    client: &Client,
+    organization_id: &str,
+) -> Result<i64, OrganizationDataAccessError> {
+    let sql = "
+        SELECT COUNT(*) AS count FROM organization_members
+        WHERE organization_id = $1 AND role = $2";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| OrganizationDataAccessError::DatabaseError(err.to_string()))?;
+
+    let organization_id = parse_id(organization_id)?;
+
+    let row = client
+        .query_one(&stm, &[&organization_id, &OWNER_ROLE])
+        .await
+        .map_err(|err| OrganizationDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(row.try_get::<_, i64>("count").unwrap_or_default())
+}
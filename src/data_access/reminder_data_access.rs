@@ -0,0 +1,409 @@
+use std::fmt::{self, Display};
+
+use chrono::{DateTime, Utc};
+use tokio_postgres::{Client, Row};
+use uuid::Uuid;
+
+use crate::entities::reminder::{
+    Reminder, ReminderNotification, FAILED_STATUS, PENDING_STATUS, SENT_STATUS,
+};
+
+pub enum ReminderDataAccessError {
+    DatabaseError(String),
+    MappingError(String),
+    ParameterError(String),
+}
+
+impl Display for ReminderDataAccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReminderDataAccessError::DatabaseError(err) => write!(f, "{}", err),
+            ReminderDataAccessError::MappingError(err) => write!(f, "{}", err),
+            ReminderDataAccessError::ParameterError(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+fn row_to_reminder(row: &Row) -> Result<Reminder, ReminderDataAccessError> {
+    let id = row.try_get::<_, Uuid>("id").unwrap_or_default().to_string();
+    let goal_id = row
+        .try_get::<_, Uuid>("goal_id")
+        .unwrap_or_default()
+        .to_string();
+    let user_id = row
+        .try_get::<_, Uuid>("user_id")
+        .unwrap_or_default()
+        .to_string();
+    let remind_at = row
+        .try_get::<_, DateTime<Utc>>("remind_at")
+        .unwrap_or_default();
+    let minutes_before_due = row
+        .try_get::<_, Option<i32>>("minutes_before_due")
+        .unwrap_or_default();
+    let timezone = row.try_get::<_, String>("timezone").unwrap_or_default();
+    let channel = row.try_get::<_, String>("channel").unwrap_or_default();
+    let webhook_url = row
+        .try_get::<_, Option<String>>("webhook_url")
+        .unwrap_or_default();
+    let status = row.try_get::<_, String>("status").unwrap_or_default();
+    let attempts = row.try_get::<_, i32>("attempts").unwrap_or_default();
+    let sent_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("sent_at")
+        .unwrap_or_default();
+    let last_error = row
+        .try_get::<_, Option<String>>("last_error")
+        .unwrap_or_default();
+
+    Reminder::from_db_fields(
+        &id,
+        &goal_id,
+        &user_id,
+        remind_at,
+        minutes_before_due,
+        &timezone,
+        &channel,
+        webhook_url,
+        &status,
+        attempts,
+        sent_at,
+        last_error,
+    )
+    .map_err(|err| ReminderDataAccessError::MappingError(err.to_string()))
+}
+
+// Returns the id generated by the database
+pub async fn add_reminder(
+    client: &Client,
+    reminder: &Reminder,
+) -> Result<String, ReminderDataAccessError> {
+    let sql = "
+        INSERT INTO goal_reminders
+            (goal_id, user_id, remind_at, minutes_before_due, timezone, channel, webhook_url,
+                status)
+        VALUES
+            ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING id";
+
+    let goal_id = Uuid::parse_str(&reminder.get_goal_id())
+        .map_err(|err| ReminderDataAccessError::ParameterError(err.to_string()))?;
+    let user_id = Uuid::parse_str(&reminder.get_user_id())
+        .map_err(|err| ReminderDataAccessError::ParameterError(err.to_string()))?;
+    let remind_at = reminder.get_remind_at();
+    let minutes_before_due = reminder.get_minutes_before_due();
+    let timezone = reminder.get_timezone();
+    let channel = reminder.get_channel();
+    let webhook_url = reminder.get_webhook_url();
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| ReminderDataAccessError::DatabaseError(err.to_string()))?;
+
+    let row = client
+        .query_one(
+            &stm,
+            &[
+                &goal_id,
+                &user_id,
+                &remind_at,
+                &minutes_before_due,
+                &timezone,
+                &channel,
+                &webhook_url,
+                &PENDING_STATUS,
+            ],
+        )
+        .await
+        .map_err(|err| ReminderDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = row.try_get::<_, Uuid>("id").unwrap_or_default().to_string();
+
+    Ok(id)
+}
+
+pub async fn find_reminder_by_id(
+    client: &Client,
+    id: &str,
+) -> Result<Option<Reminder>, ReminderDataAccessError> {
+    let sql = "SELECT * FROM goal_reminders WHERE id = $1";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| ReminderDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = Uuid::parse_str(id)
+        .map_err(|err| ReminderDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&id])
+        .await
+        .map_err(|err| ReminderDataAccessError::DatabaseError(err.to_string()))?;
+
+    if rows.is_empty() {
+        return Ok(None);
+    }
+
+    let reminder = row_to_reminder(&rows[0])?;
+
+    Ok(Some(reminder))
+}
+
+// Reminders of the user on the goal, soonest first
+pub async fn find_reminders(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<Vec<Reminder>, ReminderDataAccessError> {
+    let sql = "
+        SELECT * FROM goal_reminders
+        WHERE goal_id = $1 AND user_id = $2
+        ORDER BY remind_at";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| ReminderDataAccessError::DatabaseError(err.to_string()))?;
+
+    let goal_id = Uuid::parse_str(goal_id)
+        .map_err(|err| ReminderDataAccessError::ParameterError(err.to_string()))?;
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| ReminderDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&goal_id, &user_id])
+        .await
+        .map_err(|err| ReminderDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut reminders = Vec::new();
+    for row in rows.iter() {
+        reminders.push(row_to_reminder(row)?);
+    }
+
+    Ok(reminders)
+}
+
+// Reminders of the user on all of their goals, soonest first
+pub async fn find_user_reminders(
+    client: &Client,
+    user_id: &str,
+) -> Result<Vec<Reminder>, ReminderDataAccessError> {
+    let sql = "SELECT * FROM goal_reminders WHERE user_id = $1 ORDER BY remind_at";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| ReminderDataAccessError::DatabaseError(err.to_string()))?;
+
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| ReminderDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&user_id])
+        .await
+        .map_err(|err| ReminderDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut reminders = Vec::new();
+    for row in rows.iter() {
+        reminders.push(row_to_reminder(row)?);
+    }
+
+    Ok(reminders)
+}
+
+pub async fn delete_reminder(client: &Client, id: &str) -> Result<(), ReminderDataAccessError> {
+    let sql = "DELETE FROM goal_reminders WHERE id = $1";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| ReminderDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = Uuid::parse_str(id)
+        .map_err(|err| ReminderDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&id])
+        .await
+        .map_err(|err| ReminderDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+// Locks the due reminders for lock_seconds, so they are sent by a single instance. Rows locked by
+// another instance are skipped instead of waited for. A reminder that is not marked as sent or
+// failed before its lock expires (ex: the instance stopped) is claimed again
+pub async fn claim_due_reminders(
+    client: &Client,
+    limit: i64,
+    lock_seconds: f64,
+) -> Result<Vec<ReminderNotification>, ReminderDataAccessError> {
+    let sql = "
+        WITH due AS (
+            SELECT id FROM goal_reminders
+            WHERE status = $1 AND remind_at <= now()
+                AND (locked_until IS NULL OR locked_until <= now())
+                AND goal_id NOT IN (SELECT id FROM goals WHERE deleted_at IS NOT NULL)
+            ORDER BY remind_at
+            LIMIT $2
+            FOR UPDATE SKIP LOCKED
+        ), claimed AS (
+            UPDATE goal_reminders r SET locked_until = now() + make_interval(secs => $3)
+            FROM due WHERE r.id = due.id
+            RETURNING r.*
+        )
+        SELECT c.*, g.text AS goal_text, u.name AS user_name, u.email AS user_email
+        FROM claimed c
+        JOIN goals g ON g.id = c.goal_id
+        JOIN users u ON u.id = c.user_id";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| ReminderDataAccessError::DatabaseError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&PENDING_STATUS, &limit, &lock_seconds])
+        .await
+        .map_err(|err| ReminderDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut notifications = Vec::new();
+    for row in rows.iter() {
+        let reminder = row_to_reminder(row)?;
+        notifications.push(ReminderNotification {
+            reminder_id: reminder.get_id(),
+            goal_id: reminder.get_goal_id(),
+            goal_text: row.try_get::<_, String>("goal_text").unwrap_or_default(),
+            user_id: reminder.get_user_id(),
+            user_name: row.try_get::<_, String>("user_name").unwrap_or_default(),
+            user_email: row.try_get::<_, String>("user_email").unwrap_or_default(),
+            remind_at: reminder.get_local_remind_at(),
+            timezone: reminder.get_timezone(),
+            channel: reminder.get_channel(),
+            webhook_url: reminder.get_webhook_url(),
+            attempts: reminder.get_attempts(),
+        });
+    }
+
+    Ok(notifications)
+}
+
+pub async fn mark_reminder_sent(client: &Client, id: &str) -> Result<(), ReminderDataAccessError> {
+    let sql = "
+        UPDATE goal_reminders
+        SET status = $2, sent_at = now(), attempts = attempts + 1, locked_until = NULL
+        WHERE id = $1";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| ReminderDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = Uuid::parse_str(id)
+        .map_err(|err| ReminderDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&id, &SENT_STATUS])
+        .await
+        .map_err(|err| ReminderDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+// The reminder is tried again once retry_in_seconds have passed, until max_attempts is reached
+pub async fn mark_reminder_failed(
+    client: &Client,
+    id: &str,
+    error: &str,
+    max_attempts: i32,
+    retry_in_seconds: f64,
+) -> Result<(), ReminderDataAccessError> {
+    let sql = "
+        UPDATE goal_reminders
+        SET attempts = attempts + 1,
+            last_error = $2,
+            status = CASE WHEN attempts + 1 >= $3 THEN $4 ELSE status END,
+            locked_until = now() + make_interval(secs => $5)
+        WHERE id = $1";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| ReminderDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = Uuid::parse_str(id)
+        .map_err(|err| ReminderDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(
+            &stm,
+            &[
+                &id,
+                &error,
+                &max_attempts,
+                &FAILED_STATUS,
+                &retry_in_seconds,
+            ],
+        )
+        .await
+        .map_err(|err| ReminderDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+// The reminders relative to the due date of the goal follow it. The ones moved to the future are
+// sent again, even if they were sent or failed for the previous due date
+pub async fn reschedule_due_reminders(
+    client: &Client,
+    goal_id: &str,
+    due_at: DateTime<Utc>,
+) -> Result<u64, ReminderDataAccessError> {
+    let sql = "
+        WITH rescheduled AS (
+            SELECT id, $2 - make_interval(mins => minutes_before_due) AS remind_at
+            FROM goal_reminders
+            WHERE goal_id = $1 AND minutes_before_due IS NOT NULL
+        )
+        UPDATE goal_reminders r
+        SET remind_at = rescheduled.remind_at,
+            status = CASE WHEN rescheduled.remind_at > now() THEN $3 ELSE r.status END,
+            attempts = CASE WHEN rescheduled.remind_at > now() THEN 0 ELSE r.attempts END,
+            sent_at = CASE WHEN rescheduled.remind_at > now() THEN NULL ELSE r.sent_at END,
+            last_error = CASE WHEN rescheduled.remind_at > now() THEN NULL ELSE r.last_error END,
+            locked_until = NULL
+        FROM rescheduled WHERE r.id = rescheduled.id";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| ReminderDataAccessError::DatabaseError(err.to_string()))?;
+
+    let goal_id = Uuid::parse_str(goal_id)
+        .map_err(|err| ReminderDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&goal_id, &due_at, &PENDING_STATUS])
+        .await
+        .map_err(|err| ReminderDataAccessError::DatabaseError(err.to_string()))
+}
+
+// The reminders relative to the due date are removed with it
+pub async fn delete_due_reminders(
+    client: &Client,
+    goal_id: &str,
+) -> Result<u64, ReminderDataAccessError> {
+    let sql = "DELETE FROM goal_reminders WHERE goal_id = $1 AND minutes_before_due IS NOT NULL";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| ReminderDataAccessError::DatabaseError(err.to_string()))?;
+
+    let goal_id = Uuid::parse_str(goal_id)
+        .map_err(|err| ReminderDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&goal_id])
+        .await
+        .map_err(|err| ReminderDataAccessError::DatabaseError(err.to_string()))
+}
@@ -0,0 +1,161 @@
+use std::fmt::{self, Display};
+
+use chrono::{DateTime, Utc};
+use tokio_postgres::{Client, Row};
+use uuid::Uuid;
+
+use crate::entities::share_link::ShareLink;
+
+pub enum ShareLinkDataAccessError {
+    DatabaseError(String),
+    MappingError(String),
+    ParameterError(String),
+}
+
+impl Display for ShareLinkDataAccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShareLinkDataAccessError::DatabaseError(err) => write!(f, "{}", err),
+            ShareLinkDataAccessError::MappingError(err) => write!(f, "{}", err),
+            ShareLinkDataAccessError::ParameterError(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+fn row_to_share_link(row: &Row) -> Result<ShareLink, ShareLinkDataAccessError> {
+    let id = row.try_get::<_, Uuid>("id").unwrap_or_default().to_string();
+    let goal_id = row
+        .try_get::<_, Uuid>("goal_id")
+        .unwrap_or_default()
+        .to_string();
+    let created_by = row
+        .try_get::<_, Uuid>("created_by")
+        .unwrap_or_default()
+        .to_string();
+    let token_hash = row.try_get::<_, String>("token_hash").unwrap_or_default();
+    let expires_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("expires_at")
+        .unwrap_or_default();
+
+    ShareLink::from_db_fields(&id, &goal_id, &created_by, &token_hash, expires_at)
+        .map_err(|err| ShareLinkDataAccessError::MappingError(err.to_string()))
+}
+
+// Returns the id generated by the database
+pub async fn add_share_link(
+    client: &Client,
+    share_link: &ShareLink,
+) -> Result<String, ShareLinkDataAccessError> {
+    let sql = "
+        INSERT INTO share_links
+            (goal_id, created_by, token_hash, expires_at)
+        VALUES
+            ($1, $2, $3, $4)
+        RETURNING id";
+
+    let goal_id = Uuid::parse_str(&share_link.get_goal_id())
+        .map_err(|err| ShareLinkDataAccessError::ParameterError(err.to_string()))?;
+    let created_by = Uuid::parse_str(&share_link.get_created_by())
+        .map_err(|err| ShareLinkDataAccessError::ParameterError(err.to_string()))?;
+    let token_hash = share_link.get_token_hash();
+    let expires_at = share_link.get_expires_at();
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| ShareLinkDataAccessError::DatabaseError(err.to_string()))?;
+
+    let row = client
+        .query_one(&stm, &[&goal_id, &created_by, &token_hash, &expires_at])
+        .await
+        .map_err(|err| ShareLinkDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = row.try_get::<_, Uuid>("id").unwrap_or_default().to_string();
+
+    Ok(id)
+}
+
+// Revoked links are not returned
+pub async fn find_share_links(
+    client: &Client,
+    goal_id: &str,
+) -> Result<Vec<ShareLink>, ShareLinkDataAccessError> {
+    let sql = "
+        SELECT * FROM share_links
+        WHERE goal_id = $1 AND revoked_at IS NULL
+        ORDER BY created_at";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| ShareLinkDataAccessError::DatabaseError(err.to_string()))?;
+
+    let goal_id = Uuid::parse_str(goal_id)
+        .map_err(|err| ShareLinkDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&goal_id])
+        .await
+        .map_err(|err| ShareLinkDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut share_links = Vec::new();
+    for row in rows.iter() {
+        share_links.push(row_to_share_link(row)?);
+    }
+
+    Ok(share_links)
+}
+
+// Revoked links are not returned
+pub async fn find_share_link_by_token_hash(
+    client: &Client,
+    token_hash: &str,
+) -> Result<Option<ShareLink>, ShareLinkDataAccessError> {
+    let sql = "SELECT * FROM share_links WHERE token_hash = $1 AND revoked_at IS NULL";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| ShareLinkDataAccessError::DatabaseError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&token_hash])
+        .await
+        .map_err(|err| ShareLinkDataAccessError::DatabaseError(err.to_string()))?;
+
+    if rows.is_empty() {
+        return Ok(None);
+    }
+
+    let share_link = row_to_share_link(&rows[0])?;
+
+    Ok(Some(share_link))
+}
+
+// Returns the number of revoked links, 0 when the link is not found for the goal
+pub async fn revoke_share_link(
+    client: &Client,
+    id: &str,
+    goal_id: &str,
+) -> Result<u64, ShareLinkDataAccessError> {
+    let sql = "
+        UPDATE share_links SET revoked_at = now()
+        WHERE id = $1 AND goal_id = $2 AND revoked_at IS NULL";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| ShareLinkDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = Uuid::parse_str(id)
+        .map_err(|err| ShareLinkDataAccessError::ParameterError(err.to_string()))?;
+    let goal_id = Uuid::parse_str(goal_id)
+        .map_err(|err| ShareLinkDataAccessError::ParameterError(err.to_string()))?;
+
+    let revoked = client
+        .execute(&stm, &[&id, &goal_id])
+        .await
+        .map_err(|err| ShareLinkDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(revoked)
+}
@@ -1,6 +1,8 @@
 use std::fmt::Display;
 
-use tokio_postgres::Client;
+use chrono::{DateTime, Utc};
+
+use tokio_postgres::{Client, Row};
 use uuid::Uuid;
 
 use crate::{entities::user::User, errors::user_errors::InvalidUserError};
@@ -21,12 +23,55 @@ impl Display for UserDataAccessError {
     }
 }
 
-pub async fn add_user(client: &Client, user: &User) -> Result<(), UserDataAccessError> {
+fn row_to_user(row: &Row) -> Result<User, UserDataAccessError> {
+    let id = row.try_get::<_, Uuid>("id").unwrap_or_default();
+    let name = row.try_get::<_, String>("name").unwrap_or_default();
+    let email = row.try_get::<_, String>("email").unwrap_or_default();
+    let password_hash = row
+        .try_get::<_, String>("password_hash")
+        .unwrap_or_default();
+    let phone = row.try_get::<_, String>("phone").unwrap_or_default();
+    let role = row.try_get::<_, String>("role").unwrap_or_default();
+    let disabled_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("disabled_at")
+        .unwrap_or_default();
+    let password_reset_required = row
+        .try_get::<_, bool>("password_reset_required")
+        .unwrap_or_default();
+    let password_reset_token_hash = row
+        .try_get::<_, Option<String>>("password_reset_token_hash")
+        .unwrap_or_default();
+    let password_reset_expires_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("password_reset_expires_at")
+        .unwrap_or_default();
+    let password_changed_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("password_changed_at")
+        .unwrap_or_default();
+
+    User::from_db_fields(
+        &id,
+        &name,
+        &email,
+        &password_hash,
+        &phone,
+        &role,
+        disabled_at.is_some(),
+        password_reset_required,
+        password_reset_token_hash,
+        password_reset_expires_at,
+        password_changed_at,
+    )
+    .map_err(UserDataAccessError::MappingError)
+}
+
+// Returns the id of the new user
+pub async fn add_user(client: &Client, user: &User) -> Result<String, UserDataAccessError> {
     let sql = "
         INSERT INTO users
             (name, email, phone, password_hash)
         VALUES
-            ($1, $2, $3, $4)";
+            ($1, $2, $3, $4)
+        RETURNING id";
 
     let name = user.get_name();
     let email = user.get_email();
@@ -36,14 +81,14 @@ pub async fn add_user(client: &Client, user: &User) -> Result<(), UserDataAccess
     let stm = client
         .prepare(sql)
         .await
-        .map_err(|err| UserDataAccessError::DbError(err))?;
+        .map_err(UserDataAccessError::DbError)?;
 
-    client
-        .execute(&stm, &[&name, &email, &phone, &password_hash])
+    let row = client
+        .query_one(&stm, &[&name, &email, &phone, &password_hash])
         .await
-        .map_err(|err| UserDataAccessError::DbError(err))?;
+        .map_err(UserDataAccessError::DbError)?;
 
-    Ok(())
+    Ok(row.try_get::<_, Uuid>("id").unwrap_or_default().to_string())
 }
 
 pub async fn find_user_by_email(
@@ -55,27 +100,18 @@ pub async fn find_user_by_email(
     let stm = client
         .prepare(str)
         .await
-        .map_err(|err| UserDataAccessError::DbError(err))?;
+        .map_err(UserDataAccessError::DbError)?;
 
     let rows = client
         .query(&stm, &[&email])
         .await
-        .map_err(|err| UserDataAccessError::DbError(err))?;
+        .map_err(UserDataAccessError::DbError)?;
 
-    if rows.len() == 0 {
+    if rows.is_empty() {
         return Ok(None);
     }
 
-    let id = rows[0].try_get::<_, Uuid>("id").unwrap_or_default();
-    let name = rows[0].try_get::<_, String>("name").unwrap_or_default();
-    let email = rows[0].try_get::<_, String>("email").unwrap_or_default();
-    let password_hash = rows[0]
-        .try_get::<_, String>("password_hash")
-        .unwrap_or_default();
-    let phone = rows[0].try_get::<_, String>("phone").unwrap_or_default();
-
-    let user = User::from_db_fields(&id, &name, &email, &password_hash, &phone)
-        .map_err(|err| UserDataAccessError::MappingError(err))?;
+    let user = row_to_user(&rows[0])?;
 
     Ok(Some(user))
 }
@@ -89,30 +125,201 @@ pub async fn find_user_by_id(
     let stm = client
         .prepare(str)
         .await
-        .map_err(|err| UserDataAccessError::DbError(err))?;
+        .map_err(UserDataAccessError::DbError)?;
 
     let id =
-        Uuid::parse_str(&id).map_err(|err| UserDataAccessError::ParameterError(err.to_string()))?;
+        Uuid::parse_str(id).map_err(|err| UserDataAccessError::ParameterError(err.to_string()))?;
 
     let rows = client
         .query(&stm, &[&id])
         .await
-        .map_err(|err| UserDataAccessError::DbError(err))?;
+        .map_err(UserDataAccessError::DbError)?;
 
-    if rows.len() == 0 {
+    if rows.is_empty() {
         return Ok(None);
     }
 
-    let id = rows[0].try_get::<_, Uuid>("id").unwrap_or_default();
-    let name = rows[0].try_get::<_, String>("name").unwrap_or_default();
-    let email = rows[0].try_get::<_, String>("email").unwrap_or_default();
-    let password_hash = rows[0]
-        .try_get::<_, String>("password_hash")
-        .unwrap_or_default();
-    let phone = rows[0].try_get::<_, String>("phone").unwrap_or_default();
-
-    let user = User::from_db_fields(&id, &name, &email, &password_hash, &phone)
-        .map_err(|err| UserDataAccessError::MappingError(err))?;
+    let user = row_to_user(&rows[0])?;
 
     Ok(Some(user))
 }
+
+pub async fn update_user_password_hash(
+    client: &Client,
+    id: &str,
+    password_hash: &str,
+) -> Result<(), UserDataAccessError> {
+    let sql = "UPDATE users SET password_hash = $1 WHERE id = $2";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(UserDataAccessError::DbError)?;
+
+    let id =
+        Uuid::parse_str(id).map_err(|err| UserDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&password_hash, &id])
+        .await
+        .map_err(UserDataAccessError::DbError)?;
+
+    Ok(())
+}
+
+// Search by name or e-mail (case insensitive), ordered by name
+pub async fn find_users(
+    client: &Client,
+    search: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<User>, UserDataAccessError> {
+    let sql = "
+        SELECT * FROM users
+        WHERE name ILIKE $1 OR email ILIKE $1
+        ORDER BY name, email
+        LIMIT $2 OFFSET $3";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(UserDataAccessError::DbError)?;
+
+    let pattern = format!("%{}%", search);
+
+    let rows = client
+        .query(&stm, &[&pattern, &limit, &offset])
+        .await
+        .map_err(UserDataAccessError::DbError)?;
+
+    let mut users = Vec::new();
+    for row in rows.iter() {
+        users.push(row_to_user(row)?);
+    }
+
+    Ok(users)
+}
+
+pub async fn count_users_by_role(client: &Client, role: &str) -> Result<i64, UserDataAccessError> {
+    let sql = "SELECT COUNT(*) AS count FROM users WHERE role = $1";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(UserDataAccessError::DbError)?;
+
+    let row = client
+        .query_one(&stm, &[&role])
+        .await
+        .map_err(UserDataAccessError::DbError)?;
+
+    Ok(row.try_get::<_, i64>("count").unwrap_or_default())
+}
+
+pub async fn update_user_role(
+    client: &Client,
+    id: &str,
+    role: &str,
+) -> Result<(), UserDataAccessError> {
+    let sql = "UPDATE users SET role = $1 WHERE id = $2";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(UserDataAccessError::DbError)?;
+
+    let id =
+        Uuid::parse_str(id).map_err(|err| UserDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&role, &id])
+        .await
+        .map_err(UserDataAccessError::DbError)?;
+
+    Ok(())
+}
+
+pub async fn update_user_disabled(
+    client: &Client,
+    id: &str,
+    is_disabled: bool,
+) -> Result<(), UserDataAccessError> {
+    let sql = if is_disabled {
+        "UPDATE users SET disabled_at = now() WHERE id = $1 AND disabled_at IS NULL"
+    } else {
+        "UPDATE users SET disabled_at = NULL WHERE id = $1"
+    };
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(UserDataAccessError::DbError)?;
+
+    let id =
+        Uuid::parse_str(id).map_err(|err| UserDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&id])
+        .await
+        .map_err(UserDataAccessError::DbError)?;
+
+    Ok(())
+}
+
+// Forces a password reset: the user cannot sign in until the password is changed with the reset
+// token, and the JWTs issued until now are not accepted any more
+pub async fn set_user_password_reset(
+    client: &Client,
+    id: &str,
+    token_hash: &str,
+    expires_at: DateTime<Utc>,
+) -> Result<(), UserDataAccessError> {
+    let sql = "
+        UPDATE users SET password_reset_required = true, password_reset_token_hash = $2,
+            password_reset_expires_at = $3, password_changed_at = now()
+        WHERE id = $1";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(UserDataAccessError::DbError)?;
+
+    let id =
+        Uuid::parse_str(id).map_err(|err| UserDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&id, &token_hash, &expires_at])
+        .await
+        .map_err(UserDataAccessError::DbError)?;
+
+    Ok(())
+}
+
+// A new password chosen by the user ends the password reset and the sessions opened with the
+// previous one. update_user_password_hash only upgrades the hash of the same password
+pub async fn update_user_password(
+    client: &Client,
+    id: &str,
+    password_hash: &str,
+) -> Result<(), UserDataAccessError> {
+    let sql = "
+        UPDATE users SET password_hash = $2, password_changed_at = now(),
+            password_reset_required = false, password_reset_token_hash = NULL,
+            password_reset_expires_at = NULL
+        WHERE id = $1";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(UserDataAccessError::DbError)?;
+
+    let id =
+        Uuid::parse_str(id).map_err(|err| UserDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&id, &password_hash])
+        .await
+        .map_err(UserDataAccessError::DbError)?;
+
+    Ok(())
+}
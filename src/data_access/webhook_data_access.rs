@@ -0,0 +1,472 @@
+use std::fmt::{self, Display};
+
+use chrono::{DateTime, Utc};
+use tokio_postgres::{Client, Row};
+use uuid::Uuid;
+
+use crate::entities::webhook::{
+    PendingWebhookDelivery, Webhook, WebhookDelivery, WebhookEvent, FAILED_STATUS, PENDING_STATUS,
+    SENT_STATUS,
+};
+
+pub enum WebhookDataAccessError {
+    DatabaseError(String),
+    MappingError(String),
+    ParameterError(String),
+}
+
+impl Display for WebhookDataAccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WebhookDataAccessError::DatabaseError(err) => write!(f, "{}", err),
+            WebhookDataAccessError::MappingError(err) => write!(f, "{}", err),
+            WebhookDataAccessError::ParameterError(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+fn row_to_webhook(row: &Row) -> Result<Webhook, WebhookDataAccessError> {
+    let id = row.try_get::<_, Uuid>("id").unwrap_or_default().to_string();
+    let user_id = row
+        .try_get::<_, Uuid>("user_id")
+        .unwrap_or_default()
+        .to_string();
+    let url = row.try_get::<_, String>("url").unwrap_or_default();
+    let secret = row.try_get::<_, String>("secret").unwrap_or_default();
+    let event_types = row
+        .try_get::<_, Vec<String>>("event_types")
+        .unwrap_or_default();
+    let created_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("created_at")
+        .unwrap_or_default();
+
+    Webhook::from_db_fields(&id, &user_id, &url, &secret, event_types, created_at)
+        .map_err(|err| WebhookDataAccessError::MappingError(err.to_string()))
+}
+
+fn row_to_delivery(row: &Row) -> Result<WebhookDelivery, WebhookDataAccessError> {
+    let id = row.try_get::<_, Uuid>("id").unwrap_or_default().to_string();
+    let webhook_id = row
+        .try_get::<_, Uuid>("webhook_id")
+        .unwrap_or_default()
+        .to_string();
+    let event_id = row
+        .try_get::<_, Uuid>("event_id")
+        .unwrap_or_default()
+        .to_string();
+    let event_type = row.try_get::<_, String>("event_type").unwrap_or_default();
+    let payload = row.try_get::<_, String>("payload").unwrap_or_default();
+    let status = row.try_get::<_, String>("status").unwrap_or_default();
+    let attempts = row.try_get::<_, i32>("attempts").unwrap_or_default();
+    let response_status = row
+        .try_get::<_, Option<i32>>("response_status")
+        .unwrap_or_default();
+    let last_error = row
+        .try_get::<_, Option<String>>("last_error")
+        .unwrap_or_default();
+    let next_attempt_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("next_attempt_at")
+        .unwrap_or_default();
+    let delivered_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("delivered_at")
+        .unwrap_or_default();
+    let created_at = row
+        .try_get::<_, Option<DateTime<Utc>>>("created_at")
+        .unwrap_or_default();
+
+    WebhookDelivery::from_db_fields(
+        &id,
+        &webhook_id,
+        &event_id,
+        &event_type,
+        &payload,
+        &status,
+        attempts,
+        response_status,
+        last_error,
+        next_attempt_at,
+        delivered_at,
+        created_at,
+    )
+    .map_err(|err| WebhookDataAccessError::MappingError(err.to_string()))
+}
+
+// Returns the id generated by the database
+pub async fn add_webhook(
+    client: &Client,
+    webhook: &Webhook,
+) -> Result<String, WebhookDataAccessError> {
+    let sql = "
+        INSERT INTO webhooks
+            (user_id, url, secret, event_types)
+        VALUES
+            ($1, $2, $3, $4)
+        RETURNING id";
+
+    let user_id = Uuid::parse_str(&webhook.get_user_id())
+        .map_err(|err| WebhookDataAccessError::ParameterError(err.to_string()))?;
+    let url = webhook.get_url();
+    let secret = webhook.get_secret();
+    let event_types = webhook.get_event_types();
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| WebhookDataAccessError::DatabaseError(err.to_string()))?;
+
+    let row = client
+        .query_one(&stm, &[&user_id, &url, &secret, &event_types])
+        .await
+        .map_err(|err| WebhookDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = row.try_get::<_, Uuid>("id").unwrap_or_default().to_string();
+
+    Ok(id)
+}
+
+pub async fn find_webhook_by_id(
+    client: &Client,
+    id: &str,
+) -> Result<Option<Webhook>, WebhookDataAccessError> {
+    let sql = "SELECT * FROM webhooks WHERE id = $1";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| WebhookDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = Uuid::parse_str(id)
+        .map_err(|err| WebhookDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&id])
+        .await
+        .map_err(|err| WebhookDataAccessError::DatabaseError(err.to_string()))?;
+
+    if rows.is_empty() {
+        return Ok(None);
+    }
+
+    let webhook = row_to_webhook(&rows[0])?;
+
+    Ok(Some(webhook))
+}
+
+pub async fn find_webhooks(
+    client: &Client,
+    user_id: &str,
+) -> Result<Vec<Webhook>, WebhookDataAccessError> {
+    let sql = "SELECT * FROM webhooks WHERE user_id = $1 ORDER BY created_at, id";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| WebhookDataAccessError::DatabaseError(err.to_string()))?;
+
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| WebhookDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&user_id])
+        .await
+        .map_err(|err| WebhookDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut webhooks = Vec::new();
+    for row in rows.iter() {
+        webhooks.push(row_to_webhook(row)?);
+    }
+
+    Ok(webhooks)
+}
+
+// Returns the number of webhooks deleted, 0 when the webhook is not found for the user. Its
+// deliveries are deleted with it
+pub async fn delete_webhook(
+    client: &Client,
+    id: &str,
+    user_id: &str,
+) -> Result<u64, WebhookDataAccessError> {
+    let sql = "DELETE FROM webhooks WHERE id = $1 AND user_id = $2";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| WebhookDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = Uuid::parse_str(id)
+        .map_err(|err| WebhookDataAccessError::ParameterError(err.to_string()))?;
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| WebhookDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&id, &user_id])
+        .await
+        .map_err(|err| WebhookDataAccessError::DatabaseError(err.to_string()))
+}
+
+// Queues a delivery of the event for every webhook of the user subscribed to its type. Returns the
+// number of deliveries queued
+pub async fn enqueue_event(
+    client: &Client,
+    user_id: &str,
+    event: &WebhookEvent,
+) -> Result<u64, WebhookDataAccessError> {
+    let sql = "
+        INSERT INTO webhook_deliveries
+            (webhook_id, event_id, event_type, payload, status)
+        SELECT id, $2, $3, $4, $5 FROM webhooks
+        WHERE user_id = $1 AND $3 = ANY(event_types)";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| WebhookDataAccessError::DatabaseError(err.to_string()))?;
+
+    let user_id = Uuid::parse_str(user_id)
+        .map_err(|err| WebhookDataAccessError::ParameterError(err.to_string()))?;
+    let event_id = Uuid::parse_str(&event.id)
+        .map_err(|err| WebhookDataAccessError::ParameterError(err.to_string()))?;
+    let payload = event.to_payload();
+
+    client
+        .execute(
+            &stm,
+            &[
+                &user_id,
+                &event_id,
+                &event.event_type,
+                &payload,
+                &PENDING_STATUS,
+            ],
+        )
+        .await
+        .map_err(|err| WebhookDataAccessError::DatabaseError(err.to_string()))
+}
+
+// Queues a delivery of the event for one webhook, whatever its event types. Returns the id
+// generated by the database
+pub async fn enqueue_delivery(
+    client: &Client,
+    webhook_id: &str,
+    event: &WebhookEvent,
+) -> Result<String, WebhookDataAccessError> {
+    let sql = "
+        INSERT INTO webhook_deliveries
+            (webhook_id, event_id, event_type, payload, status)
+        VALUES
+            ($1, $2, $3, $4, $5)
+        RETURNING id";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| WebhookDataAccessError::DatabaseError(err.to_string()))?;
+
+    let webhook_id = Uuid::parse_str(webhook_id)
+        .map_err(|err| WebhookDataAccessError::ParameterError(err.to_string()))?;
+    let event_id = Uuid::parse_str(&event.id)
+        .map_err(|err| WebhookDataAccessError::ParameterError(err.to_string()))?;
+    let payload = event.to_payload();
+
+    let row = client
+        .query_one(
+            &stm,
+            &[
+                &webhook_id,
+                &event_id,
+                &event.event_type,
+                &payload,
+                &PENDING_STATUS,
+            ],
+        )
+        .await
+        .map_err(|err| WebhookDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = row.try_get::<_, Uuid>("id").unwrap_or_default().to_string();
+
+    Ok(id)
+}
+
+pub async fn find_delivery_by_id(
+    client: &Client,
+    id: &str,
+) -> Result<Option<WebhookDelivery>, WebhookDataAccessError> {
+    let sql = "SELECT * FROM webhook_deliveries WHERE id = $1";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| WebhookDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = Uuid::parse_str(id)
+        .map_err(|err| WebhookDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&id])
+        .await
+        .map_err(|err| WebhookDataAccessError::DatabaseError(err.to_string()))?;
+
+    if rows.is_empty() {
+        return Ok(None);
+    }
+
+    let delivery = row_to_delivery(&rows[0])?;
+
+    Ok(Some(delivery))
+}
+
+// Newest first, optionally only the deliveries with the status
+pub async fn find_deliveries(
+    client: &Client,
+    webhook_id: &str,
+    status: Option<String>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<WebhookDelivery>, WebhookDataAccessError> {
+    let sql = "
+        SELECT * FROM webhook_deliveries
+        WHERE webhook_id = $1 AND ($2::text IS NULL OR status = $2)
+        ORDER BY created_at DESC, id DESC
+        LIMIT $3 OFFSET $4";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| WebhookDataAccessError::DatabaseError(err.to_string()))?;
+
+    let webhook_id = Uuid::parse_str(webhook_id)
+        .map_err(|err| WebhookDataAccessError::ParameterError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&webhook_id, &status, &limit, &offset])
+        .await
+        .map_err(|err| WebhookDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut deliveries = Vec::new();
+    for row in rows.iter() {
+        deliveries.push(row_to_delivery(row)?);
+    }
+
+    Ok(deliveries)
+}
+
+// Locks the pending deliveries that are due by pushing their next attempt, so the other instances
+// skip them while they are being sent
+pub async fn claim_due_deliveries(
+    client: &Client,
+    limit: i64,
+    lock_seconds: f64,
+) -> Result<Vec<PendingWebhookDelivery>, WebhookDataAccessError> {
+    let sql = "
+        WITH due AS (
+            SELECT id FROM webhook_deliveries
+            WHERE status = $1 AND next_attempt_at <= now()
+            ORDER BY next_attempt_at
+            LIMIT $2
+            FOR UPDATE SKIP LOCKED
+        ), claimed AS (
+            UPDATE webhook_deliveries d SET next_attempt_at = now() + make_interval(secs => $3)
+            FROM due WHERE d.id = due.id
+            RETURNING d.*
+        )
+        SELECT c.*, w.url, w.secret
+        FROM claimed c
+        JOIN webhooks w ON w.id = c.webhook_id";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| WebhookDataAccessError::DatabaseError(err.to_string()))?;
+
+    let rows = client
+        .query(&stm, &[&PENDING_STATUS, &limit, &lock_seconds])
+        .await
+        .map_err(|err| WebhookDataAccessError::DatabaseError(err.to_string()))?;
+
+    let mut deliveries = Vec::new();
+    for row in rows.iter() {
+        let delivery = row_to_delivery(row)?;
+        deliveries.push(PendingWebhookDelivery {
+            delivery_id: delivery.get_id(),
+            event_id: delivery.get_event_id(),
+            event_type: delivery.get_event_type(),
+            payload: delivery.get_payload(),
+            url: row.try_get::<_, String>("url").unwrap_or_default(),
+            secret: row.try_get::<_, String>("secret").unwrap_or_default(),
+            attempts: delivery.get_attempts(),
+        });
+    }
+
+    Ok(deliveries)
+}
+
+pub async fn mark_delivery_sent(
+    client: &Client,
+    id: &str,
+    response_status: i32,
+) -> Result<(), WebhookDataAccessError> {
+    let sql = "
+        UPDATE webhook_deliveries
+        SET status = $2, attempts = attempts + 1, response_status = $3, last_error = NULL,
+            delivered_at = now()
+        WHERE id = $1";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| WebhookDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = Uuid::parse_str(id)
+        .map_err(|err| WebhookDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(&stm, &[&id, &SENT_STATUS, &response_status])
+        .await
+        .map_err(|err| WebhookDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+// The delivery is tried again after retry_in_seconds, until max_attempts is reached
+pub async fn mark_delivery_failed(
+    client: &Client,
+    id: &str,
+    response_status: Option<i32>,
+    error: &str,
+    max_attempts: i32,
+    retry_in_seconds: f64,
+) -> Result<(), WebhookDataAccessError> {
+    let sql = "
+        UPDATE webhook_deliveries
+        SET attempts = attempts + 1,
+            response_status = $2,
+            last_error = $3,
+            status = CASE WHEN attempts + 1 >= $4 THEN $5 ELSE status END,
+            next_attempt_at = now() + make_interval(secs => $6)
+        WHERE id = $1";
+
+    let stm = client
+        .prepare(sql)
+        .await
+        .map_err(|err| WebhookDataAccessError::DatabaseError(err.to_string()))?;
+
+    let id = Uuid::parse_str(id)
+        .map_err(|err| WebhookDataAccessError::ParameterError(err.to_string()))?;
+
+    client
+        .execute(
+            &stm,
+            &[
+                &id,
+                &response_status,
+                &error,
+                &max_attempts,
+                &FAILED_STATUS,
+                &retry_in_seconds,
+            ],
+        )
+        .await
+        .map_err(|err| WebhookDataAccessError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
@@ -1,10 +1,10 @@
-use tokio_postgres::{NoTls, Error};
+use tokio_postgres::{tls::NoTlsStream, Client, Connection, NoTls, Error, Socket};
+
+const CONNECTION_STRING: &str = "host=localhost user=didorgas password=1234 dbname=goals_db";
 
 pub async fn establish_connection() -> Result<tokio_postgres::Client, Error>
 {
-    let connection_string = "host=localhost user=didorgas password=1234 dbname=goals_db";
-
-    let (client, connection) = tokio_postgres::connect(connection_string, NoTls).await?;
+    let (client, connection) = tokio_postgres::connect(CONNECTION_STRING, NoTls).await?;
 
     tokio::spawn(async move {
         if let Err(err) = connection.await {
@@ -14,3 +14,10 @@ pub async fn establish_connection() -> Result<tokio_postgres::Client, Error>
 
     Ok(client)
 }
+
+// The caller polls the connection itself, to receive the LISTEN notifications
+pub async fn establish_listener_connection(
+) -> Result<(Client, Connection<Socket, NoTlsStream>), Error>
+{
+    tokio_postgres::connect(CONNECTION_STRING, NoTls).await
+}
@@ -0,0 +1,263 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::errors::api_key_errors::InvalidApiKeyError;
+
+use super::user::User;
+
+pub const READ_SCOPE: &str = "goals:read";
+pub const WRITE_SCOPE: &str = "goals:write";
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateApiKeyDto {
+    pub name: String,
+    pub scope: String,
+    // Optional RFC 3339 date, ex: 2024-12-31T23:59:59Z
+    pub expires_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ApiKeyDto {
+    pub id: String,
+    pub name: String,
+    pub prefix: String,
+    pub scope: String,
+    pub expires_at: Option<String>,
+    pub last_used_at: Option<String>,
+}
+
+// Only returned once, when the key is created. The plain key is never stored
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreatedApiKeyDto {
+    pub id: String,
+    pub name: String,
+    pub scope: String,
+    pub expires_at: Option<String>,
+    pub key: String,
+}
+
+#[derive(Debug)]
+pub struct ApiKey {
+    id: String,
+    user_id: String,
+    name: String,
+    prefix: String,
+    key_hash: String,
+    scope: String,
+    expires_at: Option<DateTime<Utc>>,
+    last_used_at: Option<DateTime<Utc>>,
+}
+
+impl ApiKey {
+    fn new() -> ApiKey {
+        ApiKey {
+            id: String::from("NO_ID"),
+            user_id: String::from("NO_USER_ID"),
+            name: String::from("NO_NAME"),
+            prefix: String::from("NO_PREFIX"),
+            key_hash: String::from("NO_KEY_HASH"),
+            scope: String::from(READ_SCOPE),
+            expires_at: None,
+            last_used_at: None,
+        }
+    }
+
+    pub fn validate_id(id: &str) -> Result<(), InvalidApiKeyError> {
+        match Uuid::parse_str(id) {
+            Err(_) => Err(InvalidApiKeyError::new(Some(String::from(
+                "Api key id is not a valid UUID",
+            )))),
+            Ok(_) => Ok(()),
+        }
+    }
+
+    pub fn validate_name(name: &str) -> Result<(), InvalidApiKeyError> {
+        if name.trim().is_empty() {
+            return Err(InvalidApiKeyError::new(Some(String::from(
+                "Api key name is required and cannot be blank",
+            ))));
+        }
+        if name.chars().count() > 60 {
+            return Err(InvalidApiKeyError::new(Some(String::from(
+                "Api key name must be at most 60 characters long",
+            ))));
+        }
+        Ok(())
+    }
+
+    pub fn validate_prefix(prefix: &str) -> Result<(), InvalidApiKeyError> {
+        if prefix.is_empty() {
+            return Err(InvalidApiKeyError::new(Some(String::from(
+                "Api key prefix is required and cannot be empty",
+            ))));
+        }
+        Ok(())
+    }
+
+    pub fn validate_key_hash(key_hash: &str) -> Result<(), InvalidApiKeyError> {
+        if key_hash.is_empty() {
+            return Err(InvalidApiKeyError::new(Some(String::from(
+                "Api key hash is required and cannot be empty",
+            ))));
+        }
+        Ok(())
+    }
+
+    pub fn validate_scope(scope: &str) -> Result<(), InvalidApiKeyError> {
+        if scope != READ_SCOPE && scope != WRITE_SCOPE {
+            return Err(InvalidApiKeyError::new(Some(format!(
+                "Api key scope must be '{}' or '{}'",
+                READ_SCOPE, WRITE_SCOPE
+            ))));
+        }
+        Ok(())
+    }
+
+    pub fn validate_expires_at(expires_at: &DateTime<Utc>) -> Result<(), InvalidApiKeyError> {
+        if *expires_at <= Utc::now() {
+            return Err(InvalidApiKeyError::new(Some(String::from(
+                "Api key expiration date must be in the future",
+            ))));
+        }
+        Ok(())
+    }
+
+    pub fn set_id(&mut self, id: String) -> Result<(), InvalidApiKeyError> {
+        ApiKey::validate_id(&id)?;
+        self.id = id;
+        Ok(())
+    }
+
+    pub fn set_user_id(&mut self, user_id: String) -> Result<(), InvalidApiKeyError> {
+        User::validate_id(&user_id)
+            .map_err(|err| InvalidApiKeyError::new(Some(err.to_string())))?;
+        self.user_id = user_id;
+        Ok(())
+    }
+
+    pub fn set_name(&mut self, name: String) -> Result<(), InvalidApiKeyError> {
+        ApiKey::validate_name(&name)?;
+        self.name = name;
+        Ok(())
+    }
+
+    pub fn set_prefix(&mut self, prefix: String) -> Result<(), InvalidApiKeyError> {
+        ApiKey::validate_prefix(&prefix)?;
+        self.prefix = prefix;
+        Ok(())
+    }
+
+    pub fn set_key_hash(&mut self, key_hash: String) -> Result<(), InvalidApiKeyError> {
+        ApiKey::validate_key_hash(&key_hash)?;
+        self.key_hash = key_hash;
+        Ok(())
+    }
+
+    pub fn set_scope(&mut self, scope: String) -> Result<(), InvalidApiKeyError> {
+        ApiKey::validate_scope(&scope)?;
+        self.scope = scope;
+        Ok(())
+    }
+
+    pub fn set_expires_at(&mut self, expires_at: Option<DateTime<Utc>>) {
+        self.expires_at = expires_at;
+    }
+
+    pub fn set_last_used_at(&mut self, last_used_at: Option<DateTime<Utc>>) {
+        self.last_used_at = last_used_at;
+    }
+
+    pub fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    pub fn get_user_id(&self) -> String {
+        self.user_id.clone()
+    }
+
+    pub fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn get_prefix(&self) -> String {
+        self.prefix.clone()
+    }
+
+    pub fn get_key_hash(&self) -> String {
+        self.key_hash.clone()
+    }
+
+    pub fn get_scope(&self) -> String {
+        self.scope.clone()
+    }
+
+    pub fn get_expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+
+    pub fn get_last_used_at(&self) -> Option<DateTime<Utc>> {
+        self.last_used_at
+    }
+
+    pub fn is_expired(&self) -> bool {
+        match self.expires_at {
+            None => false,
+            Some(expires_at) => expires_at <= Utc::now(),
+        }
+    }
+
+    // A write key can also read, a read key can only read
+    pub fn allows_scope(&self, required_scope: &str) -> bool {
+        self.scope == WRITE_SCOPE || self.scope == required_scope
+    }
+
+    pub fn from_create_api_key_dto(
+        create_api_key: CreateApiKeyDto,
+        user_id: &str,
+        prefix: &str,
+        key_hash: &str,
+    ) -> Result<ApiKey, InvalidApiKeyError> {
+        let mut api_key = ApiKey::new();
+        api_key.set_user_id(user_id.to_string())?;
+        api_key.set_name(create_api_key.name)?;
+        api_key.set_scope(create_api_key.scope)?;
+        api_key.set_prefix(prefix.to_string())?;
+        api_key.set_key_hash(key_hash.to_string())?;
+        if let Some(expires_at) = create_api_key.expires_at {
+            let expires_at = DateTime::parse_from_rfc3339(&expires_at)
+                .map_err(|_| {
+                    InvalidApiKeyError::new(Some(String::from(
+                        "Api key expiration date is not a valid RFC 3339 date",
+                    )))
+                })?
+                .with_timezone(&Utc);
+            ApiKey::validate_expires_at(&expires_at)?;
+            api_key.set_expires_at(Some(expires_at));
+        }
+        Ok(api_key)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_db_fields(
+        id: &str,
+        user_id: &str,
+        name: &str,
+        prefix: &str,
+        key_hash: &str,
+        scope: &str,
+        expires_at: Option<DateTime<Utc>>,
+        last_used_at: Option<DateTime<Utc>>,
+    ) -> Result<ApiKey, InvalidApiKeyError> {
+        let mut api_key = ApiKey::new();
+        api_key.set_id(id.to_string())?;
+        api_key.set_user_id(user_id.to_string())?;
+        api_key.set_name(name.to_string())?;
+        api_key.set_prefix(prefix.to_string())?;
+        api_key.set_key_hash(key_hash.to_string())?;
+        api_key.set_scope(scope.to_string())?;
+        api_key.set_expires_at(expires_at);
+        api_key.set_last_used_at(last_used_at);
+        Ok(api_key)
+    }
+}
@@ -0,0 +1,225 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::errors::audit_event_errors::InvalidAuditEventError;
+
+use super::user::User;
+
+pub const USER_SIGNED_UP_AUDIT_EVENT: &str = "user.signed_up";
+pub const USER_SIGNED_IN_AUDIT_EVENT: &str = "user.signed_in";
+pub const USER_SIGN_IN_FAILED_AUDIT_EVENT: &str = "user.sign_in_failed";
+pub const USER_TOKEN_VERIFICATION_FAILED_AUDIT_EVENT: &str = "user.token_verification_failed";
+pub const USER_PASSWORD_CHANGED_AUDIT_EVENT: &str = "user.password_changed";
+pub const USER_PASSWORD_CHANGE_FAILED_AUDIT_EVENT: &str = "user.password_change_failed";
+pub const ADMIN_USER_DISABLED_AUDIT_EVENT: &str = "admin.user_disabled";
+pub const ADMIN_USER_ENABLED_AUDIT_EVENT: &str = "admin.user_enabled";
+pub const ADMIN_PASSWORD_RESET_FORCED_AUDIT_EVENT: &str = "admin.password_reset_forced";
+pub const ADMIN_USER_GOALS_VIEWED_AUDIT_EVENT: &str = "admin.user_goals_viewed";
+
+pub const AUDIT_EVENT_TYPES: [&str; 10] = [
+    USER_SIGNED_UP_AUDIT_EVENT,
+    USER_SIGNED_IN_AUDIT_EVENT,
+    USER_SIGN_IN_FAILED_AUDIT_EVENT,
+    USER_TOKEN_VERIFICATION_FAILED_AUDIT_EVENT,
+    USER_PASSWORD_CHANGED_AUDIT_EVENT,
+    USER_PASSWORD_CHANGE_FAILED_AUDIT_EVENT,
+    ADMIN_USER_DISABLED_AUDIT_EVENT,
+    ADMIN_USER_ENABLED_AUDIT_EVENT,
+    ADMIN_PASSWORD_RESET_FORCED_AUDIT_EVENT,
+    ADMIN_USER_GOALS_VIEWED_AUDIT_EVENT,
+];
+
+// Where the request came from, taken from the connection and the headers by the routes
+#[derive(Clone, Debug, Default)]
+pub struct AuditContext {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+// user_id is the account the event is about and actor_id the admin that made the change. email is
+// the one sent in the request, so the failed sign ins for unknown accounts can be found too
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AuditEventDto {
+    pub id: i64,
+    pub event_type: String,
+    pub user_id: Option<String>,
+    pub actor_id: Option<String>,
+    pub email: Option<String>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub details: Value,
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AuditEventsQueryDto {
+    pub event_type: Option<String>,
+    pub user_id: Option<String>,
+    pub email: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+// Events are only added, the log is kept when the users are deleted
+pub struct AuditEvent {
+    id: i64,
+    event_type: String,
+    user_id: Option<String>,
+    actor_id: Option<String>,
+    email: Option<String>,
+    ip: Option<String>,
+    user_agent: Option<String>,
+    details: String,
+    created_at: Option<DateTime<Utc>>,
+}
+
+impl AuditEvent {
+    fn new() -> AuditEvent {
+        AuditEvent {
+            id: 0,
+            event_type: String::from(USER_SIGNED_IN_AUDIT_EVENT),
+            user_id: None,
+            actor_id: None,
+            email: None,
+            ip: None,
+            user_agent: None,
+            details: String::from("{}"),
+            created_at: None,
+        }
+    }
+
+    // A new event of one of the AUDIT_EVENT_TYPES
+    pub fn from_context(event_type: &str, context: &AuditContext) -> AuditEvent {
+        let mut event = AuditEvent::new();
+        event.event_type = event_type.to_string();
+        event.ip = context.ip.clone();
+        event.user_agent = context.user_agent.clone();
+        event
+    }
+
+    pub fn validate_event_type(event_type: &str) -> Result<(), InvalidAuditEventError> {
+        if !AUDIT_EVENT_TYPES.contains(&event_type) {
+            return Err(InvalidAuditEventError::new(Some(format!(
+                "Unknown audit event type: {}",
+                event_type
+            ))));
+        }
+        Ok(())
+    }
+
+    pub fn set_id(&mut self, id: i64) {
+        self.id = id;
+    }
+
+    pub fn set_event_type(&mut self, event_type: String) -> Result<(), InvalidAuditEventError> {
+        AuditEvent::validate_event_type(&event_type)?;
+        self.event_type = event_type;
+        Ok(())
+    }
+
+    pub fn set_user_id(&mut self, user_id: Option<String>) -> Result<(), InvalidAuditEventError> {
+        if let Some(user_id) = &user_id {
+            User::validate_id(user_id)
+                .map_err(|err| InvalidAuditEventError::new(Some(err.to_string())))?;
+        }
+        self.user_id = user_id;
+        Ok(())
+    }
+
+    pub fn set_actor_id(&mut self, actor_id: Option<String>) -> Result<(), InvalidAuditEventError> {
+        if let Some(actor_id) = &actor_id {
+            User::validate_id(actor_id)
+                .map_err(|err| InvalidAuditEventError::new(Some(err.to_string())))?;
+        }
+        self.actor_id = actor_id;
+        Ok(())
+    }
+
+    pub fn set_email(&mut self, email: Option<String>) {
+        self.email = email;
+    }
+
+    pub fn set_ip(&mut self, ip: Option<String>) {
+        self.ip = ip;
+    }
+
+    pub fn set_user_agent(&mut self, user_agent: Option<String>) {
+        self.user_agent = user_agent;
+    }
+
+    pub fn set_details(&mut self, details: String) {
+        self.details = details;
+    }
+
+    pub fn set_created_at(&mut self, created_at: Option<DateTime<Utc>>) {
+        self.created_at = created_at;
+    }
+
+    pub fn get_id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn get_event_type(&self) -> String {
+        self.event_type.clone()
+    }
+
+    pub fn get_user_id(&self) -> Option<String> {
+        self.user_id.clone()
+    }
+
+    pub fn get_actor_id(&self) -> Option<String> {
+        self.actor_id.clone()
+    }
+
+    pub fn get_email(&self) -> Option<String> {
+        self.email.clone()
+    }
+
+    pub fn get_ip(&self) -> Option<String> {
+        self.ip.clone()
+    }
+
+    pub fn get_user_agent(&self) -> Option<String> {
+        self.user_agent.clone()
+    }
+
+    pub fn get_details(&self) -> String {
+        self.details.clone()
+    }
+
+    // Details that are not valid JSON are returned as a string
+    pub fn get_details_value(&self) -> Value {
+        serde_json::from_str::<Value>(&self.details)
+            .unwrap_or_else(|_| Value::String(self.details.clone()))
+    }
+
+    pub fn get_created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_db_fields(
+        id: i64,
+        event_type: &str,
+        user_id: Option<String>,
+        actor_id: Option<String>,
+        email: Option<String>,
+        ip: Option<String>,
+        user_agent: Option<String>,
+        details: &str,
+        created_at: Option<DateTime<Utc>>,
+    ) -> Result<AuditEvent, InvalidAuditEventError> {
+        let mut event = AuditEvent::new();
+        event.set_id(id);
+        event.set_event_type(event_type.to_string())?;
+        event.set_user_id(user_id)?;
+        event.set_actor_id(actor_id)?;
+        event.set_email(email);
+        event.set_ip(ip);
+        event.set_user_agent(user_agent);
+        event.set_details(details.to_string());
+        event.set_created_at(created_at);
+        Ok(event)
+    }
+}
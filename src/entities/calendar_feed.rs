@@ -0,0 +1,96 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::calendar_feed_errors::InvalidCalendarFeedError;
+
+use super::user::User;
+
+// Only returned once, when the feed is created. The plain token is never stored
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreatedCalendarFeedDto {
+    pub url: String,
+    pub token: String,
+    pub created_at: Option<String>,
+}
+
+// The iCalendar document of the feed and its ETag, a hash of the document
+pub struct CalendarDocument {
+    pub body: String,
+    pub etag: String,
+}
+
+// Read only access to the calendar of a user, for whoever has the token. A user has one feed,
+// creating a new one replaces the token
+pub struct CalendarFeed {
+    user_id: String,
+    token_hash: String,
+    created_at: Option<DateTime<Utc>>,
+}
+
+impl CalendarFeed {
+    fn new() -> CalendarFeed {
+        CalendarFeed {
+            user_id: String::from("NO_USER_ID"),
+            token_hash: String::from("NO_TOKEN_HASH"),
+            created_at: None,
+        }
+    }
+
+    pub fn validate_token_hash(token_hash: &str) -> Result<(), InvalidCalendarFeedError> {
+        if token_hash.is_empty() {
+            return Err(InvalidCalendarFeedError::new(Some(String::from(
+                "Calendar feed token hash is required and cannot be empty",
+            ))));
+        }
+        Ok(())
+    }
+
+    pub fn set_user_id(&mut self, user_id: String) -> Result<(), InvalidCalendarFeedError> {
+        User::validate_id(&user_id)
+            .map_err(|err| InvalidCalendarFeedError::new(Some(err.to_string())))?;
+        self.user_id = user_id;
+        Ok(())
+    }
+
+    pub fn set_token_hash(&mut self, token_hash: String) -> Result<(), InvalidCalendarFeedError> {
+        CalendarFeed::validate_token_hash(&token_hash)?;
+        self.token_hash = token_hash;
+        Ok(())
+    }
+
+    pub fn set_created_at(&mut self, created_at: Option<DateTime<Utc>>) {
+        self.created_at = created_at;
+    }
+
+    pub fn get_user_id(&self) -> String {
+        self.user_id.clone()
+    }
+
+    pub fn get_token_hash(&self) -> String {
+        self.token_hash.clone()
+    }
+
+    pub fn get_created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    pub fn from_token_hash(
+        user_id: &str,
+        token_hash: &str,
+    ) -> Result<CalendarFeed, InvalidCalendarFeedError> {
+        let mut calendar_feed = CalendarFeed::new();
+        calendar_feed.set_user_id(user_id.to_string())?;
+        calendar_feed.set_token_hash(token_hash.to_string())?;
+        Ok(calendar_feed)
+    }
+
+    pub fn from_db_fields(
+        user_id: &str,
+        token_hash: &str,
+        created_at: Option<DateTime<Utc>>,
+    ) -> Result<CalendarFeed, InvalidCalendarFeedError> {
+        let mut calendar_feed = CalendarFeed::from_token_hash(user_id, token_hash)?;
+        calendar_feed.set_created_at(created_at);
+        Ok(calendar_feed)
+    }
+}
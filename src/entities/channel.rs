@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::errors::channel_errors::InvalidChannelError;
+
+use super::{
+    goal::{CreateGoalDto, Goal, GoalDto, UpdateGoalDto},
+    goal_event::GoalEventDto,
+    organization::Organization,
+    user::User,
+};
+
+pub const GOAL_CHANNEL: &str = "goal";
+pub const ORGANIZATION_CHANNEL: &str = "organization";
+pub const USER_CHANNEL: &str = "user";
+
+// Browsers cannot set headers on a WebSocket, the JWT or api key can be sent in the query instead
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WebSocketQueryDto {
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ViewerDto {
+    pub user_id: String,
+    pub name: String,
+}
+
+// Messages sent by the clients. request_id is chosen by the client and sent back with the result
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Subscribe {
+        channel: String,
+    },
+    Unsubscribe {
+        channel: String,
+    },
+    CreateGoal {
+        request_id: String,
+        goal: CreateGoalDto,
+    },
+    UpdateGoal {
+        request_id: String,
+        goal_id: String,
+        goal: UpdateGoalDto,
+    },
+    DeleteGoal {
+        request_id: String,
+        goal_id: String,
+    },
+    Ping,
+}
+
+// Messages sent to the clients. status is the status the REST route would respond with
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Subscribed {
+        channel: String,
+        viewers: Vec<ViewerDto>,
+    },
+    Unsubscribed {
+        channel: String,
+    },
+    Presence {
+        channel: String,
+        viewers: Vec<ViewerDto>,
+    },
+    Event {
+        channels: Vec<String>,
+        event: GoalEventDto,
+    },
+    Result {
+        request_id: String,
+        status: u16,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        goal: Option<GoalDto>,
+    },
+    Error {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        request_id: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        channel: Option<String>,
+        status: u16,
+        message: String,
+    },
+    Pong,
+}
+
+// A board clients subscribe to, named "{kind}:{id}": the goals of an organization, a single goal or
+// the personal goals of a user
+pub struct Channel {
+    kind: String,
+    resource_id: String,
+}
+
+impl Channel {
+    fn new() -> Channel {
+        Channel {
+            kind: String::from(USER_CHANNEL),
+            resource_id: String::from("NO_RESOURCE_ID"),
+        }
+    }
+
+    pub fn validate_kind(kind: &str) -> Result<(), InvalidChannelError> {
+        match kind {
+            GOAL_CHANNEL | ORGANIZATION_CHANNEL | USER_CHANNEL => Ok(()),
+            _ => Err(InvalidChannelError::new(Some(format!(
+                "Unknown channel kind: {}",
+                kind
+            )))),
+        }
+    }
+
+    pub fn set_kind(&mut self, kind: String) -> Result<(), InvalidChannelError> {
+        Channel::validate_kind(&kind)?;
+        self.kind = kind;
+        Ok(())
+    }
+
+    // The kind is set first, the id is validated as the id of its resource. Ids are stored
+    // hyphenated and lowercase, as in the events, so a channel has a single name
+    pub fn set_resource_id(&mut self, resource_id: String) -> Result<(), InvalidChannelError> {
+        let validation = match self.kind.as_str() {
+            GOAL_CHANNEL => Goal::validate_id(&resource_id).map_err(|err| err.to_string()),
+            ORGANIZATION_CHANNEL => {
+                Organization::validate_id(&resource_id).map_err(|err| err.to_string())
+            }
+            _ => User::validate_id(&resource_id).map_err(|err| err.to_string()),
+        };
+        validation.map_err(|err_msg| InvalidChannelError::new(Some(err_msg)))?;
+        self.resource_id = Uuid::parse_str(&resource_id)
+            .map(|id| id.to_string())
+            .unwrap_or(resource_id);
+        Ok(())
+    }
+
+    pub fn get_kind(&self) -> String {
+        self.kind.clone()
+    }
+
+    pub fn get_resource_id(&self) -> String {
+        self.resource_id.clone()
+    }
+
+    pub fn get_name(&self) -> String {
+        format!("{}:{}", self.kind, self.resource_id)
+    }
+
+    // The events are already limited to the goals the user can see
+    pub fn matches_event(&self, event: &GoalEventDto) -> bool {
+        let organization_id = event.data["organization_id"].as_str();
+        match self.kind.as_str() {
+            GOAL_CHANNEL => event.goal_id == self.resource_id,
+            ORGANIZATION_CHANNEL => organization_id == Some(self.resource_id.as_str()),
+            _ => {
+                organization_id.is_none()
+                    && event.data["user_id"].as_str() == Some(self.resource_id.as_str())
+            }
+        }
+    }
+
+    pub fn from_name(name: &str) -> Result<Channel, InvalidChannelError> {
+        let (kind, resource_id) = name.split_once(':').ok_or_else(|| {
+            InvalidChannelError::new(Some(String::from(
+                "Channels are named {kind}:{id}, with the kinds goal, organization or user",
+            )))
+        })?;
+
+        let mut channel = Channel::new();
+        channel.set_kind(kind.to_string())?;
+        channel.set_resource_id(resource_id.to_string())?;
+        Ok(channel)
+    }
+}
+
+// A user with the channel open in at least one connection
+pub struct ChannelViewer {
+    user_id: String,
+    name: String,
+}
+
+impl ChannelViewer {
+    pub fn get_user_id(&self) -> String {
+        self.user_id.clone()
+    }
+
+    pub fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn from_db_fields(user_id: &str, name: &str) -> Result<ChannelViewer, InvalidChannelError> {
+        User::validate_id(user_id)
+            .map_err(|err| InvalidChannelError::new(Some(err.to_string())))?;
+        Ok(ChannelViewer {
+            user_id: user_id.to_string(),
+            name: name.to_string(),
+        })
+    }
+}
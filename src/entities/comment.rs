@@ -0,0 +1,233 @@
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::errors::comment_errors::InvalidCommentError;
+
+use super::{goal::Goal, user::User};
+
+const MAX_TEXT_LENGTH: usize = 5000;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateCommentDto {
+    pub text: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateCommentDto {
+    pub text: String,
+}
+
+// mentions holds the ids of the mentioned users
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CommentDto {
+    pub id: String,
+    pub goal_id: String,
+    pub user_id: String,
+    pub text: String,
+    pub mentions: Vec<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+// Previous text of a comment, kept when the comment is edited
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CommentEditDto {
+    pub text: String,
+    pub edited_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CommentsQueryDto {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+pub struct Comment {
+    id: String,
+    goal_id: String,
+    user_id: String,
+    text: String,
+    mentions: Vec<String>,
+    created_at: Option<DateTime<Utc>>,
+    updated_at: Option<DateTime<Utc>>,
+}
+
+impl Comment {
+    fn new() -> Comment {
+        Comment {
+            id: String::from("NO_ID"),
+            goal_id: String::from("NO_GOAL_ID"),
+            user_id: String::from("NO_USER_ID"),
+            text: String::from("NO_TEXT"),
+            mentions: Vec::new(),
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    pub fn validate_id(id: &str) -> Result<(), InvalidCommentError> {
+        match Uuid::parse_str(id) {
+            Err(_) => Err(InvalidCommentError::new(Some(String::from(
+                "Comment id is not a valid UUID",
+            )))),
+            Ok(_) => Ok(()),
+        }
+    }
+
+    pub fn validate_text(text: &str) -> Result<(), InvalidCommentError> {
+        if text.trim().is_empty() {
+            return Err(InvalidCommentError::new(Some(String::from(
+                "Comment text is required and cannot be blank",
+            ))));
+        }
+        if text.chars().count() > MAX_TEXT_LENGTH {
+            return Err(InvalidCommentError::new(Some(format!(
+                "Comment text must be at most {} characters long",
+                MAX_TEXT_LENGTH
+            ))));
+        }
+        Ok(())
+    }
+
+    // Users are mentioned by e-mail, ex: "@jane@example.com". Returns the lowercased e-mails,
+    // without duplicates
+    pub fn extract_mentions(text: &str) -> Vec<String> {
+        let regex =
+            Regex::new(r"(?:^|\s)@([A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,})").unwrap();
+        let mut emails: Vec<String> = Vec::new();
+        for captures in regex.captures_iter(text) {
+            let email = captures[1].trim_end_matches('.').to_lowercase();
+            if !emails.contains(&email) {
+                emails.push(email);
+            }
+        }
+        emails
+    }
+
+    pub fn set_id(&mut self, id: String) -> Result<(), InvalidCommentError> {
+        Comment::validate_id(&id)?;
+        self.id = id;
+        Ok(())
+    }
+
+    pub fn set_goal_id(&mut self, goal_id: String) -> Result<(), InvalidCommentError> {
+        Goal::validate_id(&goal_id)
+            .map_err(|err| InvalidCommentError::new(Some(err.to_string())))?;
+        self.goal_id = goal_id;
+        Ok(())
+    }
+
+    pub fn set_user_id(&mut self, user_id: String) -> Result<(), InvalidCommentError> {
+        User::validate_id(&user_id)
+            .map_err(|err| InvalidCommentError::new(Some(err.to_string())))?;
+        self.user_id = user_id;
+        Ok(())
+    }
+
+    pub fn set_text(&mut self, text: String) -> Result<(), InvalidCommentError> {
+        Comment::validate_text(&text)?;
+        self.text = text;
+        Ok(())
+    }
+
+    pub fn set_mentions(&mut self, mentions: Vec<String>) -> Result<(), InvalidCommentError> {
+        for user_id in mentions.iter() {
+            User::validate_id(user_id)
+                .map_err(|err| InvalidCommentError::new(Some(err.to_string())))?;
+        }
+        self.mentions = mentions;
+        Ok(())
+    }
+
+    pub fn set_created_at(&mut self, created_at: Option<DateTime<Utc>>) {
+        self.created_at = created_at;
+    }
+
+    pub fn set_updated_at(&mut self, updated_at: Option<DateTime<Utc>>) {
+        self.updated_at = updated_at;
+    }
+
+    pub fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    pub fn get_goal_id(&self) -> String {
+        self.goal_id.clone()
+    }
+
+    pub fn get_user_id(&self) -> String {
+        self.user_id.clone()
+    }
+
+    pub fn get_text(&self) -> String {
+        self.text.clone()
+    }
+
+    pub fn get_mentions(&self) -> Vec<String> {
+        self.mentions.clone()
+    }
+
+    pub fn get_created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    pub fn get_updated_at(&self) -> Option<DateTime<Utc>> {
+        self.updated_at
+    }
+
+    pub fn from_create_comment_dto(
+        create_comment: CreateCommentDto,
+        goal_id: &str,
+        user_id: &str,
+    ) -> Result<Comment, InvalidCommentError> {
+        let mut comment = Comment::new();
+        comment.set_goal_id(goal_id.to_string())?;
+        comment.set_user_id(user_id.to_string())?;
+        comment.set_text(create_comment.text)?;
+        Ok(comment)
+    }
+
+    pub fn from_db_fields(
+        id: &str,
+        goal_id: &str,
+        user_id: &str,
+        text: &str,
+        mentions: Vec<String>,
+        created_at: Option<DateTime<Utc>>,
+        updated_at: Option<DateTime<Utc>>,
+    ) -> Result<Comment, InvalidCommentError> {
+        let mut comment = Comment::new();
+        comment.set_id(id.to_string())?;
+        comment.set_goal_id(goal_id.to_string())?;
+        comment.set_user_id(user_id.to_string())?;
+        comment.set_text(text.to_string())?;
+        comment.set_mentions(mentions)?;
+        comment.set_created_at(created_at);
+        comment.set_updated_at(updated_at);
+        Ok(comment)
+    }
+}
+
+pub struct CommentEdit {
+    text: String,
+    edited_at: Option<DateTime<Utc>>,
+}
+
+impl CommentEdit {
+    pub fn get_text(&self) -> String {
+        self.text.clone()
+    }
+
+    pub fn get_edited_at(&self) -> Option<DateTime<Utc>> {
+        self.edited_at
+    }
+
+    pub fn from_db_fields(text: &str, edited_at: Option<DateTime<Utc>>) -> CommentEdit {
+        CommentEdit {
+            text: text.to_string(),
+            edited_at,
+        }
+    }
+}
@@ -1,26 +1,90 @@
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
 use uuid::Uuid;
 
 use crate::errors::goal_errors::InvalidGoalError;
 
-use super::user::User;
+use super::{organization::Organization, user::User};
 
+// Without organization_id the goal is a personal goal of the user. due_at is an RFC 3339 date
 #[derive(Debug, Deserialize, Serialize)]
 pub struct CreateGoalDto {
     pub text: String,
+    pub organization_id: Option<String>,
+    pub due_at: Option<String>,
 }
 
+// completed marks the goal as completed or not and due_at changes the due date, they are left as
+// they are when missing. due_at set to null removes the due date
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateGoalDto {
+    pub text: String,
+    pub completed: Option<bool>,
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub due_at: Option<Option<String>>,
+}
+
+// Tells a field set to null (Some(None)) from a missing one (None)
+fn deserialize_present<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Option<String>>, D::Error> {
+    Option::<String>::deserialize(deserializer).map(Some)
+}
+
+// permission is only present for the goals shared with the user, deleted_at for the goals in the
+// trash. The dates are only present when the goal has a due date, is completed or archived
 #[derive(Debug, Deserialize, Serialize)]
 pub struct GoalDto {
     pub id: String,
     pub text: String,
     pub user_id: String,
+    pub organization_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permission: Option<String>,
+    pub note_count: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archived_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<String>,
+}
+
+// shared=true lists the goals other users shared with the user, archived=true the archived goals
+// instead of the active ones
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GoalsQueryDto {
+    pub organization_id: Option<String>,
+    pub shared: Option<bool>,
+    pub archived: Option<bool>,
+}
+
+// Personal goals in the trash, or the ones of the organization
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TrashedGoalsQueryDto {
+    pub organization_id: Option<String>,
 }
 
+// version increases with every change of any goal, updated_at is the date of the last change and
+// text_updated_at and completion_updated_at the dates of the text and of the completion kept by the
+// last writer. Archived goals are left out of the
+// lists, goals in the trash are left out everywhere
 pub struct Goal {
     id: String,
     text: String,
     user_id: String,
+    organization_id: Option<String>,
+    note_count: i64,
+    version: i64,
+    updated_at: Option<DateTime<Utc>>,
+    text_updated_at: Option<DateTime<Utc>>,
+    due_at: Option<DateTime<Utc>>,
+    completed_at: Option<DateTime<Utc>>,
+    completion_updated_at: Option<DateTime<Utc>>,
+    archived_at: Option<DateTime<Utc>>,
+    deleted_at: Option<DateTime<Utc>>,
 }
 
 impl Goal {
@@ -29,6 +93,16 @@ impl Goal {
             id: String::from("NO _ID"),
             text: String::from("NO_TEXT"),
             user_id: String::from("NO_USER_ID"),
+            organization_id: None,
+            note_count: 0,
+            version: 0,
+            updated_at: None,
+            text_updated_at: None,
+            due_at: None,
+            completed_at: None,
+            completion_updated_at: None,
+            archived_at: None,
+            deleted_at: None,
         }
     }
 
@@ -50,6 +124,14 @@ impl Goal {
         Ok(())
     }
 
+    pub fn parse_due_at(due_at: &str) -> Result<DateTime<Utc>, InvalidGoalError> {
+        DateTime::parse_from_rfc3339(due_at)
+            .map(|date| date.with_timezone(&Utc))
+            .map_err(|_| {
+                InvalidGoalError::new(Some(String::from("due_at must be an RFC 3339 date")))
+            })
+    }
+
     pub fn set_id(&mut self, id: String) -> Result<(), InvalidGoalError> {
         Goal::validate_id(&id)?;
         self.id = id;
@@ -68,6 +150,62 @@ impl Goal {
         Ok(())
     }
 
+    pub fn set_organization_id(
+        &mut self,
+        organization_id: Option<String>,
+    ) -> Result<(), InvalidGoalError> {
+        if let Some(organization_id) = &organization_id {
+            Organization::validate_id(organization_id)
+                .map_err(|err| InvalidGoalError::new(Some(err.to_string())))?;
+        }
+        self.organization_id = organization_id;
+        Ok(())
+    }
+
+    pub fn set_note_count(&mut self, note_count: i64) {
+        self.note_count = note_count;
+    }
+
+    pub fn set_version(&mut self, version: i64) {
+        self.version = version;
+    }
+
+    pub fn set_updated_at(&mut self, updated_at: Option<DateTime<Utc>>) {
+        self.updated_at = updated_at;
+    }
+
+    pub fn set_text_updated_at(&mut self, text_updated_at: Option<DateTime<Utc>>) {
+        self.text_updated_at = text_updated_at;
+    }
+
+    pub fn set_due_at(&mut self, due_at: Option<DateTime<Utc>>) {
+        self.due_at = due_at;
+    }
+
+    pub fn set_completed_at(&mut self, completed_at: Option<DateTime<Utc>>) {
+        self.completed_at = completed_at;
+    }
+
+    pub fn set_completion_updated_at(&mut self, completion_updated_at: Option<DateTime<Utc>>) {
+        self.completion_updated_at = completion_updated_at;
+    }
+
+    // A goal completed again keeps the date it was first completed
+    pub fn set_completed(&mut self, completed: bool) {
+        self.completed_at = match completed {
+            true => self.completed_at.or(Some(Utc::now())),
+            false => None,
+        };
+    }
+
+    pub fn set_archived_at(&mut self, archived_at: Option<DateTime<Utc>>) {
+        self.archived_at = archived_at;
+    }
+
+    pub fn set_deleted_at(&mut self, deleted_at: Option<DateTime<Utc>>) {
+        self.deleted_at = deleted_at;
+    }
+
     pub fn get_id(&self) -> String {
         self.id.clone()
     }
@@ -80,6 +218,46 @@ impl Goal {
         self.user_id.clone()
     }
 
+    pub fn get_organization_id(&self) -> Option<String> {
+        self.organization_id.clone()
+    }
+
+    pub fn get_note_count(&self) -> i64 {
+        self.note_count
+    }
+
+    pub fn get_version(&self) -> i64 {
+        self.version
+    }
+
+    pub fn get_updated_at(&self) -> Option<DateTime<Utc>> {
+        self.updated_at
+    }
+
+    pub fn get_text_updated_at(&self) -> Option<DateTime<Utc>> {
+        self.text_updated_at
+    }
+
+    pub fn get_due_at(&self) -> Option<DateTime<Utc>> {
+        self.due_at
+    }
+
+    pub fn get_completed_at(&self) -> Option<DateTime<Utc>> {
+        self.completed_at
+    }
+
+    pub fn get_completion_updated_at(&self) -> Option<DateTime<Utc>> {
+        self.completion_updated_at
+    }
+
+    pub fn get_archived_at(&self) -> Option<DateTime<Utc>> {
+        self.archived_at
+    }
+
+    pub fn get_deleted_at(&self) -> Option<DateTime<Utc>> {
+        self.deleted_at
+    }
+
     pub fn from_create_goal_dto(
         create_goal: CreateGoalDto,
         user_id: &str,
@@ -87,14 +265,43 @@ impl Goal {
         let mut goal = Goal::new();
         goal.set_user_id(user_id.to_string())?;
         goal.set_text(create_goal.text)?;
+        goal.set_organization_id(create_goal.organization_id)?;
+        if let Some(due_at) = create_goal.due_at {
+            goal.set_due_at(Some(Goal::parse_due_at(&due_at)?));
+        }
         Ok(goal)
     }
 
-    pub fn from_db_fields(id: &str, text: &str, user_id: &str) -> Result<Goal, InvalidGoalError> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_db_fields(
+        id: &str,
+        text: &str,
+        user_id: &str,
+        organization_id: Option<String>,
+        note_count: i64,
+        version: i64,
+        updated_at: Option<DateTime<Utc>>,
+        text_updated_at: Option<DateTime<Utc>>,
+        due_at: Option<DateTime<Utc>>,
+        completed_at: Option<DateTime<Utc>>,
+        completion_updated_at: Option<DateTime<Utc>>,
+        archived_at: Option<DateTime<Utc>>,
+        deleted_at: Option<DateTime<Utc>>,
+    ) -> Result<Goal, InvalidGoalError> {
         let mut goal = Goal::new();
         goal.set_id(id.to_string())?;
         goal.set_text(text.to_string())?;
         goal.set_user_id(user_id.to_string())?;
+        goal.set_organization_id(organization_id)?;
+        goal.set_note_count(note_count);
+        goal.set_version(version);
+        goal.set_updated_at(updated_at);
+        goal.set_text_updated_at(text_updated_at);
+        goal.set_due_at(due_at);
+        goal.set_completed_at(completed_at);
+        goal.set_completion_updated_at(completion_updated_at);
+        goal.set_archived_at(archived_at);
+        goal.set_deleted_at(deleted_at);
         Ok(goal)
     }
 }
@@ -0,0 +1,144 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::errors::goal_event_errors::InvalidGoalEventError;
+
+use super::{
+    goal::Goal,
+    user::User,
+    webhook::{
+        GOAL_ARCHIVED_EVENT, GOAL_COMPLETED_EVENT, GOAL_CREATED_EVENT, GOAL_DELETED_EVENT,
+        GOAL_RESTORED_EVENT, GOAL_UNARCHIVED_EVENT, GOAL_UPDATED_EVENT,
+    },
+};
+
+// Ids increase with every event, they are the ids of the Server-Sent Events
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GoalEventDto {
+    pub id: i64,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub goal_id: String,
+    pub data: Value,
+    pub created_at: Option<String>,
+}
+
+// last_event_id is the id to resume from, even when there are no events. waiting is true when
+// events of the user after it cannot be read yet, they wait for earlier transactions to end or for
+// the next page
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GoalEventsDto {
+    pub events: Vec<GoalEventDto>,
+    pub last_event_id: i64,
+    pub waiting: bool,
+}
+
+// Change of a goal, stored for every user that can see the goal when it happens
+pub struct GoalEvent {
+    id: i64,
+    user_id: String,
+    goal_id: String,
+    event_type: String,
+    payload: String,
+    created_at: Option<DateTime<Utc>>,
+}
+
+impl GoalEvent {
+    fn new() -> GoalEvent {
+        GoalEvent {
+            id: 0,
+            user_id: String::from("NO_USER_ID"),
+            goal_id: String::from("NO_GOAL_ID"),
+            event_type: String::from(GOAL_UPDATED_EVENT),
+            payload: String::from("{}"),
+            created_at: None,
+        }
+    }
+
+    pub fn validate_event_type(event_type: &str) -> Result<(), InvalidGoalEventError> {
+        match event_type {
+            GOAL_CREATED_EVENT
+            | GOAL_UPDATED_EVENT
+            | GOAL_DELETED_EVENT
+            | GOAL_RESTORED_EVENT
+            | GOAL_ARCHIVED_EVENT
+            | GOAL_UNARCHIVED_EVENT
+            | GOAL_COMPLETED_EVENT => Ok(()),
+            _ => Err(InvalidGoalEventError::new(Some(format!(
+                "Unknown goal event type: {}",
+                event_type
+            )))),
+        }
+    }
+
+    pub fn set_id(&mut self, id: i64) {
+        self.id = id;
+    }
+
+    pub fn set_user_id(&mut self, user_id: String) -> Result<(), InvalidGoalEventError> {
+        User::validate_id(&user_id)
+            .map_err(|err| InvalidGoalEventError::new(Some(err.to_string())))?;
+        self.user_id = user_id;
+        Ok(())
+    }
+
+    pub fn set_goal_id(&mut self, goal_id: String) -> Result<(), InvalidGoalEventError> {
+        Goal::validate_id(&goal_id)
+            .map_err(|err| InvalidGoalEventError::new(Some(err.to_string())))?;
+        self.goal_id = goal_id;
+        Ok(())
+    }
+
+    pub fn set_event_type(&mut self, event_type: String) -> Result<(), InvalidGoalEventError> {
+        GoalEvent::validate_event_type(&event_type)?;
+        self.event_type = event_type;
+        Ok(())
+    }
+
+    pub fn set_payload(&mut self, payload: String) {
+        self.payload = payload;
+    }
+
+    pub fn set_created_at(&mut self, created_at: Option<DateTime<Utc>>) {
+        self.created_at = created_at;
+    }
+
+    pub fn get_id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn get_goal_id(&self) -> String {
+        self.goal_id.clone()
+    }
+
+    pub fn get_event_type(&self) -> String {
+        self.event_type.clone()
+    }
+
+    pub fn get_payload(&self) -> String {
+        self.payload.clone()
+    }
+
+    pub fn get_created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    pub fn from_db_fields(
+        id: i64,
+        user_id: &str,
+        goal_id: &str,
+        event_type: &str,
+        payload: &str,
+        created_at: Option<DateTime<Utc>>,
+    ) -> Result<GoalEvent, InvalidGoalEventError> {
+        let mut goal_event = GoalEvent::new();
+        goal_event.set_id(id);
+        goal_event.set_user_id(user_id.to_string())?;
+        goal_event.set_goal_id(goal_id.to_string())?;
+        goal_event.set_event_type(event_type.to_string())?;
+        goal_event.set_payload(payload.to_string());
+        goal_event.set_created_at(created_at);
+        Ok(goal_event)
+    }
+}
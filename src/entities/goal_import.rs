@@ -0,0 +1,137 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::note;
+
+pub const IMPORTED_STATUS: &str = "imported";
+pub const VALID_STATUS: &str = "valid";
+pub const INVALID_STATUS: &str = "invalid";
+
+pub const TRELLO_SOURCE: &str = "trello";
+pub const TODOIST_SOURCE: &str = "todoist";
+pub const ASANA_SOURCE: &str = "asana";
+
+// The format defaults to json. With dry_run the rows are only validated. The goals are added to
+// the organization when organization_id is present
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ImportGoalsQueryDto {
+    pub format: Option<String>,
+    pub dry_run: Option<bool>,
+    pub organization_id: Option<String>,
+}
+
+// An element of the JSON array, the array can also have the texts only
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ImportedGoalDto {
+    pub text: String,
+    pub completed: Option<bool>,
+}
+
+// status is imported, or valid in a dry run, when the goal passed the validation, invalid otherwise
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ImportRowReportDto {
+    pub row: usize,
+    pub status: String,
+    pub text: Option<String>,
+    pub goal_id: Option<String>,
+    pub error: Option<String>,
+}
+
+// imported is 0 in a dry run
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ImportReportDto {
+    pub dry_run: bool,
+    pub total: usize,
+    pub valid: usize,
+    pub invalid: usize,
+    pub imported: usize,
+    pub rows: Vec<ImportRowReportDto>,
+}
+
+// A row read from the document, before the validation. row is the line of the row in CSV and
+// Markdown, its position from 1 in JSON. error is set when the row could not be read. note is added
+// to the journal of the goal, with what an import could not map to a goal field
+#[derive(Debug)]
+pub struct ImportRow {
+    pub row: usize,
+    pub text: String,
+    pub completed: bool,
+    pub due_at: Option<DateTime<Utc>>,
+    pub note: Option<String>,
+    pub error: Option<String>,
+}
+
+impl ImportRow {
+    pub fn new(row: usize, text: &str, completed: bool) -> ImportRow {
+        ImportRow {
+            row,
+            text: text.to_string(),
+            completed,
+            due_at: None,
+            note: None,
+            error: None,
+        }
+    }
+
+    pub fn with_due_at(mut self, due_at: Option<DateTime<Utc>>) -> ImportRow {
+        self.due_at = due_at;
+        self
+    }
+
+    // Blank notes are left out and long ones are cut to the length of a note
+    pub fn with_note(mut self, note: String) -> ImportRow {
+        let note = note.trim();
+        if note.is_empty() {
+            return self;
+        }
+
+        self.note = match note.char_indices().nth(note::MAX_TEXT_LENGTH) {
+            None => Some(note.to_string()),
+            Some((index, _)) => Some(note[..index].to_string()),
+        };
+        self
+    }
+
+    pub fn unreadable(row: usize, error: String) -> ImportRow {
+        ImportRow {
+            row,
+            text: String::new(),
+            completed: false,
+            due_at: None,
+            note: None,
+            error: Some(error),
+        }
+    }
+
+    // true, yes, x, 1 and done are completed, anything else is not
+    pub fn parse_completed(value: &str) -> bool {
+        matches!(
+            value.trim().to_lowercase().as_str(),
+            "true" | "yes" | "x" | "1" | "done"
+        )
+    }
+}
+
+// What the import of a task tool export did with each kind of item, with the count of items. to
+// is the goal field the items were mapped to, reason why they were skipped
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ImportSummaryEntryDto {
+    pub item: String,
+    pub count: usize,
+    pub to: Option<String>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ToolImportReportDto {
+    pub source: String,
+    pub mapped: Vec<ImportSummaryEntryDto>,
+    pub skipped: Vec<ImportSummaryEntryDto>,
+    pub report: ImportReportDto,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ToolImportQueryDto {
+    pub dry_run: Option<bool>,
+    pub organization_id: Option<String>,
+}
@@ -0,0 +1,224 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::errors::goal_revision_errors::InvalidGoalRevisionError;
+
+use super::{goal::Goal, user::User};
+
+pub const CREATE_ACTION: &str = "create";
+pub const UPDATE_ACTION: &str = "update";
+pub const DELETE_ACTION: &str = "delete";
+pub const RESTORE_ACTION: &str = "restore";
+pub const ARCHIVE_ACTION: &str = "archive";
+pub const UNARCHIVE_ACTION: &str = "unarchive";
+pub const REVERT_ACTION: &str = "revert";
+
+// changes has the fields that changed, each with its before and after values. actor_id is missing
+// for the changes made by the server, like the auto-archiving, or when the user was deleted
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GoalRevisionDto {
+    pub id: i64,
+    pub action: String,
+    pub actor_id: Option<String>,
+    pub actor_name: Option<String>,
+    pub changes: Value,
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GoalHistoryQueryDto {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+// A change of a goal. before and after are JSON snapshots of the fields of the goal a user can
+// change, before is missing for the creation
+pub struct GoalRevision {
+    id: i64,
+    goal_id: String,
+    actor_id: Option<String>,
+    actor_name: Option<String>,
+    action: String,
+    before: Option<String>,
+    after: String,
+    created_at: Option<DateTime<Utc>>,
+}
+
+impl GoalRevision {
+    fn new() -> GoalRevision {
+        GoalRevision {
+            id: 0,
+            goal_id: String::from("NO_GOAL_ID"),
+            actor_id: None,
+            actor_name: None,
+            action: String::from(UPDATE_ACTION),
+            before: None,
+            after: String::from("{}"),
+            created_at: None,
+        }
+    }
+
+    pub fn parse_id(id: &str) -> Result<i64, InvalidGoalRevisionError> {
+        id.parse::<i64>().map_err(|_| {
+            InvalidGoalRevisionError::new(Some(String::from("Revision id is not a valid number")))
+        })
+    }
+
+    pub fn validate_action(action: &str) -> Result<(), InvalidGoalRevisionError> {
+        match action {
+            CREATE_ACTION | UPDATE_ACTION | DELETE_ACTION | RESTORE_ACTION | ARCHIVE_ACTION
+            | UNARCHIVE_ACTION | REVERT_ACTION => Ok(()),
+            _ => Err(InvalidGoalRevisionError::new(Some(format!(
+                "Unknown revision action: {}",
+                action
+            )))),
+        }
+    }
+
+    // The fields kept in the history, the counters and versions are left out
+    pub fn snapshot(goal: &Goal) -> String {
+        json!({
+            "text": goal.get_text(),
+            "due_at": goal.get_due_at().map(|date| date.to_rfc3339()),
+            "completed_at": goal.get_completed_at().map(|date| date.to_rfc3339()),
+            "archived_at": goal.get_archived_at().map(|date| date.to_rfc3339()),
+            "deleted_at": goal.get_deleted_at().map(|date| date.to_rfc3339()),
+        })
+        .to_string()
+    }
+
+    pub fn set_id(&mut self, id: i64) {
+        self.id = id;
+    }
+
+    pub fn set_goal_id(&mut self, goal_id: String) -> Result<(), InvalidGoalRevisionError> {
+        Goal::validate_id(&goal_id)
+            .map_err(|err| InvalidGoalRevisionError::new(Some(err.to_string())))?;
+        self.goal_id = goal_id;
+        Ok(())
+    }
+
+    pub fn set_actor_id(
+        &mut self,
+        actor_id: Option<String>,
+    ) -> Result<(), InvalidGoalRevisionError> {
+        if let Some(actor_id) = &actor_id {
+            User::validate_id(actor_id)
+                .map_err(|err| InvalidGoalRevisionError::new(Some(err.to_string())))?;
+        }
+        self.actor_id = actor_id;
+        Ok(())
+    }
+
+    pub fn set_actor_name(&mut self, actor_name: Option<String>) {
+        self.actor_name = actor_name;
+    }
+
+    pub fn set_action(&mut self, action: String) -> Result<(), InvalidGoalRevisionError> {
+        GoalRevision::validate_action(&action)?;
+        self.action = action;
+        Ok(())
+    }
+
+    pub fn set_before(&mut self, before: Option<String>) {
+        self.before = before;
+    }
+
+    pub fn set_after(&mut self, after: String) {
+        self.after = after;
+    }
+
+    pub fn set_created_at(&mut self, created_at: Option<DateTime<Utc>>) {
+        self.created_at = created_at;
+    }
+
+    pub fn get_id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn get_goal_id(&self) -> String {
+        self.goal_id.clone()
+    }
+
+    pub fn get_actor_id(&self) -> Option<String> {
+        self.actor_id.clone()
+    }
+
+    pub fn get_actor_name(&self) -> Option<String> {
+        self.actor_name.clone()
+    }
+
+    pub fn get_action(&self) -> String {
+        self.action.clone()
+    }
+
+    pub fn get_created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    fn parse_snapshot(snapshot: Option<&str>) -> Map<String, Value> {
+        match snapshot.and_then(|snapshot| serde_json::from_str::<Value>(snapshot).ok()) {
+            Some(Value::Object(fields)) => fields,
+            _ => Map::new(),
+        }
+    }
+
+    // Fields whose value differs between the snapshots, a field missing from one is null
+    pub fn get_changes(&self) -> Value {
+        let before = GoalRevision::parse_snapshot(self.before.as_deref());
+        let after = GoalRevision::parse_snapshot(Some(&self.after));
+
+        let mut changes = Map::new();
+        for (field, after_value) in after.iter() {
+            let before_value = before.get(field).cloned().unwrap_or(Value::Null);
+            if &before_value != after_value {
+                changes.insert(
+                    field.clone(),
+                    json!({ "before": before_value, "after": after_value }),
+                );
+            }
+        }
+        Value::Object(changes)
+    }
+
+    // Text of the goal once the change was made
+    pub fn get_after_text(&self) -> Option<String> {
+        GoalRevision::parse_snapshot(Some(&self.after))
+            .get("text")
+            .and_then(|text| text.as_str())
+            .map(|text| text.to_string())
+    }
+
+    // Completion date of the goal once the change was made
+    pub fn get_after_completed_at(&self) -> Option<DateTime<Utc>> {
+        GoalRevision::parse_snapshot(Some(&self.after))
+            .get("completed_at")
+            .and_then(|date| date.as_str())
+            .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+            .map(|date| date.with_timezone(&Utc))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_db_fields(
+        id: i64,
+        goal_id: &str,
+        actor_id: Option<String>,
+        actor_name: Option<String>,
+        action: &str,
+        before: Option<String>,
+        after: &str,
+        created_at: Option<DateTime<Utc>>,
+    ) -> Result<GoalRevision, InvalidGoalRevisionError> {
+        let mut revision = GoalRevision::new();
+        revision.set_id(id);
+        revision.set_goal_id(goal_id.to_string())?;
+        revision.set_actor_id(actor_id)?;
+        revision.set_actor_name(actor_name);
+        revision.set_action(action.to_string())?;
+        revision.set_before(before);
+        revision.set_after(after.to_string());
+        revision.set_created_at(created_at);
+        Ok(revision)
+    }
+}
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+use crate::errors::goal_errors::InvalidGoalError;
+
+use super::{goal::Goal, user::User};
+
+pub const VIEW_PERMISSION: &str = "view";
+pub const EDIT_PERMISSION: &str = "edit";
+
+// The goal is shared with the user that signed up with the e-mail
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateGoalShareDto {
+    pub email: String,
+    pub permission: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GoalShareDto {
+    pub goal_id: String,
+    pub user_id: String,
+    pub name: String,
+    pub email: String,
+    pub permission: String,
+}
+
+// Access of a user to a single goal of another user
+pub struct GoalShare {
+    goal_id: String,
+    user_id: String,
+    permission: String,
+}
+
+impl GoalShare {
+    fn new() -> GoalShare {
+        GoalShare {
+            goal_id: String::from("NO_GOAL_ID"),
+            user_id: String::from("NO_USER_ID"),
+            permission: String::from(VIEW_PERMISSION),
+        }
+    }
+
+    pub fn validate_permission(permission: &str) -> Result<(), InvalidGoalError> {
+        if permission != VIEW_PERMISSION && permission != EDIT_PERMISSION {
+            return Err(InvalidGoalError::new(Some(format!(
+                "Share permission must be one of: {}, {}",
+                VIEW_PERMISSION, EDIT_PERMISSION
+            ))));
+        }
+        Ok(())
+    }
+
+    pub fn set_goal_id(&mut self, goal_id: String) -> Result<(), InvalidGoalError> {
+        Goal::validate_id(&goal_id)?;
+        self.goal_id = goal_id;
+        Ok(())
+    }
+
+    pub fn set_user_id(&mut self, user_id: String) -> Result<(), InvalidGoalError> {
+        User::validate_id(&user_id).map_err(|err| InvalidGoalError::new(Some(err.to_string())))?;
+        self.user_id = user_id;
+        Ok(())
+    }
+
+    pub fn set_permission(&mut self, permission: String) -> Result<(), InvalidGoalError> {
+        GoalShare::validate_permission(&permission)?;
+        self.permission = permission;
+        Ok(())
+    }
+
+    pub fn get_goal_id(&self) -> String {
+        self.goal_id.clone()
+    }
+
+    pub fn get_user_id(&self) -> String {
+        self.user_id.clone()
+    }
+
+    pub fn get_permission(&self) -> String {
+        self.permission.clone()
+    }
+
+    pub fn from_db_fields(
+        goal_id: &str,
+        user_id: &str,
+        permission: &str,
+    ) -> Result<GoalShare, InvalidGoalError> {
+        let mut goal_share = GoalShare::new();
+        goal_share.set_goal_id(goal_id.to_string())?;
+        goal_share.set_user_id(user_id.to_string())?;
+        goal_share.set_permission(permission.to_string())?;
+        Ok(goal_share)
+    }
+}
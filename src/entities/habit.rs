@@ -0,0 +1,516 @@
+use std::collections::BTreeSet;
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::habit_errors::InvalidHabitError;
+
+use super::goal::Goal;
+
+pub const DAILY_RECURRENCE: &str = "daily";
+pub const WEEKDAYS_RECURRENCE: &str = "weekdays";
+pub const EVERY_N_DAYS_RECURRENCE: &str = "every_n_days";
+pub const WEEKLY_RECURRENCE: &str = "weekly";
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const MAX_INTERVAL_DAYS: i32 = 365;
+
+// interval_days is required by every_n_days and times_per_week by weekly, they are ignored by the
+// other recurrences. start_date defaults to today
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SetHabitDto {
+    pub recurrence: String,
+    pub interval_days: Option<i32>,
+    pub times_per_week: Option<i32>,
+    pub start_date: Option<String>,
+}
+
+// Streaks are counted in periods: days, weekdays, blocks of interval_days days or weeks
+#[derive(Debug, Deserialize, Serialize)]
+pub struct HabitDto {
+    pub goal_id: String,
+    pub recurrence: String,
+    pub interval_days: Option<i32>,
+    pub times_per_week: Option<i32>,
+    pub start_date: String,
+    pub current_streak: i64,
+    pub longest_streak: i64,
+    pub last_check_in: Option<String>,
+}
+
+// Dates are YYYY-MM-DD, the check-in defaults to today
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CheckInDto {
+    pub date: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct HabitHistoryQueryDto {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+// One entry per day, period_completed tells if the period of the day reached its target
+#[derive(Debug, Deserialize, Serialize)]
+pub struct HabitDayDto {
+    pub date: String,
+    pub checked_in: bool,
+    pub period_completed: bool,
+}
+
+// Recurrence rule of a goal tracked as a habit
+pub struct Habit {
+    goal_id: String,
+    recurrence: String,
+    interval_days: Option<i32>,
+    times_per_week: Option<i32>,
+    start_date: NaiveDate,
+}
+
+impl Habit {
+    fn new() -> Habit {
+        Habit {
+            goal_id: String::from("NO_GOAL_ID"),
+            recurrence: String::from(DAILY_RECURRENCE),
+            interval_days: None,
+            times_per_week: None,
+            start_date: NaiveDate::default(),
+        }
+    }
+
+    pub fn parse_date(date: &str) -> Result<NaiveDate, InvalidHabitError> {
+        NaiveDate::parse_from_str(date, DATE_FORMAT).map_err(|_| {
+            InvalidHabitError::new(Some(format!(
+                "Date is not a valid YYYY-MM-DD date: {}",
+                date
+            )))
+        })
+    }
+
+    pub fn format_date(date: &NaiveDate) -> String {
+        date.format(DATE_FORMAT).to_string()
+    }
+
+    pub fn validate_recurrence(
+        recurrence: &str,
+        interval_days: Option<i32>,
+        times_per_week: Option<i32>,
+    ) -> Result<(), InvalidHabitError> {
+        match recurrence {
+            DAILY_RECURRENCE | WEEKDAYS_RECURRENCE => Ok(()),
+            EVERY_N_DAYS_RECURRENCE => match interval_days {
+                Some(days) if (1..=MAX_INTERVAL_DAYS).contains(&days) => Ok(()),
+                _ => Err(InvalidHabitError::new(Some(format!(
+                    "interval_days must be between 1 and {} for the {} recurrence",
+                    MAX_INTERVAL_DAYS, EVERY_N_DAYS_RECURRENCE
+                )))),
+            },
+            WEEKLY_RECURRENCE => match times_per_week {
+                Some(times) if (1..=7).contains(&times) => Ok(()),
+                _ => Err(InvalidHabitError::new(Some(format!(
+                    "times_per_week must be between 1 and 7 for the {} recurrence",
+                    WEEKLY_RECURRENCE
+                )))),
+            },
+            _ => Err(InvalidHabitError::new(Some(format!(
+                "Recurrence must be one of: {}, {}, {}, {}",
+                DAILY_RECURRENCE, WEEKDAYS_RECURRENCE, EVERY_N_DAYS_RECURRENCE, WEEKLY_RECURRENCE
+            )))),
+        }
+    }
+
+    pub fn set_goal_id(&mut self, goal_id: String) -> Result<(), InvalidHabitError> {
+        Goal::validate_id(&goal_id).map_err(|err| InvalidHabitError::new(Some(err.to_string())))?;
+        self.goal_id = goal_id;
+        Ok(())
+    }
+
+    // Only the value used by the recurrence is kept
+    pub fn set_recurrence(
+        &mut self,
+        recurrence: String,
+        interval_days: Option<i32>,
+        times_per_week: Option<i32>,
+    ) -> Result<(), InvalidHabitError> {
+        Habit::validate_recurrence(&recurrence, interval_days, times_per_week)?;
+        self.interval_days = match recurrence.as_str() {
+            EVERY_N_DAYS_RECURRENCE => interval_days,
+            _ => None,
+        };
+        self.times_per_week = match recurrence.as_str() {
+            WEEKLY_RECURRENCE => times_per_week,
+            _ => None,
+        };
+        self.recurrence = recurrence;
+        Ok(())
+    }
+
+    pub fn set_start_date(&mut self, start_date: NaiveDate) {
+        self.start_date = start_date;
+    }
+
+    pub fn get_goal_id(&self) -> String {
+        self.goal_id.clone()
+    }
+
+    pub fn get_recurrence(&self) -> String {
+        self.recurrence.clone()
+    }
+
+    pub fn get_interval_days(&self) -> Option<i32> {
+        self.interval_days
+    }
+
+    pub fn get_times_per_week(&self) -> Option<i32> {
+        self.times_per_week
+    }
+
+    pub fn get_start_date(&self) -> NaiveDate {
+        self.start_date
+    }
+
+    // First day of the period the date belongs to. Weekends are not part of any period of the
+    // weekdays recurrence and the days before the start date are not part of any period
+    fn period_start(&self, date: NaiveDate) -> Option<NaiveDate> {
+        if date < self.start_date {
+            return None;
+        }
+        match self.recurrence.as_str() {
+            WEEKDAYS_RECURRENCE => match date.weekday() {
+                Weekday::Sat | Weekday::Sun => None,
+                _ => Some(date),
+            },
+            EVERY_N_DAYS_RECURRENCE => {
+                let interval = self.interval_days.unwrap_or(1) as i64;
+                let elapsed = (date - self.start_date).num_days();
+                Some(self.start_date + Duration::days(elapsed - elapsed % interval))
+            }
+            WEEKLY_RECURRENCE => {
+                let monday = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+                Some(monday.max(self.start_date))
+            }
+            _ => Some(date),
+        }
+    }
+
+    // Last day of the period that starts on the date
+    fn period_end(&self, period_start: NaiveDate) -> NaiveDate {
+        match self.recurrence.as_str() {
+            EVERY_N_DAYS_RECURRENCE => {
+                period_start + Duration::days(self.interval_days.unwrap_or(1) as i64 - 1)
+            }
+            WEEKLY_RECURRENCE => {
+                let days_to_sunday = 6 - period_start.weekday().num_days_from_monday() as i64;
+                period_start + Duration::days(days_to_sunday)
+            }
+            _ => period_start,
+        }
+    }
+
+    // The first week of a weekly habit is cut at the start date, its target is scaled to the days
+    // left in it (rounded up), so a 3 times a week habit started on a Saturday needs 1 check-in
+    fn required_check_ins(&self, period_start: NaiveDate, period_end: NaiveDate) -> usize {
+        match self.recurrence.as_str() {
+            WEEKLY_RECURRENCE => {
+                let times = self.times_per_week.unwrap_or(1) as i64;
+                let days = (period_end - period_start).num_days() + 1;
+                ((times * days + 6) / 7).clamp(1, times) as usize
+            }
+            _ => 1,
+        }
+    }
+
+    fn is_period_completed(
+        &self,
+        period_start: NaiveDate,
+        check_ins: &BTreeSet<NaiveDate>,
+    ) -> bool {
+        let period_end = self.period_end(period_start);
+        check_ins.range(period_start..=period_end).count()
+            >= self.required_check_ins(period_start, period_end)
+    }
+
+    // Completion of every period from the start date to the period of today, oldest first
+    fn completed_periods(&self, check_ins: &BTreeSet<NaiveDate>, today: NaiveDate) -> Vec<bool> {
+        let mut periods = Vec::new();
+        let mut date = self.start_date;
+        while date <= today {
+            match self.period_start(date) {
+                None => date += Duration::days(1),
+                Some(period_start) => {
+                    periods.push(self.is_period_completed(period_start, check_ins));
+                    date = self.period_end(period_start) + Duration::days(1);
+                }
+            }
+        }
+        periods
+    }
+
+    // Returns the current and the longest streak, in periods. The period of today is still in
+    // progress, so the current streak is not broken until it is over
+    pub fn compute_streaks(&self, check_ins: &[NaiveDate], today: NaiveDate) -> (i64, i64) {
+        let check_ins: BTreeSet<NaiveDate> = check_ins.iter().cloned().collect();
+        let mut periods = self.completed_periods(&check_ins, today);
+
+        let mut longest = 0;
+        let mut run = 0;
+        for completed in periods.iter() {
+            run = if *completed { run + 1 } else { 0 };
+            longest = longest.max(run);
+        }
+
+        if self.period_start(today).is_some() && periods.last() == Some(&false) {
+            periods.pop();
+        }
+        let current = periods
+            .iter()
+            .rev()
+            .take_while(|completed| **completed)
+            .count() as i64;
+
+        (current, longest)
+    }
+
+    // One entry per day between the dates, both included
+    pub fn history(
+        &self,
+        check_ins: &[NaiveDate],
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Vec<HabitDayDto> {
+        let check_ins: BTreeSet<NaiveDate> = check_ins.iter().cloned().collect();
+        let mut days = Vec::new();
+        let mut date = from;
+        while date <= to {
+            let period_completed = match self.period_start(date) {
+                None => false,
+                Some(period_start) => self.is_period_completed(period_start, &check_ins),
+            };
+            days.push(HabitDayDto {
+                date: Habit::format_date(&date),
+                checked_in: check_ins.contains(&date),
+                period_completed,
+            });
+            date += Duration::days(1);
+        }
+        days
+    }
+
+    pub fn from_set_habit_dto(
+        set_habit: SetHabitDto,
+        goal_id: &str,
+        today: NaiveDate,
+    ) -> Result<Habit, InvalidHabitError> {
+        let mut habit = Habit::new();
+        habit.set_goal_id(goal_id.to_string())?;
+        habit.set_recurrence(
+            set_habit.recurrence,
+            set_habit.interval_days,
+            set_habit.times_per_week,
+        )?;
+        let start_date = match set_habit.start_date {
+            None => today,
+            Some(start_date) => Habit::parse_date(&start_date)?,
+        };
+        habit.set_start_date(start_date);
+        Ok(habit)
+    }
+
+    pub fn from_db_fields(
+        goal_id: &str,
+        recurrence: &str,
+        interval_days: Option<i32>,
+        times_per_week: Option<i32>,
+        start_date: NaiveDate,
+    ) -> Result<Habit, InvalidHabitError> {
+        let mut habit = Habit::new();
+        habit.set_goal_id(goal_id.to_string())?;
+        habit.set_recurrence(recurrence.to_string(), interval_days, times_per_week)?;
+        habit.set_start_date(start_date);
+        Ok(habit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GOAL_ID: &str = "6f1c8f4e-2a52-4d8e-9a39-0c3f1b2d7e11";
+
+    fn date(value: &str) -> NaiveDate {
+        Habit::parse_date(value).unwrap()
+    }
+
+    fn dates(values: &[&str]) -> Vec<NaiveDate> {
+        values.iter().map(|value| date(value)).collect()
+    }
+
+    fn habit(
+        recurrence: &str,
+        interval_days: Option<i32>,
+        times_per_week: Option<i32>,
+        start_date: &str,
+    ) -> Habit {
+        Habit::from_db_fields(
+            GOAL_ID,
+            recurrence,
+            interval_days,
+            times_per_week,
+            date(start_date),
+        )
+        .unwrap()
+    }
+
+    // 2024-05-06 is a Monday
+
+    #[test]
+    fn daily_counts_consecutive_days() {
+        let habit = habit(DAILY_RECURRENCE, None, None, "2024-05-06");
+        let check_ins = dates(&["2024-05-06", "2024-05-07", "2024-05-08", "2024-05-10"]);
+
+        assert_eq!(
+            habit.compute_streaks(&check_ins, date("2024-05-10")),
+            (1, 3)
+        );
+    }
+
+    #[test]
+    fn daily_today_in_progress_does_not_break_the_streak() {
+        let habit = habit(DAILY_RECURRENCE, None, None, "2024-05-06");
+        let check_ins = dates(&["2024-05-06", "2024-05-07"]);
+
+        assert_eq!(
+            habit.compute_streaks(&check_ins, date("2024-05-08")),
+            (2, 2)
+        );
+        assert_eq!(
+            habit.compute_streaks(&check_ins, date("2024-05-09")),
+            (0, 2)
+        );
+    }
+
+    #[test]
+    fn weekdays_skip_the_weekend() {
+        let habit = habit(WEEKDAYS_RECURRENCE, None, None, "2024-05-06");
+        let check_ins = dates(&[
+            "2024-05-06",
+            "2024-05-07",
+            "2024-05-08",
+            "2024-05-09",
+            "2024-05-10",
+            "2024-05-13",
+        ]);
+
+        assert_eq!(
+            habit.compute_streaks(&check_ins, date("2024-05-13")),
+            (6, 6)
+        );
+    }
+
+    #[test]
+    fn weekdays_missed_friday_breaks_the_streak_on_the_weekend() {
+        let habit = habit(WEEKDAYS_RECURRENCE, None, None, "2024-05-06");
+        let check_ins = dates(&["2024-05-06", "2024-05-07", "2024-05-08", "2024-05-09"]);
+
+        assert_eq!(
+            habit.compute_streaks(&check_ins, date("2024-05-10")),
+            (4, 4)
+        );
+        assert_eq!(
+            habit.compute_streaks(&check_ins, date("2024-05-11")),
+            (0, 4)
+        );
+    }
+
+    #[test]
+    fn every_n_days_needs_one_check_in_per_block() {
+        let habit = habit(EVERY_N_DAYS_RECURRENCE, Some(3), None, "2024-05-06");
+        // Blocks: 06-08, 09-11, 12-14, 15-17
+        let check_ins = dates(&["2024-05-08", "2024-05-09", "2024-05-15"]);
+
+        assert_eq!(
+            habit.compute_streaks(&check_ins, date("2024-05-11")),
+            (2, 2)
+        );
+        assert_eq!(
+            habit.compute_streaks(&check_ins, date("2024-05-16")),
+            (1, 2)
+        );
+    }
+
+    #[test]
+    fn every_n_days_current_block_in_progress() {
+        let habit = habit(EVERY_N_DAYS_RECURRENCE, Some(3), None, "2024-05-06");
+        let check_ins = dates(&["2024-05-06"]);
+
+        assert_eq!(
+            habit.compute_streaks(&check_ins, date("2024-05-11")),
+            (1, 1)
+        );
+        assert_eq!(
+            habit.compute_streaks(&check_ins, date("2024-05-12")),
+            (0, 1)
+        );
+    }
+
+    #[test]
+    fn weekly_needs_the_times_per_week() {
+        let habit = habit(WEEKLY_RECURRENCE, None, Some(2), "2024-05-06");
+        let check_ins = dates(&["2024-05-06", "2024-05-09", "2024-05-14", "2024-05-20"]);
+
+        // The week of the 13th has a single check-in
+        assert_eq!(
+            habit.compute_streaks(&check_ins, date("2024-05-20")),
+            (0, 1)
+        );
+    }
+
+    #[test]
+    fn weekly_current_week_in_progress() {
+        let habit = habit(WEEKLY_RECURRENCE, None, Some(2), "2024-05-06");
+        let check_ins = dates(&["2024-05-06", "2024-05-09", "2024-05-13"]);
+
+        assert_eq!(
+            habit.compute_streaks(&check_ins, date("2024-05-15")),
+            (1, 1)
+        );
+        assert_eq!(
+            habit.compute_streaks(&check_ins, date("2024-05-20")),
+            (0, 1)
+        );
+    }
+
+    #[test]
+    fn weekly_partial_first_week_has_a_scaled_target() {
+        // Started on a Saturday, 2 days are left in the first week: 3 * 2 / 7 rounded up is 1
+        let habit = habit(WEEKLY_RECURRENCE, None, Some(3), "2024-05-11");
+        let check_ins = dates(&["2024-05-12", "2024-05-13", "2024-05-15", "2024-05-17"]);
+
+        assert_eq!(
+            habit.compute_streaks(&check_ins, date("2024-05-20")),
+            (2, 2)
+        );
+    }
+
+    #[test]
+    fn weekly_partial_first_week_still_needs_a_check_in() {
+        let habit = habit(WEEKLY_RECURRENCE, None, Some(3), "2024-05-11");
+        let check_ins = dates(&["2024-05-13", "2024-05-15", "2024-05-17"]);
+
+        assert_eq!(
+            habit.compute_streaks(&check_ins, date("2024-05-20")),
+            (1, 1)
+        );
+    }
+
+    #[test]
+    fn days_before_the_start_date_are_not_counted() {
+        let habit = habit(DAILY_RECURRENCE, None, None, "2024-05-08");
+        let check_ins = dates(&["2024-05-06", "2024-05-07", "2024-05-08"]);
+
+        assert_eq!(
+            habit.compute_streaks(&check_ins, date("2024-05-08")),
+            (1, 1)
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::errors::organization_errors::InvalidOrganizationError;
+
+use super::{
+    organization::{Organization, OrganizationMember, OWNER_ROLE},
+    user::User,
+};
+
+const INVITATION_DAYS: i64 = 7;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateInvitationDto {
+    pub email: String,
+    pub role: String,
+}
+
+// Only returned once, when the invitation is created. The plain token is never stored
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreatedInvitationDto {
+    pub id: String,
+    pub organization_id: String,
+    pub email: String,
+    pub role: String,
+    pub expires_at: String,
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct InvitationTokenDto {
+    pub token: String,
+}
+
+pub struct Invitation {
+    id: String,
+    organization_id: String,
+    email: String,
+    role: String,
+    token_hash: String,
+    expires_at: DateTime<Utc>,
+}
+
+impl Invitation {
+    fn new() -> Invitation {
+        Invitation {
+            id: String::from("NO_ID"),
+            organization_id: String::from("NO_ORGANIZATION_ID"),
+            email: String::from("NO_EMAIL"),
+            role: String::from("NO_ROLE"),
+            token_hash: String::from("NO_TOKEN_HASH"),
+            expires_at: Utc::now(),
+        }
+    }
+
+    pub fn validate_id(id: &str) -> Result<(), InvalidOrganizationError> {
+        match Uuid::parse_str(id) {
+            Err(_) => Err(InvalidOrganizationError::new(Some(String::from(
+                "Invitation id is not a valid UUID",
+            )))),
+            Ok(_) => Ok(()),
+        }
+    }
+
+    // Nobody can be invited as owner, owners are promoted from the members
+    pub fn validate_role(role: &str) -> Result<(), InvalidOrganizationError> {
+        OrganizationMember::validate_role(role)?;
+        if role == OWNER_ROLE {
+            return Err(InvalidOrganizationError::new(Some(String::from(
+                "Invitations cannot give the owner role",
+            ))));
+        }
+        Ok(())
+    }
+
+    pub fn validate_token_hash(token_hash: &str) -> Result<(), InvalidOrganizationError> {
+        if token_hash.is_empty() {
+            return Err(InvalidOrganizationError::new(Some(String::from(
+                "Invitation token hash is required and cannot be empty",
+            ))));
+        }
+        Ok(())
+    }
+
+    pub fn set_id(&mut self, id: String) -> Result<(), InvalidOrganizationError> {
+        Invitation::validate_id(&id)?;
+        self.id = id;
+        Ok(())
+    }
+
+    pub fn set_organization_id(
+        &mut self,
+        organization_id: String,
+    ) -> Result<(), InvalidOrganizationError> {
+        Organization::validate_id(&organization_id)?;
+        self.organization_id = organization_id;
+        Ok(())
+    }
+
+    pub fn set_email(&mut self, email: String) -> Result<(), InvalidOrganizationError> {
+        User::validate_email(&email)
+            .map_err(|err| InvalidOrganizationError::new(Some(err.to_string())))?;
+        self.email = email.to_lowercase();
+        Ok(())
+    }
+
+    pub fn set_role(&mut self, role: String) -> Result<(), InvalidOrganizationError> {
+        Invitation::validate_role(&role)?;
+        self.role = role;
+        Ok(())
+    }
+
+    pub fn set_token_hash(&mut self, token_hash: String) -> Result<(), InvalidOrganizationError> {
+        Invitation::validate_token_hash(&token_hash)?;
+        self.token_hash = token_hash;
+        Ok(())
+    }
+
+    pub fn set_expires_at(&mut self, expires_at: DateTime<Utc>) {
+        self.expires_at = expires_at;
+    }
+
+    pub fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    pub fn get_organization_id(&self) -> String {
+        self.organization_id.clone()
+    }
+
+    pub fn get_email(&self) -> String {
+        self.email.clone()
+    }
+
+    pub fn get_role(&self) -> String {
+        self.role.clone()
+    }
+
+    pub fn get_token_hash(&self) -> String {
+        self.token_hash.clone()
+    }
+
+    pub fn get_expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+
+    pub fn is_for_email(&self, email: &str) -> bool {
+        self.email == email.to_lowercase()
+    }
+
+    pub fn from_create_invitation_dto(
+        create_invitation: CreateInvitationDto,
+        organization_id: &str,
+        token_hash: &str,
+    ) -> Result<Invitation, InvalidOrganizationError> {
+        let mut invitation = Invitation::new();
+        invitation.set_organization_id(organization_id.to_string())?;
+        invitation.set_email(create_invitation.email)?;
+        invitation.set_role(create_invitation.role)?;
+        invitation.set_token_hash(token_hash.to_string())?;
+        invitation.set_expires_at(Utc::now() + Duration::days(INVITATION_DAYS));
+        Ok(invitation)
+    }
+
+    pub fn from_db_fields(
+        id: &str,
+        organization_id: &str,
+        email: &str,
+        role: &str,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Invitation, InvalidOrganizationError> {
+        let mut invitation = Invitation::new();
+        invitation.set_id(id.to_string())?;
+        invitation.set_organization_id(organization_id.to_string())?;
+        invitation.set_email(email.to_string())?;
+        invitation.set_role(role.to_string())?;
+        invitation.set_token_hash(token_hash.to_string())?;
+        invitation.set_expires_at(expires_at);
+        Ok(invitation)
+    }
+}
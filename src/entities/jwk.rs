@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+// Public key in the JSON Web Key format (RFC 7517), only the fields used by RS256 and EdDSA keys
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct JwkDto {
+    pub kty: String,
+    #[serde(rename = "use")]
+    pub key_use: String,
+    pub alg: String,
+    pub kid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub e: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crv: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct JwkSetDto {
+    pub keys: Vec<JwkDto>,
+}
@@ -1,2 +1,22 @@
 pub mod user;
 pub mod goal;
+pub mod api_key;
+pub mod jwk;
+pub mod organization;
+pub mod invitation;
+pub mod goal_share;
+pub mod share_link;
+pub mod comment;
+pub mod note;
+pub mod habit;
+pub mod reminder;
+pub mod notification;
+pub mod webhook;
+pub mod goal_event;
+pub mod channel;
+pub mod sync;
+pub mod goal_revision;
+pub mod audit_event;
+pub mod user_export;
+pub mod goal_import;
+pub mod calendar_feed;
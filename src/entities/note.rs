@@ -0,0 +1,168 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::errors::note_errors::InvalidNoteError;
+
+use super::{goal::Goal, user::User};
+
+pub const MAX_TEXT_LENGTH: usize = 20000;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateNoteDto {
+    pub text: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateNoteDto {
+    pub text: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct NoteDto {
+    pub id: String,
+    pub goal_id: String,
+    pub user_id: String,
+    pub text: String,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct NotesQueryDto {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+// Journal entry about the progress of a goal
+pub struct Note {
+    id: String,
+    goal_id: String,
+    user_id: String,
+    text: String,
+    created_at: Option<DateTime<Utc>>,
+    updated_at: Option<DateTime<Utc>>,
+}
+
+impl Note {
+    fn new() -> Note {
+        Note {
+            id: String::from("NO_ID"),
+            goal_id: String::from("NO_GOAL_ID"),
+            user_id: String::from("NO_USER_ID"),
+            text: String::from("NO_TEXT"),
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    pub fn validate_id(id: &str) -> Result<(), InvalidNoteError> {
+        match Uuid::parse_str(id) {
+            Err(_) => Err(InvalidNoteError::new(Some(String::from(
+                "Note id is not a valid UUID",
+            )))),
+            Ok(_) => Ok(()),
+        }
+    }
+
+    pub fn validate_text(text: &str) -> Result<(), InvalidNoteError> {
+        if text.trim().is_empty() {
+            return Err(InvalidNoteError::new(Some(String::from(
+                "Note text is required and cannot be blank",
+            ))));
+        }
+        if text.chars().count() > MAX_TEXT_LENGTH {
+            return Err(InvalidNoteError::new(Some(format!(
+                "Note text must be at most {} characters long",
+                MAX_TEXT_LENGTH
+            ))));
+        }
+        Ok(())
+    }
+
+    pub fn set_id(&mut self, id: String) -> Result<(), InvalidNoteError> {
+        Note::validate_id(&id)?;
+        self.id = id;
+        Ok(())
+    }
+
+    pub fn set_goal_id(&mut self, goal_id: String) -> Result<(), InvalidNoteError> {
+        Goal::validate_id(&goal_id).map_err(|err| InvalidNoteError::new(Some(err.to_string())))?;
+        self.goal_id = goal_id;
+        Ok(())
+    }
+
+    pub fn set_user_id(&mut self, user_id: String) -> Result<(), InvalidNoteError> {
+        User::validate_id(&user_id).map_err(|err| InvalidNoteError::new(Some(err.to_string())))?;
+        self.user_id = user_id;
+        Ok(())
+    }
+
+    pub fn set_text(&mut self, text: String) -> Result<(), InvalidNoteError> {
+        Note::validate_text(&text)?;
+        self.text = text;
+        Ok(())
+    }
+
+    pub fn set_created_at(&mut self, created_at: Option<DateTime<Utc>>) {
+        self.created_at = created_at;
+    }
+
+    pub fn set_updated_at(&mut self, updated_at: Option<DateTime<Utc>>) {
+        self.updated_at = updated_at;
+    }
+
+    pub fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    pub fn get_goal_id(&self) -> String {
+        self.goal_id.clone()
+    }
+
+    pub fn get_user_id(&self) -> String {
+        self.user_id.clone()
+    }
+
+    pub fn get_text(&self) -> String {
+        self.text.clone()
+    }
+
+    pub fn get_created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    pub fn get_updated_at(&self) -> Option<DateTime<Utc>> {
+        self.updated_at
+    }
+
+    pub fn from_create_note_dto(
+        create_note: CreateNoteDto,
+        goal_id: &str,
+        user_id: &str,
+    ) -> Result<Note, InvalidNoteError> {
+        let mut note = Note::new();
+        note.set_goal_id(goal_id.to_string())?;
+        note.set_user_id(user_id.to_string())?;
+        note.set_text(create_note.text)?;
+        Ok(note)
+    }
+
+    pub fn from_db_fields(
+        id: &str,
+        goal_id: &str,
+        user_id: &str,
+        text: &str,
+        created_at: Option<DateTime<Utc>>,
+        updated_at: Option<DateTime<Utc>>,
+    ) -> Result<Note, InvalidNoteError> {
+        let mut note = Note::new();
+        note.set_id(id.to_string())?;
+        note.set_goal_id(goal_id.to_string())?;
+        note.set_user_id(user_id.to_string())?;
+        note.set_text(text.to_string())?;
+        note.set_created_at(created_at);
+        note.set_updated_at(updated_at);
+        Ok(note)
+    }
+}
@@ -0,0 +1,246 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::errors::notification_errors::InvalidNotificationError;
+
+use super::{goal::Goal, user::User};
+
+pub const REMINDER_KIND: &str = "reminder";
+pub const GOAL_SHARED_KIND: &str = "goal_shared";
+pub const COMMENT_KIND: &str = "comment";
+pub const MENTION_KIND: &str = "mention";
+pub const DEADLINE_KIND: &str = "deadline";
+
+const MAX_TEXT_LENGTH: usize = 500;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct NotificationDto {
+    pub id: String,
+    pub kind: String,
+    pub goal_id: Option<String>,
+    pub actor_id: Option<String>,
+    pub text: String,
+    pub read: bool,
+    pub read_at: Option<String>,
+    pub created_at: Option<String>,
+}
+
+// Newest first. cursor is the next_cursor of the previous page
+#[derive(Debug, Deserialize, Serialize)]
+pub struct NotificationsQueryDto {
+    pub unread: Option<bool>,
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+}
+
+// next_cursor is missing on the last page
+#[derive(Debug, Deserialize, Serialize)]
+pub struct NotificationPageDto {
+    pub notifications: Vec<NotificationDto>,
+    pub unread_count: i64,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UnreadCountDto {
+    pub unread_count: i64,
+}
+
+// Something that happened to a user, shown in their inbox. The actor is the user that caused it,
+// if any
+pub struct Notification {
+    id: String,
+    user_id: String,
+    kind: String,
+    goal_id: Option<String>,
+    actor_id: Option<String>,
+    text: String,
+    read_at: Option<DateTime<Utc>>,
+    created_at: Option<DateTime<Utc>>,
+}
+
+impl Notification {
+    fn new() -> Notification {
+        Notification {
+            id: String::from("NO_ID"),
+            user_id: String::from("NO_USER_ID"),
+            kind: String::from(REMINDER_KIND),
+            goal_id: None,
+            actor_id: None,
+            text: String::from("NO_TEXT"),
+            read_at: None,
+            created_at: None,
+        }
+    }
+
+    pub fn validate_id(id: &str) -> Result<(), InvalidNotificationError> {
+        match Uuid::parse_str(id) {
+            Err(_) => Err(InvalidNotificationError::new(Some(String::from(
+                "Notification id is not a valid UUID",
+            )))),
+            Ok(_) => Ok(()),
+        }
+    }
+
+    pub fn validate_kind(kind: &str) -> Result<(), InvalidNotificationError> {
+        match kind {
+            REMINDER_KIND | GOAL_SHARED_KIND | COMMENT_KIND | MENTION_KIND | DEADLINE_KIND => {
+                Ok(())
+            }
+            _ => Err(InvalidNotificationError::new(Some(format!(
+                "Unknown notification kind: {}",
+                kind
+            )))),
+        }
+    }
+
+    // The cursor points to the last notification of a page: its creation date in microseconds
+    // and its id
+    pub fn encode_cursor(created_at: &DateTime<Utc>, id: &str) -> String {
+        URL_SAFE_NO_PAD.encode(format!("{}:{}", created_at.timestamp_micros(), id))
+    }
+
+    pub fn decode_cursor(
+        cursor: &str,
+    ) -> Result<(DateTime<Utc>, String), InvalidNotificationError> {
+        let invalid = || InvalidNotificationError::new(Some(String::from("Invalid cursor")));
+
+        let decoded = URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalid())?;
+        let decoded = String::from_utf8(decoded).map_err(|_| invalid())?;
+        let (micros, id) = decoded.split_once(':').ok_or_else(invalid)?;
+        let micros = micros.parse::<i64>().map_err(|_| invalid())?;
+        let created_at = DateTime::<Utc>::from_timestamp_micros(micros).ok_or_else(invalid)?;
+        Notification::validate_id(id).map_err(|_| invalid())?;
+
+        Ok((created_at, id.to_string()))
+    }
+
+    pub fn set_id(&mut self, id: String) -> Result<(), InvalidNotificationError> {
+        Notification::validate_id(&id)?;
+        self.id = id;
+        Ok(())
+    }
+
+    pub fn set_user_id(&mut self, user_id: String) -> Result<(), InvalidNotificationError> {
+        User::validate_id(&user_id)
+            .map_err(|err| InvalidNotificationError::new(Some(err.to_string())))?;
+        self.user_id = user_id;
+        Ok(())
+    }
+
+    pub fn set_kind(&mut self, kind: String) -> Result<(), InvalidNotificationError> {
+        Notification::validate_kind(&kind)?;
+        self.kind = kind;
+        Ok(())
+    }
+
+    pub fn set_goal_id(&mut self, goal_id: Option<String>) -> Result<(), InvalidNotificationError> {
+        if let Some(goal_id) = &goal_id {
+            Goal::validate_id(goal_id)
+                .map_err(|err| InvalidNotificationError::new(Some(err.to_string())))?;
+        }
+        self.goal_id = goal_id;
+        Ok(())
+    }
+
+    pub fn set_actor_id(
+        &mut self,
+        actor_id: Option<String>,
+    ) -> Result<(), InvalidNotificationError> {
+        if let Some(actor_id) = &actor_id {
+            User::validate_id(actor_id)
+                .map_err(|err| InvalidNotificationError::new(Some(err.to_string())))?;
+        }
+        self.actor_id = actor_id;
+        Ok(())
+    }
+
+    // Long texts are cut, the inbox only shows a summary
+    pub fn set_text(&mut self, text: String) {
+        self.text = match text.char_indices().nth(MAX_TEXT_LENGTH) {
+            None => text,
+            Some((index, _)) => format!("{}...", &text[..index]),
+        };
+    }
+
+    pub fn set_read_at(&mut self, read_at: Option<DateTime<Utc>>) {
+        self.read_at = read_at;
+    }
+
+    pub fn set_created_at(&mut self, created_at: Option<DateTime<Utc>>) {
+        self.created_at = created_at;
+    }
+
+    pub fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    pub fn get_user_id(&self) -> String {
+        self.user_id.clone()
+    }
+
+    pub fn get_kind(&self) -> String {
+        self.kind.clone()
+    }
+
+    pub fn get_goal_id(&self) -> Option<String> {
+        self.goal_id.clone()
+    }
+
+    pub fn get_actor_id(&self) -> Option<String> {
+        self.actor_id.clone()
+    }
+
+    pub fn get_text(&self) -> String {
+        self.text.clone()
+    }
+
+    pub fn get_read_at(&self) -> Option<DateTime<Utc>> {
+        self.read_at
+    }
+
+    pub fn get_created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    pub fn from_event(
+        user_id: &str,
+        kind: &str,
+        goal_id: Option<String>,
+        actor_id: Option<String>,
+        text: String,
+    ) -> Result<Notification, InvalidNotificationError> {
+        let mut notification = Notification::new();
+        notification.set_user_id(user_id.to_string())?;
+        notification.set_kind(kind.to_string())?;
+        notification.set_goal_id(goal_id)?;
+        notification.set_actor_id(actor_id)?;
+        notification.set_text(text);
+        Ok(notification)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_db_fields(
+        id: &str,
+        user_id: &str,
+        kind: &str,
+        goal_id: Option<String>,
+        actor_id: Option<String>,
+        text: &str,
+        read_at: Option<DateTime<Utc>>,
+        created_at: Option<DateTime<Utc>>,
+    ) -> Result<Notification, InvalidNotificationError> {
+        let mut notification = Notification::new();
+        notification.set_id(id.to_string())?;
+        notification.set_user_id(user_id.to_string())?;
+        notification.set_kind(kind.to_string())?;
+        notification.set_goal_id(goal_id)?;
+        notification.set_actor_id(actor_id)?;
+        notification.set_text(text.to_string());
+        notification.set_read_at(read_at);
+        notification.set_created_at(created_at);
+        Ok(notification)
+    }
+}
@@ -0,0 +1,205 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::errors::organization_errors::InvalidOrganizationError;
+
+use super::user::User;
+
+pub const OWNER_ROLE: &str = "owner";
+pub const ADMIN_ROLE: &str = "admin";
+pub const MEMBER_ROLE: &str = "member";
+pub const VIEWER_ROLE: &str = "viewer";
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateOrganizationDto {
+    pub name: String,
+}
+
+// Organization with the role of the user asking for it
+#[derive(Debug, Deserialize, Serialize)]
+pub struct OrganizationDto {
+    pub id: String,
+    pub name: String,
+    pub role: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MemberDto {
+    pub user_id: String,
+    pub name: String,
+    pub email: String,
+    pub role: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateMemberDto {
+    pub role: String,
+}
+
+pub struct Organization {
+    id: String,
+    name: String,
+}
+
+impl Organization {
+    fn new() -> Organization {
+        Organization {
+            id: String::from("NO_ID"),
+            name: String::from("NO_NAME"),
+        }
+    }
+
+    pub fn validate_id(id: &str) -> Result<(), InvalidOrganizationError> {
+        match Uuid::parse_str(id) {
+            Err(_) => Err(InvalidOrganizationError::new(Some(String::from(
+                "Organization id is not a valid UUID",
+            )))),
+            Ok(_) => Ok(()),
+        }
+    }
+
+    pub fn validate_name(name: &str) -> Result<(), InvalidOrganizationError> {
+        if name.trim().is_empty() {
+            return Err(InvalidOrganizationError::new(Some(String::from(
+                "Organization name is required and cannot be blank",
+            ))));
+        }
+        if name.chars().count() > 120 {
+            return Err(InvalidOrganizationError::new(Some(String::from(
+                "Organization name must be at most 120 characters long",
+            ))));
+        }
+        Ok(())
+    }
+
+    pub fn set_id(&mut self, id: String) -> Result<(), InvalidOrganizationError> {
+        Organization::validate_id(&id)?;
+        self.id = id;
+        Ok(())
+    }
+
+    pub fn set_name(&mut self, name: String) -> Result<(), InvalidOrganizationError> {
+        Organization::validate_name(&name)?;
+        self.name = name;
+        Ok(())
+    }
+
+    pub fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    pub fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn from_create_organization_dto(
+        create_organization: CreateOrganizationDto,
+    ) -> Result<Organization, InvalidOrganizationError> {
+        let mut organization = Organization::new();
+        organization.set_name(create_organization.name)?;
+        Ok(organization)
+    }
+
+    pub fn from_db_fields(id: &str, name: &str) -> Result<Organization, InvalidOrganizationError> {
+        let mut organization = Organization::new();
+        organization.set_id(id.to_string())?;
+        organization.set_name(name.to_string())?;
+        Ok(organization)
+    }
+}
+
+// Membership of a user in a organization. The role decides what the user can do:
+// owner > admin > member > viewer
+pub struct OrganizationMember {
+    organization_id: String,
+    user_id: String,
+    role: String,
+}
+
+impl OrganizationMember {
+    fn new() -> OrganizationMember {
+        OrganizationMember {
+            organization_id: String::from("NO_ORGANIZATION_ID"),
+            user_id: String::from("NO_USER_ID"),
+            role: String::from(VIEWER_ROLE),
+        }
+    }
+
+    pub fn validate_role(role: &str) -> Result<(), InvalidOrganizationError> {
+        if ![OWNER_ROLE, ADMIN_ROLE, MEMBER_ROLE, VIEWER_ROLE].contains(&role) {
+            return Err(InvalidOrganizationError::new(Some(format!(
+                "Member role must be one of: {}, {}, {}, {}",
+                OWNER_ROLE, ADMIN_ROLE, MEMBER_ROLE, VIEWER_ROLE
+            ))));
+        }
+        Ok(())
+    }
+
+    pub fn set_organization_id(
+        &mut self,
+        organization_id: String,
+    ) -> Result<(), InvalidOrganizationError> {
+        Organization::validate_id(&organization_id)?;
+        self.organization_id = organization_id;
+        Ok(())
+    }
+
+    pub fn set_user_id(&mut self, user_id: String) -> Result<(), InvalidOrganizationError> {
+        User::validate_id(&user_id)
+            .map_err(|err| InvalidOrganizationError::new(Some(err.to_string())))?;
+        self.user_id = user_id;
+        Ok(())
+    }
+
+    pub fn set_role(&mut self, role: String) -> Result<(), InvalidOrganizationError> {
+        OrganizationMember::validate_role(&role)?;
+        self.role = role;
+        Ok(())
+    }
+
+    pub fn get_organization_id(&self) -> String {
+        self.organization_id.clone()
+    }
+
+    pub fn get_user_id(&self) -> String {
+        self.user_id.clone()
+    }
+
+    pub fn get_role(&self) -> String {
+        self.role.clone()
+    }
+
+    pub fn is_owner(&self) -> bool {
+        self.role == OWNER_ROLE
+    }
+
+    // Owners and admins manage the members and the invitations
+    pub fn can_manage_members(&self) -> bool {
+        self.role == OWNER_ROLE || self.role == ADMIN_ROLE
+    }
+
+    // Viewers can only read the goals of the organization
+    pub fn can_write_goals(&self) -> bool {
+        self.role != VIEWER_ROLE
+    }
+
+    // Only owners can give or take the owner and admin roles
+    pub fn can_assign_role(&self, role: &str) -> bool {
+        match role {
+            OWNER_ROLE | ADMIN_ROLE => self.is_owner(),
+            _ => self.can_manage_members(),
+        }
+    }
+
+    pub fn from_db_fields(
+        organization_id: &str,
+        user_id: &str,
+        role: &str,
+    ) -> Result<OrganizationMember, InvalidOrganizationError> {
+        let mut member = OrganizationMember::new();
+        member.set_organization_id(organization_id.to_string())?;
+        member.set_user_id(user_id.to_string())?;
+        member.set_role(role.to_string())?;
+        Ok(member)
+    }
+}
@@ -0,0 +1,405 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::errors::reminder_errors::InvalidReminderError;
+
+use super::{goal::Goal, user::User};
+
+pub const EMAIL_CHANNEL: &str = "email";
+pub const WEBHOOK_CHANNEL: &str = "webhook";
+pub const LOG_CHANNEL: &str = "log";
+
+pub const PENDING_STATUS: &str = "pending";
+pub const SENT_STATUS: &str = "sent";
+pub const FAILED_STATUS: &str = "failed";
+
+const DEFAULT_TIMEZONE: &str = "UTC";
+const MAX_URL_LENGTH: usize = 2000;
+// One year
+const MAX_MINUTES_BEFORE_DUE: i32 = 525_600;
+
+// remind_at is a local date time in the time zone (ex: 2024-05-01T09:00) or an RFC 3339 date with
+// an offset. minutes_before_due sets the reminder relative to the due date of the goal instead, one
+// of them is required. The time zone is an IANA name, UTC by default, and the channel defaults to
+// email
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateReminderDto {
+    pub remind_at: Option<String>,
+    pub minutes_before_due: Option<i32>,
+    pub timezone: Option<String>,
+    pub channel: Option<String>,
+    pub webhook_url: Option<String>,
+}
+
+// remind_at is given in the time zone of the reminder. minutes_before_due is only present for the
+// reminders relative to the due date, remind_at follows the due date
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ReminderDto {
+    pub id: String,
+    pub goal_id: String,
+    pub remind_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minutes_before_due: Option<i32>,
+    pub timezone: String,
+    pub channel: String,
+    pub webhook_url: Option<String>,
+    pub status: String,
+    pub attempts: i32,
+    pub sent_at: Option<String>,
+    pub last_error: Option<String>,
+}
+
+// What the notifiers send when a reminder is due, also the JSON body of the webhook
+#[derive(Debug, Serialize)]
+pub struct ReminderNotification {
+    pub reminder_id: String,
+    pub goal_id: String,
+    pub goal_text: String,
+    pub user_id: String,
+    pub user_name: String,
+    pub user_email: String,
+    pub remind_at: String,
+    pub timezone: String,
+    pub channel: String,
+    #[serde(skip_serializing)]
+    pub webhook_url: Option<String>,
+    #[serde(skip_serializing)]
+    pub attempts: i32,
+}
+
+pub struct Reminder {
+    id: String,
+    goal_id: String,
+    user_id: String,
+    remind_at: DateTime<Utc>,
+    minutes_before_due: Option<i32>,
+    timezone: String,
+    channel: String,
+    webhook_url: Option<String>,
+    status: String,
+    attempts: i32,
+    sent_at: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+}
+
+impl Reminder {
+    fn new() -> Reminder {
+        Reminder {
+            id: String::from("NO_ID"),
+            goal_id: String::from("NO_GOAL_ID"),
+            user_id: String::from("NO_USER_ID"),
+            remind_at: DateTime::<Utc>::default(),
+            minutes_before_due: None,
+            timezone: String::from(DEFAULT_TIMEZONE),
+            channel: String::from(EMAIL_CHANNEL),
+            webhook_url: None,
+            status: String::from(PENDING_STATUS),
+            attempts: 0,
+            sent_at: None,
+            last_error: None,
+        }
+    }
+
+    pub fn validate_id(id: &str) -> Result<(), InvalidReminderError> {
+        match Uuid::parse_str(id) {
+            Err(_) => Err(InvalidReminderError::new(Some(String::from(
+                "Reminder id is not a valid UUID",
+            )))),
+            Ok(_) => Ok(()),
+        }
+    }
+
+    pub fn parse_timezone(timezone: &str) -> Result<Tz, InvalidReminderError> {
+        Tz::from_str(timezone).map_err(|_| {
+            InvalidReminderError::new(Some(format!(
+                "Time zone is not a valid IANA time zone: {}",
+                timezone
+            )))
+        })
+    }
+
+    // Local date times that do not exist in the time zone (DST gaps) are rejected, ambiguous ones
+    // (DST overlaps) take the earliest instant
+    pub fn parse_remind_at(
+        remind_at: &str,
+        timezone: &Tz,
+    ) -> Result<DateTime<Utc>, InvalidReminderError> {
+        if let Ok(date) = DateTime::parse_from_rfc3339(remind_at) {
+            return Ok(date.with_timezone(&Utc));
+        }
+
+        let local = NaiveDateTime::parse_from_str(remind_at, "%Y-%m-%dT%H:%M:%S")
+            .or_else(|_| NaiveDateTime::parse_from_str(remind_at, "%Y-%m-%dT%H:%M"))
+            .map_err(|_| {
+                InvalidReminderError::new(Some(format!(
+                    "remind_at is not a valid date time: {}",
+                    remind_at
+                )))
+            })?;
+
+        match timezone.from_local_datetime(&local).earliest() {
+            None => Err(InvalidReminderError::new(Some(format!(
+                "{} does not exist in the time zone {}",
+                remind_at, timezone
+            )))),
+            Some(date) => Ok(date.with_timezone(&Utc)),
+        }
+    }
+
+    pub fn validate_minutes_before_due(
+        minutes_before_due: i32,
+    ) -> Result<(), InvalidReminderError> {
+        if !(0..=MAX_MINUTES_BEFORE_DUE).contains(&minutes_before_due) {
+            return Err(InvalidReminderError::new(Some(format!(
+                "minutes_before_due must be between 0 and {}",
+                MAX_MINUTES_BEFORE_DUE
+            ))));
+        }
+        Ok(())
+    }
+
+    pub fn validate_channel(channel: &str) -> Result<(), InvalidReminderError> {
+        if channel != EMAIL_CHANNEL && channel != WEBHOOK_CHANNEL && channel != LOG_CHANNEL {
+            return Err(InvalidReminderError::new(Some(format!(
+                "Channel must be one of: {}, {}, {}",
+                EMAIL_CHANNEL, WEBHOOK_CHANNEL, LOG_CHANNEL
+            ))));
+        }
+        Ok(())
+    }
+
+    pub fn validate_webhook_url(webhook_url: &str) -> Result<(), InvalidReminderError> {
+        if !(webhook_url.starts_with("https://") || webhook_url.starts_with("http://"))
+            || webhook_url.len() > MAX_URL_LENGTH
+        {
+            return Err(InvalidReminderError::new(Some(String::from(
+                "Webhook url must be an http or https url",
+            ))));
+        }
+        Ok(())
+    }
+
+    pub fn set_id(&mut self, id: String) -> Result<(), InvalidReminderError> {
+        Reminder::validate_id(&id)?;
+        self.id = id;
+        Ok(())
+    }
+
+    pub fn set_goal_id(&mut self, goal_id: String) -> Result<(), InvalidReminderError> {
+        Goal::validate_id(&goal_id)
+            .map_err(|err| InvalidReminderError::new(Some(err.to_string())))?;
+        self.goal_id = goal_id;
+        Ok(())
+    }
+
+    pub fn set_user_id(&mut self, user_id: String) -> Result<(), InvalidReminderError> {
+        User::validate_id(&user_id)
+            .map_err(|err| InvalidReminderError::new(Some(err.to_string())))?;
+        self.user_id = user_id;
+        Ok(())
+    }
+
+    pub fn set_remind_at(&mut self, remind_at: DateTime<Utc>) {
+        self.remind_at = remind_at;
+    }
+
+    pub fn set_minutes_before_due(
+        &mut self,
+        minutes_before_due: Option<i32>,
+    ) -> Result<(), InvalidReminderError> {
+        if let Some(minutes_before_due) = minutes_before_due {
+            Reminder::validate_minutes_before_due(minutes_before_due)?;
+        }
+        self.minutes_before_due = minutes_before_due;
+        Ok(())
+    }
+
+    pub fn set_timezone(&mut self, timezone: String) -> Result<(), InvalidReminderError> {
+        Reminder::parse_timezone(&timezone)?;
+        self.timezone = timezone;
+        Ok(())
+    }
+
+    // The webhook url is required by the webhook channel and ignored by the others
+    pub fn set_channel(
+        &mut self,
+        channel: String,
+        webhook_url: Option<String>,
+    ) -> Result<(), InvalidReminderError> {
+        Reminder::validate_channel(&channel)?;
+        self.webhook_url = match channel.as_str() {
+            WEBHOOK_CHANNEL => match webhook_url {
+                None => {
+                    return Err(InvalidReminderError::new(Some(String::from(
+                        "Webhook url is required by the webhook channel",
+                    ))))
+                }
+                Some(webhook_url) => {
+                    Reminder::validate_webhook_url(&webhook_url)?;
+                    Some(webhook_url)
+                }
+            },
+            _ => None,
+        };
+        self.channel = channel;
+        Ok(())
+    }
+
+    pub fn set_status(&mut self, status: String) {
+        self.status = status;
+    }
+
+    pub fn set_attempts(&mut self, attempts: i32) {
+        self.attempts = attempts;
+    }
+
+    pub fn set_sent_at(&mut self, sent_at: Option<DateTime<Utc>>) {
+        self.sent_at = sent_at;
+    }
+
+    pub fn set_last_error(&mut self, last_error: Option<String>) {
+        self.last_error = last_error;
+    }
+
+    pub fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    pub fn get_goal_id(&self) -> String {
+        self.goal_id.clone()
+    }
+
+    pub fn get_user_id(&self) -> String {
+        self.user_id.clone()
+    }
+
+    pub fn get_remind_at(&self) -> DateTime<Utc> {
+        self.remind_at
+    }
+
+    pub fn get_minutes_before_due(&self) -> Option<i32> {
+        self.minutes_before_due
+    }
+
+    // remind_at in the time zone of the reminder, RFC 3339
+    pub fn get_local_remind_at(&self) -> String {
+        match Tz::from_str(&self.timezone) {
+            Err(_) => self.remind_at.to_rfc3339(),
+            Ok(timezone) => self.remind_at.with_timezone(&timezone).to_rfc3339(),
+        }
+    }
+
+    pub fn get_timezone(&self) -> String {
+        self.timezone.clone()
+    }
+
+    pub fn get_channel(&self) -> String {
+        self.channel.clone()
+    }
+
+    pub fn get_webhook_url(&self) -> Option<String> {
+        self.webhook_url.clone()
+    }
+
+    pub fn get_status(&self) -> String {
+        self.status.clone()
+    }
+
+    pub fn get_attempts(&self) -> i32 {
+        self.attempts
+    }
+
+    pub fn get_sent_at(&self) -> Option<DateTime<Utc>> {
+        self.sent_at
+    }
+
+    pub fn get_last_error(&self) -> Option<String> {
+        self.last_error.clone()
+    }
+
+    // due_at is the due date of the goal, required by the reminders relative to it
+    pub fn from_create_reminder_dto(
+        create_reminder: CreateReminderDto,
+        goal_id: &str,
+        user_id: &str,
+        due_at: Option<DateTime<Utc>>,
+    ) -> Result<Reminder, InvalidReminderError> {
+        let mut reminder = Reminder::new();
+        reminder.set_goal_id(goal_id.to_string())?;
+        reminder.set_user_id(user_id.to_string())?;
+
+        let timezone_name = create_reminder
+            .timezone
+            .unwrap_or_else(|| DEFAULT_TIMEZONE.to_string());
+        let timezone = Reminder::parse_timezone(&timezone_name)?;
+        reminder.set_timezone(timezone_name)?;
+
+        let remind_at = match (
+            create_reminder.remind_at,
+            create_reminder.minutes_before_due,
+        ) {
+            (Some(remind_at), None) => Reminder::parse_remind_at(&remind_at, &timezone)?,
+            (None, Some(minutes_before_due)) => {
+                reminder.set_minutes_before_due(Some(minutes_before_due))?;
+                let due_at = due_at.ok_or_else(|| {
+                    InvalidReminderError::new(Some(String::from(
+                        "The goal has no due date, set remind_at instead",
+                    )))
+                })?;
+                due_at - Duration::minutes(minutes_before_due as i64)
+            }
+            _ => {
+                return Err(InvalidReminderError::new(Some(String::from(
+                    "One of remind_at and minutes_before_due is required",
+                ))))
+            }
+        };
+        if remind_at <= Utc::now() {
+            return Err(InvalidReminderError::new(Some(String::from(
+                "remind_at must be in the future",
+            ))));
+        }
+        reminder.set_remind_at(remind_at);
+
+        let channel = create_reminder
+            .channel
+            .unwrap_or_else(|| EMAIL_CHANNEL.to_string());
+        reminder.set_channel(channel, create_reminder.webhook_url)?;
+
+        Ok(reminder)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_db_fields(
+        id: &str,
+        goal_id: &str,
+        user_id: &str,
+        remind_at: DateTime<Utc>,
+        minutes_before_due: Option<i32>,
+        timezone: &str,
+        channel: &str,
+        webhook_url: Option<String>,
+        status: &str,
+        attempts: i32,
+        sent_at: Option<DateTime<Utc>>,
+        last_error: Option<String>,
+    ) -> Result<Reminder, InvalidReminderError> {
+        let mut reminder = Reminder::new();
+        reminder.set_id(id.to_string())?;
+        reminder.set_goal_id(goal_id.to_string())?;
+        reminder.set_user_id(user_id.to_string())?;
+        reminder.set_remind_at(remind_at);
+        reminder.set_minutes_before_due(minutes_before_due)?;
+        reminder.set_timezone(timezone.to_string())?;
+        reminder.set_channel(channel.to_string(), webhook_url)?;
+        reminder.set_status(status.to_string());
+        reminder.set_attempts(attempts);
+        reminder.set_sent_at(sent_at);
+        reminder.set_last_error(last_error);
+        Ok(reminder)
+    }
+}
@@ -0,0 +1,181 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::errors::share_link_errors::InvalidShareLinkError;
+
+use super::{goal::Goal, user::User};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateShareLinkDto {
+    // Optional RFC 3339 date, ex: 2024-12-31T23:59:59Z
+    pub expires_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ShareLinkDto {
+    pub id: String,
+    pub goal_id: String,
+    pub expires_at: Option<String>,
+}
+
+// Only returned once, when the link is created. The plain token is never stored
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreatedShareLinkDto {
+    pub id: String,
+    pub goal_id: String,
+    pub expires_at: Option<String>,
+    pub token: String,
+}
+
+// What anyone with the link can see, nothing about the owner
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PublicGoalDto {
+    pub id: String,
+    pub text: String,
+}
+
+// Read only public access to a goal, for whoever has the token
+pub struct ShareLink {
+    id: String,
+    goal_id: String,
+    created_by: String,
+    token_hash: String,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl ShareLink {
+    fn new() -> ShareLink {
+        ShareLink {
+            id: String::from("NO_ID"),
+            goal_id: String::from("NO_GOAL_ID"),
+            created_by: String::from("NO_USER_ID"),
+            token_hash: String::from("NO_TOKEN_HASH"),
+            expires_at: None,
+        }
+    }
+
+    pub fn validate_id(id: &str) -> Result<(), InvalidShareLinkError> {
+        match Uuid::parse_str(id) {
+            Err(_) => Err(InvalidShareLinkError::new(Some(String::from(
+                "Share link id is not a valid UUID",
+            )))),
+            Ok(_) => Ok(()),
+        }
+    }
+
+    pub fn validate_token_hash(token_hash: &str) -> Result<(), InvalidShareLinkError> {
+        if token_hash.is_empty() {
+            return Err(InvalidShareLinkError::new(Some(String::from(
+                "Share link token hash is required and cannot be empty",
+            ))));
+        }
+        Ok(())
+    }
+
+    pub fn validate_expires_at(expires_at: &DateTime<Utc>) -> Result<(), InvalidShareLinkError> {
+        if *expires_at <= Utc::now() {
+            return Err(InvalidShareLinkError::new(Some(String::from(
+                "Share link expiration date must be in the future",
+            ))));
+        }
+        Ok(())
+    }
+
+    pub fn set_id(&mut self, id: String) -> Result<(), InvalidShareLinkError> {
+        ShareLink::validate_id(&id)?;
+        self.id = id;
+        Ok(())
+    }
+
+    pub fn set_goal_id(&mut self, goal_id: String) -> Result<(), InvalidShareLinkError> {
+        Goal::validate_id(&goal_id)
+            .map_err(|err| InvalidShareLinkError::new(Some(err.to_string())))?;
+        self.goal_id = goal_id;
+        Ok(())
+    }
+
+    pub fn set_created_by(&mut self, created_by: String) -> Result<(), InvalidShareLinkError> {
+        User::validate_id(&created_by)
+            .map_err(|err| InvalidShareLinkError::new(Some(err.to_string())))?;
+        self.created_by = created_by;
+        Ok(())
+    }
+
+    pub fn set_token_hash(&mut self, token_hash: String) -> Result<(), InvalidShareLinkError> {
+        ShareLink::validate_token_hash(&token_hash)?;
+        self.token_hash = token_hash;
+        Ok(())
+    }
+
+    pub fn set_expires_at(&mut self, expires_at: Option<DateTime<Utc>>) {
+        self.expires_at = expires_at;
+    }
+
+    pub fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    pub fn get_goal_id(&self) -> String {
+        self.goal_id.clone()
+    }
+
+    pub fn get_created_by(&self) -> String {
+        self.created_by.clone()
+    }
+
+    pub fn get_token_hash(&self) -> String {
+        self.token_hash.clone()
+    }
+
+    pub fn get_expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+
+    pub fn is_expired(&self) -> bool {
+        match self.expires_at {
+            None => false,
+            Some(expires_at) => expires_at <= Utc::now(),
+        }
+    }
+
+    pub fn from_create_share_link_dto(
+        create_share_link: CreateShareLinkDto,
+        goal_id: &str,
+        created_by: &str,
+        token_hash: &str,
+    ) -> Result<ShareLink, InvalidShareLinkError> {
+        let mut share_link = ShareLink::new();
+        share_link.set_goal_id(goal_id.to_string())?;
+        share_link.set_created_by(created_by.to_string())?;
+        share_link.set_token_hash(token_hash.to_string())?;
+        if let Some(expires_at) = create_share_link.expires_at {
+            let expires_at = DateTime::parse_from_rfc3339(&expires_at)
+                .map_err(|_| {
+                    InvalidShareLinkError::new(Some(String::from(
+                        "Share link expiration date is not a valid RFC 3339 date",
+                    )))
+                })?
+                .with_timezone(&Utc);
+            ShareLink::validate_expires_at(&expires_at)?;
+            share_link.set_expires_at(Some(expires_at));
+        }
+        Ok(share_link)
+    }
+
+    pub fn from_db_fields(
+        id: &str,
+        goal_id: &str,
+        created_by: &str,
+        token_hash: &str,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<ShareLink, InvalidShareLinkError> {
+        let mut share_link = ShareLink::new();
+        share_link.set_id(id.to_string())?;
+        share_link.set_goal_id(goal_id.to_string())?;
+        share_link.set_created_by(created_by.to_string())?;
+        share_link.set_token_hash(token_hash.to_string())?;
+        share_link.set_expires_at(expires_at);
+        Ok(share_link)
+    }
+}
@@ -0,0 +1,314 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::sync_errors::InvalidSyncError;
+
+use super::{goal::Goal, user::User};
+
+pub const CREATE_OPERATION: &str = "create";
+pub const UPDATE_OPERATION: &str = "update";
+pub const DELETE_OPERATION: &str = "delete";
+
+pub const APPLIED_STATUS: &str = "applied";
+pub const IGNORED_STATUS: &str = "ignored";
+pub const REJECTED_STATUS: &str = "rejected";
+
+// Without cursor every goal of the user is returned. cursor is the cursor of the previous response
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SyncQueryDto {
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+// Goals in the trash come with their deleted_at, they are tombstoned once purged
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SyncGoalDto {
+    pub id: String,
+    pub text: String,
+    pub user_id: String,
+    pub organization_id: Option<String>,
+    pub version: i64,
+    pub updated_at: Option<String>,
+    pub text_updated_at: Option<String>,
+    pub due_at: Option<String>,
+    pub completed_at: Option<String>,
+    pub completion_updated_at: Option<String>,
+    pub archived_at: Option<String>,
+    pub deleted_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GoalTombstoneDto {
+    pub goal_id: String,
+    pub version: i64,
+    pub deleted_at: Option<String>,
+}
+
+// Goals and tombstones are applied in the order of their versions. reset is true when the client
+// must drop the goals it has and keep the ones received, the cursor was missing or too old
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SyncChangesDto {
+    pub goals: Vec<SyncGoalDto>,
+    pub tombstones: Vec<GoalTombstoneDto>,
+    pub cursor: String,
+    pub has_more: bool,
+    pub reset: bool,
+}
+
+// goal_id is generated by the client for the goals it creates. updated_at is when the change was
+// made on the client, it decides which text and which completion are kept. completed is left as it
+// is when missing
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SyncMutationDto {
+    pub operation: String,
+    pub goal_id: String,
+    pub text: Option<String>,
+    pub completed: Option<bool>,
+    pub organization_id: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SyncMutationsDto {
+    pub mutations: Vec<SyncMutationDto>,
+}
+
+// ignored: the change lost against a newer one or the goal was deleted. rejected: the change is
+// invalid or not allowed, message tells why
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SyncMutationResultDto {
+    pub goal_id: String,
+    pub operation: String,
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SyncMutationResultsDto {
+    pub results: Vec<SyncMutationResultDto>,
+}
+
+// Position of a client in the changes: the last version it received and when the cursor was issued
+pub struct SyncCursor {
+    version: i64,
+    issued_at: DateTime<Utc>,
+}
+
+impl SyncCursor {
+    pub fn new(version: i64) -> SyncCursor {
+        SyncCursor {
+            version,
+            issued_at: Utc::now(),
+        }
+    }
+
+    pub fn get_version(&self) -> i64 {
+        self.version
+    }
+
+    pub fn get_issued_at(&self) -> DateTime<Utc> {
+        self.issued_at
+    }
+
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!(
+            "{}:{}",
+            self.version,
+            self.issued_at.timestamp_micros()
+        ))
+    }
+
+    pub fn decode(cursor: &str) -> Result<SyncCursor, InvalidSyncError> {
+        let invalid = || InvalidSyncError::new(Some(String::from("Invalid cursor")));
+
+        let decoded = URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalid())?;
+        let decoded = String::from_utf8(decoded).map_err(|_| invalid())?;
+        let (version, micros) = decoded.split_once(':').ok_or_else(invalid)?;
+        let version = version.parse::<i64>().map_err(|_| invalid())?;
+        let micros = micros.parse::<i64>().map_err(|_| invalid())?;
+        let issued_at = DateTime::<Utc>::from_timestamp_micros(micros).ok_or_else(invalid)?;
+
+        Ok(SyncCursor { version, issued_at })
+    }
+}
+
+// A change made offline by a client
+pub struct SyncMutation {
+    operation: String,
+    goal_id: String,
+    text: Option<String>,
+    completed: Option<bool>,
+    organization_id: Option<String>,
+    updated_at: DateTime<Utc>,
+}
+
+impl SyncMutation {
+    fn new() -> SyncMutation {
+        SyncMutation {
+            operation: String::from(UPDATE_OPERATION),
+            goal_id: String::from("NO_GOAL_ID"),
+            text: None,
+            completed: None,
+            organization_id: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    pub fn validate_operation(operation: &str) -> Result<(), InvalidSyncError> {
+        match operation {
+            CREATE_OPERATION | UPDATE_OPERATION | DELETE_OPERATION => Ok(()),
+            _ => Err(InvalidSyncError::new(Some(format!(
+                "Unknown operation: {}",
+                operation
+            )))),
+        }
+    }
+
+    // Dates in the future are set to now, a wrong clock on a client cannot win every conflict
+    pub fn parse_updated_at(updated_at: Option<String>) -> Result<DateTime<Utc>, InvalidSyncError> {
+        let now = Utc::now();
+        match updated_at {
+            None => Ok(now),
+            Some(updated_at) => {
+                let updated_at = DateTime::parse_from_rfc3339(&updated_at).map_err(|_| {
+                    InvalidSyncError::new(Some(String::from("updated_at must be an RFC 3339 date")))
+                })?;
+                Ok(updated_at.with_timezone(&Utc).min(now))
+            }
+        }
+    }
+
+    pub fn set_operation(&mut self, operation: String) -> Result<(), InvalidSyncError> {
+        SyncMutation::validate_operation(&operation)?;
+        self.operation = operation;
+        Ok(())
+    }
+
+    pub fn set_goal_id(&mut self, goal_id: String) -> Result<(), InvalidSyncError> {
+        Goal::validate_id(&goal_id).map_err(|err| InvalidSyncError::new(Some(err.to_string())))?;
+        self.goal_id = goal_id;
+        Ok(())
+    }
+
+    // The text and the organization are validated by the goal they create or change
+    pub fn set_text(&mut self, text: Option<String>) {
+        self.text = text;
+    }
+
+    pub fn set_completed(&mut self, completed: Option<bool>) {
+        self.completed = completed;
+    }
+
+    pub fn set_organization_id(&mut self, organization_id: Option<String>) {
+        self.organization_id = organization_id;
+    }
+
+    pub fn set_updated_at(&mut self, updated_at: Option<String>) -> Result<(), InvalidSyncError> {
+        self.updated_at = SyncMutation::parse_updated_at(updated_at)?;
+        Ok(())
+    }
+
+    pub fn get_operation(&self) -> String {
+        self.operation.clone()
+    }
+
+    pub fn get_goal_id(&self) -> String {
+        self.goal_id.clone()
+    }
+
+    pub fn get_text(&self) -> Option<String> {
+        self.text.clone()
+    }
+
+    pub fn get_completed(&self) -> Option<bool> {
+        self.completed
+    }
+
+    pub fn get_organization_id(&self) -> Option<String> {
+        self.organization_id.clone()
+    }
+
+    pub fn get_updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    pub fn from_sync_mutation_dto(
+        mutation_dto: SyncMutationDto,
+    ) -> Result<SyncMutation, InvalidSyncError> {
+        let mut mutation = SyncMutation::new();
+        mutation.set_operation(mutation_dto.operation)?;
+        mutation.set_goal_id(mutation_dto.goal_id)?;
+        mutation.set_text(mutation_dto.text);
+        mutation.set_completed(mutation_dto.completed);
+        mutation.set_organization_id(mutation_dto.organization_id);
+        mutation.set_updated_at(mutation_dto.updated_at)?;
+        Ok(mutation)
+    }
+}
+
+// A goal the user could see that was deleted, or that the user cannot see anymore
+pub struct GoalTombstone {
+    goal_id: String,
+    user_id: String,
+    version: i64,
+    deleted_at: Option<DateTime<Utc>>,
+}
+
+impl GoalTombstone {
+    fn new() -> GoalTombstone {
+        GoalTombstone {
+            goal_id: String::from("NO_GOAL_ID"),
+            user_id: String::from("NO_USER_ID"),
+            version: 0,
+            deleted_at: None,
+        }
+    }
+
+    pub fn set_goal_id(&mut self, goal_id: String) -> Result<(), InvalidSyncError> {
+        Goal::validate_id(&goal_id).map_err(|err| InvalidSyncError::new(Some(err.to_string())))?;
+        self.goal_id = goal_id;
+        Ok(())
+    }
+
+    pub fn set_user_id(&mut self, user_id: String) -> Result<(), InvalidSyncError> {
+        User::validate_id(&user_id).map_err(|err| InvalidSyncError::new(Some(err.to_string())))?;
+        self.user_id = user_id;
+        Ok(())
+    }
+
+    pub fn set_version(&mut self, version: i64) {
+        self.version = version;
+    }
+
+    pub fn set_deleted_at(&mut self, deleted_at: Option<DateTime<Utc>>) {
+        self.deleted_at = deleted_at;
+    }
+
+    pub fn get_goal_id(&self) -> String {
+        self.goal_id.clone()
+    }
+
+    pub fn get_version(&self) -> i64 {
+        self.version
+    }
+
+    pub fn get_deleted_at(&self) -> Option<DateTime<Utc>> {
+        self.deleted_at
+    }
+
+    pub fn from_db_fields(
+        goal_id: &str,
+        user_id: &str,
+        version: i64,
+        deleted_at: Option<DateTime<Utc>>,
+    ) -> Result<GoalTombstone, InvalidSyncError> {
+        let mut tombstone = GoalTombstone::new();
+        tombstone.set_goal_id(goal_id.to_string())?;
+        tombstone.set_user_id(user_id.to_string())?;
+        tombstone.set_version(version);
+        tombstone.set_deleted_at(deleted_at);
+        Ok(tombstone)
+    }
+}
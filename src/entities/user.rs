@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -26,6 +27,44 @@ pub struct CredentialsDto {
     pub password: String,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ChangePasswordDto {
+    pub email: String,
+    pub password: String,
+    pub new_password: String,
+    pub reset_token: Option<String>,
+}
+
+// Returned to the admin that forces a password reset, who gives the token to the user through
+// another channel than the account (in person, by phone)
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PasswordResetDto {
+    pub reset_token: String,
+    pub expires_at: String,
+}
+
+// User as seen by the admins
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UserDto {
+    pub id: String,
+    pub name: String,
+    pub email: String,
+    pub phone: String,
+    pub role: String,
+    pub is_disabled: bool,
+    pub password_reset_required: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UsersQueryDto {
+    pub search: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+pub const USER_ROLE: &str = "user";
+pub const ADMIN_ROLE: &str = "admin";
+
 #[derive(Debug)]
 pub struct User {
     id: String,
@@ -34,6 +73,12 @@ pub struct User {
     password: String,
     password_hash: String,
     phone: String,
+    role: String,
+    is_disabled: bool,
+    password_reset_required: bool,
+    password_reset_token_hash: Option<String>,
+    password_reset_expires_at: Option<DateTime<Utc>>,
+    password_changed_at: Option<DateTime<Utc>>,
 }
 
 impl User {
@@ -45,6 +90,12 @@ impl User {
             password: String::from("NO_PASSWORD"),
             password_hash: String::from("NO_PASSWORD_HASH"),
             phone: String::from("NO_PHONE"),
+            role: String::from(USER_ROLE),
+            is_disabled: false,
+            password_reset_required: false,
+            password_reset_token_hash: None,
+            password_reset_expires_at: None,
+            password_changed_at: None,
         }
     }
 
@@ -93,9 +144,11 @@ impl User {
                 "User password is required and cannot be empty",
             ))));
         }
-        if password.len() < 3 || password.len() > 32 {
+        // Only a hard limit here, so existing users can always sign in. New passwords are
+        // checked against the configurable password policy on sign up
+        if password.chars().count() > 1024 {
             return Err(InvalidUserError::new(Some(String::from(
-                "User password must be betweeen 3 and 32 characters long",
+                "User password must be at most 1024 characters long",
             ))));
         }
         Ok(())
@@ -126,6 +179,16 @@ impl User {
         Ok(())
     }
 
+    pub fn validate_role(role: &str) -> Result<(), InvalidUserError> {
+        if role != USER_ROLE && role != ADMIN_ROLE {
+            return Err(InvalidUserError::new(Some(format!(
+                "User role must be '{}' or '{}'",
+                USER_ROLE, ADMIN_ROLE
+            ))));
+        }
+        Ok(())
+    }
+
     pub fn set_id(&mut self, id: String) -> Result<(), InvalidUserError> {
         User::validate_id(&id)?;
         self.id = id;
@@ -162,6 +225,33 @@ impl User {
         Ok(())
     }
 
+    pub fn set_role(&mut self, role: String) -> Result<(), InvalidUserError> {
+        User::validate_role(&role)?;
+        self.role = role;
+        Ok(())
+    }
+
+    pub fn set_is_disabled(&mut self, is_disabled: bool) {
+        self.is_disabled = is_disabled;
+    }
+
+    pub fn set_password_reset_required(&mut self, password_reset_required: bool) {
+        self.password_reset_required = password_reset_required;
+    }
+
+    pub fn set_password_reset_token(
+        &mut self,
+        token_hash: Option<String>,
+        expires_at: Option<DateTime<Utc>>,
+    ) {
+        self.password_reset_token_hash = token_hash;
+        self.password_reset_expires_at = expires_at;
+    }
+
+    pub fn set_password_changed_at(&mut self, password_changed_at: Option<DateTime<Utc>>) {
+        self.password_changed_at = password_changed_at;
+    }
+
     pub fn get_id(&self) -> String {
         self.id.clone()
     }
@@ -186,6 +276,39 @@ impl User {
         self.phone.clone()
     }
 
+    pub fn get_role(&self) -> String {
+        self.role.clone()
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.role == ADMIN_ROLE
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.is_disabled
+    }
+
+    pub fn is_password_reset_required(&self) -> bool {
+        self.password_reset_required
+    }
+
+    pub fn get_password_reset_token_hash(&self) -> Option<String> {
+        self.password_reset_token_hash.clone()
+    }
+
+    pub fn get_password_reset_expires_at(&self) -> Option<DateTime<Utc>> {
+        self.password_reset_expires_at
+    }
+
+    // JWTs issued before the last password change or forced reset are not accepted any more.
+    // issued_at is in seconds, like the iat claim
+    pub fn is_token_revoked(&self, issued_at: i64) -> bool {
+        match self.password_changed_at {
+            None => false,
+            Some(password_changed_at) => issued_at < password_changed_at.timestamp(),
+        }
+    }
+
     pub fn from_create_user_dto(create_user: CreateUserDto) -> Result<User, InvalidUserError> {
         let mut user = User::new();
         user.set_name(create_user.name)?;
@@ -202,12 +325,19 @@ impl User {
         Ok(user)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn from_db_fields(
         id: &Uuid,
         name: &str,
         email: &str,
         password_hash: &str,
         phone: &str,
+        role: &str,
+        is_disabled: bool,
+        password_reset_required: bool,
+        password_reset_token_hash: Option<String>,
+        password_reset_expires_at: Option<DateTime<Utc>>,
+        password_changed_at: Option<DateTime<Utc>>,
     ) -> Result<User, InvalidUserError> {
         let mut user = User::new();
         user.set_id(id.to_string())?;
@@ -215,6 +345,11 @@ impl User {
         user.set_email(email.to_string())?;
         user.set_password_hash(password_hash.to_string())?;
         user.set_phone(phone.to_string())?;
+        user.set_role(role.to_string())?;
+        user.set_is_disabled(is_disabled);
+        user.set_password_reset_required(password_reset_required);
+        user.set_password_reset_token(password_reset_token_hash, password_reset_expires_at);
+        user.set_password_changed_at(password_changed_at);
         Ok(user)
     }
 }
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+use crate::errors::user_errors::InvalidUserError;
+
+use super::{habit::HabitDto, note::NoteDto};
+
+pub const JSON_FORMAT: &str = "json";
+pub const CSV_FORMAT: &str = "csv";
+pub const MARKDOWN_FORMAT: &str = "markdown";
+
+// The format defaults to json
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UserExportQueryDto {
+    pub format: Option<String>,
+}
+
+// A personal goal of the user in the export, archived ones included, with its notes and progress.
+// The export also has the profile of the user and its date
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ExportedGoalDto {
+    pub id: String,
+    pub text: String,
+    pub due_at: Option<String>,
+    pub completed_at: Option<String>,
+    pub archived_at: Option<String>,
+    pub habit: Option<HabitDto>,
+    pub check_ins: Vec<String>,
+    pub notes: Vec<NoteDto>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DataFormat {
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl DataFormat {
+    pub fn parse(format: Option<&str>) -> Result<DataFormat, InvalidUserError> {
+        match format.map(|format| format.trim().to_lowercase()).as_deref() {
+            None | Some(JSON_FORMAT) => Ok(DataFormat::Json),
+            Some(CSV_FORMAT) => Ok(DataFormat::Csv),
+            Some(MARKDOWN_FORMAT) | Some("md") => Ok(DataFormat::Markdown),
+            Some(format) => Err(InvalidUserError::new(Some(format!(
+                "Unknown format: {}, use json, csv or markdown",
+                format
+            )))),
+        }
+    }
+
+    pub fn get_content_type(&self) -> &'static str {
+        match self {
+            DataFormat::Json => "application/json",
+            DataFormat::Csv => "text/csv; charset=utf-8",
+            DataFormat::Markdown => "text/markdown; charset=utf-8",
+        }
+    }
+
+    pub fn get_extension(&self) -> &'static str {
+        match self {
+            DataFormat::Json => "json",
+            DataFormat::Csv => "csv",
+            DataFormat::Markdown => "md",
+        }
+    }
+}
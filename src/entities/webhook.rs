@@ -0,0 +1,470 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::errors::webhook_errors::InvalidWebhookError;
+
+use super::user::User;
+
+pub const GOAL_CREATED_EVENT: &str = "goal.created";
+pub const GOAL_UPDATED_EVENT: &str = "goal.updated";
+pub const GOAL_DELETED_EVENT: &str = "goal.deleted";
+pub const GOAL_RESTORED_EVENT: &str = "goal.restored";
+pub const GOAL_ARCHIVED_EVENT: &str = "goal.archived";
+pub const GOAL_UNARCHIVED_EVENT: &str = "goal.unarchived";
+pub const GOAL_COMPLETED_EVENT: &str = "goal.completed";
+pub const GOAL_SHARED_EVENT: &str = "goal.shared";
+pub const COMMENT_CREATED_EVENT: &str = "comment.created";
+pub const PASSWORD_CHANGED_EVENT: &str = "user.password_changed";
+// Only sent by the test endpoint, webhooks cannot subscribe to it
+pub const PING_EVENT: &str = "ping";
+
+pub const EVENT_TYPES: [&str; 10] = [
+    GOAL_CREATED_EVENT,
+    GOAL_UPDATED_EVENT,
+    GOAL_DELETED_EVENT,
+    GOAL_RESTORED_EVENT,
+    GOAL_ARCHIVED_EVENT,
+    GOAL_UNARCHIVED_EVENT,
+    GOAL_COMPLETED_EVENT,
+    GOAL_SHARED_EVENT,
+    COMMENT_CREATED_EVENT,
+    PASSWORD_CHANGED_EVENT,
+];
+
+pub const PENDING_STATUS: &str = "pending";
+pub const SENT_STATUS: &str = "sent";
+pub const FAILED_STATUS: &str = "failed";
+
+const MAX_URL_LENGTH: usize = 2000;
+const MIN_SECRET_LENGTH: usize = 16;
+const MAX_SECRET_LENGTH: usize = 200;
+
+// The secret signs the payloads, a random one is generated when it is missing
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateWebhookDto {
+    pub url: String,
+    pub event_types: Vec<String>,
+    pub secret: Option<String>,
+}
+
+// The secret is only returned when the webhook is created
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WebhookDto {
+    pub id: String,
+    pub url: String,
+    pub event_types: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    pub created_at: Option<String>,
+}
+
+// payload is the JSON body sent to the webhook. next_attempt_at is only present while the delivery
+// is pending
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WebhookDeliveryDto {
+    pub id: String,
+    pub webhook_id: String,
+    pub event_id: String,
+    pub event_type: String,
+    pub payload: Value,
+    pub status: String,
+    pub attempts: i32,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub next_attempt_at: Option<String>,
+    pub delivered_at: Option<String>,
+    pub created_at: Option<String>,
+}
+
+// Newest first
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WebhookDeliveriesQueryDto {
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+// JSON body POSTed to the webhooks. The id is the same for every webhook receiving the event, so
+// receivers can drop the retries they already processed
+#[derive(Debug, Serialize)]
+pub struct WebhookEvent {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub created_at: String,
+    pub data: Value,
+}
+
+impl WebhookEvent {
+    pub fn new(event_type: &str, data: Value) -> WebhookEvent {
+        WebhookEvent {
+            id: Uuid::new_v4().to_string(),
+            event_type: event_type.to_string(),
+            created_at: Utc::now().to_rfc3339(),
+            data,
+        }
+    }
+
+    pub fn to_payload(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+// A delivery claimed by the dispatcher, with what is needed to send and sign it
+pub struct PendingWebhookDelivery {
+    pub delivery_id: String,
+    pub event_id: String,
+    pub event_type: String,
+    pub payload: String,
+    pub url: String,
+    pub secret: String,
+    pub attempts: i32,
+}
+
+// Endpoint of a user that receives the events it subscribed to
+pub struct Webhook {
+    id: String,
+    user_id: String,
+    url: String,
+    secret: String,
+    event_types: Vec<String>,
+    created_at: Option<DateTime<Utc>>,
+}
+
+impl Webhook {
+    fn new() -> Webhook {
+        Webhook {
+            id: String::from("NO_ID"),
+            user_id: String::from("NO_USER_ID"),
+            url: String::from("NO_URL"),
+            secret: String::from("NO_SECRET"),
+            event_types: Vec::new(),
+            created_at: None,
+        }
+    }
+
+    pub fn validate_id(id: &str) -> Result<(), InvalidWebhookError> {
+        match Uuid::parse_str(id) {
+            Err(_) => Err(InvalidWebhookError::new(Some(String::from(
+                "Webhook id is not a valid UUID",
+            )))),
+            Ok(_) => Ok(()),
+        }
+    }
+
+    pub fn validate_url(url: &str) -> Result<(), InvalidWebhookError> {
+        if !(url.starts_with("https://") || url.starts_with("http://"))
+            || url.len() > MAX_URL_LENGTH
+        {
+            return Err(InvalidWebhookError::new(Some(String::from(
+                "Webhook url must be an http or https url",
+            ))));
+        }
+        Ok(())
+    }
+
+    pub fn validate_secret(secret: &str) -> Result<(), InvalidWebhookError> {
+        let length = secret.chars().count();
+        if !(MIN_SECRET_LENGTH..=MAX_SECRET_LENGTH).contains(&length) {
+            return Err(InvalidWebhookError::new(Some(format!(
+                "Webhook secret must be between {} and {} characters long",
+                MIN_SECRET_LENGTH, MAX_SECRET_LENGTH
+            ))));
+        }
+        Ok(())
+    }
+
+    pub fn validate_event_types(event_types: &[String]) -> Result<(), InvalidWebhookError> {
+        if event_types.is_empty() {
+            return Err(InvalidWebhookError::new(Some(String::from(
+                "A webhook must subscribe to at least one event type",
+            ))));
+        }
+        for event_type in event_types.iter() {
+            if !EVENT_TYPES.contains(&event_type.as_str()) {
+                return Err(InvalidWebhookError::new(Some(format!(
+                    "Unknown event type: {}. Event types are: {}",
+                    event_type,
+                    EVENT_TYPES.join(", ")
+                ))));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn validate_delivery_status(status: &str) -> Result<(), InvalidWebhookError> {
+        match status {
+            PENDING_STATUS | SENT_STATUS | FAILED_STATUS => Ok(()),
+            _ => Err(InvalidWebhookError::new(Some(format!(
+                "Delivery status must be one of: {}, {}, {}",
+                PENDING_STATUS, SENT_STATUS, FAILED_STATUS
+            )))),
+        }
+    }
+
+    pub fn set_id(&mut self, id: String) -> Result<(), InvalidWebhookError> {
+        Webhook::validate_id(&id)?;
+        self.id = id;
+        Ok(())
+    }
+
+    pub fn set_user_id(&mut self, user_id: String) -> Result<(), InvalidWebhookError> {
+        User::validate_id(&user_id)
+            .map_err(|err| InvalidWebhookError::new(Some(err.to_string())))?;
+        self.user_id = user_id;
+        Ok(())
+    }
+
+    pub fn set_url(&mut self, url: String) -> Result<(), InvalidWebhookError> {
+        Webhook::validate_url(&url)?;
+        self.url = url;
+        Ok(())
+    }
+
+    pub fn set_secret(&mut self, secret: String) -> Result<(), InvalidWebhookError> {
+        Webhook::validate_secret(&secret)?;
+        self.secret = secret;
+        Ok(())
+    }
+
+    // Duplicated event types are kept once
+    pub fn set_event_types(&mut self, event_types: Vec<String>) -> Result<(), InvalidWebhookError> {
+        Webhook::validate_event_types(&event_types)?;
+        let mut event_types = event_types;
+        event_types.sort();
+        event_types.dedup();
+        self.event_types = event_types;
+        Ok(())
+    }
+
+    pub fn set_created_at(&mut self, created_at: Option<DateTime<Utc>>) {
+        self.created_at = created_at;
+    }
+
+    pub fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    pub fn get_user_id(&self) -> String {
+        self.user_id.clone()
+    }
+
+    pub fn get_url(&self) -> String {
+        self.url.clone()
+    }
+
+    pub fn get_secret(&self) -> String {
+        self.secret.clone()
+    }
+
+    pub fn get_event_types(&self) -> Vec<String> {
+        self.event_types.clone()
+    }
+
+    pub fn get_created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    pub fn from_create_webhook_dto(
+        create_webhook: CreateWebhookDto,
+        user_id: &str,
+        generated_secret: String,
+    ) -> Result<Webhook, InvalidWebhookError> {
+        let mut webhook = Webhook::new();
+        webhook.set_user_id(user_id.to_string())?;
+        webhook.set_url(create_webhook.url)?;
+        webhook.set_secret(create_webhook.secret.unwrap_or(generated_secret))?;
+        webhook.set_event_types(create_webhook.event_types)?;
+        Ok(webhook)
+    }
+
+    pub fn from_db_fields(
+        id: &str,
+        user_id: &str,
+        url: &str,
+        secret: &str,
+        event_types: Vec<String>,
+        created_at: Option<DateTime<Utc>>,
+    ) -> Result<Webhook, InvalidWebhookError> {
+        let mut webhook = Webhook::new();
+        webhook.set_id(id.to_string())?;
+        webhook.set_user_id(user_id.to_string())?;
+        webhook.set_url(url.to_string())?;
+        webhook.set_secret(secret.to_string())?;
+        webhook.set_event_types(event_types)?;
+        webhook.set_created_at(created_at);
+        Ok(webhook)
+    }
+}
+
+// One attempt log of an event sent to a webhook
+pub struct WebhookDelivery {
+    id: String,
+    webhook_id: String,
+    event_id: String,
+    event_type: String,
+    payload: String,
+    status: String,
+    attempts: i32,
+    response_status: Option<i32>,
+    last_error: Option<String>,
+    next_attempt_at: Option<DateTime<Utc>>,
+    delivered_at: Option<DateTime<Utc>>,
+    created_at: Option<DateTime<Utc>>,
+}
+
+impl WebhookDelivery {
+    fn new() -> WebhookDelivery {
+        WebhookDelivery {
+            id: String::from("NO_ID"),
+            webhook_id: String::from("NO_WEBHOOK_ID"),
+            event_id: String::from("NO_EVENT_ID"),
+            event_type: String::from(PING_EVENT),
+            payload: String::from("{}"),
+            status: String::from(PENDING_STATUS),
+            attempts: 0,
+            response_status: None,
+            last_error: None,
+            next_attempt_at: None,
+            delivered_at: None,
+            created_at: None,
+        }
+    }
+
+    pub fn validate_id(id: &str) -> Result<(), InvalidWebhookError> {
+        match Uuid::parse_str(id) {
+            Err(_) => Err(InvalidWebhookError::new(Some(String::from(
+                "Delivery id is not a valid UUID",
+            )))),
+            Ok(_) => Ok(()),
+        }
+    }
+
+    pub fn set_id(&mut self, id: String) -> Result<(), InvalidWebhookError> {
+        WebhookDelivery::validate_id(&id)?;
+        self.id = id;
+        Ok(())
+    }
+
+    pub fn set_webhook_id(&mut self, webhook_id: String) -> Result<(), InvalidWebhookError> {
+        Webhook::validate_id(&webhook_id)?;
+        self.webhook_id = webhook_id;
+        Ok(())
+    }
+
+    pub fn set_event(&mut self, event_id: String, event_type: String, payload: String) {
+        self.event_id = event_id;
+        self.event_type = event_type;
+        self.payload = payload;
+    }
+
+    pub fn set_status(&mut self, status: String) -> Result<(), InvalidWebhookError> {
+        Webhook::validate_delivery_status(&status)?;
+        self.status = status;
+        Ok(())
+    }
+
+    pub fn set_attempts(&mut self, attempts: i32) {
+        self.attempts = attempts;
+    }
+
+    pub fn set_response(&mut self, response_status: Option<i32>, last_error: Option<String>) {
+        self.response_status = response_status;
+        self.last_error = last_error;
+    }
+
+    pub fn set_dates(
+        &mut self,
+        next_attempt_at: Option<DateTime<Utc>>,
+        delivered_at: Option<DateTime<Utc>>,
+        created_at: Option<DateTime<Utc>>,
+    ) {
+        self.next_attempt_at = next_attempt_at;
+        self.delivered_at = delivered_at;
+        self.created_at = created_at;
+    }
+
+    pub fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    pub fn get_webhook_id(&self) -> String {
+        self.webhook_id.clone()
+    }
+
+    pub fn get_event_id(&self) -> String {
+        self.event_id.clone()
+    }
+
+    pub fn get_event_type(&self) -> String {
+        self.event_type.clone()
+    }
+
+    pub fn get_payload(&self) -> String {
+        self.payload.clone()
+    }
+
+    pub fn get_status(&self) -> String {
+        self.status.clone()
+    }
+
+    pub fn get_attempts(&self) -> i32 {
+        self.attempts
+    }
+
+    pub fn get_response_status(&self) -> Option<i32> {
+        self.response_status
+    }
+
+    pub fn get_last_error(&self) -> Option<String> {
+        self.last_error.clone()
+    }
+
+    // Only meaningful while the delivery is pending
+    pub fn get_next_attempt_at(&self) -> Option<DateTime<Utc>> {
+        match self.status.as_str() {
+            PENDING_STATUS => self.next_attempt_at,
+            _ => None,
+        }
+    }
+
+    pub fn get_delivered_at(&self) -> Option<DateTime<Utc>> {
+        self.delivered_at
+    }
+
+    pub fn get_created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_db_fields(
+        id: &str,
+        webhook_id: &str,
+        event_id: &str,
+        event_type: &str,
+        payload: &str,
+        status: &str,
+        attempts: i32,
+        response_status: Option<i32>,
+        last_error: Option<String>,
+        next_attempt_at: Option<DateTime<Utc>>,
+        delivered_at: Option<DateTime<Utc>>,
+        created_at: Option<DateTime<Utc>>,
+    ) -> Result<WebhookDelivery, InvalidWebhookError> {
+        let mut delivery = WebhookDelivery::new();
+        delivery.set_id(id.to_string())?;
+        delivery.set_webhook_id(webhook_id.to_string())?;
+        delivery.set_event(
+            event_id.to_string(),
+            event_type.to_string(),
+            payload.to_string(),
+        );
+        delivery.set_status(status.to_string())?;
+        delivery.set_attempts(attempts);
+        delivery.set_response(response_status, last_error);
+        delivery.set_dates(next_attempt_at, delivered_at, created_at);
+        Ok(delivery)
+    }
+}
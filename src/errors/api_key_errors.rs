@@ -0,0 +1,24 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+#[derive(Debug)]
+pub struct InvalidApiKeyError(String);
+
+impl InvalidApiKeyError {
+    pub fn new(message: Option<String>) -> InvalidApiKeyError {
+        match message {
+            None => InvalidApiKeyError("Err: Api key is invalid".into()),
+            Some(msg) => InvalidApiKeyError(msg),
+        }
+    }
+}
+
+impl Display for InvalidApiKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for InvalidApiKeyError {}
@@ -0,0 +1,24 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+#[derive(Debug)]
+pub struct InvalidAuditEventError(String);
+
+impl InvalidAuditEventError {
+    pub fn new(message: Option<String>) -> InvalidAuditEventError {
+        match message {
+            None => InvalidAuditEventError("Err: Audit event is invalid".into()),
+            Some(msg) => InvalidAuditEventError(msg),
+        }
+    }
+}
+
+impl Display for InvalidAuditEventError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for InvalidAuditEventError {}
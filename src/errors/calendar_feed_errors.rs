@@ -0,0 +1,24 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+#[derive(Debug)]
+pub struct InvalidCalendarFeedError(String);
+
+impl InvalidCalendarFeedError {
+    pub fn new(message: Option<String>) -> InvalidCalendarFeedError {
+        match message {
+            None => InvalidCalendarFeedError("Err: Calendar feed is invalid".into()),
+            Some(msg) => InvalidCalendarFeedError(msg),
+        }
+    }
+}
+
+impl Display for InvalidCalendarFeedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for InvalidCalendarFeedError {}
@@ -0,0 +1,24 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+#[derive(Debug)]
+pub struct InvalidChannelError(String);
+
+impl InvalidChannelError {
+    pub fn new(message: Option<String>) -> InvalidChannelError {
+        match message {
+            None => InvalidChannelError("Err: Channel is invalid".into()),
+            Some(msg) => InvalidChannelError(msg),
+        }
+    }
+}
+
+impl Display for InvalidChannelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for InvalidChannelError {}
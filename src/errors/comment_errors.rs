@@ -0,0 +1,24 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+#[derive(Debug)]
+pub struct InvalidCommentError(String);
+
+impl InvalidCommentError {
+    pub fn new(message: Option<String>) -> InvalidCommentError {
+        match message {
+            None => InvalidCommentError("Err: Comment is invalid".into()),
+            Some(msg) => InvalidCommentError(msg),
+        }
+    }
+}
+
+impl Display for InvalidCommentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for InvalidCommentError {}
@@ -0,0 +1,24 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+#[derive(Debug)]
+pub struct InvalidGoalEventError(String);
+
+impl InvalidGoalEventError {
+    pub fn new(message: Option<String>) -> InvalidGoalEventError {
+        match message {
+            None => InvalidGoalEventError("Err: Goal event is invalid".into()),
+            Some(msg) => InvalidGoalEventError(msg),
+        }
+    }
+}
+
+impl Display for InvalidGoalEventError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for InvalidGoalEventError {}
@@ -0,0 +1,24 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+#[derive(Debug)]
+pub struct InvalidGoalRevisionError(String);
+
+impl InvalidGoalRevisionError {
+    pub fn new(message: Option<String>) -> InvalidGoalRevisionError {
+        match message {
+            None => InvalidGoalRevisionError("Err: Goal revision is invalid".into()),
+            Some(msg) => InvalidGoalRevisionError(msg),
+        }
+    }
+}
+
+impl Display for InvalidGoalRevisionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for InvalidGoalRevisionError {}
@@ -0,0 +1,24 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+#[derive(Debug)]
+pub struct InvalidHabitError(String);
+
+impl InvalidHabitError {
+    pub fn new(message: Option<String>) -> InvalidHabitError {
+        match message {
+            None => InvalidHabitError("Err: Habit is invalid".into()),
+            Some(msg) => InvalidHabitError(msg),
+        }
+    }
+}
+
+impl Display for InvalidHabitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for InvalidHabitError {}
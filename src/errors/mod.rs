@@ -1,2 +1,17 @@
 pub mod user_errors;
 pub mod goal_errors;
+pub mod api_key_errors;
+pub mod organization_errors;
+pub mod share_link_errors;
+pub mod comment_errors;
+pub mod note_errors;
+pub mod habit_errors;
+pub mod reminder_errors;
+pub mod notification_errors;
+pub mod webhook_errors;
+pub mod goal_event_errors;
+pub mod channel_errors;
+pub mod sync_errors;
+pub mod goal_revision_errors;
+pub mod audit_event_errors;
+pub mod calendar_feed_errors;
@@ -0,0 +1,24 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+#[derive(Debug)]
+pub struct InvalidNoteError(String);
+
+impl InvalidNoteError {
+    pub fn new(message: Option<String>) -> InvalidNoteError {
+        match message {
+            None => InvalidNoteError("Err: Note is invalid".into()),
+            Some(msg) => InvalidNoteError(msg),
+        }
+    }
+}
+
+impl Display for InvalidNoteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for InvalidNoteError {}
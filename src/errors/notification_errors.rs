@@ -0,0 +1,24 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+#[derive(Debug)]
+pub struct InvalidNotificationError(String);
+
+impl InvalidNotificationError {
+    pub fn new(message: Option<String>) -> InvalidNotificationError {
+        match message {
+            None => InvalidNotificationError("Err: Notification is invalid".into()),
+            Some(msg) => InvalidNotificationError(msg),
+        }
+    }
+}
+
+impl Display for InvalidNotificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for InvalidNotificationError {}
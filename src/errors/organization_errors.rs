@@ -0,0 +1,24 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+#[derive(Debug)]
+pub struct InvalidOrganizationError(String);
+
+impl InvalidOrganizationError {
+    pub fn new(message: Option<String>) -> InvalidOrganizationError {
+        match message {
+            None => InvalidOrganizationError("Err: Organization is invalid".into()),
+            Some(msg) => InvalidOrganizationError(msg),
+        }
+    }
+}
+
+impl Display for InvalidOrganizationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for InvalidOrganizationError {}
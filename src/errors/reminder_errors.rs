@@ -0,0 +1,24 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+#[derive(Debug)]
+pub struct InvalidReminderError(String);
+
+impl InvalidReminderError {
+    pub fn new(message: Option<String>) -> InvalidReminderError {
+        match message {
+            None => InvalidReminderError("Err: Reminder is invalid".into()),
+            Some(msg) => InvalidReminderError(msg),
+        }
+    }
+}
+
+impl Display for InvalidReminderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for InvalidReminderError {}
@@ -0,0 +1,24 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+#[derive(Debug)]
+pub struct InvalidShareLinkError(String);
+
+impl InvalidShareLinkError {
+    pub fn new(message: Option<String>) -> InvalidShareLinkError {
+        match message {
+            None => InvalidShareLinkError("Err: Share link is invalid".into()),
+            Some(msg) => InvalidShareLinkError(msg),
+        }
+    }
+}
+
+impl Display for InvalidShareLinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for InvalidShareLinkError {}
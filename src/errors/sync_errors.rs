@@ -0,0 +1,24 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+#[derive(Debug)]
+pub struct InvalidSyncError(String);
+
+impl InvalidSyncError {
+    pub fn new(message: Option<String>) -> InvalidSyncError {
+        match message {
+            None => InvalidSyncError("Err: Sync request is invalid".into()),
+            Some(msg) => InvalidSyncError(msg),
+        }
+    }
+}
+
+impl Display for InvalidSyncError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for InvalidSyncError {}
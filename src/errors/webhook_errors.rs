@@ -0,0 +1,24 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+#[derive(Debug)]
+pub struct InvalidWebhookError(String);
+
+impl InvalidWebhookError {
+    pub fn new(message: Option<String>) -> InvalidWebhookError {
+        match message {
+            None => InvalidWebhookError("Err: Webhook is invalid".into()),
+            Some(msg) => InvalidWebhookError(msg),
+        }
+    }
+}
+
+impl Display for InvalidWebhookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for InvalidWebhookError {}
@@ -1,7 +1,48 @@
-use actix_web::{App, HttpServer};
+#![allow(clippy::enum_variant_names)]
 
+use std::time::Duration;
+
+use actix_web::{web, App, HttpServer};
+
+use crate::routes::admin_routes::*;
+use crate::routes::api_key_routes::*;
+use crate::routes::calendar_routes::*;
+use crate::routes::comment_routes::*;
 use crate::routes::goal_routes::*;
+use crate::routes::goal_share_routes::*;
+use crate::routes::goal_stream_routes::*;
+use crate::routes::habit_routes::*;
+use crate::routes::note_routes::*;
+use crate::routes::notification_routes::*;
+use crate::routes::organization_routes::*;
+use crate::routes::public_goal_routes::*;
+use crate::routes::reminder_routes::*;
+use crate::routes::share_link_routes::*;
+use crate::routes::sync_routes::*;
 use crate::routes::user_routes::*;
+use crate::routes::webhook_routes::*;
+use crate::routes::websocket_routes::*;
+use crate::routes::well_known_routes::*;
+use crate::services::audit_services::init_trusted_proxies;
+use crate::services::auth_services::init_password_hash_config;
+use crate::services::goal_event_services::spawn_goal_event_listener;
+use crate::services::jwt_key_services::init_jwt_keys;
+use crate::services::notifier_services::init_notifiers;
+use crate::services::password_policy_services::init_password_policy;
+use crate::services::scheduler_services::spawn_periodic;
+use crate::use_cases::admin::bootstrap_admin::{self, BootstrapAdminError};
+use crate::use_cases::goals::archive_completed_goals::{self, ArchiveCompletedGoalsError};
+use crate::use_cases::goals::notify_approaching_deadlines::{
+    self, NotifyApproachingDeadlinesError,
+};
+use crate::use_cases::goals::purge_goal_events::{self, PurgeGoalEventsError};
+use crate::use_cases::goals::purge_trashed_goals::{self, PurgeTrashedGoalsError};
+use crate::use_cases::realtime::purge_stale_presence::{self, PurgeStalePresenceError};
+use crate::use_cases::reminders::dispatch_due_reminders::{self, DispatchDueRemindersError};
+use crate::use_cases::sync::purge_goal_tombstones::{self, PurgeGoalTombstonesError};
+use crate::use_cases::webhooks::dispatch_webhook_deliveries::{
+    self, DispatchWebhookDeliveriesError,
+};
 
 mod data_access;
 mod entities;
@@ -11,18 +52,183 @@ mod services;
 mod use_cases;
 mod utils;
 mod db;
+mod config;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()>
 {
-    HttpServer::new(|| {
+    init_jwt_keys().map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+    init_password_hash_config()
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+    init_password_policy()
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+    init_notifiers().map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+    init_trusted_proxies()
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+
+    match bootstrap_admin::execute().await {
+        Err(BootstrapAdminError::DatabaseError(err_msg))
+        | Err(BootstrapAdminError::UserNotFoundError(err_msg)) => {
+            eprintln!("Bootstrap admin error: {}", err_msg)
+        }
+        Ok(Some(email)) => println!("User {} promoted to admin", email),
+        Ok(None) => {}
+    }
+
+    let reminder_poll_seconds = config::get_env_parsed("REMINDER_POLL_SECONDS", 30).max(1);
+    spawn_periodic(Duration::from_secs(reminder_poll_seconds), || async {
+        if let Err(DispatchDueRemindersError::DatabaseError(err_msg)) =
+            dispatch_due_reminders::execute().await
+        {
+            eprintln!("Reminder scheduler error: {}", err_msg)
+        }
+    });
+
+    let webhook_poll_seconds = config::get_env_parsed("WEBHOOK_POLL_SECONDS", 10).max(1);
+    spawn_periodic(Duration::from_secs(webhook_poll_seconds), || async {
+        if let Err(DispatchWebhookDeliveriesError::DatabaseError(err_msg)) =
+            dispatch_webhook_deliveries::execute().await
+        {
+            eprintln!("Webhook dispatcher error: {}", err_msg)
+        }
+    });
+
+    spawn_goal_event_listener();
+    spawn_periodic(Duration::from_secs(3600), || async {
+        if let Err(PurgeGoalEventsError::DatabaseError(err_msg)) = purge_goal_events::execute().await
+        {
+            eprintln!("Goal events purge error: {}", err_msg)
+        }
+    });
+    spawn_periodic(Duration::from_secs(60), || async {
+        if let Err(PurgeStalePresenceError::DatabaseError(err_msg)) =
+            purge_stale_presence::execute().await
+        {
+            eprintln!("Presence purge error: {}", err_msg)
+        }
+    });
+    spawn_periodic(Duration::from_secs(3600), || async {
+        if let Err(PurgeGoalTombstonesError::DatabaseError(err_msg)) =
+            purge_goal_tombstones::execute().await
+        {
+            eprintln!("Goal tombstones purge error: {}", err_msg)
+        }
+    });
+    spawn_periodic(Duration::from_secs(3600), || async {
+        if let Err(PurgeTrashedGoalsError::DatabaseError(err_msg)) =
+            purge_trashed_goals::execute().await
+        {
+            eprintln!("Goal trash purge error: {}", err_msg)
+        }
+    });
+    spawn_periodic(Duration::from_secs(3600), || async {
+        if let Err(ArchiveCompletedGoalsError::DatabaseError(err_msg)) =
+            archive_completed_goals::execute().await
+        {
+            eprintln!("Goal auto-archive error: {}", err_msg)
+        }
+    });
+    spawn_periodic(Duration::from_secs(60), || async {
+        if let Err(NotifyApproachingDeadlinesError::DatabaseError(err_msg)) =
+            notify_approaching_deadlines::execute().await
+        {
+            eprintln!("Deadline notifications error: {}", err_msg)
+        }
+    });
+
+    // Only the imports take large bodies, the other routes keep the default limits
+    let import_max_bytes: usize = config::get_env_parsed("IMPORT_MAX_BYTES", 10 * 1024 * 1024);
+
+    HttpServer::new(move || {
         App::new()
             .service(signup_route)
             .service(signin_route)
             .service(verify_token_route)
+            .service(change_password_route)
+            .service(export_user_data_route)
             .service(add_goal_route)
             .service(get_goals_route)
+            .service(get_trashed_goals_route)
+            .service(restore_goal_route)
+            .service(purge_goal_route)
+            .service(archive_goal_route)
+            .service(unarchive_goal_route)
+            .service(get_goal_history_route)
+            .service(revert_goal_route)
+            .service(
+                web::resource("/api/goals/import")
+                    .app_data(web::PayloadConfig::new(import_max_bytes))
+                    .route(web::post().to(import_goals_route)),
+            )
+            .service(
+                web::resource("/api/goals/import/{source}")
+                    .app_data(web::PayloadConfig::new(import_max_bytes))
+                    .route(web::post().to(import_goals_from_tool_route)),
+            )
+            .service(goal_stream_route)
+            .service(websocket_route)
+            .service(get_changes_route)
+            .service(apply_mutations_route)
             .service(delete_goal_route)
+            .service(update_goal_route)
+            .service(add_goal_share_route)
+            .service(get_goal_shares_route)
+            .service(revoke_goal_share_route)
+            .service(add_share_link_route)
+            .service(get_share_links_route)
+            .service(revoke_share_link_route)
+            .service(get_public_goal_route)
+            .service(create_calendar_feed_route)
+            .service(revoke_calendar_feed_route)
+            .service(get_calendar_feed_route)
+            .service(add_comment_route)
+            .service(get_comments_route)
+            .service(update_comment_route)
+            .service(delete_comment_route)
+            .service(get_comment_history_route)
+            .service(add_note_route)
+            .service(get_notes_route)
+            .service(update_note_route)
+            .service(delete_note_route)
+            .service(add_reminder_route)
+            .service(get_reminders_route)
+            .service(delete_reminder_route)
+            .service(get_notifications_route)
+            .service(get_unread_count_route)
+            .service(mark_all_notifications_read_route)
+            .service(mark_notification_read_route)
+            .service(mark_notification_unread_route)
+            .service(delete_notification_route)
+            .service(add_webhook_route)
+            .service(get_webhooks_route)
+            .service(delete_webhook_route)
+            .service(get_webhook_deliveries_route)
+            .service(test_webhook_route)
+            .service(set_habit_route)
+            .service(get_habit_route)
+            .service(delete_habit_route)
+            .service(check_in_route)
+            .service(undo_check_in_route)
+            .service(get_habit_history_route)
+            .service(add_api_key_route)
+            .service(get_api_keys_route)
+            .service(revoke_api_key_route)
+            .service(jwks_route)
+            .service(admin_get_users_route)
+            .service(admin_disable_user_route)
+            .service(admin_enable_user_route)
+            .service(admin_force_password_reset_route)
+            .service(admin_get_user_goals_route)
+            .service(admin_get_audit_events_route)
+            .service(admin_export_audit_events_route)
+            .service(add_organization_route)
+            .service(get_organizations_route)
+            .service(get_members_route)
+            .service(update_member_route)
+            .service(remove_member_route)
+            .service(add_invitation_route)
+            .service(accept_invitation_route)
+            .service(decline_invitation_route)
     })
     .bind(("127.0.0.1", 5000))?
     .run()
@@ -0,0 +1,185 @@
+use actix_web::{get, post, web, HttpRequest, HttpResponse, Responder};
+
+use crate::{
+    entities::{audit_event::AuditEventsQueryDto, user::UsersQueryDto},
+    use_cases::admin::{
+        export_audit_events::{self, ExportAuditEventsError},
+        force_password_reset::{self, ForcePasswordResetError},
+        get_audit_events::{self, GetAuditEventsError},
+        get_user_goals::{self, GetUserGoalsError},
+        get_users::{self, GetUsersError},
+        set_user_disabled::{self, SetUserDisabledError},
+    },
+    utils::routes_utils::{extract_admin_id_from_headers, extract_audit_context},
+};
+
+const ADMIN_MESSAGE: &str = "Missing or invalid admin JWT in authorization headers";
+
+#[get("/api/admin/users")]
+async fn admin_get_users_route(
+    query: web::Query<UsersQueryDto>,
+    req: HttpRequest,
+) -> impl Responder {
+    let admin_id = match extract_admin_id_from_headers(&req).await {
+        None => return HttpResponse::Forbidden().body(ADMIN_MESSAGE),
+        Some(id) => id,
+    };
+
+    match get_users::execute(query.into_inner(), admin_id).await {
+        Err(error) => match error {
+            GetUsersError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            GetUsersError::ForbiddenError(err_msg) => HttpResponse::Forbidden().body(err_msg),
+        },
+        Ok(users) => HttpResponse::Ok().json(users),
+    }
+}
+
+async fn set_user_disabled(req: HttpRequest, user_id: String, is_disabled: bool) -> HttpResponse {
+    let admin_id = match extract_admin_id_from_headers(&req).await {
+        None => return HttpResponse::Forbidden().body(ADMIN_MESSAGE),
+        Some(id) => id,
+    };
+
+    let audit = extract_audit_context(&req);
+
+    match set_user_disabled::execute(user_id, admin_id, is_disabled, audit).await {
+        Err(error) => match error {
+            SetUserDisabledError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            SetUserDisabledError::ForbiddenError(err_msg) => {
+                HttpResponse::Forbidden().body(err_msg)
+            }
+            SetUserDisabledError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            SetUserDisabledError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(_) => HttpResponse::NoContent().body(""),
+    }
+}
+
+#[post("/api/admin/users/{userId}/disable")]
+async fn admin_disable_user_route(req: HttpRequest, path: web::Path<String>) -> impl Responder {
+    set_user_disabled(req, path.into_inner(), true).await
+}
+
+#[post("/api/admin/users/{userId}/enable")]
+async fn admin_enable_user_route(req: HttpRequest, path: web::Path<String>) -> impl Responder {
+    set_user_disabled(req, path.into_inner(), false).await
+}
+
+#[post("/api/admin/users/{userId}/force-password-reset")]
+async fn admin_force_password_reset_route(
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    let admin_id = match extract_admin_id_from_headers(&req).await {
+        None => return HttpResponse::Forbidden().body(ADMIN_MESSAGE),
+        Some(id) => id,
+    };
+
+    let audit = extract_audit_context(&req);
+
+    match force_password_reset::execute(path.into_inner(), admin_id, audit).await {
+        Err(error) => match error {
+            ForcePasswordResetError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            ForcePasswordResetError::ForbiddenError(err_msg) => {
+                HttpResponse::Forbidden().body(err_msg)
+            }
+            ForcePasswordResetError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            ForcePasswordResetError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(password_reset) => HttpResponse::Ok().json(password_reset),
+    }
+}
+
+#[get("/api/admin/users/{userId}/goals")]
+async fn admin_get_user_goals_route(req: HttpRequest, path: web::Path<String>) -> impl Responder {
+    let admin_id = match extract_admin_id_from_headers(&req).await {
+        None => return HttpResponse::Forbidden().body(ADMIN_MESSAGE),
+        Some(id) => id,
+    };
+
+    let audit = extract_audit_context(&req);
+
+    match get_user_goals::execute(path.into_inner(), admin_id, audit).await {
+        Err(error) => match error {
+            GetUserGoalsError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            GetUserGoalsError::ForbiddenError(err_msg) => HttpResponse::Forbidden().body(err_msg),
+            GetUserGoalsError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            GetUserGoalsError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(goals) => HttpResponse::Ok().json(goals),
+    }
+}
+
+#[get("/api/admin/audit-events")]
+async fn admin_get_audit_events_route(
+    query: web::Query<AuditEventsQueryDto>,
+    req: HttpRequest,
+) -> impl Responder {
+    let admin_id = match extract_admin_id_from_headers(&req).await {
+        None => return HttpResponse::Forbidden().body(ADMIN_MESSAGE),
+        Some(id) => id,
+    };
+
+    match get_audit_events::execute(query.into_inner(), admin_id).await {
+        Err(error) => match error {
+            GetAuditEventsError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            GetAuditEventsError::ForbiddenError(err_msg) => HttpResponse::Forbidden().body(err_msg),
+            GetAuditEventsError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(events) => HttpResponse::Ok().json(events),
+    }
+}
+
+#[get("/api/admin/audit-events/export")]
+async fn admin_export_audit_events_route(
+    query: web::Query<AuditEventsQueryDto>,
+    req: HttpRequest,
+) -> impl Responder {
+    let admin_id = match extract_admin_id_from_headers(&req).await {
+        None => return HttpResponse::Forbidden().body(ADMIN_MESSAGE),
+        Some(id) => id,
+    };
+
+    match export_audit_events::execute(query.into_inner(), admin_id).await {
+        Err(error) => match error {
+            ExportAuditEventsError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            ExportAuditEventsError::ForbiddenError(err_msg) => {
+                HttpResponse::Forbidden().body(err_msg)
+            }
+            ExportAuditEventsError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(lines) => HttpResponse::Ok()
+            .content_type("application/x-ndjson")
+            .insert_header((
+                "Content-Disposition",
+                "attachment; filename=\"audit-events.jsonl\"",
+            ))
+            .body(lines),
+    }
+}
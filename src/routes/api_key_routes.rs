@@ -0,0 +1,84 @@
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse, Responder};
+
+use crate::{
+    entities::api_key::CreateApiKeyDto,
+    use_cases::api_keys::{
+        create_api_key::{self, CreateApiKeyError},
+        get_all_api_keys::{self, GetAllApiKeysError},
+        revoke_api_key::{self, RevokeApiKeyError},
+    },
+    utils::routes_utils::extract_user_id_from_headers,
+};
+
+// Api keys can only be managed with a JWT, an api key cannot create or revoke other keys
+const JWT_MESSAGE: &str = "Missing or invalid JWT in authorization headers";
+
+#[post("/api/users/api-keys")]
+async fn add_api_key_route(
+    req_body: web::Json<CreateApiKeyDto>,
+    req: HttpRequest,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_headers(&req).await {
+        None => return HttpResponse::BadRequest().body(JWT_MESSAGE),
+        Some(id) => id,
+    };
+
+    match create_api_key::execute(req_body.into_inner(), user_id).await {
+        Err(error) => match error {
+            CreateApiKeyError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+            CreateApiKeyError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            CreateApiKeyError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+        },
+        Ok(created_api_key) => HttpResponse::Created().json(created_api_key),
+    }
+}
+
+#[get("/api/users/api-keys")]
+async fn get_api_keys_route(req: HttpRequest) -> impl Responder {
+    let user_id = match extract_user_id_from_headers(&req).await {
+        None => return HttpResponse::BadRequest().body(JWT_MESSAGE),
+        Some(id) => id,
+    };
+
+    match get_all_api_keys::execute(user_id).await {
+        Err(error) => match error {
+            GetAllApiKeysError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            GetAllApiKeysError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+        },
+        Ok(api_keys) => HttpResponse::Ok().json(api_keys),
+    }
+}
+
+#[delete("/api/users/api-keys/{apiKeyId}")]
+async fn revoke_api_key_route(req: HttpRequest, path: web::Path<String>) -> impl Responder {
+    let user_id = match extract_user_id_from_headers(&req).await {
+        None => return HttpResponse::BadRequest().body(JWT_MESSAGE),
+        Some(id) => id,
+    };
+
+    let api_key_id = path.into_inner();
+
+    match revoke_api_key::execute(api_key_id, user_id).await {
+        Err(error) => match error {
+            RevokeApiKeyError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            RevokeApiKeyError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            RevokeApiKeyError::ApiKeyNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            RevokeApiKeyError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(_) => HttpResponse::NoContent().body(""),
+    }
+}
@@ -0,0 +1,106 @@
+use actix_web::{delete, get, http::header, post, web, HttpRequest, HttpResponse, Responder};
+
+use crate::{
+    entities::api_key::WRITE_SCOPE,
+    use_cases::calendar::{
+        create_calendar_feed::{self, CreateCalendarFeedError},
+        get_calendar_feed::{self, GetCalendarFeedError},
+        revoke_calendar_feed::{self, RevokeCalendarFeedError},
+    },
+    utils::routes_utils::extract_user_id_from_request,
+};
+
+const AUTH_MESSAGE: &str = "Missing or invalid JWT or api key in authorization headers";
+
+#[post("/api/users/me/calendar-feed")]
+async fn create_calendar_feed_route(req: HttpRequest) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    match create_calendar_feed::execute(user_id).await {
+        Err(error) => match error {
+            CreateCalendarFeedError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            CreateCalendarFeedError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            CreateCalendarFeedError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(calendar_feed) => HttpResponse::Created().json(calendar_feed),
+    }
+}
+
+#[delete("/api/users/me/calendar-feed")]
+async fn revoke_calendar_feed_route(req: HttpRequest) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    match revoke_calendar_feed::execute(user_id).await {
+        Err(error) => match error {
+            RevokeCalendarFeedError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            RevokeCalendarFeedError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            RevokeCalendarFeedError::CalendarFeedNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+        },
+        Ok(_) => HttpResponse::NoContent().finish(),
+    }
+}
+
+// No authorization headers, calendar apps only have the URL, the token in it gives the access.
+// Clients sending the ETag back in If-None-Match get a 304 while the feed did not change
+#[get("/calendar/{token}.ics")]
+async fn get_calendar_feed_route(req: HttpRequest, path: web::Path<String>) -> impl Responder {
+    let token = path.into_inner();
+
+    match get_calendar_feed::execute(token).await {
+        Err(error) => match error {
+            GetCalendarFeedError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            GetCalendarFeedError::NotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+        },
+        Ok(document) => {
+            let not_modified = req
+                .headers()
+                .get(header::IF_NONE_MATCH)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| {
+                    value
+                        .split(',')
+                        .any(|etag| etag.trim() == document.etag || etag.trim() == "*")
+                })
+                .unwrap_or(false);
+
+            let mut response = if not_modified {
+                HttpResponse::NotModified()
+            } else {
+                HttpResponse::Ok()
+            };
+
+            response
+                .insert_header((header::ETAG, document.etag.clone()))
+                .insert_header(("Cache-Control", "private, no-cache"))
+                .insert_header(("X-Robots-Tag", "noindex"));
+
+            if not_modified {
+                return response.finish();
+            }
+
+            response
+                .content_type("text/calendar; charset=utf-8")
+                .body(document.body)
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder};
+
+use crate::{
+    entities::{
+        api_key::{READ_SCOPE, WRITE_SCOPE},
+        comment::{CommentsQueryDto, CreateCommentDto, UpdateCommentDto},
+    },
+    use_cases::comments::{
+        create_comment::{self, CreateCommentError},
+        delete_comment::{self, DeleteCommentError},
+        get_comment_history::{self, GetCommentHistoryError},
+        get_comments::{self, GetCommentsError},
+        update_comment::{self, UpdateCommentError},
+    },
+    utils::routes_utils::extract_user_id_from_request,
+};
+
+const AUTH_MESSAGE: &str = "Missing or invalid JWT or api key in authorization headers";
+
+#[post("/api/goals/{goalId}/comments")]
+async fn add_comment_route(
+    req_body: web::Json<CreateCommentDto>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let goal_id = path.into_inner();
+
+    match create_comment::execute(goal_id, req_body.into_inner(), user_id).await {
+        Err(error) => match error {
+            CreateCommentError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            CreateCommentError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            CreateCommentError::GoalNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            CreateCommentError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(comment) => HttpResponse::Created().json(comment),
+    }
+}
+
+#[get("/api/goals/{goalId}/comments")]
+async fn get_comments_route(
+    query: web::Query<CommentsQueryDto>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, READ_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let goal_id = path.into_inner();
+
+    match get_comments::execute(goal_id, query.into_inner(), user_id).await {
+        Err(error) => match error {
+            GetCommentsError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            GetCommentsError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            GetCommentsError::GoalNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            GetCommentsError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(comments) => HttpResponse::Ok().json(comments),
+    }
+}
+
+#[put("/api/goals/{goalId}/comments/{commentId}")]
+async fn update_comment_route(
+    req_body: web::Json<UpdateCommentDto>,
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let (goal_id, comment_id) = path.into_inner();
+
+    match update_comment::execute(goal_id, comment_id, req_body.into_inner(), user_id).await {
+        Err(error) => match error {
+            UpdateCommentError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            UpdateCommentError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            UpdateCommentError::CommentNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            UpdateCommentError::ForbiddenError(err_msg) => HttpResponse::Forbidden().body(err_msg),
+            UpdateCommentError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(comment) => HttpResponse::Ok().json(comment),
+    }
+}
+
+#[delete("/api/goals/{goalId}/comments/{commentId}")]
+async fn delete_comment_route(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let (goal_id, comment_id) = path.into_inner();
+
+    match delete_comment::execute(goal_id, comment_id, user_id).await {
+        Err(error) => match error {
+            DeleteCommentError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            DeleteCommentError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            DeleteCommentError::CommentNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            DeleteCommentError::ForbiddenError(err_msg) => HttpResponse::Forbidden().body(err_msg),
+            DeleteCommentError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(_) => HttpResponse::NoContent().body(""),
+    }
+}
+
+#[get("/api/goals/{goalId}/comments/{commentId}/history")]
+async fn get_comment_history_route(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, READ_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let (goal_id, comment_id) = path.into_inner();
+
+    match get_comment_history::execute(goal_id, comment_id, user_id).await {
+        Err(error) => match error {
+            GetCommentHistoryError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            GetCommentHistoryError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            GetCommentHistoryError::CommentNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            GetCommentHistoryError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(edits) => HttpResponse::Ok().json(edits),
+    }
+}
@@ -1,24 +1,39 @@
-use actix_web::{delete, get, post, web, HttpRequest, HttpResponse, Responder};
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder};
 
 use crate::{
-    entities::goal::CreateGoalDto,
+    entities::{
+        api_key::{READ_SCOPE, WRITE_SCOPE},
+        goal::{CreateGoalDto, GoalsQueryDto, TrashedGoalsQueryDto, UpdateGoalDto},
+        goal_import::{ImportGoalsQueryDto, ToolImportQueryDto},
+        goal_revision::GoalHistoryQueryDto,
+    },
     use_cases::goals::{
+        archive_goal::{self, ArchiveGoalError},
         create_goal::{self, CreateGoalError},
         delete_goal::{self, DeleteGoalError},
         get_all_goals::{self, GetAllGoalsError},
+        get_goal_history::{self, GetGoalHistoryError},
+        get_trashed_goals::{self, GetTrashedGoalsError},
+        import_from_tool::{self, ImportFromToolError},
+        import_goals::{self, ImportGoalsError},
+        purge_goal::{self, PurgeGoalError},
+        restore_goal::{self, RestoreGoalError},
+        revert_goal::{self, RevertGoalError},
+        unarchive_goal::{self, UnarchiveGoalError},
+        update_goal::{self, UpdateGoalError},
     },
-    utils::routes_utils::extract_user_id_from_headers,
+    utils::routes_utils::extract_user_id_from_request,
 };
 
-const JWT_MESSAGE: &'static str = "Missing or invalid JWT in authorization headers";
+const AUTH_MESSAGE: &str = "Missing or invalid JWT or api key in authorization headers";
 
 #[post("/api/goals")]
 pub async fn add_goal_route(
     req_body: web::Json<CreateGoalDto>,
     req: HttpRequest,
 ) -> impl Responder {
-    let user_id = match extract_user_id_from_headers(&req) {
-        None => return HttpResponse::BadRequest().body(JWT_MESSAGE),
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
         Some(id) => id,
     };
 
@@ -28,6 +43,7 @@ pub async fn add_goal_route(
                 HttpResponse::BadRequest().body(err_msg)
             }
             CreateGoalError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            CreateGoalError::ForbiddenError(err_msg) => HttpResponse::Forbidden().body(err_msg),
 
             CreateGoalError::DatabaseError(err_msg) => {
                 HttpResponse::InternalServerError().body(err_msg)
@@ -38,18 +54,22 @@ pub async fn add_goal_route(
 }
 
 #[get("/api/goals")]
-async fn get_goals_route(req: HttpRequest) -> impl Responder {
-    let user_id = match extract_user_id_from_headers(&req) {
-        None => return HttpResponse::BadRequest().body(JWT_MESSAGE),
+async fn get_goals_route(query: web::Query<GoalsQueryDto>, req: HttpRequest) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, READ_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
         Some(id) => id,
     };
 
-    match get_all_goals::execute(user_id).await {
+    match get_all_goals::execute(query.into_inner(), user_id).await {
         Err(error) => match error {
             GetAllGoalsError::DatabaseError(err_msg) => {
                 HttpResponse::InternalServerError().body(err_msg)
             }
             GetAllGoalsError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            GetAllGoalsError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+            GetAllGoalsError::ForbiddenError(err_msg) => HttpResponse::Forbidden().body(err_msg),
         },
         Ok(goals) => HttpResponse::Ok().json(goals),
     }
@@ -57,8 +77,8 @@ async fn get_goals_route(req: HttpRequest) -> impl Responder {
 
 #[delete("/api/goals/{goalId}")]
 async fn delete_goal_route(req: HttpRequest, path: web::Path<String>) -> impl Responder {
-    let user_id = match extract_user_id_from_headers(&req) {
-        None => return HttpResponse::BadRequest().body(JWT_MESSAGE),
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
         Some(id) => id,
     };
 
@@ -72,6 +92,10 @@ async fn delete_goal_route(req: HttpRequest, path: web::Path<String>) -> impl Re
 
             DeleteGoalError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
 
+            DeleteGoalError::GoalNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+
+            DeleteGoalError::ForbiddenError(err_msg) => HttpResponse::Forbidden().body(err_msg),
+
             DeleteGoalError::InvalidRequestError(err_msg) => {
                 HttpResponse::BadRequest().body(err_msg)
             }
@@ -79,3 +103,283 @@ async fn delete_goal_route(req: HttpRequest, path: web::Path<String>) -> impl Re
         Ok(_) => HttpResponse::NoContent().body(""),
     }
 }
+
+#[put("/api/goals/{goalId}")]
+async fn update_goal_route(
+    req_body: web::Json<UpdateGoalDto>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let goal_id = path.into_inner();
+
+    match update_goal::execute(goal_id, req_body.into_inner(), user_id).await {
+        Err(error) => match error {
+            UpdateGoalError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            UpdateGoalError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            UpdateGoalError::GoalNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            UpdateGoalError::ForbiddenError(err_msg) => HttpResponse::Forbidden().body(err_msg),
+            UpdateGoalError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(goal) => HttpResponse::Ok().json(goal),
+    }
+}
+
+#[get("/api/goals/trash")]
+async fn get_trashed_goals_route(
+    query: web::Query<TrashedGoalsQueryDto>,
+    req: HttpRequest,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, READ_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    match get_trashed_goals::execute(query.into_inner(), user_id).await {
+        Err(error) => match error {
+            GetTrashedGoalsError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            GetTrashedGoalsError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            GetTrashedGoalsError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+            GetTrashedGoalsError::ForbiddenError(err_msg) => {
+                HttpResponse::Forbidden().body(err_msg)
+            }
+        },
+        Ok(goals) => HttpResponse::Ok().json(goals),
+    }
+}
+
+#[put("/api/goals/{goalId}/restore")]
+async fn restore_goal_route(req: HttpRequest, path: web::Path<String>) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let goal_id = path.into_inner();
+
+    match restore_goal::execute(goal_id, user_id).await {
+        Err(error) => match error {
+            RestoreGoalError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            RestoreGoalError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            RestoreGoalError::GoalNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            RestoreGoalError::ForbiddenError(err_msg) => HttpResponse::Forbidden().body(err_msg),
+            RestoreGoalError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(goal) => HttpResponse::Ok().json(goal),
+    }
+}
+
+#[delete("/api/goals/trash/{goalId}")]
+async fn purge_goal_route(req: HttpRequest, path: web::Path<String>) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let goal_id = path.into_inner();
+
+    match purge_goal::execute(goal_id, user_id).await {
+        Err(error) => match error {
+            PurgeGoalError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            PurgeGoalError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            PurgeGoalError::GoalNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            PurgeGoalError::ForbiddenError(err_msg) => HttpResponse::Forbidden().body(err_msg),
+            PurgeGoalError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(_) => HttpResponse::NoContent().body(""),
+    }
+}
+
+#[put("/api/goals/{goalId}/archive")]
+async fn archive_goal_route(req: HttpRequest, path: web::Path<String>) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let goal_id = path.into_inner();
+
+    match archive_goal::execute(goal_id, user_id).await {
+        Err(error) => match error {
+            ArchiveGoalError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            ArchiveGoalError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            ArchiveGoalError::GoalNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            ArchiveGoalError::ForbiddenError(err_msg) => HttpResponse::Forbidden().body(err_msg),
+            ArchiveGoalError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(goal) => HttpResponse::Ok().json(goal),
+    }
+}
+
+#[put("/api/goals/{goalId}/unarchive")]
+async fn unarchive_goal_route(req: HttpRequest, path: web::Path<String>) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let goal_id = path.into_inner();
+
+    match unarchive_goal::execute(goal_id, user_id).await {
+        Err(error) => match error {
+            UnarchiveGoalError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            UnarchiveGoalError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            UnarchiveGoalError::GoalNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            UnarchiveGoalError::ForbiddenError(err_msg) => HttpResponse::Forbidden().body(err_msg),
+            UnarchiveGoalError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(goal) => HttpResponse::Ok().json(goal),
+    }
+}
+
+#[get("/api/goals/{goalId}/history")]
+async fn get_goal_history_route(
+    query: web::Query<GoalHistoryQueryDto>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, READ_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let goal_id = path.into_inner();
+
+    match get_goal_history::execute(goal_id, query.into_inner(), user_id).await {
+        Err(error) => match error {
+            GetGoalHistoryError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            GetGoalHistoryError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            GetGoalHistoryError::GoalNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            GetGoalHistoryError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(revisions) => HttpResponse::Ok().json(revisions),
+    }
+}
+
+#[post("/api/goals/{goalId}/history/{revisionId}/revert")]
+async fn revert_goal_route(req: HttpRequest, path: web::Path<(String, String)>) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let (goal_id, revision_id) = path.into_inner();
+
+    match revert_goal::execute(goal_id, revision_id, user_id).await {
+        Err(error) => match error {
+            RevertGoalError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            RevertGoalError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            RevertGoalError::GoalNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            RevertGoalError::RevisionNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            RevertGoalError::ForbiddenError(err_msg) => HttpResponse::Forbidden().body(err_msg),
+            RevertGoalError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(goal) => HttpResponse::Ok().json(goal),
+    }
+}
+
+// The body is the CSV, JSON or Markdown document, its format is in the query. Registered in main
+// with the larger payload limit of the imports, POST /api/goals/import
+pub async fn import_goals_route(
+    req_body: String,
+    query: web::Query<ImportGoalsQueryDto>,
+    req: HttpRequest,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    match import_goals::execute(req_body, query.into_inner(), user_id).await {
+        Err(error) => match error {
+            ImportGoalsError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+            ImportGoalsError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            ImportGoalsError::ForbiddenError(err_msg) => HttpResponse::Forbidden().body(err_msg),
+            ImportGoalsError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+        },
+        Ok(report) => HttpResponse::Ok().json(report),
+    }
+}
+
+// The body is the export file of the source: trello, todoist or asana. Registered in main with the
+// larger payload limit of the imports, POST /api/goals/import/{source}
+pub async fn import_goals_from_tool_route(
+    req_body: String,
+    path: web::Path<String>,
+    query: web::Query<ToolImportQueryDto>,
+    req: HttpRequest,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    match import_from_tool::execute(path.into_inner(), req_body, query.into_inner(), user_id).await
+    {
+        Err(error) => match error {
+            ImportFromToolError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+            ImportFromToolError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            ImportFromToolError::ForbiddenError(err_msg) => HttpResponse::Forbidden().body(err_msg),
+            ImportFromToolError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+        },
+        Ok(report) => HttpResponse::Ok().json(report),
+    }
+}
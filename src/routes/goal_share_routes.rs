@@ -0,0 +1,106 @@
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse, Responder};
+
+use crate::{
+    entities::{
+        api_key::{READ_SCOPE, WRITE_SCOPE},
+        goal_share::CreateGoalShareDto,
+    },
+    use_cases::goal_shares::{
+        get_goal_shares::{self, GetGoalSharesError},
+        revoke_goal_share::{self, RevokeGoalShareError},
+        share_goal::{self, ShareGoalError},
+    },
+    utils::routes_utils::extract_user_id_from_request,
+};
+
+const AUTH_MESSAGE: &str = "Missing or invalid JWT or api key in authorization headers";
+
+#[post("/api/goals/{goalId}/shares")]
+async fn add_goal_share_route(
+    req_body: web::Json<CreateGoalShareDto>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let goal_id = path.into_inner();
+
+    match share_goal::execute(goal_id, req_body.into_inner(), user_id).await {
+        Err(error) => match error {
+            ShareGoalError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            ShareGoalError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            ShareGoalError::GoalNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            ShareGoalError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(goal_share) => HttpResponse::Created().json(goal_share),
+    }
+}
+
+#[get("/api/goals/{goalId}/shares")]
+async fn get_goal_shares_route(req: HttpRequest, path: web::Path<String>) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, READ_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let goal_id = path.into_inner();
+
+    match get_goal_shares::execute(goal_id, user_id).await {
+        Err(error) => match error {
+            GetGoalSharesError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            GetGoalSharesError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            GetGoalSharesError::GoalNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            GetGoalSharesError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(goal_shares) => HttpResponse::Ok().json(goal_shares),
+    }
+}
+
+#[delete("/api/goals/{goalId}/shares/{userId}")]
+async fn revoke_goal_share_route(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let (goal_id, shared_user_id) = path.into_inner();
+
+    match revoke_goal_share::execute(goal_id, shared_user_id, user_id).await {
+        Err(error) => match error {
+            RevokeGoalShareError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            RevokeGoalShareError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            RevokeGoalShareError::GoalNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            RevokeGoalShareError::ShareNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            RevokeGoalShareError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(_) => HttpResponse::NoContent().body(""),
+    }
+}
@@ -0,0 +1,134 @@
+use std::{collections::VecDeque, time::Duration};
+
+use actix_web::{get, web::Bytes, HttpRequest, HttpResponse, Responder};
+use futures_util::{stream, StreamExt};
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+
+use crate::{
+    entities::{api_key::READ_SCOPE, goal_event::GoalEventDto},
+    services::goal_event_services::{subscribe_goal_events, GoalEventSignal},
+    use_cases::goals::get_goal_events::{self, GetGoalEventsError},
+    utils::routes_utils::extract_user_id_from_request,
+};
+
+const AUTH_MESSAGE: &str = "Missing or invalid JWT or api key in authorization headers";
+const LAST_EVENT_ID_MESSAGE: &str = "Last-Event-ID must be the id of an event";
+const KEEP_ALIVE_SECONDS: u64 = 15;
+const RETRY_MILLISECONDS: u64 = 3000;
+const KEEP_ALIVE: &[u8] = b": keep-alive\n\n";
+
+struct StreamState {
+    user_id: String,
+    last_event_id: i64,
+    waiting: bool,
+    pending: VecDeque<GoalEventDto>,
+    receiver: Receiver<GoalEventSignal>,
+}
+
+// Server-Sent Events of the goals the user can see: goal.created, goal.updated and goal.deleted.
+// Clients resume with the Last-Event-ID header, missed events are sent first
+#[get("/api/goals/stream")]
+async fn goal_stream_route(req: HttpRequest) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, READ_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let last_event_id = match req.headers().get("Last-Event-ID") {
+        None => None,
+        Some(value) => match value
+            .to_str()
+            .ok()
+            .and_then(|id| id.trim().parse::<i64>().ok())
+        {
+            None => return HttpResponse::BadRequest().body(LAST_EVENT_ID_MESSAGE),
+            Some(id) => Some(id),
+        },
+    };
+
+    // Subscribed before reading the missed events, so no event is lost in between
+    let receiver = subscribe_goal_events();
+
+    let goal_events = match get_goal_events::execute(user_id.clone(), last_event_id).await {
+        Err(error) => match error {
+            GetGoalEventsError::DatabaseError(err_msg) => {
+                return HttpResponse::InternalServerError().body(err_msg)
+            }
+            GetGoalEventsError::UserNotFoundError(err_msg) => {
+                return HttpResponse::NotFound().body(err_msg)
+            }
+        },
+        Ok(goal_events) => goal_events,
+    };
+
+    let state = StreamState {
+        user_id,
+        last_event_id: last_event_id.unwrap_or(goal_events.last_event_id),
+        waiting: goal_events.waiting,
+        pending: goal_events.events.into(),
+        receiver,
+    };
+
+    let retry = stream::once(async {
+        Ok::<_, actix_web::Error>(Bytes::from(format!("retry: {}\n\n", RETRY_MILLISECONDS)))
+    });
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(retry.chain(stream::unfold(state, next_message)))
+}
+
+// Sends the pending events, then waits for a signal about the user to read the new ones. A comment
+// is sent when nothing happens, so proxies keep the connection open, and the events held back by
+// running transactions are read again. The stream ends when the events cannot be read, the client
+// reconnects with the id of the last event it received
+async fn next_message(
+    mut state: StreamState,
+) -> Option<(Result<Bytes, actix_web::Error>, StreamState)> {
+    loop {
+        if let Some(event) = state.pending.pop_front() {
+            state.last_event_id = event.id;
+            return Some((Ok(Bytes::from(format_event(&event))), state));
+        }
+
+        let signal = tokio::time::timeout(
+            Duration::from_secs(KEEP_ALIVE_SECONDS),
+            state.receiver.recv(),
+        )
+        .await;
+
+        let timed_out = signal.is_err();
+        match signal {
+            Err(_) if !state.waiting => return Some((Ok(Bytes::from_static(KEEP_ALIVE)), state)),
+            Err(_) => {}
+            Ok(Err(RecvError::Closed)) => return None,
+            Ok(Err(RecvError::Lagged(_))) => {}
+            Ok(Ok(signal))
+                if signal.concerns(&state.user_id, state.last_event_id, state.waiting) => {}
+            Ok(Ok(_)) => continue,
+        }
+
+        match get_goal_events::execute(state.user_id.clone(), Some(state.last_event_id)).await {
+            Err(_) => return None,
+            Ok(goal_events) => {
+                state.waiting = goal_events.waiting;
+                state.pending.extend(goal_events.events);
+            }
+        }
+
+        if timed_out && state.pending.is_empty() {
+            return Some((Ok(Bytes::from_static(KEEP_ALIVE)), state));
+        }
+    }
+}
+
+fn format_event(event: &GoalEventDto) -> String {
+    format!(
+        "id: {}\nevent: {}\ndata: {}\n\n",
+        event.id,
+        event.event_type,
+        serde_json::to_string(event).unwrap_or_default()
+    )
+}
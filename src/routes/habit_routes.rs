@@ -0,0 +1,190 @@
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder};
+
+use crate::{
+    entities::{
+        api_key::{READ_SCOPE, WRITE_SCOPE},
+        habit::{CheckInDto, HabitHistoryQueryDto, SetHabitDto},
+    },
+    use_cases::goals::habits::{
+        check_in::{self, CheckInError},
+        delete_habit::{self, DeleteHabitError},
+        get_habit::{self, GetHabitError},
+        get_habit_history::{self, GetHabitHistoryError},
+        set_habit::{self, SetHabitError},
+        undo_check_in::{self, UndoCheckInError},
+    },
+    utils::routes_utils::extract_user_id_from_request,
+};
+
+const AUTH_MESSAGE: &str = "Missing or invalid JWT or api key in authorization headers";
+
+#[put("/api/goals/{goalId}/habit")]
+async fn set_habit_route(
+    req_body: web::Json<SetHabitDto>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let goal_id = path.into_inner();
+
+    match set_habit::execute(goal_id, req_body.into_inner(), user_id).await {
+        Err(error) => match error {
+            SetHabitError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            SetHabitError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            SetHabitError::GoalNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            SetHabitError::ForbiddenError(err_msg) => HttpResponse::Forbidden().body(err_msg),
+            SetHabitError::InvalidRequestError(err_msg) => HttpResponse::BadRequest().body(err_msg),
+        },
+        Ok(habit) => HttpResponse::Ok().json(habit),
+    }
+}
+
+#[get("/api/goals/{goalId}/habit")]
+async fn get_habit_route(req: HttpRequest, path: web::Path<String>) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, READ_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let goal_id = path.into_inner();
+
+    match get_habit::execute(goal_id, user_id).await {
+        Err(error) => match error {
+            GetHabitError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            GetHabitError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            GetHabitError::GoalNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            GetHabitError::HabitNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            GetHabitError::InvalidRequestError(err_msg) => HttpResponse::BadRequest().body(err_msg),
+        },
+        Ok(habit) => HttpResponse::Ok().json(habit),
+    }
+}
+
+#[delete("/api/goals/{goalId}/habit")]
+async fn delete_habit_route(req: HttpRequest, path: web::Path<String>) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let goal_id = path.into_inner();
+
+    match delete_habit::execute(goal_id, user_id).await {
+        Err(error) => match error {
+            DeleteHabitError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            DeleteHabitError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            DeleteHabitError::GoalNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            DeleteHabitError::HabitNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            DeleteHabitError::ForbiddenError(err_msg) => HttpResponse::Forbidden().body(err_msg),
+            DeleteHabitError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(_) => HttpResponse::NoContent().body(""),
+    }
+}
+
+#[post("/api/goals/{goalId}/habit/check-ins")]
+async fn check_in_route(
+    req_body: web::Json<CheckInDto>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let goal_id = path.into_inner();
+
+    match check_in::execute(goal_id, req_body.into_inner(), user_id).await {
+        Err(error) => match error {
+            CheckInError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            CheckInError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            CheckInError::GoalNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            CheckInError::HabitNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            CheckInError::ForbiddenError(err_msg) => HttpResponse::Forbidden().body(err_msg),
+            CheckInError::InvalidRequestError(err_msg) => HttpResponse::BadRequest().body(err_msg),
+        },
+        Ok(habit) => HttpResponse::Ok().json(habit),
+    }
+}
+
+#[delete("/api/goals/{goalId}/habit/check-ins/{date}")]
+async fn undo_check_in_route(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let (goal_id, date) = path.into_inner();
+
+    match undo_check_in::execute(goal_id, date, user_id).await {
+        Err(error) => match error {
+            UndoCheckInError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            UndoCheckInError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            UndoCheckInError::GoalNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            UndoCheckInError::HabitNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            UndoCheckInError::CheckInNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            UndoCheckInError::ForbiddenError(err_msg) => HttpResponse::Forbidden().body(err_msg),
+            UndoCheckInError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(habit) => HttpResponse::Ok().json(habit),
+    }
+}
+
+#[get("/api/goals/{goalId}/habit/history")]
+async fn get_habit_history_route(
+    query: web::Query<HabitHistoryQueryDto>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, READ_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let goal_id = path.into_inner();
+
+    match get_habit_history::execute(goal_id, query.into_inner(), user_id).await {
+        Err(error) => match error {
+            GetHabitHistoryError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            GetHabitHistoryError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            GetHabitHistoryError::GoalNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            GetHabitHistoryError::HabitNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            GetHabitHistoryError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(days) => HttpResponse::Ok().json(days),
+    }
+}
@@ -1,2 +1,19 @@
 pub mod user_routes;
 pub mod goal_routes;
+pub mod api_key_routes;
+pub mod well_known_routes;
+pub mod admin_routes;
+pub mod organization_routes;
+pub mod goal_share_routes;
+pub mod share_link_routes;
+pub mod public_goal_routes;
+pub mod comment_routes;
+pub mod note_routes;
+pub mod habit_routes;
+pub mod reminder_routes;
+pub mod notification_routes;
+pub mod webhook_routes;
+pub mod goal_stream_routes;
+pub mod websocket_routes;
+pub mod sync_routes;
+pub mod calendar_routes;
@@ -0,0 +1,126 @@
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder};
+
+use crate::{
+    entities::{
+        api_key::{READ_SCOPE, WRITE_SCOPE},
+        note::{CreateNoteDto, NotesQueryDto, UpdateNoteDto},
+    },
+    use_cases::notes::{
+        create_note::{self, CreateNoteError},
+        delete_note::{self, DeleteNoteError},
+        get_notes::{self, GetNotesError},
+        update_note::{self, UpdateNoteError},
+    },
+    utils::routes_utils::extract_user_id_from_request,
+};
+
+const AUTH_MESSAGE: &str = "Missing or invalid JWT or api key in authorization headers";
+
+#[post("/api/goals/{goalId}/notes")]
+async fn add_note_route(
+    req_body: web::Json<CreateNoteDto>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let goal_id = path.into_inner();
+
+    match create_note::execute(goal_id, req_body.into_inner(), user_id).await {
+        Err(error) => match error {
+            CreateNoteError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            CreateNoteError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            CreateNoteError::GoalNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            CreateNoteError::ForbiddenError(err_msg) => HttpResponse::Forbidden().body(err_msg),
+            CreateNoteError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(note) => HttpResponse::Created().json(note),
+    }
+}
+
+#[get("/api/goals/{goalId}/notes")]
+async fn get_notes_route(
+    query: web::Query<NotesQueryDto>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, READ_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let goal_id = path.into_inner();
+
+    match get_notes::execute(goal_id, query.into_inner(), user_id).await {
+        Err(error) => match error {
+            GetNotesError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            GetNotesError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            GetNotesError::GoalNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            GetNotesError::InvalidRequestError(err_msg) => HttpResponse::BadRequest().body(err_msg),
+        },
+        Ok(notes) => HttpResponse::Ok().json(notes),
+    }
+}
+
+#[put("/api/goals/{goalId}/notes/{noteId}")]
+async fn update_note_route(
+    req_body: web::Json<UpdateNoteDto>,
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let (goal_id, note_id) = path.into_inner();
+
+    match update_note::execute(goal_id, note_id, req_body.into_inner(), user_id).await {
+        Err(error) => match error {
+            UpdateNoteError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            UpdateNoteError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            UpdateNoteError::NoteNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            UpdateNoteError::ForbiddenError(err_msg) => HttpResponse::Forbidden().body(err_msg),
+            UpdateNoteError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(note) => HttpResponse::Ok().json(note),
+    }
+}
+
+#[delete("/api/goals/{goalId}/notes/{noteId}")]
+async fn delete_note_route(req: HttpRequest, path: web::Path<(String, String)>) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let (goal_id, note_id) = path.into_inner();
+
+    match delete_note::execute(goal_id, note_id, user_id).await {
+        Err(error) => match error {
+            DeleteNoteError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            DeleteNoteError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            DeleteNoteError::NoteNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            DeleteNoteError::ForbiddenError(err_msg) => HttpResponse::Forbidden().body(err_msg),
+            DeleteNoteError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(_) => HttpResponse::NoContent().body(""),
+    }
+}
@@ -0,0 +1,147 @@
+use actix_web::{delete, get, put, web, HttpRequest, HttpResponse, Responder};
+
+use crate::{
+    entities::notification::NotificationsQueryDto,
+    use_cases::notifications::{
+        delete_notification::{self, DeleteNotificationError},
+        get_notifications::{self, GetNotificationsError},
+        get_unread_count::{self, GetUnreadCountError},
+        mark_all_notifications_read::{self, MarkAllNotificationsReadError},
+        mark_notification_read::{self, MarkNotificationReadError},
+    },
+    utils::routes_utils::extract_user_id_from_headers,
+};
+
+const JWT_MESSAGE: &str = "Missing or invalid JWT in authorization headers";
+
+#[get("/api/notifications")]
+async fn get_notifications_route(
+    req: HttpRequest,
+    query: web::Query<NotificationsQueryDto>,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_headers(&req).await {
+        None => return HttpResponse::BadRequest().body(JWT_MESSAGE),
+        Some(id) => id,
+    };
+
+    match get_notifications::execute(query.into_inner(), user_id).await {
+        Err(error) => match error {
+            GetNotificationsError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            GetNotificationsError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            GetNotificationsError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(page) => HttpResponse::Ok().json(page),
+    }
+}
+
+#[get("/api/notifications/unread-count")]
+async fn get_unread_count_route(req: HttpRequest) -> impl Responder {
+    let user_id = match extract_user_id_from_headers(&req).await {
+        None => return HttpResponse::BadRequest().body(JWT_MESSAGE),
+        Some(id) => id,
+    };
+
+    match get_unread_count::execute(user_id).await {
+        Err(error) => match error {
+            GetUnreadCountError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            GetUnreadCountError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+        },
+        Ok(unread_count) => HttpResponse::Ok().json(unread_count),
+    }
+}
+
+#[put("/api/notifications/read-all")]
+async fn mark_all_notifications_read_route(req: HttpRequest) -> impl Responder {
+    let user_id = match extract_user_id_from_headers(&req).await {
+        None => return HttpResponse::BadRequest().body(JWT_MESSAGE),
+        Some(id) => id,
+    };
+
+    match mark_all_notifications_read::execute(user_id).await {
+        Err(error) => match error {
+            MarkAllNotificationsReadError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            MarkAllNotificationsReadError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+        },
+        Ok(unread_count) => HttpResponse::Ok().json(unread_count),
+    }
+}
+
+#[put("/api/notifications/{notificationId}/read")]
+async fn mark_notification_read_route(req: HttpRequest, path: web::Path<String>) -> impl Responder {
+    mark_notification(req, path.into_inner(), true).await
+}
+
+#[put("/api/notifications/{notificationId}/unread")]
+async fn mark_notification_unread_route(
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    mark_notification(req, path.into_inner(), false).await
+}
+
+async fn mark_notification(req: HttpRequest, notification_id: String, read: bool) -> HttpResponse {
+    let user_id = match extract_user_id_from_headers(&req).await {
+        None => return HttpResponse::BadRequest().body(JWT_MESSAGE),
+        Some(id) => id,
+    };
+
+    match mark_notification_read::execute(notification_id, read, user_id).await {
+        Err(error) => match error {
+            MarkNotificationReadError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            MarkNotificationReadError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            MarkNotificationReadError::NotificationNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            MarkNotificationReadError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(()) => HttpResponse::NoContent().body(""),
+    }
+}
+
+#[delete("/api/notifications/{notificationId}")]
+async fn delete_notification_route(req: HttpRequest, path: web::Path<String>) -> impl Responder {
+    let user_id = match extract_user_id_from_headers(&req).await {
+        None => return HttpResponse::BadRequest().body(JWT_MESSAGE),
+        Some(id) => id,
+    };
+
+    let notification_id = path.into_inner();
+
+    match delete_notification::execute(notification_id, user_id).await {
+        Err(error) => match error {
+            DeleteNotificationError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            DeleteNotificationError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            DeleteNotificationError::NotificationNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            DeleteNotificationError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(()) => HttpResponse::NoContent().body(""),
+    }
+}
@@ -0,0 +1,242 @@
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder};
+
+use crate::{
+    entities::{
+        invitation::{CreateInvitationDto, InvitationTokenDto},
+        organization::{CreateOrganizationDto, UpdateMemberDto},
+    },
+    use_cases::organizations::{
+        accept_invitation::{self, AcceptInvitationError},
+        create_invitation::{self, CreateInvitationError},
+        create_organization::{self, CreateOrganizationError},
+        decline_invitation::{self, DeclineInvitationError},
+        get_members::{self, GetMembersError},
+        get_organizations::{self, GetOrganizationsError},
+        remove_member::{self, RemoveMemberError},
+        update_member::{self, UpdateMemberError},
+    },
+    utils::routes_utils::extract_user_id_from_headers,
+};
+
+const JWT_MESSAGE: &str = "Missing or invalid JWT in authorization headers";
+
+#[post("/api/organizations")]
+async fn add_organization_route(
+    req_body: web::Json<CreateOrganizationDto>,
+    req: HttpRequest,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_headers(&req).await {
+        None => return HttpResponse::BadRequest().body(JWT_MESSAGE),
+        Some(id) => id,
+    };
+
+    match create_organization::execute(req_body.into_inner(), user_id).await {
+        Err(error) => match error {
+            CreateOrganizationError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+            CreateOrganizationError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            CreateOrganizationError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+        },
+        Ok(organization) => HttpResponse::Created().json(organization),
+    }
+}
+
+#[get("/api/organizations")]
+async fn get_organizations_route(req: HttpRequest) -> impl Responder {
+    let user_id = match extract_user_id_from_headers(&req).await {
+        None => return HttpResponse::BadRequest().body(JWT_MESSAGE),
+        Some(id) => id,
+    };
+
+    match get_organizations::execute(user_id).await {
+        Err(error) => match error {
+            GetOrganizationsError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            GetOrganizationsError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+        },
+        Ok(organizations) => HttpResponse::Ok().json(organizations),
+    }
+}
+
+#[get("/api/organizations/{organizationId}/members")]
+async fn get_members_route(req: HttpRequest, path: web::Path<String>) -> impl Responder {
+    let user_id = match extract_user_id_from_headers(&req).await {
+        None => return HttpResponse::BadRequest().body(JWT_MESSAGE),
+        Some(id) => id,
+    };
+
+    let organization_id = path.into_inner();
+
+    match get_members::execute(organization_id, user_id).await {
+        Err(error) => match error {
+            GetMembersError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            GetMembersError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            GetMembersError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+            GetMembersError::ForbiddenError(err_msg) => HttpResponse::Forbidden().body(err_msg),
+        },
+        Ok(members) => HttpResponse::Ok().json(members),
+    }
+}
+
+#[put("/api/organizations/{organizationId}/members/{userId}")]
+async fn update_member_route(
+    req_body: web::Json<UpdateMemberDto>,
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_headers(&req).await {
+        None => return HttpResponse::BadRequest().body(JWT_MESSAGE),
+        Some(id) => id,
+    };
+
+    let (organization_id, member_id) = path.into_inner();
+
+    match update_member::execute(organization_id, member_id, req_body.into_inner(), user_id).await {
+        Err(error) => match error {
+            UpdateMemberError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            UpdateMemberError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            UpdateMemberError::MemberNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            UpdateMemberError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+            UpdateMemberError::ForbiddenError(err_msg) => HttpResponse::Forbidden().body(err_msg),
+        },
+        Ok(_) => HttpResponse::NoContent().body(""),
+    }
+}
+
+#[delete("/api/organizations/{organizationId}/members/{userId}")]
+async fn remove_member_route(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_headers(&req).await {
+        None => return HttpResponse::BadRequest().body(JWT_MESSAGE),
+        Some(id) => id,
+    };
+
+    let (organization_id, member_id) = path.into_inner();
+
+    match remove_member::execute(organization_id, member_id, user_id).await {
+        Err(error) => match error {
+            RemoveMemberError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            RemoveMemberError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            RemoveMemberError::MemberNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            RemoveMemberError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+            RemoveMemberError::ForbiddenError(err_msg) => HttpResponse::Forbidden().body(err_msg),
+        },
+        Ok(_) => HttpResponse::NoContent().body(""),
+    }
+}
+
+#[post("/api/organizations/{organizationId}/invitations")]
+async fn add_invitation_route(
+    req_body: web::Json<CreateInvitationDto>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_headers(&req).await {
+        None => return HttpResponse::BadRequest().body(JWT_MESSAGE),
+        Some(id) => id,
+    };
+
+    let organization_id = path.into_inner();
+
+    match create_invitation::execute(organization_id, req_body.into_inner(), user_id).await {
+        Err(error) => match error {
+            CreateInvitationError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            CreateInvitationError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            CreateInvitationError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+            CreateInvitationError::ForbiddenError(err_msg) => {
+                HttpResponse::Forbidden().body(err_msg)
+            }
+        },
+        Ok(invitation) => HttpResponse::Created().json(invitation),
+    }
+}
+
+#[post("/api/invitations/accept")]
+async fn accept_invitation_route(
+    req_body: web::Json<InvitationTokenDto>,
+    req: HttpRequest,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_headers(&req).await {
+        None => return HttpResponse::BadRequest().body(JWT_MESSAGE),
+        Some(id) => id,
+    };
+
+    match accept_invitation::execute(req_body.into_inner(), user_id).await {
+        Err(error) => match error {
+            AcceptInvitationError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            AcceptInvitationError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            AcceptInvitationError::InvitationNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            AcceptInvitationError::ForbiddenError(err_msg) => {
+                HttpResponse::Forbidden().body(err_msg)
+            }
+        },
+        Ok(_) => HttpResponse::NoContent().body(""),
+    }
+}
+
+#[post("/api/invitations/decline")]
+async fn decline_invitation_route(
+    req_body: web::Json<InvitationTokenDto>,
+    req: HttpRequest,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_headers(&req).await {
+        None => return HttpResponse::BadRequest().body(JWT_MESSAGE),
+        Some(id) => id,
+    };
+
+    match decline_invitation::execute(req_body.into_inner(), user_id).await {
+        Err(error) => match error {
+            DeclineInvitationError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            DeclineInvitationError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            DeclineInvitationError::InvitationNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            DeclineInvitationError::ForbiddenError(err_msg) => {
+                HttpResponse::Forbidden().body(err_msg)
+            }
+        },
+        Ok(_) => HttpResponse::NoContent().body(""),
+    }
+}
@@ -0,0 +1,22 @@
+use actix_web::{get, web, HttpResponse, Responder};
+
+use crate::use_cases::share_links::get_public_goal::{self, GetPublicGoalError};
+
+// No authorization headers, the token of the share link gives the access
+#[get("/public/goals/{token}")]
+async fn get_public_goal_route(path: web::Path<String>) -> impl Responder {
+    let token = path.into_inner();
+
+    match get_public_goal::execute(token).await {
+        Err(error) => match error {
+            GetPublicGoalError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            GetPublicGoalError::NotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+        },
+        Ok(goal) => HttpResponse::Ok()
+            .insert_header(("Cache-Control", "no-store"))
+            .insert_header(("X-Robots-Tag", "noindex"))
+            .json(goal),
+    }
+}
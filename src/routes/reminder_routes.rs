@@ -0,0 +1,103 @@
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse, Responder};
+
+use crate::{
+    entities::{
+        api_key::{READ_SCOPE, WRITE_SCOPE},
+        reminder::CreateReminderDto,
+    },
+    use_cases::reminders::{
+        create_reminder::{self, CreateReminderError},
+        delete_reminder::{self, DeleteReminderError},
+        get_reminders::{self, GetRemindersError},
+    },
+    utils::routes_utils::extract_user_id_from_request,
+};
+
+const AUTH_MESSAGE: &str = "Missing or invalid JWT or api key in authorization headers";
+
+#[post("/api/goals/{goalId}/reminders")]
+async fn add_reminder_route(
+    req_body: web::Json<CreateReminderDto>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let goal_id = path.into_inner();
+
+    match create_reminder::execute(goal_id, req_body.into_inner(), user_id).await {
+        Err(error) => match error {
+            CreateReminderError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            CreateReminderError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            CreateReminderError::GoalNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            CreateReminderError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(reminder) => HttpResponse::Created().json(reminder),
+    }
+}
+
+#[get("/api/goals/{goalId}/reminders")]
+async fn get_reminders_route(req: HttpRequest, path: web::Path<String>) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, READ_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let goal_id = path.into_inner();
+
+    match get_reminders::execute(goal_id, user_id).await {
+        Err(error) => match error {
+            GetRemindersError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            GetRemindersError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            GetRemindersError::GoalNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            GetRemindersError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(reminders) => HttpResponse::Ok().json(reminders),
+    }
+}
+
+#[delete("/api/goals/{goalId}/reminders/{reminderId}")]
+async fn delete_reminder_route(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let (goal_id, reminder_id) = path.into_inner();
+
+    match delete_reminder::execute(goal_id, reminder_id, user_id).await {
+        Err(error) => match error {
+            DeleteReminderError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            DeleteReminderError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            DeleteReminderError::ReminderNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            DeleteReminderError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(_) => HttpResponse::NoContent().body(""),
+    }
+}
@@ -0,0 +1,110 @@
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse, Responder};
+
+use crate::{
+    entities::{
+        api_key::{READ_SCOPE, WRITE_SCOPE},
+        share_link::CreateShareLinkDto,
+    },
+    use_cases::share_links::{
+        create_share_link::{self, CreateShareLinkError},
+        get_share_links::{self, GetShareLinksError},
+        revoke_share_link::{self, RevokeShareLinkError},
+    },
+    utils::routes_utils::extract_user_id_from_request,
+};
+
+const AUTH_MESSAGE: &str = "Missing or invalid JWT or api key in authorization headers";
+
+#[post("/api/goals/{goalId}/links")]
+async fn add_share_link_route(
+    req_body: web::Json<CreateShareLinkDto>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let goal_id = path.into_inner();
+
+    match create_share_link::execute(goal_id, req_body.into_inner(), user_id).await {
+        Err(error) => match error {
+            CreateShareLinkError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            CreateShareLinkError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            CreateShareLinkError::GoalNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            CreateShareLinkError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(share_link) => HttpResponse::Created().json(share_link),
+    }
+}
+
+#[get("/api/goals/{goalId}/links")]
+async fn get_share_links_route(req: HttpRequest, path: web::Path<String>) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, READ_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let goal_id = path.into_inner();
+
+    match get_share_links::execute(goal_id, user_id).await {
+        Err(error) => match error {
+            GetShareLinksError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            GetShareLinksError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            GetShareLinksError::GoalNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            GetShareLinksError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(share_links) => HttpResponse::Ok().json(share_links),
+    }
+}
+
+#[delete("/api/goals/{goalId}/links/{linkId}")]
+async fn revoke_share_link_route(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    let (goal_id, share_link_id) = path.into_inner();
+
+    match revoke_share_link::execute(goal_id, share_link_id, user_id).await {
+        Err(error) => match error {
+            RevokeShareLinkError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            RevokeShareLinkError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            RevokeShareLinkError::GoalNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            RevokeShareLinkError::ShareLinkNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            RevokeShareLinkError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(_) => HttpResponse::NoContent().body(""),
+    }
+}
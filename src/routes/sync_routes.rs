@@ -0,0 +1,62 @@
+use actix_web::{get, post, web, HttpRequest, HttpResponse, Responder};
+
+use crate::{
+    entities::{
+        api_key::{READ_SCOPE, WRITE_SCOPE},
+        sync::{SyncMutationsDto, SyncQueryDto},
+    },
+    use_cases::sync::{
+        apply_mutations::{self, ApplyMutationsError},
+        get_changes::{self, GetChangesError},
+    },
+    utils::routes_utils::extract_user_id_from_request,
+};
+
+const AUTH_MESSAGE: &str = "Missing or invalid JWT or api key in authorization headers";
+
+#[get("/api/sync")]
+async fn get_changes_route(query: web::Query<SyncQueryDto>, req: HttpRequest) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, READ_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    match get_changes::execute(query.into_inner(), user_id).await {
+        Err(error) => match error {
+            GetChangesError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            GetChangesError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            GetChangesError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(changes) => HttpResponse::Ok().json(changes),
+    }
+}
+
+#[post("/api/sync")]
+async fn apply_mutations_route(
+    req_body: web::Json<SyncMutationsDto>,
+    req: HttpRequest,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, WRITE_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    match apply_mutations::execute(req_body.into_inner(), user_id).await {
+        Err(error) => match error {
+            ApplyMutationsError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            ApplyMutationsError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            ApplyMutationsError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(results) => HttpResponse::Ok().json(results),
+    }
+}
@@ -1,18 +1,33 @@
-use actix_web::{get, post, web, HttpRequest, HttpResponse, Responder};
+use actix_web::{
+    error, get, post,
+    web::{self, Bytes},
+    HttpRequest, HttpResponse, Responder,
+};
+use futures_util::stream;
 
 use crate::{
-    entities::user::{CreateUserDto, CredentialsDto},
+    entities::{
+        api_key::READ_SCOPE,
+        user::{ChangePasswordDto, CreateUserDto, CredentialsDto},
+        user_export::UserExportQueryDto,
+    },
     use_cases::users::{
+        change_password::{self, ChangePasswordError},
+        export_user_data::{self, ExportUserDataError},
         sign_in::{self, SignInError},
         sign_up::{self, SignUpError},
         verify_token::{self, VerifyTokenError},
     },
-    utils::routes_utils::extract_token_from_headers,
+    utils::routes_utils::{
+        extract_audit_context, extract_token_from_headers, extract_user_id_from_request,
+    },
 };
 
+const AUTH_MESSAGE: &str = "Missing or invalid JWT or api key in authorization headers";
+
 #[post("/api/users")]
-async fn signup_route(req_body: web::Json<CreateUserDto>) -> impl Responder {
-    match sign_up::execute(req_body.into_inner()).await {
+async fn signup_route(req: HttpRequest, req_body: web::Json<CreateUserDto>) -> impl Responder {
+    match sign_up::execute(req_body.into_inner(), extract_audit_context(&req)).await {
         Err(err) => match err {
             SignUpError::RequestValidationError(validation_err) => {
                 HttpResponse::BadRequest().body(validation_err.to_string())
@@ -32,8 +47,8 @@ async fn signup_route(req_body: web::Json<CreateUserDto>) -> impl Responder {
 }
 
 #[post("/api/users/signin")]
-async fn signin_route(req_body: web::Json<CredentialsDto>) -> impl Responder {
-    match sign_in::execute(req_body.into_inner()).await {
+async fn signin_route(req: HttpRequest, req_body: web::Json<CredentialsDto>) -> impl Responder {
+    match sign_in::execute(req_body.into_inner(), extract_audit_context(&req)).await {
         Err(error) => match error {
             SignInError::InvalidRequestError(req_err) => {
                 HttpResponse::BadRequest().body(req_err.to_string())
@@ -49,6 +64,9 @@ async fn signin_route(req_body: web::Json<CredentialsDto>) -> impl Responder {
             SignInError::GenerateJwtError(err) => {
                 HttpResponse::InternalServerError().body(err.to_string())
             }
+            SignInError::AccountDisabledError(err) => HttpResponse::Forbidden().body(err),
+
+            SignInError::PasswordResetRequiredError(err) => HttpResponse::Forbidden().body(err),
         },
         Ok(signed_user) => HttpResponse::Ok().json(signed_user),
     }
@@ -61,7 +79,7 @@ async fn verify_token_route(req: HttpRequest) -> impl Responder {
         Some(token) => token,
     };
 
-    match verify_token::execute(token).await {
+    match verify_token::execute(token, extract_audit_context(&req)).await {
         Err(error) => match error {
             VerifyTokenError::DecodeTokenError(err_msg) => HttpResponse::BadRequest().body(err_msg),
 
@@ -73,3 +91,79 @@ async fn verify_token_route(req: HttpRequest) -> impl Responder {
         Ok(_) => HttpResponse::Ok().body("Token Verified"),
     }
 }
+
+#[post("/api/users/password")]
+async fn change_password_route(
+    req: HttpRequest,
+    req_body: web::Json<ChangePasswordDto>,
+) -> impl Responder {
+    match change_password::execute(req_body.into_inner(), extract_audit_context(&req)).await {
+        Err(error) => match error {
+            ChangePasswordError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+            ChangePasswordError::UserNotFound(err_msg) => HttpResponse::NotFound().body(err_msg),
+
+            ChangePasswordError::PasswordAndHashDontMatchError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+            ChangePasswordError::AccountDisabledError(err_msg) => {
+                HttpResponse::Forbidden().body(err_msg)
+            }
+            ChangePasswordError::InvalidResetTokenError(err_msg) => {
+                HttpResponse::Forbidden().body(err_msg)
+            }
+            ChangePasswordError::HashPasswordError(err_msg) => {
+                HttpResponse::InternalServerError().body(format!("Server error: {}", err_msg))
+            }
+            ChangePasswordError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+        },
+        Ok(_) => HttpResponse::NoContent().body(""),
+    }
+}
+
+#[get("/api/users/me/export")]
+async fn export_user_data_route(
+    query: web::Query<UserExportQueryDto>,
+    req: HttpRequest,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_request(&req, READ_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    match export_user_data::execute(query.into_inner(), user_id).await {
+        Err(error) => match error {
+            ExportUserDataError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+            ExportUserDataError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            ExportUserDataError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+        },
+        Ok(export) => HttpResponse::Ok()
+            .content_type(export.content_type)
+            .insert_header((
+                "Content-Disposition",
+                format!("attachment; filename=\"{}\"", export.file_name),
+            ))
+            .streaming(stream::iter(export.chunks.map(to_bytes))),
+    }
+}
+
+// A failure in the middle of the export ends the response, the client sees an incomplete file
+fn to_bytes(chunk: Result<String, ExportUserDataError>) -> Result<Bytes, actix_web::Error> {
+    match chunk {
+        Ok(chunk) => Ok(Bytes::from(chunk)),
+        Err(ExportUserDataError::DatabaseError(err_msg))
+        | Err(ExportUserDataError::UserNotFoundError(err_msg))
+        | Err(ExportUserDataError::InvalidRequestError(err_msg)) => {
+            Err(error::ErrorInternalServerError(err_msg))
+        }
+    }
+}
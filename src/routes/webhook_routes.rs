@@ -0,0 +1,145 @@
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse, Responder};
+
+use crate::{
+    entities::webhook::{CreateWebhookDto, WebhookDeliveriesQueryDto},
+    use_cases::webhooks::{
+        create_webhook::{self, CreateWebhookError},
+        delete_webhook::{self, DeleteWebhookError},
+        get_webhook_deliveries::{self, GetWebhookDeliveriesError},
+        get_webhooks::{self, GetWebhooksError},
+        test_webhook::{self, TestWebhookError},
+    },
+    utils::routes_utils::extract_user_id_from_headers,
+};
+
+const JWT_MESSAGE: &str = "Missing or invalid JWT in authorization headers";
+
+#[post("/api/webhooks")]
+async fn add_webhook_route(
+    req_body: web::Json<CreateWebhookDto>,
+    req: HttpRequest,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_headers(&req).await {
+        None => return HttpResponse::BadRequest().body(JWT_MESSAGE),
+        Some(id) => id,
+    };
+
+    match create_webhook::execute(req_body.into_inner(), user_id).await {
+        Err(error) => match error {
+            CreateWebhookError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+            CreateWebhookError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            CreateWebhookError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+        },
+        Ok(webhook) => HttpResponse::Created().json(webhook),
+    }
+}
+
+#[get("/api/webhooks")]
+async fn get_webhooks_route(req: HttpRequest) -> impl Responder {
+    let user_id = match extract_user_id_from_headers(&req).await {
+        None => return HttpResponse::BadRequest().body(JWT_MESSAGE),
+        Some(id) => id,
+    };
+
+    match get_webhooks::execute(user_id).await {
+        Err(error) => match error {
+            GetWebhooksError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            GetWebhooksError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+        },
+        Ok(webhooks) => HttpResponse::Ok().json(webhooks),
+    }
+}
+
+#[delete("/api/webhooks/{webhookId}")]
+async fn delete_webhook_route(req: HttpRequest, path: web::Path<String>) -> impl Responder {
+    let user_id = match extract_user_id_from_headers(&req).await {
+        None => return HttpResponse::BadRequest().body(JWT_MESSAGE),
+        Some(id) => id,
+    };
+
+    let webhook_id = path.into_inner();
+
+    match delete_webhook::execute(webhook_id, user_id).await {
+        Err(error) => match error {
+            DeleteWebhookError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            DeleteWebhookError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            DeleteWebhookError::WebhookNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            DeleteWebhookError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(()) => HttpResponse::NoContent().body(""),
+    }
+}
+
+#[get("/api/webhooks/{webhookId}/deliveries")]
+async fn get_webhook_deliveries_route(
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<WebhookDeliveriesQueryDto>,
+) -> impl Responder {
+    let user_id = match extract_user_id_from_headers(&req).await {
+        None => return HttpResponse::BadRequest().body(JWT_MESSAGE),
+        Some(id) => id,
+    };
+
+    let webhook_id = path.into_inner();
+
+    match get_webhook_deliveries::execute(webhook_id, query.into_inner(), user_id).await {
+        Err(error) => match error {
+            GetWebhookDeliveriesError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            GetWebhookDeliveriesError::UserNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            GetWebhookDeliveriesError::WebhookNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            GetWebhookDeliveriesError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(deliveries) => HttpResponse::Ok().json(deliveries),
+    }
+}
+
+#[post("/api/webhooks/{webhookId}/test")]
+async fn test_webhook_route(req: HttpRequest, path: web::Path<String>) -> impl Responder {
+    let user_id = match extract_user_id_from_headers(&req).await {
+        None => return HttpResponse::BadRequest().body(JWT_MESSAGE),
+        Some(id) => id,
+    };
+
+    let webhook_id = path.into_inner();
+
+    match test_webhook::execute(webhook_id, user_id).await {
+        Err(error) => match error {
+            TestWebhookError::DatabaseError(err_msg) => {
+                HttpResponse::InternalServerError().body(err_msg)
+            }
+            TestWebhookError::UserNotFoundError(err_msg) => HttpResponse::NotFound().body(err_msg),
+            TestWebhookError::WebhookNotFoundError(err_msg) => {
+                HttpResponse::NotFound().body(err_msg)
+            }
+            TestWebhookError::InvalidRequestError(err_msg) => {
+                HttpResponse::BadRequest().body(err_msg)
+            }
+        },
+        Ok(delivery) => HttpResponse::Accepted().json(delivery),
+    }
+}
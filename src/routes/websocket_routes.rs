@@ -0,0 +1,474 @@
+use std::time::{Duration, Instant};
+
+use actix_web::{get, http::StatusCode, rt, web, HttpRequest, HttpResponse, Responder};
+use actix_ws::{CloseCode, CloseReason, Closed, Message, MessageStream, Session};
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+use uuid::Uuid;
+
+use crate::{
+    entities::{
+        api_key::{READ_SCOPE, WRITE_SCOPE},
+        channel::{Channel, ClientMessage, ServerMessage, WebSocketQueryDto},
+        goal::{CreateGoalDto, UpdateGoalDto},
+    },
+    services::goal_event_services::{
+        subscribe_goal_events, subscribe_presence, GoalEventSignal, PresenceSignal,
+    },
+    use_cases::{
+        goals::{
+            create_goal::{self, CreateGoalError},
+            delete_goal::{self, DeleteGoalError},
+            get_goal_events::{self, GetGoalEventsError},
+            update_goal::{self, UpdateGoalError},
+        },
+        realtime::{
+            get_channel_viewers::{self, GetChannelViewersError},
+            join_channel::{self, JoinChannelError},
+            leave_all_channels::{self, LeaveAllChannelsError},
+            leave_channel::{self, LeaveChannelError},
+            refresh_presence::{self, RefreshPresenceError},
+        },
+    },
+    utils::routes_utils::{authenticate_token, extract_token_from_headers},
+};
+
+const AUTH_MESSAGE: &str = "Missing or invalid JWT or api key in authorization headers";
+const BINARY_MESSAGE: &str = "Messages must be JSON text";
+const HEARTBEAT_SECONDS: u64 = 30;
+const CLIENT_TIMEOUT_SECONDS: u64 = 90;
+const MAX_CHANNELS: usize = 50;
+
+struct SocketState {
+    user_id: String,
+    token: String,
+    connection_id: String,
+    last_event_id: i64,
+    waiting: bool,
+    channels: Vec<Channel>,
+}
+
+impl SocketState {
+    fn has_channel(&self, name: &str) -> bool {
+        self.channels
+            .iter()
+            .any(|channel| channel.get_name() == name)
+    }
+}
+
+// Real-time boards: clients join goal, organization or user channels, receive the goal events of
+// the channels and their viewers, and create, update or delete goals. The token is checked again
+// with the write scope for every change
+#[get("/api/ws")]
+async fn websocket_route(
+    req: HttpRequest,
+    body: web::Payload,
+    query: web::Query<WebSocketQueryDto>,
+) -> impl Responder {
+    let token = match extract_token_from_headers(&req).or(query.into_inner().token) {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(token) => token,
+    };
+    let user_id = match authenticate_token(token.clone(), READ_SCOPE).await {
+        None => return HttpResponse::BadRequest().body(AUTH_MESSAGE),
+        Some(id) => id,
+    };
+
+    // Subscribed before reading the last event id, so no event is lost in between
+    let goal_events = subscribe_goal_events();
+    let presence = subscribe_presence();
+
+    let goal_events_dto = match get_goal_events::execute(user_id.clone(), None).await {
+        Err(error) => match error {
+            GetGoalEventsError::DatabaseError(err_msg) => {
+                return HttpResponse::InternalServerError().body(err_msg)
+            }
+            GetGoalEventsError::UserNotFoundError(err_msg) => {
+                return HttpResponse::NotFound().body(err_msg)
+            }
+        },
+        Ok(goal_events) => goal_events,
+    };
+
+    let (response, session, stream) = match actix_ws::handle(&req, body) {
+        Err(err) => return HttpResponse::from_error(err),
+        Ok(handshake) => handshake,
+    };
+
+    let state = SocketState {
+        user_id,
+        token,
+        connection_id: Uuid::new_v4().to_string(),
+        last_event_id: goal_events_dto.last_event_id,
+        waiting: goal_events_dto.waiting,
+        channels: Vec::new(),
+    };
+    rt::spawn(run_session(state, session, stream, goal_events, presence));
+
+    response
+}
+
+// Runs until the client closes the connection or stops answering the pings. The presences of the
+// connection are removed when it ends
+async fn run_session(
+    mut state: SocketState,
+    mut session: Session,
+    mut stream: MessageStream,
+    mut goal_events: Receiver<GoalEventSignal>,
+    mut presence: Receiver<PresenceSignal>,
+) {
+    let mut heartbeat = tokio::time::interval(Duration::from_secs(HEARTBEAT_SECONDS));
+    let mut last_seen = Instant::now();
+
+    let reason = loop {
+        let sent = tokio::select! {
+            message = stream.recv() => {
+                last_seen = Instant::now();
+                match message {
+                    None => break None,
+                    Some(Err(_)) => break Some(CloseReason::from(CloseCode::Protocol)),
+                    Some(Ok(Message::Close(reason))) => break reason,
+                    Some(Ok(Message::Text(text))) => {
+                        handle_message(&mut state, &mut session, &text).await
+                    }
+                    Some(Ok(Message::Binary(_))) => {
+                        let message =
+                            error_message(None, None, StatusCode::BAD_REQUEST, BINARY_MESSAGE);
+                        send(&mut session, &message).await
+                    }
+                    Some(Ok(Message::Ping(bytes))) => session.pong(&bytes).await,
+                    Some(Ok(_)) => Ok(()),
+                }
+            }
+            signal = goal_events.recv() => match signal {
+                Err(RecvError::Closed) => break Some(CloseReason::from(CloseCode::Away)),
+                Err(RecvError::Lagged(_)) => send_events(&mut state, &mut session).await,
+                Ok(signal)
+                    if signal.concerns(&state.user_id, state.last_event_id, state.waiting) =>
+                {
+                    send_events(&mut state, &mut session).await
+                }
+                Ok(_) => Ok(()),
+            },
+            signal = presence.recv() => match signal {
+                Err(RecvError::Closed) => break Some(CloseReason::from(CloseCode::Away)),
+                Err(RecvError::Lagged(_)) => {
+                    let names: Vec<String> =
+                        state.channels.iter().map(|channel| channel.get_name()).collect();
+                    send_presences(&mut session, names).await
+                }
+                Ok(signal) if state.has_channel(&signal.channel) => {
+                    send_presences(&mut session, vec![signal.channel]).await
+                }
+                Ok(_) => Ok(()),
+            },
+            _ = heartbeat.tick() => {
+                if last_seen.elapsed() > Duration::from_secs(CLIENT_TIMEOUT_SECONDS) {
+                    break Some(CloseReason::from(CloseCode::Away));
+                }
+                if let Err(RefreshPresenceError::DatabaseError(err_msg)) =
+                    refresh_presence::execute(state.connection_id.clone()).await
+                {
+                    eprintln!("Presence refresh error: {}", err_msg);
+                }
+                // Events held back by a transaction that rolled back are not signaled
+                if state.waiting && send_events(&mut state, &mut session).await.is_err() {
+                    break None;
+                }
+                session.ping(b"").await
+            }
+        };
+
+        // The client is gone
+        if sent.is_err() {
+            break None;
+        }
+    };
+
+    if let Err(LeaveAllChannelsError::DatabaseError(err_msg)) =
+        leave_all_channels::execute(state.connection_id.clone()).await
+    {
+        eprintln!("Presence removal error: {}", err_msg);
+    }
+    let _ = session.close(reason).await;
+}
+
+async fn handle_message(
+    state: &mut SocketState,
+    session: &mut Session,
+    text: &str,
+) -> Result<(), Closed> {
+    let message = match serde_json::from_str::<ClientMessage>(text) {
+        Err(err) => {
+            let message = error_message(None, None, StatusCode::BAD_REQUEST, &err.to_string());
+            return send(session, &message).await;
+        }
+        Ok(message) => message,
+    };
+
+    let reply = match message {
+        ClientMessage::Subscribe { channel } => subscribe(state, channel).await,
+        ClientMessage::Unsubscribe { channel } => unsubscribe(state, channel).await,
+        ClientMessage::CreateGoal { request_id, goal } => {
+            create_goal(state, request_id, goal).await
+        }
+        ClientMessage::UpdateGoal {
+            request_id,
+            goal_id,
+            goal,
+        } => update_goal(state, request_id, goal_id, goal).await,
+        ClientMessage::DeleteGoal {
+            request_id,
+            goal_id,
+        } => delete_goal(state, request_id, goal_id).await,
+        ClientMessage::Ping => ServerMessage::Pong,
+    };
+
+    send(session, &reply).await
+}
+
+async fn subscribe(state: &mut SocketState, channel: String) -> ServerMessage {
+    if state.channels.len() >= MAX_CHANNELS && !state.has_channel(&channel) {
+        return error_message(
+            None,
+            Some(channel),
+            StatusCode::BAD_REQUEST,
+            &format!("A connection can join at most {} channels", MAX_CHANNELS),
+        );
+    }
+
+    let result = join_channel::execute(
+        state.user_id.clone(),
+        state.connection_id.clone(),
+        channel.clone(),
+    )
+    .await;
+
+    let (status, err_msg) = match result {
+        Err(JoinChannelError::DatabaseError(err_msg)) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, err_msg)
+        }
+        Err(JoinChannelError::UserNotFoundError(err_msg))
+        | Err(JoinChannelError::NotFoundError(err_msg)) => (StatusCode::NOT_FOUND, err_msg),
+        Err(JoinChannelError::InvalidRequestError(err_msg)) => (StatusCode::BAD_REQUEST, err_msg),
+        Err(JoinChannelError::ForbiddenError(err_msg)) => (StatusCode::FORBIDDEN, err_msg),
+        Ok(viewers) => {
+            // The name was validated when joining
+            let channel = match Channel::from_name(&channel) {
+                Err(err) => {
+                    return error_message(
+                        None,
+                        Some(channel),
+                        StatusCode::BAD_REQUEST,
+                        &err.to_string(),
+                    )
+                }
+                Ok(channel) => channel,
+            };
+            let name = channel.get_name();
+            if !state.has_channel(&name) {
+                state.channels.push(channel);
+            }
+            return ServerMessage::Subscribed {
+                channel: name,
+                viewers,
+            };
+        }
+    };
+
+    error_message(None, Some(channel), status, &err_msg)
+}
+
+async fn unsubscribe(state: &mut SocketState, channel: String) -> ServerMessage {
+    let result = leave_channel::execute(state.connection_id.clone(), channel.clone()).await;
+
+    let (status, err_msg) = match result {
+        Err(LeaveChannelError::DatabaseError(err_msg)) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, err_msg)
+        }
+        Err(LeaveChannelError::InvalidRequestError(err_msg)) => (StatusCode::BAD_REQUEST, err_msg),
+        Err(LeaveChannelError::NotFoundError(err_msg)) => (StatusCode::NOT_FOUND, err_msg),
+        Ok(_) => {
+            let name = Channel::from_name(&channel)
+                .map(|channel| channel.get_name())
+                .unwrap_or(channel);
+            state.channels.retain(|channel| channel.get_name() != name);
+            return ServerMessage::Unsubscribed { channel: name };
+        }
+    };
+
+    error_message(None, Some(channel), status, &err_msg)
+}
+
+async fn create_goal(
+    state: &SocketState,
+    request_id: String,
+    goal: CreateGoalDto,
+) -> ServerMessage {
+    let user_id = match authenticate_token(state.token.clone(), WRITE_SCOPE).await {
+        None => {
+            return error_message(
+                Some(request_id),
+                None,
+                StatusCode::BAD_REQUEST,
+                AUTH_MESSAGE,
+            )
+        }
+        Some(id) => id,
+    };
+
+    let (status, err_msg) = match create_goal::execute(goal, user_id).await {
+        Err(CreateGoalError::InvalidRequestError(err_msg)) => (StatusCode::BAD_REQUEST, err_msg),
+        Err(CreateGoalError::UserNotFoundError(err_msg)) => (StatusCode::NOT_FOUND, err_msg),
+        Err(CreateGoalError::ForbiddenError(err_msg)) => (StatusCode::FORBIDDEN, err_msg),
+        Err(CreateGoalError::DatabaseError(err_msg)) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, err_msg)
+        }
+        Ok(goal) => {
+            return ServerMessage::Result {
+                request_id,
+                status: StatusCode::CREATED.as_u16(),
+                goal: Some(goal),
+            }
+        }
+    };
+
+    error_message(Some(request_id), None, status, &err_msg)
+}
+
+async fn update_goal(
+    state: &SocketState,
+    request_id: String,
+    goal_id: String,
+    goal: UpdateGoalDto,
+) -> ServerMessage {
+    let user_id = match authenticate_token(state.token.clone(), WRITE_SCOPE).await {
+        None => {
+            return error_message(
+                Some(request_id),
+                None,
+                StatusCode::BAD_REQUEST,
+                AUTH_MESSAGE,
+            )
+        }
+        Some(id) => id,
+    };
+
+    let (status, err_msg) = match update_goal::execute(goal_id, goal, user_id).await {
+        Err(UpdateGoalError::DatabaseError(err_msg)) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, err_msg)
+        }
+        Err(UpdateGoalError::UserNotFoundError(err_msg))
+        | Err(UpdateGoalError::GoalNotFoundError(err_msg)) => (StatusCode::NOT_FOUND, err_msg),
+        Err(UpdateGoalError::ForbiddenError(err_msg)) => (StatusCode::FORBIDDEN, err_msg),
+        Err(UpdateGoalError::InvalidRequestError(err_msg)) => (StatusCode::BAD_REQUEST, err_msg),
+        Ok(goal) => {
+            return ServerMessage::Result {
+                request_id,
+                status: StatusCode::OK.as_u16(),
+                goal: Some(goal),
+            }
+        }
+    };
+
+    error_message(Some(request_id), None, status, &err_msg)
+}
+
+async fn delete_goal(state: &SocketState, request_id: String, goal_id: String) -> ServerMessage {
+    let user_id = match authenticate_token(state.token.clone(), WRITE_SCOPE).await {
+        None => {
+            return error_message(
+                Some(request_id),
+                None,
+                StatusCode::BAD_REQUEST,
+                AUTH_MESSAGE,
+            )
+        }
+        Some(id) => id,
+    };
+
+    let (status, err_msg) = match delete_goal::execute(goal_id, user_id).await {
+        Err(DeleteGoalError::DatabaseError(err_msg)) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, err_msg)
+        }
+        Err(DeleteGoalError::UserNotFoundError(err_msg))
+        | Err(DeleteGoalError::GoalNotFoundError(err_msg)) => (StatusCode::NOT_FOUND, err_msg),
+        Err(DeleteGoalError::ForbiddenError(err_msg)) => (StatusCode::FORBIDDEN, err_msg),
+        Err(DeleteGoalError::InvalidRequestError(err_msg)) => (StatusCode::BAD_REQUEST, err_msg),
+        Ok(_) => {
+            return ServerMessage::Result {
+                request_id,
+                status: StatusCode::NO_CONTENT.as_u16(),
+                goal: None,
+            }
+        }
+    };
+
+    error_message(Some(request_id), None, status, &err_msg)
+}
+
+// Reads the events after the last one sent and sends those of the joined channels
+async fn send_events(state: &mut SocketState, session: &mut Session) -> Result<(), Closed> {
+    let result = get_goal_events::execute(state.user_id.clone(), Some(state.last_event_id)).await;
+
+    let goal_events = match result {
+        Err(GetGoalEventsError::DatabaseError(err_msg))
+        | Err(GetGoalEventsError::UserNotFoundError(err_msg)) => {
+            let message = error_message(None, None, StatusCode::INTERNAL_SERVER_ERROR, &err_msg);
+            return send(session, &message).await;
+        }
+        Ok(goal_events) => goal_events,
+    };
+
+    state.last_event_id = goal_events.last_event_id;
+    state.waiting = goal_events.waiting;
+    for event in goal_events.events {
+        let channels: Vec<String> = state
+            .channels
+            .iter()
+            .filter(|channel| channel.matches_event(&event))
+            .map(|channel| channel.get_name())
+            .collect();
+        if !channels.is_empty() {
+            send(session, &ServerMessage::Event { channels, event }).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn send_presences(session: &mut Session, channels: Vec<String>) -> Result<(), Closed> {
+    for channel in channels {
+        let message = match get_channel_viewers::execute(channel.clone()).await {
+            Err(GetChannelViewersError::DatabaseError(err_msg)) => error_message(
+                None,
+                Some(channel),
+                StatusCode::INTERNAL_SERVER_ERROR,
+                &err_msg,
+            ),
+            Ok(viewers) => ServerMessage::Presence { channel, viewers },
+        };
+        send(session, &message).await?;
+    }
+
+    Ok(())
+}
+
+fn error_message(
+    request_id: Option<String>,
+    channel: Option<String>,
+    status: StatusCode,
+    message: &str,
+) -> ServerMessage {
+    ServerMessage::Error {
+        request_id,
+        channel,
+        status: status.as_u16(),
+        message: message.to_string(),
+    }
+}
+
+async fn send(session: &mut Session, message: &ServerMessage) -> Result<(), Closed> {
+    session
+        .text(serde_json::to_string(message).unwrap_or_default())
+        .await
+}
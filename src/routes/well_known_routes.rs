@@ -0,0 +1,11 @@
+use actix_web::{get, HttpResponse, Responder};
+
+use crate::use_cases::users::get_jwks;
+
+// Lets other services verify the tokens issued here without sharing a secret
+#[get("/.well-known/jwks.json")]
+async fn jwks_route() -> impl Responder {
+    HttpResponse::Ok()
+        .insert_header(("Cache-Control", "public, max-age=300"))
+        .json(get_jwks::execute())
+}
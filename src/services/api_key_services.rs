@@ -0,0 +1,41 @@
+use crate::services::token_services::{generate_random_token, hash_token, match_token_and_hash};
+
+// Every api key starts with it, so the auth can tell api keys and JWTs apart
+pub const API_KEY_MARKER: &str = "gr_";
+
+pub struct GeneratedApiKey {
+    pub key: String,
+    pub prefix: String,
+    pub key_hash: String,
+}
+
+pub fn is_api_key(token: &str) -> bool {
+    token.starts_with(API_KEY_MARKER)
+}
+
+// Generates a new key in the format gr_<prefix>_<secret>. The prefix is stored in plain text to
+// find the key and show it to the user, the whole key is only stored as a SHA-256 hash
+pub fn generate_api_key() -> GeneratedApiKey {
+    let prefix = generate_random_token(4);
+    let secret = generate_random_token(24);
+    let key = format!("{}{}_{}", API_KEY_MARKER, prefix, secret);
+    let key_hash = hash_token(&key);
+    GeneratedApiKey {
+        key,
+        prefix,
+        key_hash,
+    }
+}
+
+pub fn extract_prefix_from_api_key(key: &str) -> Option<String> {
+    let without_marker = key.strip_prefix(API_KEY_MARKER)?;
+    let (prefix, secret) = without_marker.split_once('_')?;
+    if prefix.is_empty() || secret.is_empty() {
+        return None;
+    }
+    Some(prefix.to_string())
+}
+
+pub fn match_api_key_and_hash(key: &str, key_hash: &str) -> bool {
+    match_token_and_hash(key, key_hash)
+}
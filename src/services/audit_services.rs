@@ -0,0 +1,146 @@
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::OnceLock,
+};
+
+use tokio_postgres::Client;
+
+use crate::{
+    config::get_env,
+    data_access::audit_event_data_access::add_audit_event,
+    entities::audit_event::{AuditContext, AuditEvent},
+};
+
+static TRUSTED_PROXIES: OnceLock<Vec<IpAddr>> = OnceLock::new();
+
+// Loads TRUSTED_PROXIES on start up, a comma separated list of the IPs of the reverse proxies in
+// front of the server. An invalid IP stops the server instead of silently trusting less proxies
+pub fn init_trusted_proxies() -> Result<(), String> {
+    let proxies = load_trusted_proxies()?;
+    let _ = TRUSTED_PROXIES.set(proxies);
+    Ok(())
+}
+
+fn load_trusted_proxies() -> Result<Vec<IpAddr>, String> {
+    match get_env("TRUSTED_PROXIES") {
+        None => Ok(Vec::new()),
+        Some(value) => value
+            .split(',')
+            .map(|ip| ip.trim())
+            .filter(|ip| !ip.is_empty())
+            .map(|ip| {
+                ip.parse::<IpAddr>()
+                    .map_err(|_| format!("Invalid IP in TRUSTED_PROXIES: {}", ip))
+            })
+            .collect(),
+    }
+}
+
+fn trusted_proxies() -> &'static [IpAddr] {
+    TRUSTED_PROXIES.get_or_init(|| load_trusted_proxies().expect("Invalid TRUSTED_PROXIES"))
+}
+
+// The address of the client of a request received from peer_ip, with the hops of its Forwarded or
+// X-Forwarded-For headers, leftmost first. Every proxy appends the address it received the request
+// from, so the hops are read from the right while the address they come from is a trusted proxy.
+// The hops left of the first untrusted one were written by the client and are ignored, and so are
+// all the hops of a request that does not come from a trusted proxy
+pub fn resolve_client_ip(peer_ip: IpAddr, hops: &[String]) -> IpAddr {
+    find_client_ip(peer_ip, hops, trusted_proxies())
+}
+
+fn find_client_ip(peer_ip: IpAddr, hops: &[String], trusted: &[IpAddr]) -> IpAddr {
+    let mut client_ip = peer_ip;
+    for hop in hops.iter().rev() {
+        if !trusted.contains(&client_ip) {
+            break;
+        }
+        match parse_hop(hop) {
+            Some(ip) => client_ip = ip,
+            None => break,
+        }
+    }
+    client_ip
+}
+
+// An IP, with or without a port, quotes or the brackets of IPv6. Obfuscated and unknown hops are
+// not addresses
+fn parse_hop(hop: &str) -> Option<IpAddr> {
+    let hop = hop.trim().trim_matches('"');
+    if let Some(bracketed) = hop.strip_prefix('[') {
+        return bracketed.split(']').next()?.parse().ok();
+    }
+    hop.parse::<IpAddr>()
+        .ok()
+        .or_else(|| hop.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+}
+
+// The audit log must not change the result of the request, so a failed insert is only logged
+pub async fn record_audit_event(client: &Client, event: &AuditEvent) {
+    if let Err(err) = add_audit_event(client, event).await {
+        eprintln!("Audit event error ({}): {}", event.get_event_type(), err);
+    }
+}
+
+// An action of an admin on the account of a user
+pub async fn record_admin_audit_event(
+    client: &Client,
+    event_type: &str,
+    admin_id: &str,
+    user_id: &str,
+    audit: &AuditContext,
+) {
+    let mut event = AuditEvent::from_context(event_type, audit);
+    let actor = event.set_actor_id(Some(admin_id.to_string()));
+    let user = event.set_user_id(Some(user_id.to_string()));
+    if let Err(err) = actor.and(user) {
+        eprintln!("Audit event error ({}): {}", event_type, err);
+        return;
+    }
+    record_audit_event(client, &event).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    fn hops(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn ignores_the_hops_of_untrusted_peers() {
+        let client_ip = find_client_ip(ip("203.0.113.7"), &hops(&["10.0.0.1"]), &[]);
+        assert_eq!(client_ip, ip("203.0.113.7"));
+    }
+
+    #[test]
+    fn takes_the_rightmost_untrusted_hop() {
+        let trusted = [ip("10.0.0.2"), ip("10.0.0.3")];
+        let client_ip = find_client_ip(
+            ip("10.0.0.3"),
+            &hops(&["1.2.3.4", "198.51.100.9", "10.0.0.2"]),
+            &trusted,
+        );
+        assert_eq!(client_ip, ip("198.51.100.9"));
+    }
+
+    #[test]
+    fn stops_at_unreadable_hops() {
+        let trusted = [ip("10.0.0.2")];
+        let client_ip = find_client_ip(ip("10.0.0.2"), &hops(&["1.2.3.4", "unknown"]), &trusted);
+        assert_eq!(client_ip, ip("10.0.0.2"));
+    }
+
+    #[test]
+    fn reads_ports_quotes_and_brackets() {
+        assert_eq!(parse_hop("198.51.100.9:4711"), Some(ip("198.51.100.9")));
+        assert_eq!(parse_hop("\"[2001:db8::1]:4711\""), Some(ip("2001:db8::1")));
+        assert_eq!(parse_hop("2001:db8::1"), Some(ip("2001:db8::1")));
+        assert_eq!(parse_hop("_hidden"), None);
+    }
+}
@@ -1,17 +1,80 @@
+use std::sync::OnceLock;
+
 use argon2::{
     password_hash::{rand_core::OsRng, PasswordHasher, PasswordVerifier, SaltString},
-    Argon2, PasswordHash,
+    Argon2, Params, PasswordHash, Version,
 };
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, TokenData, Validation};
+use jsonwebtoken::{decode, decode_header, encode, Algorithm, Header, TokenData, Validation};
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    config::{get_env, get_env_parsed_strict},
+    entities::user::USER_ROLE,
+    services::jwt_key_services::get_jwt_keys,
+};
+
+pub enum PasswordMatch {
+    NoMatch,
+    Match,
+    // The password is right but the hash is weaker than the current policy, or was made without
+    // the pepper, and should be replaced by a new one
+    MatchNeedsRehash,
+}
+
+struct PasswordHashConfig {
+    params: Params,
+    pepper: Option<String>,
+}
+
+static PASSWORD_HASH_CONFIG: OnceLock<PasswordHashConfig> = OnceLock::new();
+
+// Loads the argon2 params on start up, so invalid costs stop the server instead of the first sign up
+pub fn init_password_hash_config() -> Result<(), String> {
+    let config = load_password_hash_config()?;
+    let _ = PASSWORD_HASH_CONFIG.set(config);
+    Ok(())
+}
+
+fn get_password_hash_config() -> &'static PasswordHashConfig {
+    PASSWORD_HASH_CONFIG.get_or_init(|| {
+        load_password_hash_config().expect("Invalid password hashing configuration")
+    })
+}
+
+// Costs default to the argon2 defaults: 4096 KiB of memory, 3 iterations and 1 lane
+fn load_password_hash_config() -> Result<PasswordHashConfig, String> {
+    let m_cost = get_env_parsed_strict("ARGON2_MEMORY_KIB", Params::DEFAULT_M_COST)?;
+    let t_cost = get_env_parsed_strict("ARGON2_ITERATIONS", Params::DEFAULT_T_COST)?;
+    let p_cost = get_env_parsed_strict("ARGON2_PARALLELISM", Params::DEFAULT_P_COST)?;
+
+    let params = Params::new(m_cost, t_cost, p_cost, None)
+        .map_err(|err| format!("Invalid argon2 params: {}", err))?;
+
+    Ok(PasswordHashConfig {
+        params,
+        pepper: get_env("PASSWORD_PEPPER"),
+    })
+}
+
+fn make_argon2(config: &PasswordHashConfig, with_pepper: bool) -> Result<Argon2<'_>, String> {
+    let algorithm = argon2::Algorithm::Argon2id;
+    let params = config.params.clone();
+    match &config.pepper {
+        Some(pepper) if with_pepper => {
+            Argon2::new_with_secret(pepper.as_bytes(), algorithm, Version::V0x13, params)
+                .map_err(|err| err.to_string())
+        }
+        _ => Ok(Argon2::new(algorithm, Version::V0x13, params)),
+    }
+}
+
 pub fn hash_password(password: &str) -> Result<String, String> {
     let password = password.as_bytes();
 
     let salt = SaltString::generate(&mut OsRng);
 
-    // Argon2 with default params (Argon2id v19)
-    let argon2 = Argon2::default();
+    // Argon2id v19 with the configured params and pepper
+    let argon2 = make_argon2(get_password_hash_config(), true)?;
 
     // Hash password to PHC string ($argon2id$v=19$...)
     let password_hash = argon2
@@ -27,12 +90,54 @@ pub fn hash_password(password: &str) -> Result<String, String> {
 }
 
 pub fn match_password_and_hash(password: &str, password_hash: &str) -> Result<bool, String> {
+    let password_match = verify_password(password, password_hash)?;
+    Ok(!matches!(password_match, PasswordMatch::NoMatch))
+}
+
+// The hash params (costs) are read from the PHC string, so old hashes keep working after the
+// policy changes. Hashes made before a pepper was configured are checked without it.
+pub fn verify_password(password: &str, password_hash: &str) -> Result<PasswordMatch, String> {
+    let config = get_password_hash_config();
     let password = password.as_bytes();
-    let password_hash = PasswordHash::new(&password_hash).map_err(|err| err.to_string())?;
-    let is_match = Argon2::default()
-        .verify_password(password, &password_hash)
+    let parsed_hash = PasswordHash::new(password_hash).map_err(|err| err.to_string())?;
+
+    let is_match = make_argon2(config, true)?
+        .verify_password(password, &parsed_hash)
         .is_ok();
-    Ok(is_match)
+    if is_match {
+        if is_hash_weaker_than_policy(&parsed_hash, &config.params) {
+            return Ok(PasswordMatch::MatchNeedsRehash);
+        }
+        return Ok(PasswordMatch::Match);
+    }
+
+    if config.pepper.is_some() {
+        let is_match_without_pepper = make_argon2(config, false)?
+            .verify_password(password, &parsed_hash)
+            .is_ok();
+        if is_match_without_pepper {
+            return Ok(PasswordMatch::MatchNeedsRehash);
+        }
+    }
+
+    Ok(PasswordMatch::NoMatch)
+}
+
+fn is_hash_weaker_than_policy(password_hash: &PasswordHash, policy: &Params) -> bool {
+    if password_hash.algorithm != argon2::Algorithm::Argon2id.ident() {
+        return true;
+    }
+    if password_hash.version != Some(Version::V0x13.into()) {
+        return true;
+    }
+    match Params::try_from(password_hash) {
+        Err(_) => true,
+        Ok(params) => {
+            params.m_cost() < policy.m_cost()
+                || params.t_cost() < policy.t_cost()
+                || params.p_cost() < policy.p_cost()
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -40,10 +145,25 @@ struct Claims {
     sub: String,
     // Require. UTC Timestamp Expiration Date
     exp: usize,
+    // Tokens issued before the roles have no role claim
+    #[serde(default = "default_role")]
+    role: String,
+    // Issued at, compared with the last password change of the user. 0 for the older tokens
+    #[serde(default)]
+    iat: usize,
 }
 
-// Change it to a env value, secure and long secret, for production
-const JWT_SECRET: &str = "JWT_SECRET";
+// The claims of a valid JWT. The token can still be revoked by a password change, see
+// authenticate_jwt
+pub struct TokenClaims {
+    pub user_id: String,
+    pub role: String,
+    pub issued_at: i64,
+}
+
+fn default_role() -> String {
+    USER_ROLE.to_string()
+}
 
 fn get_one_day_expiration() -> usize {
     let now = chrono::Utc::now();
@@ -51,29 +171,56 @@ fn get_one_day_expiration() -> usize {
     (now + one_day_duration).timestamp() as usize
 }
 
-pub fn generate_auth_token(user_id: &str) -> Result<String, Box<dyn std::error::Error>> {
+pub fn generate_auth_token(
+    user_id: &str,
+    role: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
     let claims = Claims {
         sub: user_id.to_string(),
         exp: get_one_day_expiration(),
+        role: role.to_string(),
+        iat: chrono::Utc::now().timestamp() as usize,
         // exp: chrono::Utc::now().timestamp() as usize, // gen a expired token for testing
     };
 
-    let header = Header::new(jsonwebtoken::Algorithm::HS512);
-    let key = EncodingKey::from_secret(JWT_SECRET.as_ref());
-    let token = encode(&header, &claims, &key)?;
+    let signing_key = &get_jwt_keys().signing_key;
+    let mut header = Header::new(signing_key.algorithm);
+    header.kid = signing_key.kid.clone();
+    let token = encode(&header, &claims, &signing_key.encoding_key)?;
 
     Ok(token)
 }
 
 fn decode_token(token: &str) -> Result<TokenData<Claims>, Box<dyn std::error::Error>> {
-    let key = DecodingKey::from_secret(JWT_SECRET.as_ref());
-    let validation = Validation::new(jsonwebtoken::Algorithm::HS512);
-    let decoded = decode::<Claims>(&token, &key, &validation)?;
+    let keys = get_jwt_keys();
+    let header = decode_header(token)?;
+
+    // The kid picks the verification key, tokens without kid can only be HS512 ones
+    let (key, algorithm) = match header.kid {
+        Some(kid) => {
+            let key = keys
+                .find_verification_key(&kid)
+                .ok_or_else(|| format!("Unknown key id: {}", kid))?;
+            (&key.decoding_key, key.algorithm)
+        }
+        None => {
+            let key = keys
+                .secret_key
+                .as_ref()
+                .ok_or("Token without key id is not accepted")?;
+            (key, Algorithm::HS512)
+        }
+    };
+
+    let validation = Validation::new(algorithm);
+    let decoded = decode::<Claims>(token, key, &validation)?;
     Ok(decoded)
 }
 
-pub fn validate_and_get_id_from_token(token: &str) -> Result<String, String> {
-    let decoded = decode_token(&token).map_err(|err| err.to_string())?;
+// Checks the signature and the expiration only. The role claim is only a hint, admin use cases
+// check the role stored in the database
+pub fn validate_and_get_claims_from_token(token: &str) -> Result<TokenClaims, String> {
+    let decoded = decode_token(token).map_err(|err| err.to_string())?;
 
     let expiration = decoded.claims.exp;
     let now_in_sec = chrono::Utc::now().timestamp() as usize;
@@ -81,6 +228,9 @@ pub fn validate_and_get_id_from_token(token: &str) -> Result<String, String> {
         return Err("The token is expired".to_string());
     }
 
-    let user_id = decoded.claims.sub;
-    Ok(user_id)
+    Ok(TokenClaims {
+        user_id: decoded.claims.sub,
+        role: decoded.claims.role,
+        issued_at: decoded.claims.iat as i64,
+    })
 }
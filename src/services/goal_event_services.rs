@@ -0,0 +1,135 @@
+use std::{future::poll_fn, sync::OnceLock, time::Duration};
+
+use tokio::sync::broadcast;
+use tokio_postgres::AsyncMessage;
+
+use crate::{
+    data_access::{
+        channel_presence_data_access::PRESENCE_CHANNEL, goal_event_data_access::GOAL_EVENTS_CHANNEL,
+    },
+    db::establish_listener_connection,
+};
+
+const CHANNEL_CAPACITY: usize = 1024;
+const RECONNECT_SECONDS: u64 = 5;
+
+// A new goal event for the user. Without user, every stream checks for events it missed (sent
+// after the listener reconnects)
+#[derive(Clone, Debug)]
+pub struct GoalEventSignal {
+    pub id: i64,
+    pub user_id: Option<String>,
+}
+
+impl GoalEventSignal {
+    // A stream waiting for events held back by a running transaction reads them again on every
+    // signal, the commit of that transaction may not concern its user
+    pub fn concerns(&self, user_id: &str, last_event_id: i64, waiting: bool) -> bool {
+        match &self.user_id {
+            None => true,
+            Some(_) if waiting => true,
+            Some(signal_user_id) => signal_user_id == user_id && self.id > last_event_id,
+        }
+    }
+}
+
+// The viewers of the channel changed
+#[derive(Clone, Debug)]
+pub struct PresenceSignal {
+    pub channel: String,
+}
+
+static SENDER: OnceLock<broadcast::Sender<GoalEventSignal>> = OnceLock::new();
+static PRESENCE_SENDER: OnceLock<broadcast::Sender<PresenceSignal>> = OnceLock::new();
+
+fn get_sender() -> &'static broadcast::Sender<GoalEventSignal> {
+    SENDER.get_or_init(|| broadcast::channel(CHANNEL_CAPACITY).0)
+}
+
+fn get_presence_sender() -> &'static broadcast::Sender<PresenceSignal> {
+    PRESENCE_SENDER.get_or_init(|| broadcast::channel(CHANNEL_CAPACITY).0)
+}
+
+pub fn subscribe_goal_events() -> broadcast::Receiver<GoalEventSignal> {
+    get_sender().subscribe()
+}
+
+pub fn subscribe_presence() -> broadcast::Receiver<PresenceSignal> {
+    get_presence_sender().subscribe()
+}
+
+// One LISTEN connection per instance for the goal events and the presence changes, its
+// notifications are sent to every open stream and WebSocket. The connection is opened again when it
+// is lost
+pub fn spawn_goal_event_listener() {
+    tokio::spawn(async {
+        loop {
+            if let Err(err_msg) = listen().await {
+                eprintln!("Goal event listener error: {}", err_msg);
+            }
+            tokio::time::sleep(Duration::from_secs(RECONNECT_SECONDS)).await;
+        }
+    });
+}
+
+async fn listen() -> Result<(), String> {
+    let (client, mut connection) = establish_listener_connection()
+        .await
+        .map_err(|err| err.to_string())?;
+
+    let listen_query = format!(
+        "LISTEN {}; LISTEN {}",
+        GOAL_EVENTS_CHANNEL, PRESENCE_CHANNEL
+    );
+    let listen = client.batch_execute(&listen_query);
+    tokio::pin!(listen);
+
+    // The connection must be polled for the LISTEN query to complete
+    loop {
+        tokio::select! {
+            result = &mut listen => {
+                result.map_err(|err| err.to_string())?;
+                break;
+            }
+            message = poll_fn(|cx| connection.poll_message(cx)) => match message {
+                None => return Err("Connection closed".to_string()),
+                Some(Err(err)) => return Err(err.to_string()),
+                Some(Ok(_)) => {}
+            }
+        }
+    }
+
+    // Events sent while the listener was disconnected are picked up by the streams
+    let _ = get_sender().send(GoalEventSignal {
+        id: 0,
+        user_id: None,
+    });
+
+    loop {
+        match poll_fn(|cx| connection.poll_message(cx)).await {
+            None => return Err("Connection closed".to_string()),
+            Some(Err(err)) => return Err(err.to_string()),
+            Some(Ok(AsyncMessage::Notification(notification)))
+                if notification.channel() == PRESENCE_CHANNEL =>
+            {
+                let _ = get_presence_sender().send(PresenceSignal {
+                    channel: notification.payload().to_string(),
+                });
+            }
+            Some(Ok(AsyncMessage::Notification(notification))) => {
+                if let Some(signal) = parse_signal(notification.payload()) {
+                    let _ = get_sender().send(signal);
+                }
+            }
+            Some(Ok(_)) => {}
+        }
+    }
+}
+
+fn parse_signal(payload: &str) -> Option<GoalEventSignal> {
+    let (id, user_id) = payload.split_once(':')?;
+    Some(GoalEventSignal {
+        id: id.parse::<i64>().ok()?,
+        user_id: Some(user_id.to_string()),
+    })
+}
@@ -0,0 +1,792 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use csv::StringRecord;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::entities::goal_import::{
+    ImportRow, ImportSummaryEntryDto, ASANA_SOURCE, TODOIST_SOURCE, TRELLO_SOURCE,
+};
+
+// The rows to import and what was done with each kind of item of the export
+pub struct ToolImport {
+    pub rows: Vec<ImportRow>,
+    pub summary: ImportSummary,
+}
+
+#[derive(Default)]
+pub struct ImportSummary {
+    pub mapped: Vec<ImportSummaryEntryDto>,
+    pub skipped: Vec<ImportSummaryEntryDto>,
+}
+
+impl ImportSummary {
+    // Items that are not in the export are left out of the summary
+    fn map(&mut self, item: &str, count: usize, to: &str) {
+        if count > 0 {
+            self.mapped.push(ImportSummaryEntryDto {
+                item: item.to_string(),
+                count,
+                to: Some(to.to_string()),
+                reason: None,
+            });
+        }
+    }
+
+    fn skip(&mut self, item: &str, count: usize, reason: &str) {
+        if count > 0 {
+            self.skipped.push(ImportSummaryEntryDto {
+                item: item.to_string(),
+                count,
+                to: None,
+                reason: Some(reason.to_string()),
+            });
+        }
+    }
+}
+
+// Reads the export file of a task tool. Goals have no tags or sub-goals, so the board, list,
+// labels, start dates and checklists of a task are kept in a note of its goal. Due dates are the
+// due_at of the goal when they can be read, in the note otherwise
+pub trait Importer: Send + Sync {
+    fn parse(&self, body: &str) -> Result<ToolImport, String>;
+}
+
+pub fn get_importer(source: &str) -> Option<Box<dyn Importer>> {
+    match source {
+        TRELLO_SOURCE => Some(Box::new(TrelloImporter)),
+        TODOIST_SOURCE => Some(Box::new(TodoistImporter)),
+        ASANA_SOURCE => Some(Box::new(AsanaImporter)),
+        _ => None,
+    }
+}
+
+// The lines of the note of an imported goal, the empty fields are left out
+struct NoteBuilder {
+    lines: Vec<String>,
+}
+
+impl NoteBuilder {
+    fn new(source: &str) -> NoteBuilder {
+        NoteBuilder {
+            lines: vec![format!("Imported from {}", source)],
+        }
+    }
+
+    fn field(&mut self, name: &str, value: &str) -> &mut NoteBuilder {
+        if !value.trim().is_empty() {
+            self.lines.push(format!("{}: {}", name, value.trim()));
+        }
+        self
+    }
+
+    fn text(&mut self, text: &str) -> &mut NoteBuilder {
+        if !text.trim().is_empty() {
+            self.lines.push(String::new());
+            self.lines.push(text.trim().to_string());
+        }
+        self
+    }
+
+    fn build(&self) -> String {
+        self.lines.join("\n")
+    }
+}
+
+// A date and time with an offset, a date and time in UTC or a date, due at its start in UTC.
+// Recurring and natural language dates ("every monday", "tomorrow") are not read
+fn parse_due_date(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(due_at) = DateTime::parse_from_rfc3339(value) {
+        return Some(due_at.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"] {
+        if let Ok(due_at) = NaiveDateTime::parse_from_str(value, format) {
+            return Some(due_at.and_utc());
+        }
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|due_at| due_at.and_utc())
+}
+
+// The header names are compared without case, the rows keep their line in the file
+struct CsvTable {
+    headers: Vec<String>,
+    records: Vec<(usize, StringRecord)>,
+}
+
+impl CsvTable {
+    fn read(body: &str) -> Result<CsvTable, String> {
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(body.as_bytes());
+
+        let headers = reader
+            .headers()
+            .map_err(|err| err.to_string())?
+            .iter()
+            .map(|header| header.to_lowercase())
+            .collect::<Vec<String>>();
+
+        let mut records = Vec::new();
+        for record in reader.records() {
+            let record = record.map_err(|err| err.to_string())?;
+            let line = record
+                .position()
+                .map(|position| position.line())
+                .unwrap_or(0);
+            records.push((line as usize, record));
+        }
+
+        Ok(CsvTable { headers, records })
+    }
+
+    fn require_column(&self, name: &str) -> Result<usize, String> {
+        self.column(name)
+            .ok_or_else(|| format!("The CSV has no {} column", name))
+    }
+
+    fn column(&self, name: &str) -> Option<usize> {
+        self.headers
+            .iter()
+            .position(|header| header == &name.to_lowercase())
+    }
+
+    fn get(record: &StringRecord, column: Option<usize>) -> &str {
+        column
+            .and_then(|column| record.get(column))
+            .unwrap_or_default()
+    }
+}
+
+// Board JSON export, from the menu of the board: Print, export and share > Export as JSON
+pub struct TrelloImporter;
+
+#[derive(Deserialize)]
+struct TrelloBoard {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    lists: Vec<TrelloList>,
+    #[serde(default)]
+    cards: Vec<TrelloCard>,
+    #[serde(default)]
+    checklists: Vec<TrelloChecklist>,
+    #[serde(default)]
+    actions: Vec<TrelloAction>,
+    #[serde(default)]
+    members: Vec<Value>,
+}
+
+#[derive(Deserialize)]
+struct TrelloList {
+    id: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    closed: bool,
+}
+
+#[derive(Deserialize)]
+struct TrelloCard {
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    desc: String,
+    #[serde(default)]
+    closed: bool,
+    #[serde(rename = "idList", default)]
+    id_list: String,
+    #[serde(default)]
+    labels: Vec<TrelloLabel>,
+    due: Option<String>,
+    #[serde(rename = "dueComplete", default)]
+    due_complete: bool,
+    #[serde(default)]
+    attachments: Vec<Value>,
+}
+
+#[derive(Deserialize)]
+struct TrelloLabel {
+    #[serde(default)]
+    name: String,
+    color: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TrelloChecklist {
+    #[serde(rename = "idCard", default)]
+    id_card: String,
+    #[serde(default)]
+    name: String,
+    #[serde(rename = "checkItems", default)]
+    check_items: Vec<TrelloCheckItem>,
+}
+
+#[derive(Deserialize)]
+struct TrelloCheckItem {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    state: String,
+}
+
+#[derive(Deserialize)]
+struct TrelloAction {
+    #[serde(rename = "type", default)]
+    action_type: String,
+}
+
+impl Importer for TrelloImporter {
+    fn parse(&self, body: &str) -> Result<ToolImport, String> {
+        let board = serde_json::from_str::<TrelloBoard>(body)
+            .map_err(|err| format!("The body is not a Trello board export: {}", err))?;
+
+        let lists = board
+            .lists
+            .iter()
+            .map(|list| (list.id.as_str(), list))
+            .collect::<HashMap<&str, &TrelloList>>();
+
+        let mut checklists = HashMap::<&str, Vec<&TrelloChecklist>>::new();
+        for checklist in board.checklists.iter() {
+            checklists
+                .entry(checklist.id_card.as_str())
+                .or_default()
+                .push(checklist);
+        }
+
+        let mut summary = ImportSummary::default();
+        let mut rows = Vec::new();
+        let mut archived = 0;
+        let mut used_lists = Vec::new();
+        let (mut labels, mut due_dates, mut completed, mut check_items, mut descriptions) =
+            (0, 0, 0, 0, 0);
+        let (mut attachments, mut unread_due_dates) = (0, 0);
+
+        for (index, card) in board.cards.iter().enumerate() {
+            let list = lists.get(card.id_list.as_str());
+            if card.closed || list.map(|list| list.closed).unwrap_or(false) {
+                archived += 1;
+                continue;
+            }
+
+            let list_name = list.map(|list| list.name.as_str()).unwrap_or_default();
+            if !list_name.is_empty() && !used_lists.contains(&list_name) {
+                used_lists.push(list_name);
+            }
+
+            let label_names = card
+                .labels
+                .iter()
+                .map(|label| match label.name.is_empty() {
+                    true => label.color.clone().unwrap_or_default(),
+                    false => label.name.clone(),
+                })
+                .filter(|name| !name.is_empty())
+                .collect::<Vec<String>>();
+            labels += label_names.len();
+
+            let due = card.due.clone().unwrap_or_default();
+            let due_at = parse_due_date(&due);
+            due_dates += usize::from(due_at.is_some());
+            unread_due_dates += usize::from(due_at.is_none() && !due.is_empty());
+            completed += usize::from(card.due_complete);
+            descriptions += usize::from(!card.desc.trim().is_empty());
+            attachments += card.attachments.len();
+
+            let mut note = NoteBuilder::new("Trello");
+            note.field("Board", &board.name)
+                .field("List", list_name)
+                .field("Labels", &label_names.join(", "))
+                .field("Due", if due_at.is_none() { &due } else { "" })
+                .text(&card.desc);
+
+            for checklist in checklists.get(card.id.as_str()).into_iter().flatten() {
+                let items = checklist
+                    .check_items
+                    .iter()
+                    .map(|item| {
+                        let check = if item.state == "complete" { "x" } else { " " };
+                        format!("- [{}] {}", check, item.name)
+                    })
+                    .collect::<Vec<String>>();
+                check_items += items.len();
+                note.text(&format!("{}:\n{}", checklist.name, items.join("\n")));
+            }
+
+            rows.push(
+                ImportRow::new(index + 1, &card.name, card.due_complete)
+                    .with_due_at(due_at)
+                    .with_note(note.build()),
+            );
+        }
+
+        let comments = board
+            .actions
+            .iter()
+            .filter(|action| action.action_type == "commentCard")
+            .count();
+
+        summary.map("cards", rows.len(), "goals");
+        summary.map("boards", 1, "notes");
+        summary.map("lists", used_lists.len(), "notes");
+        summary.map("labels", labels, "notes");
+        summary.map("descriptions", descriptions, "notes");
+        summary.map("due dates", due_dates, "due_at");
+        summary.map("unreadable due dates", unread_due_dates, "notes");
+        summary.map("completed due dates", completed, "completion");
+        summary.map("checklist items", check_items, "notes");
+        summary.skip(
+            "archived cards",
+            archived,
+            "Archived cards and the cards of archived lists are not imported",
+        );
+        summary.skip("comments", comments, "Comments are not imported");
+        summary.skip("attachments", attachments, "Attachments are not imported");
+        summary.skip("members", board.members.len(), "Goals have no assignees");
+
+        Ok(ToolImport { rows, summary })
+    }
+}
+
+// Project CSV export, from the menu of the project: Import/export > Export as a CSV file. Labels
+// are the @words of the content, notes are the comments of the task above them
+pub struct TodoistImporter;
+
+struct TodoistTask {
+    row: usize,
+    text: String,
+    labels: Vec<String>,
+    description: String,
+    date: String,
+    section: String,
+    parent: String,
+    comments: Vec<String>,
+}
+
+impl Importer for TodoistImporter {
+    fn parse(&self, body: &str) -> Result<ToolImport, String> {
+        let table = CsvTable::read(body)?;
+        let type_column = table.require_column("TYPE")?;
+        let content_column = table.require_column("CONTENT")?;
+        let description_column = table.column("DESCRIPTION");
+        let priority_column = table.column("PRIORITY");
+        let indent_column = table.column("INDENT");
+        let responsible_column = table.column("RESPONSIBLE");
+        let date_column = table.column("DATE");
+
+        let mut summary = ImportSummary::default();
+        let mut tasks: Vec<TodoistTask> = Vec::new();
+        let mut section = String::new();
+        let mut parents: Vec<String> = Vec::new();
+        let (mut sections, mut subtasks, mut priorities, mut assignees, mut orphan_comments) =
+            (0, 0, 0, 0, 0);
+
+        for (line, record) in table.records.iter() {
+            let content = CsvTable::get(record, Some(content_column));
+            match CsvTable::get(record, Some(type_column)) {
+                "section" => {
+                    section = content.to_string();
+                    parents.clear();
+                    sections += 1;
+                }
+                "task" => {
+                    let indent = CsvTable::get(record, indent_column)
+                        .parse::<usize>()
+                        .unwrap_or(1)
+                        .max(1);
+                    parents.truncate(indent - 1);
+                    let parent = parents.last().cloned().unwrap_or_default();
+                    subtasks += usize::from(!parent.is_empty());
+
+                    let (text, labels) = split_todoist_labels(content);
+                    parents.push(text.clone());
+
+                    let priority = CsvTable::get(record, priority_column);
+                    priorities += usize::from(!priority.is_empty() && priority != "4");
+                    assignees += usize::from(!CsvTable::get(record, responsible_column).is_empty());
+
+                    tasks.push(TodoistTask {
+                        row: *line,
+                        text,
+                        labels,
+                        description: CsvTable::get(record, description_column).to_string(),
+                        date: CsvTable::get(record, date_column).to_string(),
+                        section: section.clone(),
+                        parent,
+                        comments: Vec::new(),
+                    });
+                }
+                "note" => match tasks.last_mut() {
+                    Some(task) => task.comments.push(content.to_string()),
+                    None => orphan_comments += 1,
+                },
+                _ => {}
+            }
+        }
+
+        let labels = tasks.iter().map(|task| task.labels.len()).sum();
+        let dates = tasks
+            .iter()
+            .filter(|task| parse_due_date(&task.date).is_some())
+            .count();
+        let unread_dates = tasks
+            .iter()
+            .filter(|task| !task.date.is_empty() && parse_due_date(&task.date).is_none())
+            .count();
+        let comments = tasks.iter().map(|task| task.comments.len()).sum();
+        let descriptions = tasks
+            .iter()
+            .filter(|task| !task.description.is_empty())
+            .count();
+
+        let rows = tasks
+            .into_iter()
+            .map(|task| {
+                let due_at = parse_due_date(&task.date);
+                let mut note = NoteBuilder::new("Todoist");
+                note.field("Section", &task.section)
+                    .field("Sub-task of", &task.parent)
+                    .field("Labels", &task.labels.join(", "))
+                    .field("Due", if due_at.is_none() { &task.date } else { "" })
+                    .text(&task.description);
+                for comment in task.comments.iter() {
+                    note.text(comment);
+                }
+                ImportRow::new(task.row, &task.text, false)
+                    .with_due_at(due_at)
+                    .with_note(note.build())
+            })
+            .collect::<Vec<ImportRow>>();
+
+        summary.map("tasks", rows.len(), "goals");
+        summary.map(
+            "sub-tasks",
+            subtasks,
+            "goals, with their parent in the notes",
+        );
+        summary.map("sections", sections, "notes");
+        summary.map("labels", labels, "notes");
+        summary.map("descriptions", descriptions, "notes");
+        summary.map("due dates", dates, "due_at");
+        summary.map("recurring or unreadable due dates", unread_dates, "notes");
+        summary.map("comments", comments, "notes");
+        summary.skip("priorities", priorities, "Goals have no priority");
+        summary.skip("assignees", assignees, "Goals have no assignees");
+        summary.skip(
+            "comments without a task",
+            orphan_comments,
+            "Project comments are not imported",
+        );
+
+        Ok(ToolImport { rows, summary })
+    }
+}
+
+// "Buy milk @errands @home" is the text "Buy milk" with the labels errands and home
+fn split_todoist_labels(content: &str) -> (String, Vec<String>) {
+    let (labels, words): (Vec<&str>, Vec<&str>) = content
+        .split_whitespace()
+        .partition(|word| word.len() > 1 && word.starts_with('@'));
+
+    let labels = labels
+        .into_iter()
+        .map(|label| label.trim_start_matches('@').to_string())
+        .collect();
+
+    (words.join(" "), labels)
+}
+
+// Project CSV export, from the menu of the project: Export/Print > CSV
+pub struct AsanaImporter;
+
+impl Importer for AsanaImporter {
+    fn parse(&self, body: &str) -> Result<ToolImport, String> {
+        let table = CsvTable::read(body)?;
+        let name_column = table.require_column("Name")?;
+        let completed_column = table.column("Completed At");
+        let section_column = table.column("Section/Column");
+        let projects_column = table.column("Projects");
+        let tags_column = table.column("Tags");
+        let start_column = table.column("Start Date");
+        let due_column = table.column("Due Date");
+        let notes_column = table.column("Notes");
+        let parent_column = table.column("Parent task");
+        let assignee_column = table.column("Assignee");
+
+        let mut summary = ImportSummary::default();
+        let mut rows = Vec::new();
+        let (mut unnamed, mut completed, mut subtasks, mut sections, mut projects) =
+            (0, 0, 0, 0, 0);
+        let (mut tags, mut starts, mut due_dates, mut unread_due_dates) = (0, 0, 0, 0);
+        let (mut descriptions, mut assignees) = (0, 0);
+
+        for (line, record) in table.records.iter() {
+            let name = CsvTable::get(record, Some(name_column));
+            if name.is_empty() {
+                unnamed += 1;
+                continue;
+            }
+
+            let is_completed = !CsvTable::get(record, completed_column).is_empty();
+            let section = CsvTable::get(record, section_column);
+            let project = CsvTable::get(record, projects_column);
+            let task_tags = CsvTable::get(record, tags_column);
+            let start = CsvTable::get(record, start_column);
+            let due = CsvTable::get(record, due_column);
+            let notes = CsvTable::get(record, notes_column);
+            let parent = CsvTable::get(record, parent_column);
+
+            completed += usize::from(is_completed);
+            subtasks += usize::from(!parent.is_empty());
+            sections += usize::from(!section.is_empty());
+            projects += usize::from(!project.is_empty());
+            tags += task_tags
+                .split(',')
+                .filter(|tag| !tag.trim().is_empty())
+                .count();
+            let due_at = parse_due_date(due);
+            starts += usize::from(!start.is_empty());
+            due_dates += usize::from(due_at.is_some());
+            unread_due_dates += usize::from(due_at.is_none() && !due.is_empty());
+            descriptions += usize::from(!notes.is_empty());
+            assignees += usize::from(!CsvTable::get(record, assignee_column).is_empty());
+
+            let mut note = NoteBuilder::new("Asana");
+            note.field("Project", project)
+                .field("Section", section)
+                .field("Sub-task of", parent)
+                .field("Tags", task_tags)
+                .field("Start", start)
+                .field("Due", if due_at.is_none() { due } else { "" })
+                .text(notes);
+
+            rows.push(
+                ImportRow::new(*line, name, is_completed)
+                    .with_due_at(due_at)
+                    .with_note(note.build()),
+            );
+        }
+
+        summary.map("tasks", rows.len(), "goals");
+        summary.map("completed tasks", completed, "completion");
+        summary.map(
+            "sub-tasks",
+            subtasks,
+            "goals, with their parent in the notes",
+        );
+        summary.map("projects", projects, "notes");
+        summary.map("sections", sections, "notes");
+        summary.map("tags", tags, "notes");
+        summary.map("start dates", starts, "notes");
+        summary.map("due dates", due_dates, "due_at");
+        summary.map("unreadable due dates", unread_due_dates, "notes");
+        summary.map("descriptions", descriptions, "notes");
+        summary.skip("rows without a name", unnamed, "A goal needs a text");
+        summary.skip("assignees", assignees, "Goals have no assignees");
+
+        Ok(ToolImport { rows, summary })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRELLO_BOARD: &str = r#"{
+        "name": "Home",
+        "lists": [
+            {"id": "l1", "name": "To do", "closed": false},
+            {"id": "l2", "name": "Old", "closed": true}
+        ],
+        "cards": [
+            {
+                "id": "c1", "name": "Paint the door", "desc": "Blue", "closed": false,
+                "idList": "l1", "labels": [{"name": "", "color": "green"}],
+                "due": "2024-05-01T12:00:00.000Z", "dueComplete": true, "attachments": []
+            },
+            {
+                "id": "c2", "name": "Fix the sink", "desc": "", "closed": false,
+                "idList": "l1", "labels": [], "due": "next week", "dueComplete": false
+            },
+            {"id": "c3", "name": "Archived", "closed": true, "idList": "l1"},
+            {"id": "c4", "name": "In an archived list", "closed": false, "idList": "l2"}
+        ],
+        "checklists": [
+            {
+                "idCard": "c1", "name": "Steps",
+                "checkItems": [
+                    {"name": "Sand", "state": "complete"},
+                    {"name": "Paint", "state": "incomplete"}
+                ]
+            }
+        ],
+        "actions": [{"type": "commentCard"}, {"type": "updateCard"}]
+    }"#;
+
+    const TODOIST_CSV: &str = "TYPE,CONTENT,DESCRIPTION,PRIORITY,INDENT,AUTHOR,RESPONSIBLE,DATE
+note,Project comment,,,,,,
+section,Errands,,,,,,
+task,Buy milk @shop @home,Whole milk,1,1,Ann,,2024-05-01
+note,Not the skimmed one,,,,,,
+task,Check the date,,4,2,Ann,Bob,every monday
+";
+
+    const ASANA_CSV: &str = "Task ID,Name,Completed At,Section/Column,Projects,Tags,Due Date,Notes
+1,Write the report,2024-04-30,Doing,Work,\"urgent,q2\",2024-05-01 09:30,\"Draft, then review\"
+2,,,,,,,
+3,Send the report,,Doing,Work,,someday,
+";
+
+    fn mapped(import: &ToolImport, item: &str) -> usize {
+        import
+            .summary
+            .mapped
+            .iter()
+            .find(|entry| entry.item == item)
+            .map(|entry| entry.count)
+            .unwrap_or(0)
+    }
+
+    fn skipped(import: &ToolImport, item: &str) -> usize {
+        import
+            .summary
+            .skipped
+            .iter()
+            .find(|entry| entry.item == item)
+            .map(|entry| entry.count)
+            .unwrap_or(0)
+    }
+
+    fn note(row: &ImportRow) -> &str {
+        row.note.as_deref().unwrap_or_default()
+    }
+
+    fn at(value: &str) -> Option<DateTime<Utc>> {
+        Some(
+            DateTime::parse_from_rfc3339(value)
+                .unwrap()
+                .with_timezone(&Utc),
+        )
+    }
+
+    #[test]
+    fn reads_date_only_and_datetime_due_dates() {
+        assert_eq!(
+            parse_due_date("2024-05-01T12:00:00+02:00"),
+            at("2024-05-01T10:00:00Z")
+        );
+        assert_eq!(
+            parse_due_date("2024-05-01T12:00:00"),
+            at("2024-05-01T12:00:00Z")
+        );
+        assert_eq!(
+            parse_due_date("2024-05-01 12:00"),
+            at("2024-05-01T12:00:00Z")
+        );
+        assert_eq!(parse_due_date(" 2024-05-01 "), at("2024-05-01T00:00:00Z"));
+        assert_eq!(parse_due_date("every monday"), None);
+        assert_eq!(parse_due_date(""), None);
+    }
+
+    #[test]
+    fn maps_the_trello_cards() {
+        let import = TrelloImporter.parse(TRELLO_BOARD).unwrap();
+
+        assert_eq!(import.rows.len(), 2);
+        let door = &import.rows[0];
+        assert_eq!(
+            (door.row, door.text.as_str(), door.completed),
+            (1, "Paint the door", true)
+        );
+        assert_eq!(door.due_at, at("2024-05-01T12:00:00Z"));
+        assert_eq!(
+            note(door),
+            "Imported from Trello\nBoard: Home\nList: To do\nLabels: green\n\nBlue\n\n\
+             Steps:\n- [x] Sand\n- [ ] Paint"
+        );
+
+        let sink = &import.rows[1];
+        assert_eq!((sink.row, sink.completed, sink.due_at), (2, false, None));
+        assert!(note(sink).contains("Due: next week"));
+
+        assert_eq!(mapped(&import, "cards"), 2);
+        assert_eq!(mapped(&import, "due dates"), 1);
+        assert_eq!(mapped(&import, "unreadable due dates"), 1);
+        assert_eq!(mapped(&import, "checklist items"), 2);
+        assert_eq!(skipped(&import, "archived cards"), 2);
+        assert_eq!(skipped(&import, "comments"), 1);
+    }
+
+    #[test]
+    fn rejects_a_body_that_is_not_a_trello_board() {
+        assert!(TrelloImporter.parse(r#"{"name": "Home", "cards": ["#).is_err());
+        assert!(TrelloImporter.parse(r#"{"cards": [{"name": 1}]}"#).is_err());
+    }
+
+    #[test]
+    fn maps_the_todoist_tasks() {
+        let import = TodoistImporter.parse(TODOIST_CSV).unwrap();
+
+        assert_eq!(import.rows.len(), 2);
+        let milk = &import.rows[0];
+        assert_eq!((milk.row, milk.text.as_str()), (4, "Buy milk"));
+        assert_eq!(milk.due_at, at("2024-05-01T00:00:00Z"));
+        assert_eq!(
+            note(milk),
+            "Imported from Todoist\nSection: Errands\nLabels: shop, home\n\nWhole milk\n\n\
+             Not the skimmed one"
+        );
+
+        let date = &import.rows[1];
+        assert_eq!((date.row, date.due_at), (6, None));
+        assert!(note(date).contains("Sub-task of: Buy milk"));
+        assert!(note(date).contains("Due: every monday"));
+
+        assert_eq!(mapped(&import, "sub-tasks"), 1);
+        assert_eq!(mapped(&import, "recurring or unreadable due dates"), 1);
+        assert_eq!(skipped(&import, "priorities"), 1);
+        assert_eq!(skipped(&import, "assignees"), 1);
+        assert_eq!(skipped(&import, "comments without a task"), 1);
+    }
+
+    #[test]
+    fn rejects_a_todoist_csv_without_the_required_columns() {
+        let result = TodoistImporter.parse("CONTENT,DATE\nBuy milk,2024-05-01\n");
+        assert_eq!(result.err(), Some("The CSV has no TYPE column".to_string()));
+    }
+
+    #[test]
+    fn maps_the_asana_tasks() {
+        let import = AsanaImporter.parse(ASANA_CSV).unwrap();
+
+        assert_eq!(import.rows.len(), 2);
+        let write = &import.rows[0];
+        assert_eq!((write.row, write.completed), (2, true));
+        assert_eq!(write.due_at, at("2024-05-01T09:30:00Z"));
+        assert_eq!(
+            note(write),
+            "Imported from Asana\nProject: Work\nSection: Doing\nTags: urgent,q2\n\n\
+             Draft, then review"
+        );
+
+        let send = &import.rows[1];
+        assert_eq!((send.row, send.completed, send.due_at), (4, false, None));
+        assert!(note(send).contains("Due: someday"));
+
+        assert_eq!(mapped(&import, "tags"), 2);
+        assert_eq!(skipped(&import, "rows without a name"), 1);
+    }
+
+    #[test]
+    fn rejects_an_asana_csv_without_a_name_column() {
+        assert!(AsanaImporter.parse("Task ID,Notes\n1,Draft\n").is_err());
+    }
+}
@@ -0,0 +1,204 @@
+use std::{fs, path::Path, sync::OnceLock};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey};
+use rsa::{pkcs8::DecodePublicKey, traits::PublicKeyParts, RsaPublicKey};
+
+use crate::{config::get_env, entities::jwk::JwkDto};
+
+// Change it to a env value (JWT_SECRET), secure and long secret, for production
+const DEFAULT_JWT_SECRET: &str = "JWT_SECRET";
+
+// DER prefix of a Ed25519 SubjectPublicKeyInfo, followed by the 32 bytes of the key
+const ED25519_SPKI_PREFIX: [u8; 12] = [
+    0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+];
+
+const PUBLIC_KEY_SUFFIX: &str = ".pub.pem";
+const PRIVATE_KEY_SUFFIX: &str = ".pem";
+
+pub struct SigningKey {
+    pub kid: Option<String>,
+    pub algorithm: Algorithm,
+    pub encoding_key: EncodingKey,
+}
+
+pub struct VerificationKey {
+    pub kid: String,
+    pub algorithm: Algorithm,
+    pub decoding_key: DecodingKey,
+    pub jwk: JwkDto,
+}
+
+pub struct JwtKeys {
+    pub signing_key: SigningKey,
+    pub verification_keys: Vec<VerificationKey>,
+    // HS512 key used for tokens without a kid header
+    pub secret_key: Option<DecodingKey>,
+}
+
+static JWT_KEYS: OnceLock<JwtKeys> = OnceLock::new();
+
+// Loads the keys on start up, so a bad key configuration stops the server instead of the first sign in
+pub fn init_jwt_keys() -> Result<(), String> {
+    let keys = load_jwt_keys()?;
+    let _ = JWT_KEYS.set(keys);
+    Ok(())
+}
+
+pub fn get_jwt_keys() -> &'static JwtKeys {
+    JWT_KEYS.get_or_init(|| load_jwt_keys().expect("Invalid JWT keys configuration"))
+}
+
+impl JwtKeys {
+    pub fn find_verification_key(&self, kid: &str) -> Option<&VerificationKey> {
+        self.verification_keys.iter().find(|key| key.kid == kid)
+    }
+
+    pub fn get_jwks(&self) -> Vec<JwkDto> {
+        self.verification_keys
+            .iter()
+            .map(|key| key.jwk.clone())
+            .collect()
+    }
+}
+
+// Without JWT_KEYS_DIR the tokens are signed with HS512 and JWT_SECRET, like before.
+// With it, every <kid>.pub.pem file in the directory is a verification key and the tokens are
+// signed with <kid>.pem of JWT_ACTIVE_KID. To rotate: add the new key pair, switch
+// JWT_ACTIVE_KID and remove the old public key once the tokens signed with it have expired.
+fn load_jwt_keys() -> Result<JwtKeys, String> {
+    let secret = get_env("JWT_SECRET");
+
+    let keys_dir = match get_env("JWT_KEYS_DIR") {
+        None => {
+            let secret = secret.unwrap_or_else(|| DEFAULT_JWT_SECRET.to_string());
+            return Ok(JwtKeys {
+                signing_key: SigningKey {
+                    kid: None,
+                    algorithm: Algorithm::HS512,
+                    encoding_key: EncodingKey::from_secret(secret.as_bytes()),
+                },
+                verification_keys: Vec::new(),
+                secret_key: Some(DecodingKey::from_secret(secret.as_bytes())),
+            });
+        }
+        Some(dir) => dir,
+    };
+
+    let verification_keys = load_verification_keys(&keys_dir)?;
+
+    let active_kid = get_env("JWT_ACTIVE_KID")
+        .ok_or_else(|| "JWT_ACTIVE_KID is required when JWT_KEYS_DIR is set".to_string())?;
+
+    let active_key = verification_keys
+        .iter()
+        .find(|key| key.kid == active_kid)
+        .ok_or_else(|| format!("No public key found for the active kid: {}", active_kid))?;
+
+    let signing_key = load_signing_key(&keys_dir, &active_kid, active_key.algorithm)?;
+
+    Ok(JwtKeys {
+        signing_key,
+        verification_keys,
+        // Only kept when explicitly set, to accept the HS512 tokens issued before the switch
+        secret_key: secret.map(|secret| DecodingKey::from_secret(secret.as_bytes())),
+    })
+}
+
+fn load_verification_keys(keys_dir: &str) -> Result<Vec<VerificationKey>, String> {
+    let entries = fs::read_dir(keys_dir)
+        .map_err(|err| format!("Cannot read JWT keys directory {}: {}", keys_dir, err))?;
+
+    let mut keys = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|err| err.to_string())?.path();
+        let file_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default()
+            .to_string();
+        if let Some(kid) = file_name.strip_suffix(PUBLIC_KEY_SUFFIX) {
+            keys.push(load_verification_key(&path, kid)?);
+        }
+    }
+
+    if keys.is_empty() {
+        return Err(format!(
+            "No *{} files found in {}",
+            PUBLIC_KEY_SUFFIX, keys_dir
+        ));
+    }
+
+    Ok(keys)
+}
+
+fn load_verification_key(path: &Path, kid: &str) -> Result<VerificationKey, String> {
+    let contents = fs::read(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+    let der = pem::parse(&contents)
+        .map_err(|err| format!("{}: {}", path.display(), err))?
+        .contents;
+
+    if der.len() == ED25519_SPKI_PREFIX.len() + 32 && der.starts_with(&ED25519_SPKI_PREFIX) {
+        let decoding_key = DecodingKey::from_ed_pem(&contents)
+            .map_err(|err| format!("{}: {}", path.display(), err))?;
+        let x = &der[ED25519_SPKI_PREFIX.len()..];
+        return Ok(VerificationKey {
+            kid: kid.to_string(),
+            algorithm: Algorithm::EdDSA,
+            decoding_key,
+            jwk: JwkDto {
+                kty: "OKP".to_string(),
+                key_use: "sig".to_string(),
+                alg: "EdDSA".to_string(),
+                kid: kid.to_string(),
+                n: None,
+                e: None,
+                crv: Some("Ed25519".to_string()),
+                x: Some(URL_SAFE_NO_PAD.encode(x)),
+            },
+        });
+    }
+
+    let public_key = RsaPublicKey::from_public_key_der(&der).map_err(|_| {
+        format!(
+            "{}: only RSA and Ed25519 public keys are supported",
+            path.display()
+        )
+    })?;
+    let decoding_key = DecodingKey::from_rsa_pem(&contents)
+        .map_err(|err| format!("{}: {}", path.display(), err))?;
+
+    Ok(VerificationKey {
+        kid: kid.to_string(),
+        algorithm: Algorithm::RS256,
+        decoding_key,
+        jwk: JwkDto {
+            kty: "RSA".to_string(),
+            key_use: "sig".to_string(),
+            alg: "RS256".to_string(),
+            kid: kid.to_string(),
+            n: Some(URL_SAFE_NO_PAD.encode(public_key.n().to_bytes_be())),
+            e: Some(URL_SAFE_NO_PAD.encode(public_key.e().to_bytes_be())),
+            crv: None,
+            x: None,
+        },
+    })
+}
+
+fn load_signing_key(keys_dir: &str, kid: &str, algorithm: Algorithm) -> Result<SigningKey, String> {
+    let path = Path::new(keys_dir).join(format!("{}{}", kid, PRIVATE_KEY_SUFFIX));
+    let contents = fs::read(&path).map_err(|err| format!("{}: {}", path.display(), err))?;
+
+    let encoding_key = match algorithm {
+        Algorithm::EdDSA => EncodingKey::from_ed_pem(&contents),
+        _ => EncodingKey::from_rsa_pem(&contents),
+    }
+    .map_err(|err| format!("{}: {}", path.display(), err))?;
+
+    Ok(SigningKey {
+        kid: Some(kid.to_string()),
+        algorithm,
+        encoding_key,
+    })
+}
@@ -1 +1,12 @@
 pub mod auth_services;
+pub mod api_key_services;
+pub mod jwt_key_services;
+pub mod password_policy_services;
+pub mod token_services;
+pub mod notifier_services;
+pub mod scheduler_services;
+pub mod webhook_services;
+pub mod goal_event_services;
+pub mod audit_services;
+pub mod importer_services;
+pub mod outbound_url_services;
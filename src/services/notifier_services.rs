@@ -0,0 +1,181 @@
+use std::{future::Future, pin::Pin, sync::OnceLock, time::Duration};
+
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+
+use crate::{
+    config::{get_env, get_env_parsed},
+    entities::reminder::{ReminderNotification, EMAIL_CHANNEL, LOG_CHANNEL, WEBHOOK_CHANNEL},
+    services::outbound_url_services::{check_public_url, outbound_client_builder},
+};
+
+const WEBHOOK_TIMEOUT_SECONDS: u64 = 10;
+
+pub type NotifyFuture<'a> = Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>>;
+
+// A channel the reminders are sent through. Returns the reason of the failure, so the reminder
+// can be tried again later
+pub trait Notifier: Send + Sync {
+    fn notify<'a>(&'a self, notification: &'a ReminderNotification) -> NotifyFuture<'a>;
+}
+
+// Prints the reminders, for development or when no other channel is configured
+pub struct LogNotifier;
+
+impl Notifier for LogNotifier {
+    fn notify<'a>(&'a self, notification: &'a ReminderNotification) -> NotifyFuture<'a> {
+        Box::pin(async move {
+            println!(
+                "Reminder {} for {} <{}>: {} (at {})",
+                notification.reminder_id,
+                notification.user_name,
+                notification.user_email,
+                notification.goal_text,
+                notification.remind_at
+            );
+            Ok(())
+        })
+    }
+}
+
+// Sends the reminder to the e-mail of the user through SMTP
+pub struct EmailNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl Notifier for EmailNotifier {
+    fn notify<'a>(&'a self, notification: &'a ReminderNotification) -> NotifyFuture<'a> {
+        Box::pin(async move {
+            let to = notification
+                .user_email
+                .parse::<Mailbox>()
+                .map_err(|err| format!("Invalid recipient: {}", err))?;
+            let message = Message::builder()
+                .from(self.from.clone())
+                .to(to)
+                .subject(format!("Reminder: {}", notification.goal_text))
+                .header(ContentType::TEXT_PLAIN)
+                .body(format!(
+                    "Hi {},\n\nThis is your reminder for the goal:\n\n{}\n\nScheduled at {} ({}).\n",
+                    notification.user_name,
+                    notification.goal_text,
+                    notification.remind_at,
+                    notification.timezone
+                ))
+                .map_err(|err| err.to_string())?;
+            self.transport
+                .send(message)
+                .await
+                .map_err(|err| err.to_string())?;
+            Ok(())
+        })
+    }
+}
+
+// POSTs the notification as JSON to the url of the reminder, any 2xx response is a success. Like the
+// webhooks, the url has to resolve to public addresses and redirects are not followed
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+}
+
+impl Notifier for WebhookNotifier {
+    fn notify<'a>(&'a self, notification: &'a ReminderNotification) -> NotifyFuture<'a> {
+        Box::pin(async move {
+            let url = notification
+                .webhook_url
+                .as_ref()
+                .ok_or_else(|| "Missing webhook url".to_string())?;
+            check_public_url(url).await?;
+            let response = self
+                .client
+                .post(url)
+                .json(notification)
+                .send()
+                .await
+                .map_err(|err| err.to_string())?;
+            if !response.status().is_success() {
+                return Err(format!("Webhook responded with {}", response.status()));
+            }
+            Ok(())
+        })
+    }
+}
+
+pub struct Notifiers {
+    email: Option<EmailNotifier>,
+    webhook: WebhookNotifier,
+    log: LogNotifier,
+}
+
+impl Notifiers {
+    // The e-mail channel fails until SMTP is configured
+    pub fn get(&self, channel: &str) -> Result<&dyn Notifier, String> {
+        match channel {
+            EMAIL_CHANNEL => match &self.email {
+                None => Err("SMTP is not configured".to_string()),
+                Some(email) => Ok(email),
+            },
+            WEBHOOK_CHANNEL => Ok(&self.webhook),
+            LOG_CHANNEL => Ok(&self.log),
+            _ => Err(format!("Unknown channel: {}", channel)),
+        }
+    }
+}
+
+static NOTIFIERS: OnceLock<Notifiers> = OnceLock::new();
+
+// Loads the notifiers on start up, so an invalid SMTP configuration stops the server
+pub fn init_notifiers() -> Result<(), String> {
+    let notifiers = load_notifiers()?;
+    let _ = NOTIFIERS.set(notifiers);
+    Ok(())
+}
+
+pub fn get_notifiers() -> &'static Notifiers {
+    NOTIFIERS.get_or_init(|| load_notifiers().expect("Invalid notifiers configuration"))
+}
+
+fn load_notifiers() -> Result<Notifiers, String> {
+    let client = outbound_client_builder()
+        .timeout(Duration::from_secs(WEBHOOK_TIMEOUT_SECONDS))
+        .build()
+        .map_err(|err| format!("Cannot create the webhook client: {}", err))?;
+
+    Ok(Notifiers {
+        email: load_email_notifier()?,
+        webhook: WebhookNotifier { client },
+        log: LogNotifier,
+    })
+}
+
+// SMTP_HOST enables the e-mail channel. STARTTLS is used unless SMTP_TLS is "none" (ex: a local
+// mail catcher)
+fn load_email_notifier() -> Result<Option<EmailNotifier>, String> {
+    let host = match get_env("SMTP_HOST") {
+        None => return Ok(None),
+        Some(host) => host,
+    };
+    let from = get_env("SMTP_FROM")
+        .ok_or_else(|| "SMTP_FROM is required with SMTP_HOST".to_string())?
+        .parse::<Mailbox>()
+        .map_err(|err| format!("SMTP_FROM is not a valid address: {}", err))?;
+
+    let mut builder = match get_env("SMTP_TLS").as_deref() {
+        Some("none") => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&host),
+        _ => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&host)
+            .map_err(|err| format!("Invalid SMTP_HOST: {}", err))?,
+    };
+    builder = builder.port(get_env_parsed("SMTP_PORT", 587));
+    if let (Some(username), Some(password)) = (get_env("SMTP_USERNAME"), get_env("SMTP_PASSWORD")) {
+        builder = builder.credentials(Credentials::new(username, password));
+    }
+
+    Ok(Some(EmailNotifier {
+        transport: builder.build(),
+        from,
+    }))
+}
@@ -0,0 +1,119 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::Arc,
+};
+
+use hyper::client::connect::dns::Name;
+use reqwest::{
+    dns::{Addrs, Resolve, Resolving},
+    redirect::Policy,
+    Url,
+};
+
+use crate::config::get_env_parsed;
+
+// Webhooks and reminder webhooks are sent to urls chosen by the users. Unless
+// WEBHOOK_ALLOW_PRIVATE_HOSTS is true (ex: a receiver on the same machine in development), they
+// cannot reach loopback, private, link-local or unspecified addresses, like the metadata service
+// of a cloud provider or the database
+pub fn allow_private_hosts() -> bool {
+    get_env_parsed("WEBHOOK_ALLOW_PRIVATE_HOSTS", false)
+}
+
+pub fn is_public_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ipv4(&ip),
+            None => is_public_ipv6(ip),
+        },
+    }
+}
+
+fn is_public_ipv4(ip: &Ipv4Addr) -> bool {
+    let octets = ip.octets();
+    !(ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // 0.0.0.0/8, 100.64.0.0/10 (shared address space) and 198.18.0.0/15 (benchmarking)
+        || octets[0] == 0
+        || (octets[0] == 100 && (octets[1] & 0xc0) == 64)
+        || (octets[0] == 198 && (octets[1] & 0xfe) == 18))
+}
+
+fn is_public_ipv6(ip: &Ipv6Addr) -> bool {
+    let first_segment = ip.segments()[0];
+    !(ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        // fc00::/7 (unique local) and fe80::/10 (link-local)
+        || (first_segment & 0xfe00) == 0xfc00
+        || (first_segment & 0xffc0) == 0xfe80)
+}
+
+// Resolves the host of the url and fails when one of its addresses is not public. Called when the
+// url is registered and again before each request, the addresses of a host can change
+pub async fn check_public_url(url: &str) -> Result<(), String> {
+    if allow_private_hosts() {
+        return Ok(());
+    }
+
+    let parsed = Url::parse(url).map_err(|err| format!("Invalid url: {}", err))?;
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| "The url has no host".to_string())?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+    let port = parsed.port_or_known_default().unwrap_or(443);
+
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), port))
+        .await
+        .map_err(|err| format!("Cannot resolve the host {}: {}", host, err))?
+        .collect();
+
+    if addrs.is_empty() {
+        return Err(format!("Cannot resolve the host {}", host));
+    }
+    if addrs.iter().any(|addr| !is_public_ip(&addr.ip())) {
+        return Err(format!(
+            "The host {} is a private, loopback or link-local address",
+            host
+        ));
+    }
+    Ok(())
+}
+
+// Resolver of the webhook clients. It leaves out the addresses that are not public, so a host
+// resolving to a public address when it was checked and to a private one when the request is sent
+// is not reached either
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_ip(&addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("The host {} has no public address", name.as_str()).into());
+            }
+            let addrs: Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+// Redirects are not followed, they could lead to an address that was never checked
+pub fn outbound_client_builder() -> reqwest::ClientBuilder {
+    let builder = reqwest::Client::builder().redirect(Policy::none());
+    match allow_private_hosts() {
+        true => builder,
+        false => builder.dns_resolver(Arc::new(PublicResolver)),
+    }
+}
@@ -0,0 +1,229 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Seek, SeekFrom},
+    sync::OnceLock,
+};
+
+use sha1::{Digest, Sha1};
+
+use crate::config::{get_env, get_env_parsed};
+
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub max_length: usize,
+    pub min_entropy_bits: f64,
+    // File with the uppercase SHA-1 of breached passwords, one per line and sorted, like the
+    // Pwned Passwords list ordered by hash (HASH:COUNT lines)
+    pub breached_list_path: Option<String>,
+}
+
+static PASSWORD_POLICY: OnceLock<PasswordPolicy> = OnceLock::new();
+
+// Loads the policy on start up, so a missing breached list file stops the server
+pub fn init_password_policy() -> Result<(), String> {
+    let policy = load_password_policy()?;
+    let _ = PASSWORD_POLICY.set(policy);
+    Ok(())
+}
+
+pub fn get_password_policy() -> &'static PasswordPolicy {
+    PASSWORD_POLICY
+        .get_or_init(|| load_password_policy().expect("Invalid password policy configuration"))
+}
+
+fn load_password_policy() -> Result<PasswordPolicy, String> {
+    let policy = PasswordPolicy {
+        min_length: get_env_parsed("PASSWORD_MIN_LENGTH", 8),
+        max_length: get_env_parsed("PASSWORD_MAX_LENGTH", 128),
+        min_entropy_bits: get_env_parsed("PASSWORD_MIN_ENTROPY_BITS", 40.0),
+        breached_list_path: get_env("PASSWORD_BREACHED_LIST"),
+    };
+
+    if policy.min_length == 0 || policy.min_length > policy.max_length {
+        return Err("PASSWORD_MIN_LENGTH must be between 1 and PASSWORD_MAX_LENGTH".to_string());
+    }
+
+    if let Some(path) = &policy.breached_list_path {
+        File::open(path).map_err(|err| format!("Cannot open breached list {}: {}", path, err))?;
+    }
+
+    Ok(policy)
+}
+
+// Checks a new password against the policy. Returns every rule the password breaks, so the user
+// can fix them all at once
+pub fn check_password_policy(password: &str, name: &str, email: &str) -> Result<(), Vec<String>> {
+    let policy = get_password_policy();
+    let mut errors = Vec::new();
+
+    let length = password.chars().count();
+    if length < policy.min_length || length > policy.max_length {
+        errors.push(format!(
+            "Password must be between {} and {} characters long",
+            policy.min_length, policy.max_length
+        ));
+    }
+
+    if contains_personal_info(password, name, email) {
+        errors.push("Password must not contain your name or e-mail".to_string());
+    }
+
+    let entropy_bits = estimate_entropy_bits(password);
+    if entropy_bits < policy.min_entropy_bits {
+        errors.push(format!(
+            "Password is too weak (about {:.0} bits, at least {:.0} required): use a longer \
+             password or a passphrase, and avoid repeated or sequential characters",
+            entropy_bits, policy.min_entropy_bits
+        ));
+    }
+
+    if let Some(path) = &policy.breached_list_path {
+        match is_breached_password(path, password) {
+            Err(err) => eprintln!("Breached password check error: {}", err),
+            Ok(true) => errors.push(
+                "Password was found in a data breach and cannot be used, choose another one"
+                    .to_string(),
+            ),
+            Ok(false) => {}
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn contains_personal_info(password: &str, name: &str, email: &str) -> bool {
+    let password = password.to_lowercase();
+
+    let email_local_part = email.split('@').next().unwrap_or_default().to_lowercase();
+    let name_parts = name.split_whitespace().map(|part| part.to_lowercase());
+
+    name_parts
+        .chain(std::iter::once(email_local_part))
+        .filter(|part| part.chars().count() >= 3)
+        .any(|part| password.contains(&part))
+}
+
+// Rough estimation: the size of the character pool in use gives the bits per character, and a
+// character that repeats or continues a sequence of the previous one (aa, ab, 21) counts 1 bit
+pub fn estimate_entropy_bits(password: &str) -> f64 {
+    let mut has_lower = false;
+    let mut has_upper = false;
+    let mut has_digit = false;
+    let mut has_symbol = false;
+    let mut has_other = false;
+
+    for ch in password.chars() {
+        if ch.is_ascii_lowercase() {
+            has_lower = true;
+        } else if ch.is_ascii_uppercase() {
+            has_upper = true;
+        } else if ch.is_ascii_digit() {
+            has_digit = true;
+        } else if ch.is_ascii() {
+            has_symbol = true;
+        } else {
+            has_other = true;
+        }
+    }
+
+    let mut pool = 0;
+    if has_lower {
+        pool += 26;
+    }
+    if has_upper {
+        pool += 26;
+    }
+    if has_digit {
+        pool += 10;
+    }
+    if has_symbol {
+        pool += 33;
+    }
+    if has_other {
+        pool += 100;
+    }
+    if pool == 0 {
+        return 0.0;
+    }
+
+    let bits_per_char = (pool as f64).log2();
+    let mut bits = 0.0;
+    let mut previous: Option<char> = None;
+    for ch in password.chars() {
+        let is_predictable = match previous {
+            None => false,
+            Some(prev) => (ch as i64 - prev as i64).abs() <= 1,
+        };
+        bits += if is_predictable { 1.0 } else { bits_per_char };
+        previous = Some(ch);
+    }
+
+    bits
+}
+
+// Binary search over the sorted file, so even the full Pwned Passwords list (tens of GB) is
+// checked with a few reads
+fn is_breached_password(path: &str, password: &str) -> Result<bool, String> {
+    let hash = hex::encode_upper(Sha1::digest(password.as_bytes()));
+
+    let mut file = File::open(path).map_err(|err| err.to_string())?;
+    let file_len = file.metadata().map_err(|err| err.to_string())?.len();
+
+    // Looks for the first line with a hash >= the password hash
+    let mut low = 0;
+    let mut high = file_len;
+    while low < high {
+        let middle = low + (high - low) / 2;
+        match read_line_from(&mut file, middle)? {
+            None => high = middle,
+            Some((line_hash, line_start)) => {
+                if line_hash.as_str() < hash.as_str() {
+                    low = line_start + 1;
+                } else {
+                    high = middle;
+                }
+            }
+        }
+    }
+
+    match read_line_from(&mut file, low)? {
+        None => Ok(false),
+        Some((line_hash, _)) => Ok(line_hash == hash),
+    }
+}
+
+// Reads the hash of the first line starting at the position or after it, with the line start
+fn read_line_from(file: &mut File, position: u64) -> Result<Option<(String, u64)>, String> {
+    let mut line_start = position;
+    let mut line = String::new();
+
+    // Starts one byte before to know if the position is already at the start of a line
+    let seek_to = position.saturating_sub(1);
+    file.seek(SeekFrom::Start(seek_to))
+        .map_err(|err| err.to_string())?;
+    let mut reader = BufReader::new(file);
+
+    if position > 0 {
+        let skipped = reader.read_line(&mut line).map_err(|err| err.to_string())?;
+        line_start = seek_to + skipped as u64;
+        line.clear();
+    }
+
+    let read = reader.read_line(&mut line).map_err(|err| err.to_string())?;
+    if read == 0 {
+        return Ok(None);
+    }
+
+    let line_hash = line
+        .trim()
+        .split(':')
+        .next()
+        .unwrap_or_default()
+        .to_uppercase();
+
+    Ok(Some((line_hash, line_start)))
+}
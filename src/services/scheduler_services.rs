@@ -0,0 +1,20 @@
+use std::{future::Future, time::Duration};
+
+use tokio::time::{self, MissedTickBehavior};
+
+// Runs the task in the background every period, the first run is right away. A run that takes
+// longer than the period delays the next one instead of stacking them
+pub fn spawn_periodic<F, Fut>(period: Duration, task: F)
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut interval = time::interval(period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            task().await;
+        }
+    });
+}
@@ -0,0 +1,28 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+// Random hex string from the OS random generator
+pub fn generate_random_token(bytes_len: usize) -> String {
+    let mut bytes = vec![0u8; bytes_len];
+    OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+// The tokens are long random strings, so a fast hash is enough (no need for argon2 on every request)
+pub fn hash_token(token: &str) -> String {
+    let digest = Sha256::digest(token.as_bytes());
+    hex::encode(digest)
+}
+
+// Compares the hashes without stopping at the first different byte
+pub fn match_token_and_hash(token: &str, token_hash: &str) -> bool {
+    let computed = hash_token(token);
+    if computed.len() != token_hash.len() {
+        return false;
+    }
+    computed
+        .bytes()
+        .zip(token_hash.bytes())
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+        == 0
+}
@@ -0,0 +1,90 @@
+use std::{sync::OnceLock, time::Duration};
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::{
+    config::get_env_parsed,
+    entities::webhook::PendingWebhookDelivery,
+    services::outbound_url_services::{check_public_url, outbound_client_builder},
+};
+
+const USER_AGENT: &str = "goals-rust-webhooks";
+
+// Failed attempt: the status of the response, if one was received, and the reason
+pub struct DeliveryFailure {
+    pub response_status: Option<i32>,
+    pub error: String,
+}
+
+static CLIENT: OnceLock<Result<reqwest::Client, String>> = OnceLock::new();
+
+// WEBHOOK_TIMEOUT_SECONDS bounds every attempt, 10 seconds by default. The client does not follow
+// redirects and only connects to public addresses. When it cannot be built the deliveries fail,
+// they are never sent by a client without these restrictions
+fn get_client() -> Result<&'static reqwest::Client, DeliveryFailure> {
+    CLIENT
+        .get_or_init(|| {
+            outbound_client_builder()
+                .timeout(Duration::from_secs(get_env_parsed(
+                    "WEBHOOK_TIMEOUT_SECONDS",
+                    10,
+                )))
+                .user_agent(USER_AGENT)
+                .build()
+                .map_err(|err| format!("Cannot create the webhook client: {}", err))
+        })
+        .as_ref()
+        .map_err(|error| DeliveryFailure {
+            response_status: None,
+            error: error.clone(),
+        })
+}
+
+// Hex HMAC-SHA256 of "{timestamp}.{payload}". The timestamp is part of the signature, so receivers
+// can reject old requests that are sent again
+pub fn sign_payload(secret: &str, timestamp: i64, payload: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("{}.{}", timestamp, payload).as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+// POSTs the payload to the url of the webhook, any 2xx response is a success and returns its status.
+// A url that does not resolve to public addresses any more fails without a request
+pub async fn deliver(delivery: &PendingWebhookDelivery) -> Result<i32, DeliveryFailure> {
+    check_public_url(&delivery.url)
+        .await
+        .map_err(|error| DeliveryFailure {
+            response_status: None,
+            error,
+        })?;
+
+    let timestamp = Utc::now().timestamp();
+    let signature = sign_payload(&delivery.secret, timestamp, &delivery.payload);
+
+    let response = get_client()?
+        .post(&delivery.url)
+        .header("Content-Type", "application/json")
+        .header("X-Webhook-Id", &delivery.event_id)
+        .header("X-Webhook-Event", &delivery.event_type)
+        .header("X-Webhook-Timestamp", timestamp.to_string())
+        .header("X-Webhook-Signature", format!("sha256={}", signature))
+        .body(delivery.payload.clone())
+        .send()
+        .await
+        .map_err(|err| DeliveryFailure {
+            response_status: None,
+            error: err.to_string(),
+        })?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(DeliveryFailure {
+            response_status: Some(status.as_u16() as i32),
+            error: format!("Webhook responded with {}", status),
+        });
+    }
+    Ok(status.as_u16() as i32)
+}
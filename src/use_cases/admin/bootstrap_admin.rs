@@ -0,0 +1,54 @@
+use tokio_postgres::Client;
+
+use crate::{
+    config::get_env,
+    data_access::user_data_access::{count_users_by_role, find_user_by_email, update_user_role},
+    db::establish_connection,
+    entities::user::ADMIN_ROLE,
+};
+
+pub enum BootstrapAdminError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+}
+
+// Promotes the user with the ADMIN_BOOTSTRAP_EMAIL e-mail to admin, only while there is no admin
+// yet. The user must sign up first. Returns the promoted e-mail, if any
+pub async fn execute() -> Result<Option<String>, BootstrapAdminError> {
+    let email = match get_env("ADMIN_BOOTSTRAP_EMAIL") {
+        None => return Ok(None),
+        Some(email) => email,
+    };
+
+    let client = get_connected_client().await?;
+
+    let admins_count = count_users_by_role(&client, ADMIN_ROLE)
+        .await
+        .map_err(|err| BootstrapAdminError::DatabaseError(err.to_string()))?;
+    if admins_count > 0 {
+        return Ok(None);
+    }
+
+    let opt_user = find_user_by_email(&client, &email)
+        .await
+        .map_err(|err| BootstrapAdminError::DatabaseError(err.to_string()))?;
+    let user = opt_user.ok_or_else(|| {
+        BootstrapAdminError::UserNotFoundError(format!(
+            "No user to promote to admin for the e-mail: {}",
+            email
+        ))
+    })?;
+
+    update_user_role(&client, &user.get_id(), ADMIN_ROLE)
+        .await
+        .map_err(|err| BootstrapAdminError::DatabaseError(err.to_string()))?;
+
+    Ok(Some(email))
+}
+
+async fn get_connected_client() -> Result<Client, BootstrapAdminError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| BootstrapAdminError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
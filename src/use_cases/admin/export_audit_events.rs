@@ -0,0 +1,89 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        audit_event_data_access::find_audit_events_after, user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::audit_event::AuditEventsQueryDto,
+    use_cases::admin::get_audit_events::{map_to_dto, validate_query, GetAuditEventsError},
+};
+
+const BATCH_SIZE: i64 = 1000;
+
+pub enum ExportAuditEventsError {
+    DatabaseError(String),
+    ForbiddenError(String),
+    InvalidRequestError(String),
+}
+
+// The whole log as JSON Lines, one event per line and oldest first. The filters are the ones of
+// the list, limit and offset are ignored
+pub async fn execute(
+    query: AuditEventsQueryDto,
+    admin_id: String,
+) -> Result<String, ExportAuditEventsError> {
+    validate_query(&query).map_err(|err| match err {
+        GetAuditEventsError::InvalidRequestError(err_msg) => {
+            ExportAuditEventsError::InvalidRequestError(err_msg)
+        }
+        GetAuditEventsError::DatabaseError(err_msg) => {
+            ExportAuditEventsError::DatabaseError(err_msg)
+        }
+        GetAuditEventsError::ForbiddenError(err_msg) => {
+            ExportAuditEventsError::ForbiddenError(err_msg)
+        }
+    })?;
+
+    let client = get_connected_client().await?;
+    find_admin(&client, &admin_id).await?;
+
+    let mut lines = String::new();
+    let mut after_id = 0;
+    loop {
+        let events = find_audit_events_after(
+            &client,
+            query.event_type.as_deref(),
+            query.user_id.as_deref(),
+            query.email.as_deref(),
+            after_id,
+            BATCH_SIZE,
+        )
+        .await
+        .map_err(|err| ExportAuditEventsError::DatabaseError(err.to_string()))?;
+
+        for event in events.iter() {
+            let line = serde_json::to_string(&map_to_dto(event))
+                .map_err(|err| ExportAuditEventsError::DatabaseError(err.to_string()))?;
+            lines.push_str(&line);
+            lines.push('\n');
+            after_id = event.get_id();
+        }
+
+        if (events.len() as i64) < BATCH_SIZE {
+            break;
+        }
+    }
+
+    Ok(lines)
+}
+
+async fn get_connected_client() -> Result<Client, ExportAuditEventsError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| ExportAuditEventsError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_admin(client: &Client, admin_id: &str) -> Result<(), ExportAuditEventsError> {
+    let opt_user = find_user_by_id(client, admin_id)
+        .await
+        .map_err(|err| ExportAuditEventsError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        Some(user) if user.is_admin() && !user.is_disabled() => Ok(()),
+        _ => Err(ExportAuditEventsError::ForbiddenError(
+            "Only admins can access this resource".to_string(),
+        )),
+    }
+}
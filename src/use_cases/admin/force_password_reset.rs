@@ -0,0 +1,130 @@
+use chrono::{DateTime, Duration, Utc};
+use tokio_postgres::Client;
+
+use crate::{
+    config::get_env_parsed,
+    data_access::{
+        api_key_data_access::revoke_all_api_keys,
+        user_data_access::{find_user_by_id, set_user_password_reset},
+    },
+    db::establish_connection,
+    entities::{
+        audit_event::{AuditContext, ADMIN_PASSWORD_RESET_FORCED_AUDIT_EVENT},
+        user::{PasswordResetDto, User},
+    },
+    services::{
+        audit_services::record_admin_audit_event,
+        token_services::{generate_random_token, hash_token},
+    },
+};
+
+const RESET_TOKEN_BYTES: usize = 32;
+
+pub enum ForcePasswordResetError {
+    DatabaseError(String),
+    ForbiddenError(String),
+    UserNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// The user cannot sign in again until the password is changed with POST /api/users/password and
+// the reset token, valid PASSWORD_RESET_TOKEN_HOURS (24 by default). Credentials are usually reset
+// because they were stolen, so the JWTs and the api keys of the user stop working now. The token is
+// returned once, the admin gives it to the user through another channel
+pub async fn execute(
+    user_id: String,
+    admin_id: String,
+    audit: AuditContext,
+) -> Result<PasswordResetDto, ForcePasswordResetError> {
+    User::validate_id(&user_id)
+        .map_err(|err| ForcePasswordResetError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_admin(&client, &admin_id).await?;
+    find_user(&client, &user_id).await?;
+
+    let reset_token = generate_random_token(RESET_TOKEN_BYTES);
+    let expires_at = Utc::now() + Duration::hours(get_env_parsed("PASSWORD_RESET_TOKEN_HOURS", 24));
+    reset_password(&client, &user_id, &hash_token(&reset_token), expires_at).await?;
+
+    record_admin_audit_event(
+        &client,
+        ADMIN_PASSWORD_RESET_FORCED_AUDIT_EVENT,
+        &admin_id,
+        &user_id,
+        &audit,
+    )
+    .await;
+
+    Ok(PasswordResetDto {
+        reset_token,
+        expires_at: expires_at.to_rfc3339(),
+    })
+}
+
+async fn reset_password(
+    client: &Client,
+    user_id: &str,
+    token_hash: &str,
+    expires_at: DateTime<Utc>,
+) -> Result<(), ForcePasswordResetError> {
+    client
+        .batch_execute("BEGIN")
+        .await
+        .map_err(|err| ForcePasswordResetError::DatabaseError(err.to_string()))?;
+
+    let result = match set_user_password_reset(client, user_id, token_hash, expires_at).await {
+        Err(err) => Err(err.to_string()),
+        Ok(_) => revoke_all_api_keys(client, user_id)
+            .await
+            .map_err(|err| err.to_string()),
+    };
+
+    if let Err(err) = result {
+        if let Err(rollback_err) = client.batch_execute("ROLLBACK").await {
+            eprintln!("Password reset rollback error: {}", rollback_err);
+        }
+        return Err(ForcePasswordResetError::DatabaseError(err));
+    }
+
+    client
+        .batch_execute("COMMIT")
+        .await
+        .map_err(|err| ForcePasswordResetError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+async fn get_connected_client() -> Result<Client, ForcePasswordResetError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| ForcePasswordResetError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_admin(client: &Client, admin_id: &str) -> Result<(), ForcePasswordResetError> {
+    let opt_user = find_user_by_id(client, admin_id)
+        .await
+        .map_err(|err| ForcePasswordResetError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        Some(user) if user.is_admin() && !user.is_disabled() => Ok(()),
+        _ => Err(ForcePasswordResetError::ForbiddenError(
+            "Only admins can access this resource".to_string(),
+        )),
+    }
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), ForcePasswordResetError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| ForcePasswordResetError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(ForcePasswordResetError::UserNotFoundError(format!(
+            "User not found for the id: {}",
+            user_id
+        ))),
+        Some(_) => Ok(()),
+    }
+}
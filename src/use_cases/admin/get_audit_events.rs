@@ -0,0 +1,94 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{audit_event_data_access::find_audit_events, user_data_access::find_user_by_id},
+    db::establish_connection,
+    entities::{
+        audit_event::{AuditEvent, AuditEventDto, AuditEventsQueryDto},
+        user::User,
+    },
+};
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 200;
+
+pub enum GetAuditEventsError {
+    DatabaseError(String),
+    ForbiddenError(String),
+    InvalidRequestError(String),
+}
+
+// Newest first, filtered by event type, user and e-mail
+pub async fn execute(
+    query: AuditEventsQueryDto,
+    admin_id: String,
+) -> Result<Vec<AuditEventDto>, GetAuditEventsError> {
+    validate_query(&query)?;
+
+    let client = get_connected_client().await?;
+    find_admin(&client, &admin_id).await?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let events = find_audit_events(
+        &client,
+        query.event_type.as_deref(),
+        query.user_id.as_deref(),
+        query.email.as_deref(),
+        limit,
+        offset,
+    )
+    .await
+    .map_err(|err| GetAuditEventsError::DatabaseError(err.to_string()))?;
+
+    Ok(events.iter().map(map_to_dto).collect())
+}
+
+pub fn validate_query(query: &AuditEventsQueryDto) -> Result<(), GetAuditEventsError> {
+    if let Some(event_type) = &query.event_type {
+        AuditEvent::validate_event_type(event_type)
+            .map_err(|err| GetAuditEventsError::InvalidRequestError(err.to_string()))?;
+    }
+
+    if let Some(user_id) = &query.user_id {
+        User::validate_id(user_id)
+            .map_err(|err| GetAuditEventsError::InvalidRequestError(err.to_string()))?;
+    }
+
+    Ok(())
+}
+
+async fn get_connected_client() -> Result<Client, GetAuditEventsError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| GetAuditEventsError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_admin(client: &Client, admin_id: &str) -> Result<(), GetAuditEventsError> {
+    let opt_user = find_user_by_id(client, admin_id)
+        .await
+        .map_err(|err| GetAuditEventsError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        Some(user) if user.is_admin() && !user.is_disabled() => Ok(()),
+        _ => Err(GetAuditEventsError::ForbiddenError(
+            "Only admins can access this resource".to_string(),
+        )),
+    }
+}
+
+pub fn map_to_dto(event: &AuditEvent) -> AuditEventDto {
+    AuditEventDto {
+        id: event.get_id(),
+        event_type: event.get_event_type(),
+        user_id: event.get_user_id(),
+        actor_id: event.get_actor_id(),
+        email: event.get_email(),
+        ip: event.get_ip(),
+        user_agent: event.get_user_agent(),
+        details: event.get_details_value(),
+        created_at: event.get_created_at().map(|date| date.to_rfc3339()),
+    }
+}
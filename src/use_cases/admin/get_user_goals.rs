@@ -0,0 +1,83 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{goal_data_access::find_all_goals, user_data_access::find_user_by_id},
+    db::establish_connection,
+    entities::{
+        audit_event::{AuditContext, ADMIN_USER_GOALS_VIEWED_AUDIT_EVENT},
+        goal::GoalDto,
+        user::User,
+    },
+    services::audit_services::record_admin_audit_event,
+    use_cases::goals::get_all_goals::map_to_dtos,
+};
+
+pub enum GetUserGoalsError {
+    DatabaseError(String),
+    ForbiddenError(String),
+    UserNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// Reading the goals of another user is recorded in the audit log
+pub async fn execute(
+    user_id: String,
+    admin_id: String,
+    audit: AuditContext,
+) -> Result<Vec<GoalDto>, GetUserGoalsError> {
+    User::validate_id(&user_id)
+        .map_err(|err| GetUserGoalsError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_admin(&client, &admin_id).await?;
+    find_user(&client, &user_id).await?;
+
+    let goals_db = find_all_goals(&client, &user_id, false)
+        .await
+        .map_err(|err| GetUserGoalsError::DatabaseError(err.to_string()))?;
+
+    record_admin_audit_event(
+        &client,
+        ADMIN_USER_GOALS_VIEWED_AUDIT_EVENT,
+        &admin_id,
+        &user_id,
+        &audit,
+    )
+    .await;
+
+    Ok(map_to_dtos(goals_db))
+}
+
+async fn get_connected_client() -> Result<Client, GetUserGoalsError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| GetUserGoalsError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_admin(client: &Client, admin_id: &str) -> Result<(), GetUserGoalsError> {
+    let opt_user = find_user_by_id(client, admin_id)
+        .await
+        .map_err(|err| GetUserGoalsError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        Some(user) if user.is_admin() && !user.is_disabled() => Ok(()),
+        _ => Err(GetUserGoalsError::ForbiddenError(
+            "Only admins can access this resource".to_string(),
+        )),
+    }
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), GetUserGoalsError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| GetUserGoalsError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(GetUserGoalsError::UserNotFoundError(format!(
+            "User not found for the id: {}",
+            user_id
+        ))),
+        Some(_) => Ok(()),
+    }
+}
@@ -0,0 +1,70 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::user_data_access::{find_user_by_id, find_users},
+    db::establish_connection,
+    entities::user::{User, UserDto, UsersQueryDto},
+};
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 200;
+
+pub enum GetUsersError {
+    DatabaseError(String),
+    ForbiddenError(String),
+}
+
+pub async fn execute(
+    query: UsersQueryDto,
+    admin_id: String,
+) -> Result<Vec<UserDto>, GetUsersError> {
+    let client = get_connected_client().await?;
+    find_admin(&client, &admin_id).await?;
+
+    let search = query.search.unwrap_or_default();
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let users_db = find_users(&client, search.trim(), limit, offset)
+        .await
+        .map_err(|err| GetUsersError::DatabaseError(err.to_string()))?;
+
+    Ok(map_to_dtos(users_db))
+}
+
+async fn get_connected_client() -> Result<Client, GetUsersError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| GetUsersError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_admin(client: &Client, admin_id: &str) -> Result<(), GetUsersError> {
+    let opt_user = find_user_by_id(client, admin_id)
+        .await
+        .map_err(|err| GetUsersError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        Some(user) if user.is_admin() && !user.is_disabled() => Ok(()),
+        _ => Err(GetUsersError::ForbiddenError(
+            "Only admins can access this resource".to_string(),
+        )),
+    }
+}
+
+pub fn map_to_dtos(users_db: Vec<User>) -> Vec<UserDto> {
+    let mut users_dto = Vec::new();
+    for user_db in users_db {
+        let user_dto = UserDto {
+            id: user_db.get_id(),
+            name: user_db.get_name(),
+            email: user_db.get_email(),
+            phone: user_db.get_phone(),
+            role: user_db.get_role(),
+            is_disabled: user_db.is_disabled(),
+            password_reset_required: user_db.is_password_reset_required(),
+        };
+        users_dto.push(user_dto);
+    }
+    users_dto
+}
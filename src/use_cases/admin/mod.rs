@@ -0,0 +1,7 @@
+pub mod get_users;
+pub mod set_user_disabled;
+pub mod force_password_reset;
+pub mod get_user_goals;
+pub mod bootstrap_admin;
+pub mod get_audit_events;
+pub mod export_audit_events;
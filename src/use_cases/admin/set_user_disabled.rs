@@ -0,0 +1,89 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::user_data_access::{find_user_by_id, update_user_disabled},
+    db::establish_connection,
+    entities::{
+        audit_event::{
+            AuditContext, ADMIN_USER_DISABLED_AUDIT_EVENT, ADMIN_USER_ENABLED_AUDIT_EVENT,
+        },
+        user::User,
+    },
+    services::audit_services::record_admin_audit_event,
+};
+
+pub enum SetUserDisabledError {
+    DatabaseError(String),
+    ForbiddenError(String),
+    UserNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// Disables or enables an account. Disabled users cannot sign in and their tokens and api keys
+// are rejected
+pub async fn execute(
+    user_id: String,
+    admin_id: String,
+    is_disabled: bool,
+    audit: AuditContext,
+) -> Result<(), SetUserDisabledError> {
+    User::validate_id(&user_id)
+        .map_err(|err| SetUserDisabledError::InvalidRequestError(err.to_string()))?;
+
+    if is_disabled && user_id == admin_id {
+        return Err(SetUserDisabledError::InvalidRequestError(
+            "Admins cannot disable their own account".to_string(),
+        ));
+    }
+
+    let client = get_connected_client().await?;
+    find_admin(&client, &admin_id).await?;
+    find_user(&client, &user_id).await?;
+
+    update_user_disabled(&client, &user_id, is_disabled)
+        .await
+        .map_err(|err| SetUserDisabledError::DatabaseError(err.to_string()))?;
+
+    let event_type = if is_disabled {
+        ADMIN_USER_DISABLED_AUDIT_EVENT
+    } else {
+        ADMIN_USER_ENABLED_AUDIT_EVENT
+    };
+    record_admin_audit_event(&client, event_type, &admin_id, &user_id, &audit).await;
+
+    Ok(())
+}
+
+async fn get_connected_client() -> Result<Client, SetUserDisabledError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| SetUserDisabledError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_admin(client: &Client, admin_id: &str) -> Result<(), SetUserDisabledError> {
+    let opt_user = find_user_by_id(client, admin_id)
+        .await
+        .map_err(|err| SetUserDisabledError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        Some(user) if user.is_admin() && !user.is_disabled() => Ok(()),
+        _ => Err(SetUserDisabledError::ForbiddenError(
+            "Only admins can access this resource".to_string(),
+        )),
+    }
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), SetUserDisabledError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| SetUserDisabledError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(SetUserDisabledError::UserNotFoundError(format!(
+            "User not found for the id: {}",
+            user_id
+        ))),
+        Some(_) => Ok(()),
+    }
+}
@@ -0,0 +1,97 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        api_key_data_access::{find_api_key_by_prefix, update_api_key_last_used},
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::api_key::ApiKey,
+    services::api_key_services::{extract_prefix_from_api_key, match_api_key_and_hash},
+};
+
+pub enum AuthenticateApiKeyError {
+    InvalidApiKeyError(String),
+    MissingScopeError(String),
+    DatabaseError(String),
+}
+
+// Checks the api key and its scope, returns the id of the user that owns the key
+pub async fn execute(key: String, required_scope: &str) -> Result<String, AuthenticateApiKeyError> {
+    let prefix = extract_prefix_from_api_key(&key).ok_or_else(|| {
+        AuthenticateApiKeyError::InvalidApiKeyError("Api key is not in a valid format".to_string())
+    })?;
+
+    let client = get_connected_client().await?;
+
+    let api_key = find_api_key(&client, &prefix).await?;
+
+    check_api_key(&api_key, &key, required_scope)?;
+
+    check_owner(&client, &api_key.get_user_id()).await?;
+
+    update_api_key_last_used(&client, &api_key.get_id())
+        .await
+        .map_err(|err| AuthenticateApiKeyError::DatabaseError(err.to_string()))?;
+
+    Ok(api_key.get_user_id())
+}
+
+async fn get_connected_client() -> Result<Client, AuthenticateApiKeyError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| AuthenticateApiKeyError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_api_key(client: &Client, prefix: &str) -> Result<ApiKey, AuthenticateApiKeyError> {
+    let opt_api_key = find_api_key_by_prefix(client, prefix)
+        .await
+        .map_err(|err| AuthenticateApiKeyError::DatabaseError(err.to_string()))?;
+
+    opt_api_key.ok_or_else(|| {
+        AuthenticateApiKeyError::InvalidApiKeyError(
+            "Api key not found or already revoked".to_string(),
+        )
+    })
+}
+
+async fn check_owner(client: &Client, user_id: &str) -> Result<(), AuthenticateApiKeyError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| AuthenticateApiKeyError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        Some(user) if !user.is_disabled() => Ok(()),
+        _ => Err(AuthenticateApiKeyError::InvalidApiKeyError(
+            "The owner of the api key is disabled or was removed".to_string(),
+        )),
+    }
+}
+
+fn check_api_key(
+    api_key: &ApiKey,
+    key: &str,
+    required_scope: &str,
+) -> Result<(), AuthenticateApiKeyError> {
+    if !match_api_key_and_hash(key, &api_key.get_key_hash()) {
+        return Err(AuthenticateApiKeyError::InvalidApiKeyError(
+            "Api key not found or already revoked".to_string(),
+        ));
+    }
+
+    if api_key.is_expired() {
+        return Err(AuthenticateApiKeyError::InvalidApiKeyError(
+            "The api key is expired".to_string(),
+        ));
+    }
+
+    if !api_key.allows_scope(required_scope) {
+        return Err(AuthenticateApiKeyError::MissingScopeError(format!(
+            "The api key does not have the '{}' scope",
+            required_scope
+        )));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{api_key_data_access::add_api_key, user_data_access::find_user_by_id},
+    db::establish_connection,
+    entities::api_key::{ApiKey, CreateApiKeyDto, CreatedApiKeyDto},
+    services::api_key_services::generate_api_key,
+};
+
+pub enum CreateApiKeyError {
+    InvalidRequestError(String),
+    UserNotFoundError(String),
+    DatabaseError(String),
+}
+
+pub async fn execute(
+    new_api_key: CreateApiKeyDto,
+    user_id: String,
+) -> Result<CreatedApiKeyDto, CreateApiKeyError> {
+    let client = get_connected_client().await?;
+
+    find_user(&client, &user_id).await?;
+
+    let generated = generate_api_key();
+
+    let api_key = ApiKey::from_create_api_key_dto(
+        new_api_key,
+        &user_id,
+        &generated.prefix,
+        &generated.key_hash,
+    )
+    .map_err(|err| CreateApiKeyError::InvalidRequestError(err.to_string()))?;
+
+    let id = add_api_key(&client, &api_key)
+        .await
+        .map_err(|err| CreateApiKeyError::DatabaseError(err.to_string()))?;
+
+    Ok(CreatedApiKeyDto {
+        id,
+        name: api_key.get_name(),
+        scope: api_key.get_scope(),
+        expires_at: api_key.get_expires_at().map(|date| date.to_rfc3339()),
+        key: generated.key,
+    })
+}
+
+async fn get_connected_client() -> Result<Client, CreateApiKeyError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| CreateApiKeyError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), CreateApiKeyError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| CreateApiKeyError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(CreateApiKeyError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(CreateApiKeyError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
@@ -0,0 +1,66 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{api_key_data_access::find_all_api_keys, user_data_access::find_user_by_id},
+    db::establish_connection,
+    entities::api_key::{ApiKey, ApiKeyDto},
+};
+
+pub enum GetAllApiKeysError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+}
+
+pub async fn execute(user_id: String) -> Result<Vec<ApiKeyDto>, GetAllApiKeysError> {
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    let api_keys_db = find_api_keys(&client, &user_id).await?;
+    let api_keys = map_to_dtos(api_keys_db);
+    Ok(api_keys)
+}
+
+async fn get_connected_client() -> Result<Client, GetAllApiKeysError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| GetAllApiKeysError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), GetAllApiKeysError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| GetAllApiKeysError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(GetAllApiKeysError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(GetAllApiKeysError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn find_api_keys(client: &Client, user_id: &str) -> Result<Vec<ApiKey>, GetAllApiKeysError> {
+    let api_keys = find_all_api_keys(client, user_id)
+        .await
+        .map_err(|err| GetAllApiKeysError::DatabaseError(err.to_string()))?;
+    Ok(api_keys)
+}
+
+fn map_to_dtos(api_keys_db: Vec<ApiKey>) -> Vec<ApiKeyDto> {
+    let mut api_keys_dto = Vec::new();
+    for api_key_db in api_keys_db {
+        let api_key_dto = ApiKeyDto {
+            id: api_key_db.get_id(),
+            name: api_key_db.get_name(),
+            prefix: api_key_db.get_prefix(),
+            scope: api_key_db.get_scope(),
+            expires_at: api_key_db.get_expires_at().map(|date| date.to_rfc3339()),
+            last_used_at: api_key_db.get_last_used_at().map(|date| date.to_rfc3339()),
+        };
+        api_keys_dto.push(api_key_dto);
+    }
+    api_keys_dto
+}
@@ -0,0 +1,4 @@
+pub mod authenticate_api_key;
+pub mod create_api_key;
+pub mod get_all_api_keys;
+pub mod revoke_api_key;
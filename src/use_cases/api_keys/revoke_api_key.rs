@@ -0,0 +1,65 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{api_key_data_access, user_data_access::find_user_by_id},
+    db::establish_connection,
+    entities::api_key::ApiKey,
+};
+
+pub enum RevokeApiKeyError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    ApiKeyNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+pub async fn execute(api_key_id: String, user_id: String) -> Result<(), RevokeApiKeyError> {
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    ApiKey::validate_id(&api_key_id)
+        .map_err(|err| RevokeApiKeyError::InvalidRequestError(err.to_string()))?;
+    revoke_api_key(&client, &api_key_id, &user_id).await?;
+    Ok(())
+}
+
+async fn get_connected_client() -> Result<Client, RevokeApiKeyError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| RevokeApiKeyError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), RevokeApiKeyError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| RevokeApiKeyError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(RevokeApiKeyError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(RevokeApiKeyError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn revoke_api_key(
+    client: &Client,
+    api_key_id: &str,
+    user_id: &str,
+) -> Result<(), RevokeApiKeyError> {
+    let revoked = api_key_data_access::revoke_api_key(client, api_key_id, user_id)
+        .await
+        .map_err(|err| RevokeApiKeyError::DatabaseError(err.to_string()))?;
+
+    if revoked == 0 {
+        return Err(RevokeApiKeyError::ApiKeyNotFoundError(format!(
+            "Api key not found for the id: {}",
+            api_key_id
+        )));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use tokio_postgres::Client;
+
+use crate::{
+    config::get_env,
+    data_access::{
+        calendar_feed_data_access::set_calendar_feed, user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::calendar_feed::{CalendarFeed, CreatedCalendarFeedDto},
+    services::token_services::{generate_random_token, hash_token},
+};
+
+pub enum CreateCalendarFeedError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// A new token replaces the previous one, so this also rotates a leaked feed URL. The URL is
+// relative unless PUBLIC_BASE_URL is set. The token is returned only here
+pub async fn execute(user_id: String) -> Result<CreatedCalendarFeedDto, CreateCalendarFeedError> {
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+
+    let token = generate_random_token(32);
+    let calendar_feed = CalendarFeed::from_token_hash(&user_id, &hash_token(&token))
+        .map_err(|err| CreateCalendarFeedError::InvalidRequestError(err.to_string()))?;
+
+    let calendar_feed = set_calendar_feed(&client, &calendar_feed)
+        .await
+        .map_err(|err| CreateCalendarFeedError::DatabaseError(err.to_string()))?;
+
+    let base_url = get_env("PUBLIC_BASE_URL").unwrap_or_default();
+
+    Ok(CreatedCalendarFeedDto {
+        url: format!("{}/calendar/{}.ics", base_url.trim_end_matches('/'), token),
+        token,
+        created_at: calendar_feed.get_created_at().map(|date| date.to_rfc3339()),
+    })
+}
+
+async fn get_connected_client() -> Result<Client, CreateCalendarFeedError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| CreateCalendarFeedError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), CreateCalendarFeedError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| CreateCalendarFeedError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(CreateCalendarFeedError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(CreateCalendarFeedError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
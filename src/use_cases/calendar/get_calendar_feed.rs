@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        calendar_feed_data_access::find_calendar_feed_by_token_hash,
+        goal_data_access::find_all_goals, reminder_data_access::find_user_reminders,
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{
+        calendar_feed::CalendarDocument,
+        goal::Goal,
+        reminder::{Reminder, PENDING_STATUS},
+    },
+    services::token_services::hash_token,
+};
+
+pub enum GetCalendarFeedError {
+    DatabaseError(String),
+    NotFoundError(String),
+}
+
+const NOT_FOUND_MESSAGE: &str = "Calendar feed not found";
+const MAX_LINE_OCTETS: usize = 75;
+
+// No authentication, the token is the access. Unknown and revoked tokens, and feeds of disabled
+// users, all give the same not found error
+pub async fn execute(token: String) -> Result<CalendarDocument, GetCalendarFeedError> {
+    let client = get_connected_client().await?;
+
+    let calendar_feed = find_calendar_feed_by_token_hash(&client, &hash_token(&token))
+        .await
+        .map_err(|err| GetCalendarFeedError::DatabaseError(err.to_string()))?
+        .ok_or_else(|| GetCalendarFeedError::NotFoundError(NOT_FOUND_MESSAGE.to_string()))?;
+
+    let user_id = calendar_feed.get_user_id();
+    check_user_is_enabled(&client, &user_id).await?;
+
+    let goals = find_all_goals(&client, &user_id, false)
+        .await
+        .map_err(|err| GetCalendarFeedError::DatabaseError(err.to_string()))?;
+    let reminders = find_user_reminders(&client, &user_id)
+        .await
+        .map_err(|err| GetCalendarFeedError::DatabaseError(err.to_string()))?;
+
+    let body = to_icalendar(&goals, &reminders);
+    let etag = format!("\"{}\"", hash_token(&body));
+
+    Ok(CalendarDocument { body, etag })
+}
+
+async fn get_connected_client() -> Result<Client, GetCalendarFeedError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| GetCalendarFeedError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn check_user_is_enabled(client: &Client, user_id: &str) -> Result<(), GetCalendarFeedError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| GetCalendarFeedError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        Some(user) if !user.is_disabled() => Ok(()),
+        _ => Err(GetCalendarFeedError::NotFoundError(
+            NOT_FOUND_MESSAGE.to_string(),
+        )),
+    }
+}
+
+// A goal is in the feed when it has a due date or reminders, the goals with reminders only have no
+// DUE. Each pending reminder is an alarm. The dates are stored in UTC and written in UTC, the
+// calendar app shows them in its own timezone, so the document needs no VTIMEZONE. DTSTAMP is the last update of the
+// goal, not the time of the request, so the document and its ETag only change with the data
+fn to_icalendar(goals: &[Goal], reminders: &[Reminder]) -> String {
+    let mut reminders_by_goal: HashMap<String, Vec<&Reminder>> = HashMap::new();
+    for reminder in reminders {
+        reminders_by_goal
+            .entry(reminder.get_goal_id())
+            .or_default()
+            .push(reminder);
+    }
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//goals-rust//Goals calendar feed//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "X-WR-CALNAME:Goals".to_string(),
+    ];
+
+    for goal in goals {
+        let goal_reminders = reminders_by_goal.remove(&goal.get_id());
+        if goal.get_due_at().is_some() || goal_reminders.is_some() {
+            lines.extend(to_vtodo(goal, &goal_reminders.unwrap_or_default()));
+        }
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    lines
+        .iter()
+        .map(|line| fold_line(line))
+        .collect::<Vec<String>>()
+        .join("")
+}
+
+// The reminders are sorted soonest first
+fn to_vtodo(goal: &Goal, reminders: &[&Reminder]) -> Vec<String> {
+    let pending: Vec<&Reminder> = reminders
+        .iter()
+        .copied()
+        .filter(|reminder| reminder.get_status() == PENDING_STATUS)
+        .collect();
+    let text = escape_text(&goal.get_text());
+    let updated_at = goal.get_updated_at().unwrap_or_default();
+
+    let mut lines = vec![
+        "BEGIN:VTODO".to_string(),
+        format!("UID:{}@goals-rust", goal.get_id()),
+        format!("DTSTAMP:{}", format_date(updated_at)),
+        format!("LAST-MODIFIED:{}", format_date(updated_at)),
+        format!("SUMMARY:{}", text),
+    ];
+
+    if let Some(due) = goal.get_due_at() {
+        lines.push(format!("DUE:{}", format_date(due)));
+    }
+
+    match goal.get_completed_at() {
+        Some(completed_at) => {
+            lines.push("STATUS:COMPLETED".to_string());
+            lines.push(format!("COMPLETED:{}", format_date(completed_at)));
+        }
+        None => lines.push("STATUS:NEEDS-ACTION".to_string()),
+    }
+
+    for reminder in pending {
+        lines.push("BEGIN:VALARM".to_string());
+        lines.push(format!("UID:{}@goals-rust", reminder.get_id()));
+        lines.push("ACTION:DISPLAY".to_string());
+        lines.push(format!("DESCRIPTION:{}", text));
+        lines.push(format!(
+            "TRIGGER;VALUE=DATE-TIME:{}",
+            format_date(reminder.get_remind_at())
+        ));
+        lines.push("END:VALARM".to_string());
+    }
+
+    lines.push("END:VTODO".to_string());
+    lines
+}
+
+fn format_date(date: DateTime<Utc>) -> String {
+    date.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+// Backslashes, semicolons, commas and new lines have to be escaped in text values
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace(['\r', '\n'], "\\n")
+}
+
+// Lines longer than 75 octets are folded, the continuation lines start with a space. Lines are
+// only cut between characters, never inside a multi byte UTF-8 character
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut octets = 0;
+
+    for character in line.chars() {
+        if octets + character.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(character);
+        octets += character.len_utf8();
+    }
+
+    folded.push_str("\r\n");
+    folded
+}
@@ -0,0 +1,3 @@
+pub mod create_calendar_feed;
+pub mod revoke_calendar_feed;
+pub mod get_calendar_feed;
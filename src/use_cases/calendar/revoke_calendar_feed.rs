@@ -0,0 +1,52 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{calendar_feed_data_access, user_data_access::find_user_by_id},
+    db::establish_connection,
+};
+
+pub enum RevokeCalendarFeedError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    CalendarFeedNotFoundError(String),
+}
+
+pub async fn execute(user_id: String) -> Result<(), RevokeCalendarFeedError> {
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+
+    let deleted = calendar_feed_data_access::delete_calendar_feed(&client, &user_id)
+        .await
+        .map_err(|err| RevokeCalendarFeedError::DatabaseError(err.to_string()))?;
+
+    if deleted == 0 {
+        return Err(RevokeCalendarFeedError::CalendarFeedNotFoundError(
+            "The user has no calendar feed".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+async fn get_connected_client() -> Result<Client, RevokeCalendarFeedError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| RevokeCalendarFeedError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), RevokeCalendarFeedError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| RevokeCalendarFeedError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(RevokeCalendarFeedError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(RevokeCalendarFeedError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
@@ -0,0 +1,221 @@
+use serde_json::json;
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        comment_data_access::{add_comment, find_comment_by_id, set_comment_mentions},
+        goal_data_access::{find_goal_by_id, has_goal_access},
+        notification_data_access::add_notification,
+        user_data_access::{find_user_by_email, find_user_by_id},
+        webhook_data_access::enqueue_event,
+    },
+    db::establish_connection,
+    entities::{
+        comment::{Comment, CommentDto, CreateCommentDto},
+        goal::Goal,
+        notification::{Notification, COMMENT_KIND, MENTION_KIND},
+        user::User,
+        webhook::{WebhookEvent, COMMENT_CREATED_EVENT},
+    },
+    use_cases::comments::get_comments::map_to_dto,
+};
+
+pub enum CreateCommentError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    GoalNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// Every user with access to the goal can comment on it. Only the mentioned users that can see the
+// goal are kept as mentions. The mentioned users and the owner of the goal are notified, except the
+// author
+pub async fn execute(
+    goal_id: String,
+    new_comment: CreateCommentDto,
+    user_id: String,
+) -> Result<CommentDto, CreateCommentError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| CreateCommentError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    let author = find_user(&client, &user_id).await?;
+    check_goal_access(&client, &goal_id, &user_id).await?;
+
+    let comment = Comment::from_create_comment_dto(new_comment, &goal_id, &user_id)
+        .map_err(|err| CreateCommentError::InvalidRequestError(err.to_string()))?;
+
+    client
+        .batch_execute("BEGIN")
+        .await
+        .map_err(|err| CreateCommentError::DatabaseError(err.to_string()))?;
+
+    let comment_dto = match save_comment(&client, &comment, &author, &goal_id).await {
+        Err(err) => {
+            if let Err(rollback_err) = client.batch_execute("ROLLBACK").await {
+                eprintln!("Comment create rollback error: {}", rollback_err);
+            }
+            return Err(err);
+        }
+        Ok(comment_dto) => comment_dto,
+    };
+
+    client
+        .batch_execute("COMMIT")
+        .await
+        .map_err(|err| CreateCommentError::DatabaseError(err.to_string()))?;
+
+    Ok(comment_dto)
+}
+
+// The comment, its mentions, the notifications and the webhook event are written together, a failure
+// leaves none of them
+async fn save_comment(
+    client: &Client,
+    comment: &Comment,
+    author: &User,
+    goal_id: &str,
+) -> Result<CommentDto, CreateCommentError> {
+    let id = add_comment(client, comment)
+        .await
+        .map_err(|err| CreateCommentError::DatabaseError(err.to_string()))?;
+
+    let mentions = find_mentioned_users(client, &comment.get_text(), goal_id).await?;
+    set_comment_mentions(client, &id, &mentions)
+        .await
+        .map_err(|err| CreateCommentError::DatabaseError(err.to_string()))?;
+
+    let comment = find_comment_by_id(client, &id)
+        .await
+        .map_err(|err| CreateCommentError::DatabaseError(err.to_string()))?
+        .ok_or_else(|| CreateCommentError::DatabaseError("Comment not saved".to_string()))?;
+
+    let goal = find_goal_by_id(client, goal_id)
+        .await
+        .map_err(|err| CreateCommentError::DatabaseError(err.to_string()))?
+        .ok_or_else(|| {
+            CreateCommentError::GoalNotFoundError(format!("Goal not found for the id: {}", goal_id))
+        })?;
+    notify_users(client, &goal, author, &mentions).await?;
+
+    let comment_dto = map_to_dto(&comment);
+    let event = WebhookEvent::new(COMMENT_CREATED_EVENT, json!(comment_dto));
+    enqueue_event(client, &goal.get_user_id(), &event)
+        .await
+        .map_err(|err| CreateCommentError::DatabaseError(err.to_string()))?;
+
+    Ok(comment_dto)
+}
+
+async fn get_connected_client() -> Result<Client, CreateCommentError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| CreateCommentError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<User, CreateCommentError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| CreateCommentError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(CreateCommentError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(CreateCommentError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(user) => Ok(user),
+    }
+}
+
+async fn check_goal_access(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<(), CreateCommentError> {
+    let has_access = has_goal_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| CreateCommentError::DatabaseError(err.to_string()))?;
+
+    if !has_access {
+        return Err(CreateCommentError::GoalNotFoundError(format!(
+            "Goal not found for the id: {}",
+            goal_id
+        )));
+    }
+    Ok(())
+}
+
+async fn find_mentioned_users(
+    client: &Client,
+    text: &str,
+    goal_id: &str,
+) -> Result<Vec<String>, CreateCommentError> {
+    let mut user_ids = Vec::new();
+    for email in Comment::extract_mentions(text) {
+        let opt_user = find_user_by_email(client, &email)
+            .await
+            .map_err(|err| CreateCommentError::DatabaseError(err.to_string()))?;
+        let user = match opt_user {
+            Some(user) if !user.is_disabled() => user,
+            _ => continue,
+        };
+        let has_access = has_goal_access(client, goal_id, &user.get_id())
+            .await
+            .map_err(|err| CreateCommentError::DatabaseError(err.to_string()))?;
+        if has_access {
+            user_ids.push(user.get_id());
+        }
+    }
+    Ok(user_ids)
+}
+
+// A mentioned owner gets the mention only
+async fn notify_users(
+    client: &Client,
+    goal: &Goal,
+    author: &User,
+    mentions: &[String],
+) -> Result<(), CreateCommentError> {
+    let mut notifications = Vec::new();
+    for mentioned_id in mentions.iter().filter(|id| **id != author.get_id()) {
+        notifications.push((
+            mentioned_id.clone(),
+            MENTION_KIND,
+            format!(
+                "{} mentioned you on the goal \"{}\"",
+                author.get_name(),
+                goal.get_text()
+            ),
+        ));
+    }
+    let owner_id = goal.get_user_id();
+    if owner_id != author.get_id() && !mentions.contains(&owner_id) {
+        notifications.push((
+            owner_id,
+            COMMENT_KIND,
+            format!(
+                "{} commented on your goal \"{}\"",
+                author.get_name(),
+                goal.get_text()
+            ),
+        ));
+    }
+
+    for (recipient_id, kind, text) in notifications {
+        let notification = Notification::from_event(
+            &recipient_id,
+            kind,
+            Some(goal.get_id()),
+            Some(author.get_id()),
+            text,
+        )
+        .map_err(|err| CreateCommentError::DatabaseError(err.to_string()))?;
+        add_notification(client, &notification)
+            .await
+            .map_err(|err| CreateCommentError::DatabaseError(err.to_string()))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        comment_data_access::{self, find_comment_by_id},
+        goal_data_access::{find_goal_by_id, has_goal_access},
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{comment::Comment, goal::Goal},
+};
+
+pub enum DeleteCommentError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    CommentNotFoundError(String),
+    ForbiddenError(String),
+    InvalidRequestError(String),
+}
+
+// The author or the owner of the goal deletes a comment
+pub async fn execute(
+    goal_id: String,
+    comment_id: String,
+    user_id: String,
+) -> Result<(), DeleteCommentError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| DeleteCommentError::InvalidRequestError(err.to_string()))?;
+    Comment::validate_id(&comment_id)
+        .map_err(|err| DeleteCommentError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    let comment = find_comment(&client, &goal_id, &comment_id, &user_id).await?;
+
+    if comment.get_user_id() != user_id && !is_goal_owner(&client, &goal_id, &user_id).await? {
+        return Err(DeleteCommentError::ForbiddenError(
+            "Only the author or the owner of the goal can delete a comment".to_string(),
+        ));
+    }
+
+    comment_data_access::delete_comment(&client, &comment_id)
+        .await
+        .map_err(|err| DeleteCommentError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+async fn get_connected_client() -> Result<Client, DeleteCommentError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| DeleteCommentError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), DeleteCommentError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| DeleteCommentError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(DeleteCommentError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(DeleteCommentError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+// Comments of goals the user cannot see are reported as not found
+async fn find_comment(
+    client: &Client,
+    goal_id: &str,
+    comment_id: &str,
+    user_id: &str,
+) -> Result<Comment, DeleteCommentError> {
+    let has_access = has_goal_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| DeleteCommentError::DatabaseError(err.to_string()))?;
+
+    let opt_comment = match has_access {
+        false => None,
+        true => find_comment_by_id(client, comment_id)
+            .await
+            .map_err(|err| DeleteCommentError::DatabaseError(err.to_string()))?,
+    };
+
+    match opt_comment {
+        Some(comment) if comment.get_goal_id() == goal_id => Ok(comment),
+        _ => Err(DeleteCommentError::CommentNotFoundError(format!(
+            "Comment not found for the id: {}",
+            comment_id
+        ))),
+    }
+}
+
+async fn is_goal_owner(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<bool, DeleteCommentError> {
+    let opt_goal = find_goal_by_id(client, goal_id)
+        .await
+        .map_err(|err| DeleteCommentError::DatabaseError(err.to_string()))?;
+
+    Ok(matches!(opt_goal, Some(goal) if goal.get_user_id() == user_id))
+}
@@ -0,0 +1,101 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        comment_data_access::{find_comment_by_id, find_comment_edits},
+        goal_data_access::has_goal_access,
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{
+        comment::{Comment, CommentEditDto},
+        goal::Goal,
+    },
+};
+
+pub enum GetCommentHistoryError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    CommentNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// Previous texts of the comment, oldest first
+pub async fn execute(
+    goal_id: String,
+    comment_id: String,
+    user_id: String,
+) -> Result<Vec<CommentEditDto>, GetCommentHistoryError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| GetCommentHistoryError::InvalidRequestError(err.to_string()))?;
+    Comment::validate_id(&comment_id)
+        .map_err(|err| GetCommentHistoryError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    find_comment(&client, &goal_id, &comment_id, &user_id).await?;
+
+    let edits_db = find_comment_edits(&client, &comment_id)
+        .await
+        .map_err(|err| GetCommentHistoryError::DatabaseError(err.to_string()))?;
+
+    let mut edits_dto = Vec::new();
+    for edit in edits_db {
+        edits_dto.push(CommentEditDto {
+            text: edit.get_text(),
+            edited_at: edit.get_edited_at().map(|date| date.to_rfc3339()),
+        });
+    }
+
+    Ok(edits_dto)
+}
+
+async fn get_connected_client() -> Result<Client, GetCommentHistoryError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| GetCommentHistoryError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), GetCommentHistoryError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| GetCommentHistoryError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(GetCommentHistoryError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(GetCommentHistoryError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+// Comments of goals the user cannot see are reported as not found
+async fn find_comment(
+    client: &Client,
+    goal_id: &str,
+    comment_id: &str,
+    user_id: &str,
+) -> Result<Comment, GetCommentHistoryError> {
+    let has_access = has_goal_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| GetCommentHistoryError::DatabaseError(err.to_string()))?;
+
+    let opt_comment = match has_access {
+        false => None,
+        true => find_comment_by_id(client, comment_id)
+            .await
+            .map_err(|err| GetCommentHistoryError::DatabaseError(err.to_string()))?,
+    };
+
+    match opt_comment {
+        Some(comment) if comment.get_goal_id() == goal_id => Ok(comment),
+        _ => Err(GetCommentHistoryError::CommentNotFoundError(format!(
+            "Comment not found for the id: {}",
+            comment_id
+        ))),
+    }
+}
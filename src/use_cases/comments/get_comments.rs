@@ -0,0 +1,99 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        comment_data_access::find_comments, goal_data_access::has_goal_access,
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{
+        comment::{Comment, CommentDto, CommentsQueryDto},
+        goal::Goal,
+    },
+};
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 200;
+
+pub enum GetCommentsError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    GoalNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// Every user with access to the goal reads its comments, oldest first
+pub async fn execute(
+    goal_id: String,
+    query: CommentsQueryDto,
+    user_id: String,
+) -> Result<Vec<CommentDto>, GetCommentsError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| GetCommentsError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    check_goal_access(&client, &goal_id, &user_id).await?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let comments_db = find_comments(&client, &goal_id, limit, offset)
+        .await
+        .map_err(|err| GetCommentsError::DatabaseError(err.to_string()))?;
+
+    Ok(comments_db.iter().map(map_to_dto).collect())
+}
+
+async fn get_connected_client() -> Result<Client, GetCommentsError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| GetCommentsError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), GetCommentsError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| GetCommentsError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(GetCommentsError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(GetCommentsError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn check_goal_access(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<(), GetCommentsError> {
+    let has_access = has_goal_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| GetCommentsError::DatabaseError(err.to_string()))?;
+
+    if !has_access {
+        return Err(GetCommentsError::GoalNotFoundError(format!(
+            "Goal not found for the id: {}",
+            goal_id
+        )));
+    }
+    Ok(())
+}
+
+pub fn map_to_dto(comment: &Comment) -> CommentDto {
+    CommentDto {
+        id: comment.get_id(),
+        goal_id: comment.get_goal_id(),
+        user_id: comment.get_user_id(),
+        text: comment.get_text(),
+        mentions: comment.get_mentions(),
+        created_at: comment.get_created_at().map(|date| date.to_rfc3339()),
+        updated_at: comment.get_updated_at().map(|date| date.to_rfc3339()),
+    }
+}
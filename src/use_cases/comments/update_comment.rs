@@ -0,0 +1,164 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        comment_data_access::{find_comment_by_id, set_comment_mentions, update_comment_text},
+        goal_data_access::has_goal_access,
+        user_data_access::{find_user_by_email, find_user_by_id},
+    },
+    db::establish_connection,
+    entities::{
+        comment::{Comment, CommentDto, UpdateCommentDto},
+        goal::Goal,
+    },
+    use_cases::comments::get_comments::map_to_dto,
+};
+
+pub enum UpdateCommentError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    CommentNotFoundError(String),
+    ForbiddenError(String),
+    InvalidRequestError(String),
+}
+
+// Only the author edits a comment, while they still have access to the goal. The previous text is
+// kept in the history of the comment and the mentions are found again in the new text
+pub async fn execute(
+    goal_id: String,
+    comment_id: String,
+    update_comment: UpdateCommentDto,
+    user_id: String,
+) -> Result<CommentDto, UpdateCommentError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| UpdateCommentError::InvalidRequestError(err.to_string()))?;
+    Comment::validate_id(&comment_id)
+        .map_err(|err| UpdateCommentError::InvalidRequestError(err.to_string()))?;
+    Comment::validate_text(&update_comment.text)
+        .map_err(|err| UpdateCommentError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    let comment = find_comment(&client, &goal_id, &comment_id, &user_id).await?;
+
+    if comment.get_user_id() != user_id {
+        return Err(UpdateCommentError::ForbiddenError(
+            "Only the author can edit a comment".to_string(),
+        ));
+    }
+
+    if comment.get_text() != update_comment.text {
+        let mentions = find_mentioned_users(&client, &update_comment.text, &goal_id).await?;
+        update_text_and_mentions(&client, &comment_id, &update_comment.text, &mentions).await?;
+    }
+
+    let comment = find_comment(&client, &goal_id, &comment_id, &user_id).await?;
+
+    Ok(map_to_dto(&comment))
+}
+
+async fn get_connected_client() -> Result<Client, UpdateCommentError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| UpdateCommentError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+// The text, its edit history and the mentions change together or not at all
+async fn update_text_and_mentions(
+    client: &Client,
+    comment_id: &str,
+    text: &str,
+    mentions: &[String],
+) -> Result<(), UpdateCommentError> {
+    client
+        .batch_execute("BEGIN")
+        .await
+        .map_err(|err| UpdateCommentError::DatabaseError(err.to_string()))?;
+
+    let result = match update_comment_text(client, comment_id, text).await {
+        Err(err) => Err(err),
+        Ok(_) => set_comment_mentions(client, comment_id, mentions).await,
+    };
+
+    if let Err(err) = result {
+        if let Err(rollback_err) = client.batch_execute("ROLLBACK").await {
+            eprintln!("Comment update rollback error: {}", rollback_err);
+        }
+        return Err(UpdateCommentError::DatabaseError(err.to_string()));
+    }
+
+    client
+        .batch_execute("COMMIT")
+        .await
+        .map_err(|err| UpdateCommentError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), UpdateCommentError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| UpdateCommentError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(UpdateCommentError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(UpdateCommentError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+// Comments of goals the user cannot see are reported as not found
+async fn find_comment(
+    client: &Client,
+    goal_id: &str,
+    comment_id: &str,
+    user_id: &str,
+) -> Result<Comment, UpdateCommentError> {
+    let has_access = has_goal_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| UpdateCommentError::DatabaseError(err.to_string()))?;
+
+    let opt_comment = match has_access {
+        false => None,
+        true => find_comment_by_id(client, comment_id)
+            .await
+            .map_err(|err| UpdateCommentError::DatabaseError(err.to_string()))?,
+    };
+
+    match opt_comment {
+        Some(comment) if comment.get_goal_id() == goal_id => Ok(comment),
+        _ => Err(UpdateCommentError::CommentNotFoundError(format!(
+            "Comment not found for the id: {}",
+            comment_id
+        ))),
+    }
+}
+
+async fn find_mentioned_users(
+    client: &Client,
+    text: &str,
+    goal_id: &str,
+) -> Result<Vec<String>, UpdateCommentError> {
+    let mut user_ids = Vec::new();
+    for email in Comment::extract_mentions(text) {
+        let opt_user = find_user_by_email(client, &email)
+            .await
+            .map_err(|err| UpdateCommentError::DatabaseError(err.to_string()))?;
+        let user = match opt_user {
+            Some(user) if !user.is_disabled() => user,
+            _ => continue,
+        };
+        let has_access = has_goal_access(client, goal_id, &user.get_id())
+            .await
+            .map_err(|err| UpdateCommentError::DatabaseError(err.to_string()))?;
+        if has_access {
+            user_ids.push(user.get_id());
+        }
+    }
+    Ok(user_ids)
+}
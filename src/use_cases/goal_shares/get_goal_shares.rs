@@ -0,0 +1,93 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::find_goal_by_id, goal_share_data_access::find_goal_shares,
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{goal::Goal, goal_share::GoalShareDto},
+};
+
+pub enum GetGoalSharesError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    GoalNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// Users the goal is shared with, only visible to the owner of the goal
+pub async fn execute(
+    goal_id: String,
+    user_id: String,
+) -> Result<Vec<GoalShareDto>, GetGoalSharesError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| GetGoalSharesError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    find_owned_goal(&client, &goal_id, &user_id).await?;
+
+    let goal_shares_db = find_goal_shares(&client, &goal_id)
+        .await
+        .map_err(|err| GetGoalSharesError::DatabaseError(err.to_string()))?;
+
+    let mut goal_shares_dto = Vec::new();
+    for goal_share in goal_shares_db {
+        let opt_user = find_user_by_id(&client, &goal_share.get_user_id())
+            .await
+            .map_err(|err| GetGoalSharesError::DatabaseError(err.to_string()))?;
+        if let Some(user) = opt_user {
+            goal_shares_dto.push(GoalShareDto {
+                goal_id: goal_share.get_goal_id(),
+                user_id: goal_share.get_user_id(),
+                name: user.get_name(),
+                email: user.get_email(),
+                permission: goal_share.get_permission(),
+            });
+        }
+    }
+
+    Ok(goal_shares_dto)
+}
+
+async fn get_connected_client() -> Result<Client, GetGoalSharesError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| GetGoalSharesError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), GetGoalSharesError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| GetGoalSharesError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(GetGoalSharesError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(GetGoalSharesError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn find_owned_goal(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<Goal, GetGoalSharesError> {
+    let opt_goal = find_goal_by_id(client, goal_id)
+        .await
+        .map_err(|err| GetGoalSharesError::DatabaseError(err.to_string()))?;
+
+    match opt_goal {
+        Some(goal) if goal.get_user_id() == user_id => Ok(goal),
+        _ => Err(GetGoalSharesError::GoalNotFoundError(format!(
+            "Goal not found for the id: {}",
+            goal_id
+        ))),
+    }
+}
@@ -0,0 +1,3 @@
+pub mod share_goal;
+pub mod get_goal_shares;
+pub mod revoke_goal_share;
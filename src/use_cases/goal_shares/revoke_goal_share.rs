@@ -0,0 +1,92 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::find_goal_by_id, goal_share_data_access::delete_goal_share,
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{goal::Goal, user::User},
+};
+
+pub enum RevokeGoalShareError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    GoalNotFoundError(String),
+    ShareNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// The owner revokes the access of any user, a user the goal is shared with can remove their own
+// access
+pub async fn execute(
+    goal_id: String,
+    shared_user_id: String,
+    user_id: String,
+) -> Result<(), RevokeGoalShareError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| RevokeGoalShareError::InvalidRequestError(err.to_string()))?;
+    User::validate_id(&shared_user_id)
+        .map_err(|err| RevokeGoalShareError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+
+    if shared_user_id != user_id {
+        find_owned_goal(&client, &goal_id, &user_id).await?;
+    }
+
+    let deleted = delete_goal_share(&client, &goal_id, &shared_user_id)
+        .await
+        .map_err(|err| RevokeGoalShareError::DatabaseError(err.to_string()))?;
+
+    if deleted == 0 {
+        return Err(RevokeGoalShareError::ShareNotFoundError(format!(
+            "The goal is not shared with the user id: {}",
+            shared_user_id
+        )));
+    }
+
+    Ok(())
+}
+
+async fn get_connected_client() -> Result<Client, RevokeGoalShareError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| RevokeGoalShareError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), RevokeGoalShareError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| RevokeGoalShareError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(RevokeGoalShareError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(RevokeGoalShareError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn find_owned_goal(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<Goal, RevokeGoalShareError> {
+    let opt_goal = find_goal_by_id(client, goal_id)
+        .await
+        .map_err(|err| RevokeGoalShareError::DatabaseError(err.to_string()))?;
+
+    match opt_goal {
+        Some(goal) if goal.get_user_id() == user_id => Ok(goal),
+        _ => Err(RevokeGoalShareError::GoalNotFoundError(format!(
+            "Goal not found for the id: {}",
+            goal_id
+        ))),
+    }
+}
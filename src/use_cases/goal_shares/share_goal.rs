@@ -0,0 +1,178 @@
+use serde_json::json;
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::find_goal_by_id,
+        goal_share_data_access::add_goal_share,
+        notification_data_access::add_notification,
+        user_data_access::{find_user_by_email, find_user_by_id},
+        webhook_data_access::enqueue_event,
+    },
+    db::establish_connection,
+    entities::{
+        goal::Goal,
+        goal_share::{CreateGoalShareDto, GoalShare, GoalShareDto},
+        notification::{Notification, GOAL_SHARED_KIND},
+        user::User,
+        webhook::{WebhookEvent, GOAL_SHARED_EVENT},
+    },
+};
+
+pub enum ShareGoalError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    GoalNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// Only the owner of the goal can share it. Sharing it again with the same user changes the
+// permission. The shared user is notified
+pub async fn execute(
+    goal_id: String,
+    new_share: CreateGoalShareDto,
+    user_id: String,
+) -> Result<GoalShareDto, ShareGoalError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| ShareGoalError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    let owner = find_user(&client, &user_id).await?;
+    let goal = find_owned_goal(&client, &goal_id, &user_id).await?;
+
+    let shared_user = find_shared_user(&client, &new_share.email).await?;
+    if shared_user.get_id() == user_id {
+        return Err(ShareGoalError::InvalidRequestError(
+            "A goal cannot be shared with its owner".to_string(),
+        ));
+    }
+
+    let goal_share =
+        GoalShare::from_db_fields(&goal_id, &shared_user.get_id(), &new_share.permission)
+            .map_err(|err| ShareGoalError::InvalidRequestError(err.to_string()))?;
+
+    client
+        .batch_execute("BEGIN")
+        .await
+        .map_err(|err| ShareGoalError::DatabaseError(err.to_string()))?;
+
+    if let Err(err) = save_share(&client, &goal_share, &goal, &owner, &shared_user).await {
+        if let Err(rollback_err) = client.batch_execute("ROLLBACK").await {
+            eprintln!("Goal share rollback error: {}", rollback_err);
+        }
+        return Err(err);
+    }
+
+    client
+        .batch_execute("COMMIT")
+        .await
+        .map_err(|err| ShareGoalError::DatabaseError(err.to_string()))?;
+
+    Ok(GoalShareDto {
+        goal_id,
+        user_id: shared_user.get_id(),
+        name: shared_user.get_name(),
+        email: shared_user.get_email(),
+        permission: goal_share.get_permission(),
+    })
+}
+
+// The share, the notification and the webhook event are written together, a failure leaves none
+// of them
+async fn save_share(
+    client: &Client,
+    goal_share: &GoalShare,
+    goal: &Goal,
+    owner: &User,
+    shared_user: &User,
+) -> Result<(), ShareGoalError> {
+    add_goal_share(client, goal_share)
+        .await
+        .map_err(|err| ShareGoalError::DatabaseError(err.to_string()))?;
+
+    let notification = Notification::from_event(
+        &shared_user.get_id(),
+        GOAL_SHARED_KIND,
+        Some(goal.get_id()),
+        Some(owner.get_id()),
+        format!(
+            "{} shared the goal \"{}\" with you",
+            owner.get_name(),
+            goal.get_text()
+        ),
+    )
+    .map_err(|err| ShareGoalError::DatabaseError(err.to_string()))?;
+    add_notification(client, &notification)
+        .await
+        .map_err(|err| ShareGoalError::DatabaseError(err.to_string()))?;
+
+    let event = WebhookEvent::new(
+        GOAL_SHARED_EVENT,
+        json!({
+            "goal_id": goal.get_id(),
+            "user_id": shared_user.get_id(),
+            "email": shared_user.get_email(),
+            "permission": goal_share.get_permission(),
+        }),
+    );
+    enqueue_event(client, &owner.get_id(), &event)
+        .await
+        .map_err(|err| ShareGoalError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+async fn get_connected_client() -> Result<Client, ShareGoalError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| ShareGoalError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<User, ShareGoalError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| ShareGoalError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(ShareGoalError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(ShareGoalError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(user) => Ok(user),
+    }
+}
+
+async fn find_owned_goal(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<Goal, ShareGoalError> {
+    let opt_goal = find_goal_by_id(client, goal_id)
+        .await
+        .map_err(|err| ShareGoalError::DatabaseError(err.to_string()))?;
+
+    match opt_goal {
+        Some(goal) if goal.get_user_id() == user_id => Ok(goal),
+        _ => Err(ShareGoalError::GoalNotFoundError(format!(
+            "Goal not found for the id: {}",
+            goal_id
+        ))),
+    }
+}
+
+async fn find_shared_user(client: &Client, email: &str) -> Result<User, ShareGoalError> {
+    let opt_user = find_user_by_email(client, email)
+        .await
+        .map_err(|err| ShareGoalError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        Some(user) if !user.is_disabled() => Ok(user),
+        _ => Err(ShareGoalError::UserNotFoundError(format!(
+            "User not found for the e-mail: {}",
+            email
+        ))),
+    }
+}
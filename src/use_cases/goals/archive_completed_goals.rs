@@ -0,0 +1,111 @@
+use serde_json::json;
+use tokio_postgres::Client;
+
+use crate::{
+    config::get_env_parsed,
+    data_access::{
+        goal_data_access::archive_completed_goals_before, goal_event_data_access::add_goal_event,
+        goal_revision_data_access::add_goal_revision, webhook_data_access::enqueue_event,
+    },
+    db::establish_connection,
+    entities::{
+        goal_revision::{GoalRevision, ARCHIVE_ACTION},
+        webhook::{WebhookEvent, GOAL_ARCHIVED_EVENT},
+    },
+};
+
+pub enum ArchiveCompletedGoalsError {
+    DatabaseError(String),
+}
+
+// Archives the goals completed more than GOAL_AUTO_ARCHIVE_DAYS (default 30) ago and returns how
+// many were archived. The events have no archived_by and the revisions no actor, no user archived
+// them
+pub async fn execute() -> Result<usize, ArchiveCompletedGoalsError> {
+    let client = get_connected_client().await?;
+    let completed_seconds = get_env_parsed("GOAL_AUTO_ARCHIVE_DAYS", 30.0) * 86400.0;
+
+    client
+        .batch_execute("BEGIN")
+        .await
+        .map_err(|err| ArchiveCompletedGoalsError::DatabaseError(err.to_string()))?;
+
+    let archived = match save_archives(&client, completed_seconds).await {
+        Err(err) => {
+            if let Err(rollback_err) = client.batch_execute("ROLLBACK").await {
+                eprintln!("Goal auto-archive rollback error: {}", rollback_err);
+            }
+            return Err(err);
+        }
+        Ok(archived) => archived,
+    };
+
+    client
+        .batch_execute("COMMIT")
+        .await
+        .map_err(|err| ArchiveCompletedGoalsError::DatabaseError(err.to_string()))?;
+
+    Ok(archived)
+}
+
+// The goals, their revisions and their events are written together, a failure leaves the goals to
+// the next run
+async fn save_archives(
+    client: &Client,
+    completed_seconds: f64,
+) -> Result<usize, ArchiveCompletedGoalsError> {
+    let mut goals = archive_completed_goals_before(client, completed_seconds)
+        .await
+        .map_err(|err| ArchiveCompletedGoalsError::DatabaseError(err.to_string()))?;
+
+    for goal in goals.iter_mut() {
+        let after = GoalRevision::snapshot(goal);
+        goal.set_archived_at(None);
+        let before = GoalRevision::snapshot(goal);
+        add_goal_revision(
+            client,
+            &goal.get_id(),
+            None,
+            ARCHIVE_ACTION,
+            Some(&before),
+            &after,
+        )
+        .await
+        .map_err(|err| ArchiveCompletedGoalsError::DatabaseError(err.to_string()))?;
+
+        let data = json!({
+            "id": goal.get_id(),
+            "text": goal.get_text(),
+            "user_id": goal.get_user_id(),
+            "organization_id": goal.get_organization_id(),
+            "archived_by": null,
+        });
+        add_goal_event(
+            client,
+            &goal.get_id(),
+            &goal.get_user_id(),
+            goal.get_organization_id(),
+            GOAL_ARCHIVED_EVENT,
+            &data,
+        )
+        .await
+        .map_err(|err| ArchiveCompletedGoalsError::DatabaseError(err.to_string()))?;
+
+        enqueue_event(
+            client,
+            &goal.get_user_id(),
+            &WebhookEvent::new(GOAL_ARCHIVED_EVENT, data),
+        )
+        .await
+        .map_err(|err| ArchiveCompletedGoalsError::DatabaseError(err.to_string()))?;
+    }
+
+    Ok(goals.len())
+}
+
+async fn get_connected_client() -> Result<Client, ArchiveCompletedGoalsError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| ArchiveCompletedGoalsError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
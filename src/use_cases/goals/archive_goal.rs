@@ -0,0 +1,211 @@
+use serde_json::json;
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::{self, find_goal_by_id},
+        goal_event_data_access::add_goal_event,
+        goal_revision_data_access::add_goal_revision,
+        goal_share_data_access::find_goal_share,
+        organization_data_access::find_organization_member,
+        user_data_access::find_user_by_id,
+        webhook_data_access::enqueue_event,
+    },
+    db::establish_connection,
+    entities::{
+        goal::{Goal, GoalDto},
+        goal_revision::{GoalRevision, ARCHIVE_ACTION},
+        webhook::{WebhookEvent, GOAL_ARCHIVED_EVENT},
+    },
+};
+
+pub enum ArchiveGoalError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    GoalNotFoundError(String),
+    ForbiddenError(String),
+    InvalidRequestError(String),
+}
+
+// Archived goals are left out of the goal lists, they can still be read and changed
+pub async fn execute(goal_id: String, user_id: String) -> Result<GoalDto, ArchiveGoalError> {
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    Goal::validate_id(&goal_id)
+        .map_err(|err| ArchiveGoalError::InvalidRequestError(err.to_string()))?;
+    let goal = find_goal(&client, &goal_id).await?;
+    check_can_archive(&client, &goal, &user_id).await?;
+    let before = GoalRevision::snapshot(&goal);
+
+    client
+        .batch_execute("BEGIN")
+        .await
+        .map_err(|err| ArchiveGoalError::DatabaseError(err.to_string()))?;
+
+    let archived = match save_archive(&client, &goal, &before, &user_id).await {
+        Err(err) => {
+            if let Err(rollback_err) = client.batch_execute("ROLLBACK").await {
+                eprintln!("Goal archive rollback error: {}", rollback_err);
+            }
+            return Err(err);
+        }
+        Ok(archived) => archived,
+    };
+
+    client
+        .batch_execute("COMMIT")
+        .await
+        .map_err(|err| ArchiveGoalError::DatabaseError(err.to_string()))?;
+
+    Ok(GoalDto {
+        id: archived.get_id(),
+        text: archived.get_text(),
+        user_id: archived.get_user_id(),
+        organization_id: archived.get_organization_id(),
+        permission: None,
+        note_count: archived.get_note_count(),
+        due_at: archived.get_due_at().map(|date| date.to_rfc3339()),
+        completed_at: archived.get_completed_at().map(|date| date.to_rfc3339()),
+        archived_at: archived.get_archived_at().map(|date| date.to_rfc3339()),
+        deleted_at: None,
+    })
+}
+
+// The goal, its revision and its events are written together, a failure leaves none of them.
+// Returns the archived goal
+async fn save_archive(
+    client: &Client,
+    goal: &Goal,
+    before: &str,
+    user_id: &str,
+) -> Result<Goal, ArchiveGoalError> {
+    archive_goal(client, &goal.get_id()).await?;
+
+    let archived = find_goal(client, &goal.get_id()).await?;
+    add_goal_revision(
+        client,
+        &goal.get_id(),
+        Some(user_id),
+        ARCHIVE_ACTION,
+        Some(before),
+        &GoalRevision::snapshot(&archived),
+    )
+    .await
+    .map_err(|err| ArchiveGoalError::DatabaseError(err.to_string()))?;
+
+    let data = json!({
+        "id": goal.get_id(),
+        "text": goal.get_text(),
+        "user_id": goal.get_user_id(),
+        "organization_id": goal.get_organization_id(),
+        "archived_by": user_id,
+    });
+    add_goal_event(
+        client,
+        &goal.get_id(),
+        &goal.get_user_id(),
+        goal.get_organization_id(),
+        GOAL_ARCHIVED_EVENT,
+        &data,
+    )
+    .await
+    .map_err(|err| ArchiveGoalError::DatabaseError(err.to_string()))?;
+
+    enqueue_event(
+        client,
+        &goal.get_user_id(),
+        &WebhookEvent::new(GOAL_ARCHIVED_EVENT, data),
+    )
+    .await
+    .map_err(|err| ArchiveGoalError::DatabaseError(err.to_string()))?;
+
+    Ok(archived)
+}
+
+async fn get_connected_client() -> Result<Client, ArchiveGoalError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| ArchiveGoalError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), ArchiveGoalError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| ArchiveGoalError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(ArchiveGoalError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(ArchiveGoalError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn find_goal(client: &Client, goal_id: &str) -> Result<Goal, ArchiveGoalError> {
+    let opt_goal = find_goal_by_id(client, goal_id)
+        .await
+        .map_err(|err| ArchiveGoalError::DatabaseError(err.to_string()))?;
+
+    opt_goal.ok_or_else(|| {
+        ArchiveGoalError::GoalNotFoundError(format!("Goal not found for the id: {}", goal_id))
+    })
+}
+
+// Same rules as the deletion: personal goals by their owner and organization goals by the members
+// that can write them. Goals the user has no access to are reported as not found, so their ids are
+// not leaked
+async fn check_can_archive(
+    client: &Client,
+    goal: &Goal,
+    user_id: &str,
+) -> Result<(), ArchiveGoalError> {
+    match goal.get_organization_id() {
+        None if goal.get_user_id() == user_id => return Ok(()),
+        None => {}
+        Some(organization_id) => {
+            let opt_member = find_organization_member(client, &organization_id, user_id)
+                .await
+                .map_err(|err| ArchiveGoalError::DatabaseError(err.to_string()))?;
+            match opt_member {
+                Some(member) if member.can_write_goals() => return Ok(()),
+                Some(_) => {
+                    return Err(ArchiveGoalError::ForbiddenError(
+                        "Viewers cannot archive goals of the organization".to_string(),
+                    ))
+                }
+                None => {}
+            }
+        }
+    }
+
+    let opt_share = find_goal_share(client, &goal.get_id(), user_id)
+        .await
+        .map_err(|err| ArchiveGoalError::DatabaseError(err.to_string()))?;
+
+    match opt_share {
+        Some(_) => Err(ArchiveGoalError::ForbiddenError(
+            "Shared goals can only be archived by their owner".to_string(),
+        )),
+        None => Err(ArchiveGoalError::GoalNotFoundError(format!(
+            "Goal not found for the id: {}",
+            goal.get_id()
+        ))),
+    }
+}
+
+async fn archive_goal(client: &Client, goal_id: &str) -> Result<(), ArchiveGoalError> {
+    let changed = goal_data_access::archive_goal(client, goal_id)
+        .await
+        .map_err(|err| ArchiveGoalError::DatabaseError(err.to_string()))?;
+
+    match changed {
+        0 => Err(ArchiveGoalError::InvalidRequestError(
+            "The goal is already archived".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
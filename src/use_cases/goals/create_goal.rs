@@ -1,21 +1,30 @@
+use serde_json::json;
 use tokio_postgres::Client;
 
 use crate::{
-    data_access::{goal_data_access::add_goal, user_data_access::find_user_by_id},
+    data_access::{
+        goal_data_access::add_goal, goal_event_data_access::add_goal_event,
+        goal_revision_data_access::add_goal_revision,
+        organization_data_access::find_organization_member, user_data_access::find_user_by_id,
+        webhook_data_access::enqueue_event,
+    },
     db::establish_connection,
     entities::{
-        goal::{CreateGoalDto, Goal},
+        goal::{CreateGoalDto, Goal, GoalDto},
+        goal_revision::{GoalRevision, CREATE_ACTION},
         user::User,
+        webhook::{WebhookEvent, GOAL_CREATED_EVENT},
     },
 };
 
 pub enum CreateGoalError {
     InvalidRequestError(String),
     UserNotFoundError(String),
+    ForbiddenError(String),
     DatabaseError(String),
 }
 
-pub async fn execute(new_goal: CreateGoalDto, user_id: String) -> Result<(), CreateGoalError> {
+pub async fn execute(new_goal: CreateGoalDto, user_id: String) -> Result<GoalDto, CreateGoalError> {
     let client = get_connected_client().await?;
 
     find_user(&client, &user_id).await?;
@@ -23,15 +32,89 @@ pub async fn execute(new_goal: CreateGoalDto, user_id: String) -> Result<(), Cre
     let goal = Goal::from_create_goal_dto(new_goal, &user_id)
         .map_err(|err| CreateGoalError::InvalidRequestError(err.to_string()))?;
 
-    add_goal(&client, &goal)
+    if let Some(organization_id) = goal.get_organization_id() {
+        check_can_write_goals(&client, &organization_id, &user_id).await?;
+    }
+
+    client
+        .batch_execute("BEGIN")
+        .await
+        .map_err(|err| CreateGoalError::DatabaseError(err.to_string()))?;
+
+    let id = match save_create(&client, &goal, &user_id).await {
+        Err(err) => {
+            if let Err(rollback_err) = client.batch_execute("ROLLBACK").await {
+                eprintln!("Goal create rollback error: {}", rollback_err);
+            }
+            return Err(err);
+        }
+        Ok(id) => id,
+    };
+
+    client
+        .batch_execute("COMMIT")
+        .await
+        .map_err(|err| CreateGoalError::DatabaseError(err.to_string()))?;
+
+    Ok(GoalDto {
+        id,
+        text: goal.get_text(),
+        user_id: goal.get_user_id(),
+        organization_id: goal.get_organization_id(),
+        permission: None,
+        note_count: 0,
+        due_at: goal.get_due_at().map(|date| date.to_rfc3339()),
+        completed_at: None,
+        archived_at: None,
+        deleted_at: None,
+    })
+}
+
+// The goal, its revision and its events are written together, a failure leaves none of them
+async fn save_create(
+    client: &Client,
+    goal: &Goal,
+    user_id: &str,
+) -> Result<String, CreateGoalError> {
+    let id = add_goal(client, goal)
         .await
         .map_err(|err| CreateGoalError::DatabaseError(err.to_string()))?;
 
-    Ok(())
+    let snapshot = GoalRevision::snapshot(goal);
+    add_goal_revision(client, &id, Some(user_id), CREATE_ACTION, None, &snapshot)
+        .await
+        .map_err(|err| CreateGoalError::DatabaseError(err.to_string()))?;
+
+    let data = json!({
+        "id": id,
+        "text": goal.get_text(),
+        "user_id": goal.get_user_id(),
+        "organization_id": goal.get_organization_id(),
+        "due_at": goal.get_due_at().map(|date| date.to_rfc3339()),
+    });
+    add_goal_event(
+        client,
+        &id,
+        user_id,
+        goal.get_organization_id(),
+        GOAL_CREATED_EVENT,
+        &data,
+    )
+    .await
+    .map_err(|err| CreateGoalError::DatabaseError(err.to_string()))?;
+    enqueue_event(
+        client,
+        user_id,
+        &WebhookEvent::new(GOAL_CREATED_EVENT, data),
+    )
+    .await
+    .map_err(|err| CreateGoalError::DatabaseError(err.to_string()))?;
+
+    Ok(id)
 }
 
 async fn find_user(client: &Client, user_id: &str) -> Result<User, CreateGoalError> {
-    let opt_user = match find_user_by_id(&client, &user_id).await {
+    let opt_user = match find_user_by_id(client, user_id).await {
         Err(err) => return Err(CreateGoalError::DatabaseError(err.to_string())),
         Ok(opt_user) => opt_user,
     };
@@ -40,10 +123,33 @@ async fn find_user(client: &Client, user_id: &str) -> Result<User, CreateGoalErr
         None => Err(CreateGoalError::UserNotFoundError(
             "User not found with the id from token".to_string(),
         )),
+        Some(user) if user.is_disabled() => Err(CreateGoalError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
         Some(user) => Ok(user),
     }
 }
 
+async fn check_can_write_goals(
+    client: &Client,
+    organization_id: &str,
+    user_id: &str,
+) -> Result<(), CreateGoalError> {
+    let opt_member = find_organization_member(client, organization_id, user_id)
+        .await
+        .map_err(|err| CreateGoalError::DatabaseError(err.to_string()))?;
+
+    match opt_member {
+        Some(member) if member.can_write_goals() => Ok(()),
+        Some(_) => Err(CreateGoalError::ForbiddenError(
+            "Viewers cannot create goals in the organization".to_string(),
+        )),
+        None => Err(CreateGoalError::ForbiddenError(
+            "The user is not a member of the organization".to_string(),
+        )),
+    }
+}
+
 async fn get_connected_client() -> Result<tokio_postgres::Client, CreateGoalError> {
     let client = establish_connection().await.map_err(|err| {
         eprintln!("Client connection error: {}", err);
@@ -1,23 +1,109 @@
+use chrono::Utc;
+use serde_json::json;
 use tokio_postgres::Client;
 
 use crate::{
-    data_access::{goal_data_access, user_data_access::find_user_by_id},
+    data_access::{
+        goal_data_access::{self, find_goal_by_id, has_goal_access, has_goal_delete_access},
+        goal_event_data_access::add_goal_event,
+        goal_revision_data_access::add_goal_revision,
+        user_data_access::find_user_by_id,
+        webhook_data_access::enqueue_event,
+    },
     db::establish_connection,
-    entities::goal::Goal,
+    entities::{
+        goal::Goal,
+        goal_revision::{GoalRevision, DELETE_ACTION},
+        webhook::{WebhookEvent, GOAL_DELETED_EVENT},
+    },
 };
 
 pub enum DeleteGoalError {
     DatabaseError(String),
     UserNotFoundError(String),
+    GoalNotFoundError(String),
+    ForbiddenError(String),
     InvalidRequestError(String),
 }
 
+// The goal is moved to the trash, it can be restored until it is purged
 pub async fn execute(goal_id: String, user_id: String) -> Result<(), DeleteGoalError> {
     let client = get_connected_client().await?;
     find_user(&client, &user_id).await?;
     Goal::validate_id(&goal_id)
         .map_err(|err| DeleteGoalError::InvalidRequestError(err.to_string()))?;
-    delete_goal(&client, &goal_id).await?;
+    let mut goal = find_goal(&client, &goal_id).await?;
+    check_goal_delete_access(&client, &goal_id, &user_id).await?;
+    let before = GoalRevision::snapshot(&goal);
+
+    client
+        .batch_execute("BEGIN")
+        .await
+        .map_err(|err| DeleteGoalError::DatabaseError(err.to_string()))?;
+
+    if let Err(err) = save_delete(&client, &mut goal, &before, &user_id).await {
+        if let Err(rollback_err) = client.batch_execute("ROLLBACK").await {
+            eprintln!("Goal delete rollback error: {}", rollback_err);
+        }
+        return Err(err);
+    }
+
+    client
+        .batch_execute("COMMIT")
+        .await
+        .map_err(|err| DeleteGoalError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+// The goal, its revision and its events are written together, a failure leaves none of them
+async fn save_delete(
+    client: &Client,
+    goal: &mut Goal,
+    before: &str,
+    user_id: &str,
+) -> Result<(), DeleteGoalError> {
+    trash_goal(client, &goal.get_id()).await?;
+
+    goal.set_deleted_at(Some(Utc::now()));
+    let after = GoalRevision::snapshot(goal);
+    add_goal_revision(
+        client,
+        &goal.get_id(),
+        Some(user_id),
+        DELETE_ACTION,
+        Some(before),
+        &after,
+    )
+    .await
+    .map_err(|err| DeleteGoalError::DatabaseError(err.to_string()))?;
+
+    let data = json!({
+        "id": goal.get_id(),
+        "text": goal.get_text(),
+        "user_id": goal.get_user_id(),
+        "organization_id": goal.get_organization_id(),
+        "deleted_by": user_id,
+    });
+    add_goal_event(
+        client,
+        &goal.get_id(),
+        &goal.get_user_id(),
+        goal.get_organization_id(),
+        GOAL_DELETED_EVENT,
+        &data,
+    )
+    .await
+    .map_err(|err| DeleteGoalError::DatabaseError(err.to_string()))?;
+
+    enqueue_event(
+        client,
+        &goal.get_user_id(),
+        &WebhookEvent::new(GOAL_DELETED_EVENT, data),
+    )
+    .await
+    .map_err(|err| DeleteGoalError::DatabaseError(err.to_string()))?;
+
     Ok(())
 }
 
@@ -29,23 +115,72 @@ async fn get_connected_client() -> Result<Client, DeleteGoalError> {
 }
 
 async fn find_user(client: &Client, user_id: &str) -> Result<(), DeleteGoalError> {
-    let opt_user = find_user_by_id(&client, &user_id)
+    let opt_user = find_user_by_id(client, user_id)
         .await
         .map_err(|err| DeleteGoalError::DatabaseError(err.to_string()))?;
 
     match opt_user {
-        None => {
-            return Err(DeleteGoalError::UserNotFoundError(
-                "User not found with the id present in the authorization headers".to_string(),
-            ))
-        }
+        None => Err(DeleteGoalError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(DeleteGoalError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
         Some(_) => Ok(()),
     }
 }
 
-async fn delete_goal(client: &Client, goal_id: &str) -> Result<(), DeleteGoalError> {
-    goal_data_access::delete_goal(&client, &goal_id)
+async fn find_goal(client: &Client, goal_id: &str) -> Result<Goal, DeleteGoalError> {
+    let opt_goal = find_goal_by_id(client, goal_id)
         .await
         .map_err(|err| DeleteGoalError::DatabaseError(err.to_string()))?;
-    Ok(())
+
+    opt_goal.ok_or_else(|| {
+        DeleteGoalError::GoalNotFoundError(format!("Goal not found for the id: {}", goal_id))
+    })
+}
+
+// Personal goals are deleted by their owner and organization goals by the members that can write
+// them. Goals the user has no access to are reported as not found, so their ids are not leaked
+async fn check_goal_delete_access(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<(), DeleteGoalError> {
+    let can_delete = has_goal_delete_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| DeleteGoalError::DatabaseError(err.to_string()))?;
+    if can_delete {
+        return Ok(());
+    }
+
+    let has_access = has_goal_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| DeleteGoalError::DatabaseError(err.to_string()))?;
+
+    match has_access {
+        true => Err(DeleteGoalError::ForbiddenError(
+            "Only the owner or the members of the organization that can write the goal can delete it"
+                .to_string(),
+        )),
+        false => Err(DeleteGoalError::GoalNotFoundError(format!(
+            "Goal not found for the id: {}",
+            goal_id
+        ))),
+    }
+}
+
+// Deleted at the same time by another request
+async fn trash_goal(client: &Client, goal_id: &str) -> Result<(), DeleteGoalError> {
+    let trashed = goal_data_access::trash_goal(client, goal_id)
+        .await
+        .map_err(|err| DeleteGoalError::DatabaseError(err.to_string()))?;
+
+    match trashed {
+        0 => Err(DeleteGoalError::GoalNotFoundError(format!(
+            "Goal not found for the id: {}",
+            goal_id
+        ))),
+        _ => Ok(()),
+    }
 }
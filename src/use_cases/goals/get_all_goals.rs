@@ -1,20 +1,53 @@
 use tokio_postgres::Client;
 
 use crate::{
-    data_access::{goal_data_access::find_all_goals, user_data_access::find_user_by_id},
+    data_access::{
+        goal_data_access::{find_all_goals, find_organization_goals},
+        goal_share_data_access::find_shared_goals,
+        organization_data_access::find_organization_member,
+        user_data_access::find_user_by_id,
+    },
     db::establish_connection,
-    entities::goal::{Goal, GoalDto},
+    entities::{
+        goal::{Goal, GoalDto, GoalsQueryDto},
+        goal_share::GoalShare,
+        organization::Organization,
+    },
 };
 
 pub enum GetAllGoalsError {
     DatabaseError(String),
     UserNotFoundError(String),
+    InvalidRequestError(String),
+    ForbiddenError(String),
 }
 
-pub async fn execute(user_id: String) -> Result<Vec<GoalDto>, GetAllGoalsError> {
+// Personal goals of the user, the goals of an organization when organization_id is given, or the
+// goals shared with the user when shared is true. Only the archived ones when archived is true
+pub async fn execute(
+    query: GoalsQueryDto,
+    user_id: String,
+) -> Result<Vec<GoalDto>, GetAllGoalsError> {
     let client = get_connected_client().await?;
     find_user(&client, &user_id).await?;
-    let goals_db = find_goals(&client, &user_id).await?;
+    let archived = query.archived.unwrap_or(false);
+    if query.shared.unwrap_or(false) {
+        let shared_goals_db = find_shared_goals(&client, &user_id, archived)
+            .await
+            .map_err(|err| GetAllGoalsError::DatabaseError(err.to_string()))?;
+        return Ok(map_shared_to_dtos(shared_goals_db));
+    }
+    let goals_db = match query.organization_id {
+        None => find_goals(&client, &user_id, archived).await?,
+        Some(organization_id) => {
+            Organization::validate_id(&organization_id)
+                .map_err(|err| GetAllGoalsError::InvalidRequestError(err.to_string()))?;
+            check_is_member(&client, &organization_id, &user_id).await?;
+            find_organization_goals(&client, &organization_id, archived)
+                .await
+                .map_err(|err| GetAllGoalsError::DatabaseError(err.to_string()))?
+        }
+    };
     let goals = map_to_dtos(goals_db);
     Ok(goals)
 }
@@ -27,34 +60,84 @@ async fn get_connected_client() -> Result<Client, GetAllGoalsError> {
 }
 
 async fn find_user(client: &Client, user_id: &str) -> Result<(), GetAllGoalsError> {
-    let opt_user = find_user_by_id(&client, &user_id)
+    let opt_user = find_user_by_id(client, user_id)
         .await
         .map_err(|err| GetAllGoalsError::DatabaseError(err.to_string()))?;
 
     match opt_user {
-        None => {
-            return Err(GetAllGoalsError::UserNotFoundError(
-                "User not found with the id present in the authorization headers".to_string(),
-            ))
-        }
+        None => Err(GetAllGoalsError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(GetAllGoalsError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
         Some(_) => Ok(()),
     }
 }
 
-async fn find_goals(client: &Client, user_id: &str) -> Result<Vec<Goal>, GetAllGoalsError> {
-    let goals = find_all_goals(&client, &user_id)
+// Every role, viewers included, can read the goals of the organization
+async fn check_is_member(
+    client: &Client,
+    organization_id: &str,
+    user_id: &str,
+) -> Result<(), GetAllGoalsError> {
+    let opt_member = find_organization_member(client, organization_id, user_id)
+        .await
+        .map_err(|err| GetAllGoalsError::DatabaseError(err.to_string()))?;
+
+    match opt_member {
+        Some(_) => Ok(()),
+        None => Err(GetAllGoalsError::ForbiddenError(
+            "The user is not a member of the organization".to_string(),
+        )),
+    }
+}
+
+async fn find_goals(
+    client: &Client,
+    user_id: &str,
+    archived: bool,
+) -> Result<Vec<Goal>, GetAllGoalsError> {
+    let goals = find_all_goals(client, user_id, archived)
         .await
         .map_err(|err| GetAllGoalsError::DatabaseError(err.to_string()))?;
     Ok(goals)
 }
 
-fn map_to_dtos(goals_db: Vec<Goal>) -> Vec<GoalDto> {
+pub fn map_to_dtos(goals_db: Vec<Goal>) -> Vec<GoalDto> {
     let mut goals_dto = Vec::new();
     for goal_db in goals_db {
         let goal_dto = GoalDto {
             id: goal_db.get_id(),
             text: goal_db.get_text(),
             user_id: goal_db.get_user_id(),
+            organization_id: goal_db.get_organization_id(),
+            permission: None,
+            note_count: goal_db.get_note_count(),
+            due_at: goal_db.get_due_at().map(|date| date.to_rfc3339()),
+            completed_at: goal_db.get_completed_at().map(|date| date.to_rfc3339()),
+            archived_at: goal_db.get_archived_at().map(|date| date.to_rfc3339()),
+            deleted_at: goal_db.get_deleted_at().map(|date| date.to_rfc3339()),
+        };
+        goals_dto.push(goal_dto);
+    }
+    goals_dto
+}
+
+fn map_shared_to_dtos(shared_goals_db: Vec<(Goal, GoalShare)>) -> Vec<GoalDto> {
+    let mut goals_dto = Vec::new();
+    for (goal_db, goal_share_db) in shared_goals_db {
+        let goal_dto = GoalDto {
+            id: goal_db.get_id(),
+            text: goal_db.get_text(),
+            user_id: goal_db.get_user_id(),
+            organization_id: goal_db.get_organization_id(),
+            permission: Some(goal_share_db.get_permission()),
+            note_count: goal_db.get_note_count(),
+            due_at: goal_db.get_due_at().map(|date| date.to_rfc3339()),
+            completed_at: goal_db.get_completed_at().map(|date| date.to_rfc3339()),
+            archived_at: goal_db.get_archived_at().map(|date| date.to_rfc3339()),
+            deleted_at: goal_db.get_deleted_at().map(|date| date.to_rfc3339()),
         };
         goals_dto.push(goal_dto);
     }
@@ -0,0 +1,178 @@
+use serde_json::Value;
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_event_data_access::{
+            find_goal_events, find_stable_goal_event_id, has_goal_events_after,
+        },
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::goal_event::{GoalEvent, GoalEventDto, GoalEventsDto},
+};
+
+const MAX_EVENTS: i64 = 500;
+
+pub enum GetGoalEventsError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+}
+
+// Events of the goals the user can see after the event id, oldest first, up to the stable id: the
+// events of transactions still running, and the committed ones after them, wait for the next call.
+// Without event id there are no events, only the stable id to resume from. Events are kept
+// GOAL_EVENT_RETENTION_HOURS, older ones are lost
+pub async fn execute(
+    user_id: String,
+    after_event_id: Option<i64>,
+) -> Result<GoalEventsDto, GetGoalEventsError> {
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+
+    let stable_event_id = find_stable_goal_event_id(&client)
+        .await
+        .map_err(|err| GetGoalEventsError::DatabaseError(err.to_string()))?;
+
+    let events_db = match after_event_id {
+        None => Vec::new(),
+        Some(after_event_id) => find_goal_events(
+            &client,
+            &user_id,
+            after_event_id,
+            stable_event_id,
+            MAX_EVENTS,
+        )
+        .await
+        .map_err(|err| GetGoalEventsError::DatabaseError(err.to_string()))?,
+    };
+
+    let last_event_id = match after_event_id {
+        None => stable_event_id,
+        Some(after_event_id) => events_db
+            .last()
+            .map(|event| event.get_id())
+            .unwrap_or(after_event_id),
+    };
+    let waiting = match events_db.len() as i64 == MAX_EVENTS {
+        true => true,
+        false => has_goal_events_after(&client, &user_id, last_event_id)
+            .await
+            .map_err(|err| GetGoalEventsError::DatabaseError(err.to_string()))?,
+    };
+
+    Ok(GoalEventsDto {
+        events: events_db.iter().map(map_to_dto).collect(),
+        last_event_id,
+        waiting,
+    })
+}
+
+async fn get_connected_client() -> Result<Client, GetGoalEventsError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| GetGoalEventsError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), GetGoalEventsError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| GetGoalEventsError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(GetGoalEventsError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(GetGoalEventsError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+pub fn map_to_dto(goal_event: &GoalEvent) -> GoalEventDto {
+    let payload = goal_event.get_payload();
+    GoalEventDto {
+        id: goal_event.get_id(),
+        event_type: goal_event.get_event_type(),
+        goal_id: goal_event.get_goal_id(),
+        data: serde_json::from_str(&payload).unwrap_or(Value::String(payload)),
+        created_at: goal_event.get_created_at().map(|date| date.to_rfc3339()),
+    }
+}
+
+// These tests need the database of db.rs with the tables of sql/create-tables.sql, they are skipped
+// when it cannot be reached
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+
+    async fn add_test_user(client: &Client) -> String {
+        let row = client
+            .query_one(
+                "INSERT INTO users (name, email, password_hash, phone)
+                VALUES ('Events test', $1, 'hash', '555-555-5555') RETURNING id::text",
+                &[&format!("events-{}@example.com", Uuid::new_v4())],
+            )
+            .await
+            .unwrap();
+        row.get(0)
+    }
+
+    async fn add_test_event(client: &Client, user_id: &str, event_type: &str) {
+        client
+            .execute(
+                "INSERT INTO goal_events (user_id, goal_id, event_type, payload)
+                VALUES ($1::text::uuid, uuid_generate_v4(), $2, '{}')",
+                &[&user_id, &event_type],
+            )
+            .await
+            .unwrap();
+    }
+
+    async fn read_events(user_id: &str, after_event_id: Option<i64>) -> GoalEventsDto {
+        match execute(user_id.to_string(), after_event_id).await {
+            Ok(goal_events) => goal_events,
+            Err(GetGoalEventsError::DatabaseError(err_msg))
+            | Err(GetGoalEventsError::UserNotFoundError(err_msg)) => panic!("{}", err_msg),
+        }
+    }
+
+    #[tokio::test]
+    async fn sends_the_events_committed_out_of_order() {
+        let client = match establish_connection().await {
+            Err(err) => return eprintln!("Skipped, no database: {}", err),
+            Ok(client) => client,
+        };
+        let writer = establish_connection().await.unwrap();
+        let user_id = add_test_user(&client).await;
+        let start = read_events(&user_id, None).await;
+
+        writer.batch_execute("BEGIN").await.unwrap();
+        add_test_event(&writer, &user_id, "goal.created").await;
+        add_test_event(&client, &user_id, "goal.updated").await;
+
+        let held_back = read_events(&user_id, Some(start.last_event_id)).await;
+        assert!(held_back.events.is_empty());
+        assert!(held_back.waiting);
+
+        writer.batch_execute("COMMIT").await.unwrap();
+
+        let goal_events = read_events(&user_id, Some(held_back.last_event_id)).await;
+        let event_types: Vec<String> = goal_events
+            .events
+            .iter()
+            .map(|event| event.event_type.clone())
+            .collect();
+        assert_eq!(event_types, vec!["goal.created", "goal.updated"]);
+        assert!(!goal_events.waiting);
+
+        client
+            .execute("DELETE FROM users WHERE id = $1::text::uuid", &[&user_id])
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,107 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::{find_goal_by_id, has_goal_access},
+        goal_revision_data_access::find_goal_revisions,
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{
+        goal::Goal,
+        goal_revision::{GoalHistoryQueryDto, GoalRevision, GoalRevisionDto},
+    },
+};
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 200;
+
+pub enum GetGoalHistoryError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    GoalNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// Every user with access to the goal reads its history, newest first
+pub async fn execute(
+    goal_id: String,
+    query: GoalHistoryQueryDto,
+    user_id: String,
+) -> Result<Vec<GoalRevisionDto>, GetGoalHistoryError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| GetGoalHistoryError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    check_goal_access(&client, &goal_id, &user_id).await?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let revisions_db = find_goal_revisions(&client, &goal_id, limit, offset)
+        .await
+        .map_err(|err| GetGoalHistoryError::DatabaseError(err.to_string()))?;
+
+    Ok(revisions_db.iter().map(map_to_dto).collect())
+}
+
+async fn get_connected_client() -> Result<Client, GetGoalHistoryError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| GetGoalHistoryError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), GetGoalHistoryError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| GetGoalHistoryError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(GetGoalHistoryError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(GetGoalHistoryError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+// Goals the user cannot see are reported as not found, so their ids are not leaked
+async fn check_goal_access(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<(), GetGoalHistoryError> {
+    let opt_goal = find_goal_by_id(client, goal_id)
+        .await
+        .map_err(|err| GetGoalHistoryError::DatabaseError(err.to_string()))?;
+
+    let has_access = match opt_goal {
+        None => false,
+        Some(_) => has_goal_access(client, goal_id, user_id)
+            .await
+            .map_err(|err| GetGoalHistoryError::DatabaseError(err.to_string()))?,
+    };
+
+    match has_access {
+        true => Ok(()),
+        false => Err(GetGoalHistoryError::GoalNotFoundError(format!(
+            "Goal not found for the id: {}",
+            goal_id
+        ))),
+    }
+}
+
+pub fn map_to_dto(revision: &GoalRevision) -> GoalRevisionDto {
+    GoalRevisionDto {
+        id: revision.get_id(),
+        action: revision.get_action(),
+        actor_id: revision.get_actor_id(),
+        actor_name: revision.get_actor_name(),
+        changes: revision.get_changes(),
+        created_at: revision.get_created_at().map(|date| date.to_rfc3339()),
+    }
+}
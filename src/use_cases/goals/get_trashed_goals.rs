@@ -0,0 +1,88 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::{find_trashed_goals, find_trashed_organization_goals},
+        organization_data_access::find_organization_member,
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{
+        goal::{GoalDto, TrashedGoalsQueryDto},
+        organization::Organization,
+    },
+};
+
+use super::get_all_goals::map_to_dtos;
+
+pub enum GetTrashedGoalsError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    InvalidRequestError(String),
+    ForbiddenError(String),
+}
+
+// Personal goals of the user in the trash, or the ones of an organization when organization_id is
+// given, the last deleted first
+pub async fn execute(
+    query: TrashedGoalsQueryDto,
+    user_id: String,
+) -> Result<Vec<GoalDto>, GetTrashedGoalsError> {
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    let goals_db = match query.organization_id {
+        None => find_trashed_goals(&client, &user_id)
+            .await
+            .map_err(|err| GetTrashedGoalsError::DatabaseError(err.to_string()))?,
+        Some(organization_id) => {
+            Organization::validate_id(&organization_id)
+                .map_err(|err| GetTrashedGoalsError::InvalidRequestError(err.to_string()))?;
+            check_is_member(&client, &organization_id, &user_id).await?;
+            find_trashed_organization_goals(&client, &organization_id)
+                .await
+                .map_err(|err| GetTrashedGoalsError::DatabaseError(err.to_string()))?
+        }
+    };
+    Ok(map_to_dtos(goals_db))
+}
+
+async fn get_connected_client() -> Result<Client, GetTrashedGoalsError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| GetTrashedGoalsError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), GetTrashedGoalsError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| GetTrashedGoalsError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(GetTrashedGoalsError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(GetTrashedGoalsError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+// Every role, viewers included, can read the trash of the organization
+async fn check_is_member(
+    client: &Client,
+    organization_id: &str,
+    user_id: &str,
+) -> Result<(), GetTrashedGoalsError> {
+    let opt_member = find_organization_member(client, organization_id, user_id)
+        .await
+        .map_err(|err| GetTrashedGoalsError::DatabaseError(err.to_string()))?;
+
+    match opt_member {
+        Some(_) => Ok(()),
+        None => Err(GetTrashedGoalsError::ForbiddenError(
+            "The user is not a member of the organization".to_string(),
+        )),
+    }
+}
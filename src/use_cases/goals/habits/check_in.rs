@@ -0,0 +1,126 @@
+use chrono::{Duration, Utc};
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::{has_goal_access, has_goal_write_access},
+        habit_data_access::{add_check_in, find_check_in_dates, find_habit_by_goal_id},
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{
+        goal::Goal,
+        habit::{CheckInDto, Habit, HabitDto},
+    },
+    use_cases::goals::habits::get_habit::map_to_dto,
+};
+
+pub enum CheckInError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    GoalNotFoundError(String),
+    HabitNotFoundError(String),
+    ForbiddenError(String),
+    InvalidRequestError(String),
+}
+
+// Marks the habit as done for a day, today by default. Dates are in UTC, one day ahead is
+// accepted for the users whose today is already tomorrow in UTC
+pub async fn execute(
+    goal_id: String,
+    check_in: CheckInDto,
+    user_id: String,
+) -> Result<HabitDto, CheckInError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| CheckInError::InvalidRequestError(err.to_string()))?;
+
+    let today = Utc::now().date_naive();
+    let date = match check_in.date {
+        None => today,
+        Some(date) => Habit::parse_date(&date)
+            .map_err(|err| CheckInError::InvalidRequestError(err.to_string()))?,
+    };
+    if date > today + Duration::days(1) {
+        return Err(CheckInError::InvalidRequestError(
+            "Check-ins cannot be in the future".to_string(),
+        ));
+    }
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    check_goal_write_access(&client, &goal_id, &user_id).await?;
+
+    let habit = find_habit_by_goal_id(&client, &goal_id)
+        .await
+        .map_err(|err| CheckInError::DatabaseError(err.to_string()))?
+        .ok_or_else(|| {
+            CheckInError::HabitNotFoundError(format!("The goal is not a habit: {}", goal_id))
+        })?;
+
+    if date < habit.get_start_date() {
+        return Err(CheckInError::InvalidRequestError(
+            "Check-ins cannot be before the start date of the habit".to_string(),
+        ));
+    }
+
+    add_check_in(&client, &goal_id, &user_id, &date)
+        .await
+        .map_err(|err| CheckInError::DatabaseError(err.to_string()))?;
+
+    let check_ins = find_check_in_dates(&client, &goal_id)
+        .await
+        .map_err(|err| CheckInError::DatabaseError(err.to_string()))?;
+
+    Ok(map_to_dto(&habit, &check_ins, today))
+}
+
+async fn get_connected_client() -> Result<Client, CheckInError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| CheckInError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), CheckInError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| CheckInError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(CheckInError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(CheckInError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+// Goals the user cannot see are reported as not found, goals the user can only see as forbidden
+async fn check_goal_write_access(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<(), CheckInError> {
+    let can_write = has_goal_write_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| CheckInError::DatabaseError(err.to_string()))?;
+    if can_write {
+        return Ok(());
+    }
+
+    let has_access = has_goal_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| CheckInError::DatabaseError(err.to_string()))?;
+
+    match has_access {
+        true => Err(CheckInError::ForbiddenError(
+            "The user can only view this goal".to_string(),
+        )),
+        false => Err(CheckInError::GoalNotFoundError(format!(
+            "Goal not found for the id: {}",
+            goal_id
+        ))),
+    }
+}
@@ -0,0 +1,94 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::{has_goal_access, has_goal_write_access},
+        habit_data_access,
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::goal::Goal,
+};
+
+pub enum DeleteHabitError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    GoalNotFoundError(String),
+    HabitNotFoundError(String),
+    ForbiddenError(String),
+    InvalidRequestError(String),
+}
+
+// The goal stays, only its recurrence rule and check-ins are deleted
+pub async fn execute(goal_id: String, user_id: String) -> Result<(), DeleteHabitError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| DeleteHabitError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    check_goal_write_access(&client, &goal_id, &user_id).await?;
+
+    let count = habit_data_access::delete_habit(&client, &goal_id)
+        .await
+        .map_err(|err| DeleteHabitError::DatabaseError(err.to_string()))?;
+
+    if count == 0 {
+        return Err(DeleteHabitError::HabitNotFoundError(format!(
+            "The goal is not a habit: {}",
+            goal_id
+        )));
+    }
+
+    Ok(())
+}
+
+async fn get_connected_client() -> Result<Client, DeleteHabitError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| DeleteHabitError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), DeleteHabitError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| DeleteHabitError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(DeleteHabitError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(DeleteHabitError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+// Goals the user cannot see are reported as not found, goals the user can only see as forbidden
+async fn check_goal_write_access(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<(), DeleteHabitError> {
+    let can_write = has_goal_write_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| DeleteHabitError::DatabaseError(err.to_string()))?;
+    if can_write {
+        return Ok(());
+    }
+
+    let has_access = has_goal_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| DeleteHabitError::DatabaseError(err.to_string()))?;
+
+    match has_access {
+        true => Err(DeleteHabitError::ForbiddenError(
+            "The user can only view this goal".to_string(),
+        )),
+        false => Err(DeleteHabitError::GoalNotFoundError(format!(
+            "Goal not found for the id: {}",
+            goal_id
+        ))),
+    }
+}
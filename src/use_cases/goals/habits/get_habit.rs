@@ -0,0 +1,101 @@
+use chrono::{NaiveDate, Utc};
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::has_goal_access,
+        habit_data_access::{find_check_in_dates, find_habit_by_goal_id},
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{
+        goal::Goal,
+        habit::{Habit, HabitDto},
+    },
+};
+
+pub enum GetHabitError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    GoalNotFoundError(String),
+    HabitNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// Every user with access to the goal sees its recurrence rule and streaks
+pub async fn execute(goal_id: String, user_id: String) -> Result<HabitDto, GetHabitError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| GetHabitError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    check_goal_access(&client, &goal_id, &user_id).await?;
+
+    let habit = find_habit_by_goal_id(&client, &goal_id)
+        .await
+        .map_err(|err| GetHabitError::DatabaseError(err.to_string()))?
+        .ok_or_else(|| {
+            GetHabitError::HabitNotFoundError(format!("The goal is not a habit: {}", goal_id))
+        })?;
+
+    let check_ins = find_check_in_dates(&client, &goal_id)
+        .await
+        .map_err(|err| GetHabitError::DatabaseError(err.to_string()))?;
+
+    Ok(map_to_dto(&habit, &check_ins, Utc::now().date_naive()))
+}
+
+async fn get_connected_client() -> Result<Client, GetHabitError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| GetHabitError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), GetHabitError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| GetHabitError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(GetHabitError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(GetHabitError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn check_goal_access(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<(), GetHabitError> {
+    let has_access = has_goal_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| GetHabitError::DatabaseError(err.to_string()))?;
+
+    if !has_access {
+        return Err(GetHabitError::GoalNotFoundError(format!(
+            "Goal not found for the id: {}",
+            goal_id
+        )));
+    }
+    Ok(())
+}
+
+pub fn map_to_dto(habit: &Habit, check_ins: &[NaiveDate], today: NaiveDate) -> HabitDto {
+    let (current_streak, longest_streak) = habit.compute_streaks(check_ins, today);
+    HabitDto {
+        goal_id: habit.get_goal_id(),
+        recurrence: habit.get_recurrence(),
+        interval_days: habit.get_interval_days(),
+        times_per_week: habit.get_times_per_week(),
+        start_date: Habit::format_date(&habit.get_start_date()),
+        current_streak,
+        longest_streak,
+        last_check_in: check_ins.last().map(Habit::format_date),
+    }
+}
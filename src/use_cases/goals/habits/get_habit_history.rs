@@ -0,0 +1,121 @@
+use chrono::{Duration, Utc};
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::has_goal_access,
+        habit_data_access::{find_check_in_dates, find_habit_by_goal_id},
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{
+        goal::Goal,
+        habit::{Habit, HabitDayDto, HabitHistoryQueryDto},
+    },
+};
+
+// A year by default, a bit more than a year at most
+const DEFAULT_DAYS: i64 = 365;
+const MAX_DAYS: i64 = 371;
+
+pub enum GetHabitHistoryError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    GoalNotFoundError(String),
+    HabitNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// Per day completion of the habit between two dates, both included, to render a heatmap. Ends
+// today and starts a year before by default
+pub async fn execute(
+    goal_id: String,
+    query: HabitHistoryQueryDto,
+    user_id: String,
+) -> Result<Vec<HabitDayDto>, GetHabitHistoryError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| GetHabitHistoryError::InvalidRequestError(err.to_string()))?;
+
+    let to = match query.to {
+        None => Utc::now().date_naive(),
+        Some(to) => Habit::parse_date(&to)
+            .map_err(|err| GetHabitHistoryError::InvalidRequestError(err.to_string()))?,
+    };
+    let from = match query.from {
+        None => to - Duration::days(DEFAULT_DAYS - 1),
+        Some(from) => Habit::parse_date(&from)
+            .map_err(|err| GetHabitHistoryError::InvalidRequestError(err.to_string()))?,
+    };
+    if from > to {
+        return Err(GetHabitHistoryError::InvalidRequestError(
+            "from must not be after to".to_string(),
+        ));
+    }
+    if (to - from).num_days() >= MAX_DAYS {
+        return Err(GetHabitHistoryError::InvalidRequestError(format!(
+            "The history covers at most {} days",
+            MAX_DAYS
+        )));
+    }
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    check_goal_access(&client, &goal_id, &user_id).await?;
+
+    let habit = find_habit_by_goal_id(&client, &goal_id)
+        .await
+        .map_err(|err| GetHabitHistoryError::DatabaseError(err.to_string()))?
+        .ok_or_else(|| {
+            GetHabitHistoryError::HabitNotFoundError(format!(
+                "The goal is not a habit: {}",
+                goal_id
+            ))
+        })?;
+
+    let check_ins = find_check_in_dates(&client, &goal_id)
+        .await
+        .map_err(|err| GetHabitHistoryError::DatabaseError(err.to_string()))?;
+
+    Ok(habit.history(&check_ins, from, to))
+}
+
+async fn get_connected_client() -> Result<Client, GetHabitHistoryError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| GetHabitHistoryError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), GetHabitHistoryError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| GetHabitHistoryError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(GetHabitHistoryError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(GetHabitHistoryError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn check_goal_access(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<(), GetHabitHistoryError> {
+    let has_access = has_goal_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| GetHabitHistoryError::DatabaseError(err.to_string()))?;
+
+    if !has_access {
+        return Err(GetHabitHistoryError::GoalNotFoundError(format!(
+            "Goal not found for the id: {}",
+            goal_id
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,6 @@
+pub mod set_habit;
+pub mod get_habit;
+pub mod delete_habit;
+pub mod check_in;
+pub mod undo_check_in;
+pub mod get_habit_history;
@@ -0,0 +1,104 @@
+use chrono::Utc;
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::{has_goal_access, has_goal_write_access},
+        habit_data_access::{self, find_check_in_dates},
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{
+        goal::Goal,
+        habit::{Habit, HabitDto, SetHabitDto},
+    },
+    use_cases::goals::habits::get_habit::map_to_dto,
+};
+
+pub enum SetHabitError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    GoalNotFoundError(String),
+    ForbiddenError(String),
+    InvalidRequestError(String),
+}
+
+// Turns the goal into a habit or changes its recurrence rule. The check-ins are kept, the streaks
+// are computed again with the new rule
+pub async fn execute(
+    goal_id: String,
+    set_habit: SetHabitDto,
+    user_id: String,
+) -> Result<HabitDto, SetHabitError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| SetHabitError::InvalidRequestError(err.to_string()))?;
+
+    let today = Utc::now().date_naive();
+    let habit = Habit::from_set_habit_dto(set_habit, &goal_id, today)
+        .map_err(|err| SetHabitError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    check_goal_write_access(&client, &goal_id, &user_id).await?;
+
+    habit_data_access::set_habit(&client, &habit)
+        .await
+        .map_err(|err| SetHabitError::DatabaseError(err.to_string()))?;
+
+    let check_ins = find_check_in_dates(&client, &goal_id)
+        .await
+        .map_err(|err| SetHabitError::DatabaseError(err.to_string()))?;
+
+    Ok(map_to_dto(&habit, &check_ins, today))
+}
+
+async fn get_connected_client() -> Result<Client, SetHabitError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| SetHabitError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), SetHabitError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| SetHabitError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(SetHabitError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(SetHabitError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+// Goals the user cannot see are reported as not found, goals the user can only see as forbidden
+async fn check_goal_write_access(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<(), SetHabitError> {
+    let can_write = has_goal_write_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| SetHabitError::DatabaseError(err.to_string()))?;
+    if can_write {
+        return Ok(());
+    }
+
+    let has_access = has_goal_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| SetHabitError::DatabaseError(err.to_string()))?;
+
+    match has_access {
+        true => Err(SetHabitError::ForbiddenError(
+            "The user can only view this goal".to_string(),
+        )),
+        false => Err(SetHabitError::GoalNotFoundError(format!(
+            "Goal not found for the id: {}",
+            goal_id
+        ))),
+    }
+}
@@ -0,0 +1,117 @@
+use chrono::Utc;
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::{has_goal_access, has_goal_write_access},
+        habit_data_access::{delete_check_in, find_check_in_dates, find_habit_by_goal_id},
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{
+        goal::Goal,
+        habit::{Habit, HabitDto},
+    },
+    use_cases::goals::habits::get_habit::map_to_dto,
+};
+
+pub enum UndoCheckInError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    GoalNotFoundError(String),
+    HabitNotFoundError(String),
+    CheckInNotFoundError(String),
+    ForbiddenError(String),
+    InvalidRequestError(String),
+}
+
+// Removes the check-in of a day, the streaks are computed again
+pub async fn execute(
+    goal_id: String,
+    date: String,
+    user_id: String,
+) -> Result<HabitDto, UndoCheckInError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| UndoCheckInError::InvalidRequestError(err.to_string()))?;
+    let date = Habit::parse_date(&date)
+        .map_err(|err| UndoCheckInError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    check_goal_write_access(&client, &goal_id, &user_id).await?;
+
+    let habit = find_habit_by_goal_id(&client, &goal_id)
+        .await
+        .map_err(|err| UndoCheckInError::DatabaseError(err.to_string()))?
+        .ok_or_else(|| {
+            UndoCheckInError::HabitNotFoundError(format!("The goal is not a habit: {}", goal_id))
+        })?;
+
+    let count = delete_check_in(&client, &goal_id, &date)
+        .await
+        .map_err(|err| UndoCheckInError::DatabaseError(err.to_string()))?;
+
+    if count == 0 {
+        return Err(UndoCheckInError::CheckInNotFoundError(format!(
+            "Check-in not found for the date: {}",
+            Habit::format_date(&date)
+        )));
+    }
+
+    let check_ins = find_check_in_dates(&client, &goal_id)
+        .await
+        .map_err(|err| UndoCheckInError::DatabaseError(err.to_string()))?;
+
+    Ok(map_to_dto(&habit, &check_ins, Utc::now().date_naive()))
+}
+
+async fn get_connected_client() -> Result<Client, UndoCheckInError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| UndoCheckInError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), UndoCheckInError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| UndoCheckInError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(UndoCheckInError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(UndoCheckInError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+// Goals the user cannot see are reported as not found, goals the user can only see as forbidden
+async fn check_goal_write_access(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<(), UndoCheckInError> {
+    let can_write = has_goal_write_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| UndoCheckInError::DatabaseError(err.to_string()))?;
+    if can_write {
+        return Ok(());
+    }
+
+    let has_access = has_goal_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| UndoCheckInError::DatabaseError(err.to_string()))?;
+
+    match has_access {
+        true => Err(UndoCheckInError::ForbiddenError(
+            "The user can only view this goal".to_string(),
+        )),
+        false => Err(UndoCheckInError::GoalNotFoundError(format!(
+            "Goal not found for the id: {}",
+            goal_id
+        ))),
+    }
+}
@@ -0,0 +1,58 @@
+use crate::{
+    entities::goal_import::{ToolImportQueryDto, ToolImportReportDto},
+    services::importer_services::get_importer,
+    use_cases::goals::import_goals::{import_rows, ImportGoalsError},
+};
+
+pub enum ImportFromToolError {
+    InvalidRequestError(String),
+    UserNotFoundError(String),
+    ForbiddenError(String),
+    DatabaseError(String),
+}
+
+// Reads the export file of Trello, Todoist or Asana and imports its tasks like the goals of
+// POST /api/goals/import, with a summary of what was mapped and what was skipped
+pub async fn execute(
+    source: String,
+    body: String,
+    query: ToolImportQueryDto,
+    user_id: String,
+) -> Result<ToolImportReportDto, ImportFromToolError> {
+    let source = source.to_lowercase();
+    let importer = get_importer(&source).ok_or_else(|| {
+        ImportFromToolError::InvalidRequestError(format!(
+            "Unknown source: {}, use trello, todoist or asana",
+            source
+        ))
+    })?;
+
+    let tool_import = importer
+        .parse(&body)
+        .map_err(ImportFromToolError::InvalidRequestError)?;
+
+    let report = import_rows(
+        tool_import.rows,
+        user_id,
+        query.organization_id,
+        query.dry_run.unwrap_or(false),
+    )
+    .await
+    .map_err(|err| match err {
+        ImportGoalsError::InvalidRequestError(err_msg) => {
+            ImportFromToolError::InvalidRequestError(err_msg)
+        }
+        ImportGoalsError::UserNotFoundError(err_msg) => {
+            ImportFromToolError::UserNotFoundError(err_msg)
+        }
+        ImportGoalsError::ForbiddenError(err_msg) => ImportFromToolError::ForbiddenError(err_msg),
+        ImportGoalsError::DatabaseError(err_msg) => ImportFromToolError::DatabaseError(err_msg),
+    })?;
+
+    Ok(ToolImportReportDto {
+        source,
+        mapped: tool_import.summary.mapped,
+        skipped: tool_import.summary.skipped,
+        report,
+    })
+}
@@ -0,0 +1,529 @@
+use regex::Regex;
+use serde_json::{json, Value};
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::add_goal, goal_event_data_access::add_goal_event,
+        goal_revision_data_access::add_goal_revision, note_data_access::add_note,
+        organization_data_access::find_organization_member, user_data_access::find_user_by_id,
+        webhook_data_access::enqueue_event,
+    },
+    db::establish_connection,
+    entities::{
+        goal::{CreateGoalDto, Goal},
+        goal_import::{
+            ImportGoalsQueryDto, ImportReportDto, ImportRow, ImportRowReportDto, ImportedGoalDto,
+            IMPORTED_STATUS, INVALID_STATUS, VALID_STATUS,
+        },
+        goal_revision::{GoalRevision, CREATE_ACTION},
+        note::{CreateNoteDto, Note},
+        organization::Organization,
+        user_export::DataFormat,
+        webhook::{WebhookEvent, GOAL_CREATED_EVENT},
+    },
+};
+
+pub const MAX_IMPORT_ROWS: usize = 1000;
+
+const CHECKLIST_ITEM_PATTERN: &str = r"^\s*[-*+]\s+\[([ xX])\]\s+(.*)$";
+const CSV_TEXT_COLUMNS: [&str; 3] = ["text", "title", "goal"];
+const CSV_COMPLETED_COLUMNS: [&str; 2] = ["completed", "done"];
+
+pub enum ImportGoalsError {
+    InvalidRequestError(String),
+    UserNotFoundError(String),
+    ForbiddenError(String),
+    DatabaseError(String),
+}
+
+// Reads the goals from a CSV with a text column, a JSON array or a Markdown checklist
+pub async fn execute(
+    body: String,
+    query: ImportGoalsQueryDto,
+    user_id: String,
+) -> Result<ImportReportDto, ImportGoalsError> {
+    let format = DataFormat::parse(query.format.as_deref())
+        .map_err(|err| ImportGoalsError::InvalidRequestError(err.to_string()))?;
+
+    let rows = match format {
+        DataFormat::Json => parse_json(&body)?,
+        DataFormat::Csv => parse_csv(&body)?,
+        DataFormat::Markdown => parse_markdown(&body)?,
+    };
+
+    import_rows(
+        rows,
+        user_id,
+        query.organization_id,
+        query.dry_run.unwrap_or(false),
+    )
+    .await
+}
+
+// Every row is validated as a new goal. The valid rows are added in a single transaction, so a
+// database error leaves no goal behind, and the invalid ones are only reported
+pub async fn import_rows(
+    rows: Vec<ImportRow>,
+    user_id: String,
+    organization_id: Option<String>,
+    dry_run: bool,
+) -> Result<ImportReportDto, ImportGoalsError> {
+    if rows.is_empty() {
+        return Err(ImportGoalsError::InvalidRequestError(
+            "There are no goals to import".to_string(),
+        ));
+    }
+
+    if rows.len() > MAX_IMPORT_ROWS {
+        return Err(ImportGoalsError::InvalidRequestError(format!(
+            "An import has at most {} goals",
+            MAX_IMPORT_ROWS
+        )));
+    }
+
+    if let Some(organization_id) = &organization_id {
+        Organization::validate_id(organization_id)
+            .map_err(|err| ImportGoalsError::InvalidRequestError(err.to_string()))?;
+    }
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+
+    if let Some(organization_id) = &organization_id {
+        check_can_write_goals(&client, organization_id, &user_id).await?;
+    }
+
+    let validated = rows
+        .into_iter()
+        .map(|row| {
+            let goal = validate_row(&row, &user_id, &organization_id);
+            (row, goal)
+        })
+        .collect::<Vec<(ImportRow, Result<Goal, String>)>>();
+
+    let has_valid_rows = validated.iter().any(|(_, goal)| goal.is_ok());
+    let mut goal_ids = Vec::new();
+    if !dry_run && has_valid_rows {
+        goal_ids = add_goals(&client, &validated, &user_id).await?;
+    }
+    let mut goal_ids = goal_ids.into_iter();
+
+    let mut report = ImportReportDto {
+        dry_run,
+        total: validated.len(),
+        valid: 0,
+        invalid: 0,
+        imported: 0,
+        rows: Vec::new(),
+    };
+
+    for (row, goal) in validated {
+        let row_report = match goal {
+            Err(err_msg) => {
+                report.invalid += 1;
+                ImportRowReportDto {
+                    row: row.row,
+                    status: INVALID_STATUS.to_string(),
+                    text: Some(row.text).filter(|text| !text.is_empty()),
+                    goal_id: None,
+                    error: Some(err_msg),
+                }
+            }
+            Ok(goal) => {
+                let goal_id = if dry_run { None } else { goal_ids.next() };
+                let status = if dry_run {
+                    VALID_STATUS
+                } else {
+                    IMPORTED_STATUS
+                };
+                report.valid += 1;
+                if !dry_run {
+                    report.imported += 1;
+                }
+                ImportRowReportDto {
+                    row: row.row,
+                    status: status.to_string(),
+                    text: Some(goal.get_text()),
+                    goal_id,
+                    error: None,
+                }
+            }
+        };
+        report.rows.push(row_report);
+    }
+
+    Ok(report)
+}
+
+// The note is validated with the goal, a row with an invalid note is reported as invalid instead of
+// failing the whole import once the goals are being added
+fn validate_row(
+    row: &ImportRow,
+    user_id: &str,
+    organization_id: &Option<String>,
+) -> Result<Goal, String> {
+    if let Some(err_msg) = &row.error {
+        return Err(err_msg.clone());
+    }
+
+    if let Some(note) = &row.note {
+        Note::validate_text(note).map_err(|err| err.to_string())?;
+    }
+
+    let create_goal = CreateGoalDto {
+        text: row.text.trim().to_string(),
+        organization_id: organization_id.clone(),
+        due_at: row.due_at.map(|due_at| due_at.to_rfc3339()),
+    };
+
+    let mut goal =
+        Goal::from_create_goal_dto(create_goal, user_id).map_err(|err| err.to_string())?;
+    goal.set_completed(row.completed);
+    Ok(goal)
+}
+
+// Returns the ids of the new goals, in the order of the valid rows
+async fn add_goals(
+    client: &Client,
+    validated: &[(ImportRow, Result<Goal, String>)],
+    user_id: &str,
+) -> Result<Vec<String>, ImportGoalsError> {
+    client
+        .batch_execute("BEGIN")
+        .await
+        .map_err(|err| ImportGoalsError::DatabaseError(err.to_string()))?;
+
+    let mut goal_ids = Vec::new();
+    for (row, goal) in validated.iter() {
+        let goal = match goal {
+            Err(_) => continue,
+            Ok(goal) => goal,
+        };
+        match add_imported_goal(client, goal, row.note.as_deref(), user_id).await {
+            Ok(id) => goal_ids.push(id),
+            Err(err) => {
+                if let Err(rollback_err) = client.batch_execute("ROLLBACK").await {
+                    eprintln!("Import rollback error: {}", rollback_err);
+                }
+                return Err(err);
+            }
+        }
+    }
+
+    client
+        .batch_execute("COMMIT")
+        .await
+        .map_err(|err| ImportGoalsError::DatabaseError(err.to_string()))?;
+
+    Ok(goal_ids)
+}
+
+// Same revision and events as a goal created one by one
+async fn add_imported_goal(
+    client: &Client,
+    goal: &Goal,
+    note: Option<&str>,
+    user_id: &str,
+) -> Result<String, ImportGoalsError> {
+    let id = add_goal(client, goal)
+        .await
+        .map_err(|err| ImportGoalsError::DatabaseError(err.to_string()))?;
+
+    if let Some(note) = note {
+        let create_note = CreateNoteDto {
+            text: note.to_string(),
+        };
+        let note = Note::from_create_note_dto(create_note, &id, user_id)
+            .map_err(|err| ImportGoalsError::DatabaseError(err.to_string()))?;
+        add_note(client, &note)
+            .await
+            .map_err(|err| ImportGoalsError::DatabaseError(err.to_string()))?;
+    }
+
+    let snapshot = GoalRevision::snapshot(goal);
+    add_goal_revision(client, &id, Some(user_id), CREATE_ACTION, None, &snapshot)
+        .await
+        .map_err(|err| ImportGoalsError::DatabaseError(err.to_string()))?;
+
+    let data = json!({
+        "id": id,
+        "text": goal.get_text(),
+        "user_id": goal.get_user_id(),
+        "organization_id": goal.get_organization_id(),
+        "completed_at": goal.get_completed_at().map(|date| date.to_rfc3339()),
+    });
+    add_goal_event(
+        client,
+        &id,
+        user_id,
+        goal.get_organization_id(),
+        GOAL_CREATED_EVENT,
+        &data,
+    )
+    .await
+    .map_err(|err| ImportGoalsError::DatabaseError(err.to_string()))?;
+    enqueue_event(
+        client,
+        user_id,
+        &WebhookEvent::new(GOAL_CREATED_EVENT, data),
+    )
+    .await
+    .map_err(|err| ImportGoalsError::DatabaseError(err.to_string()))?;
+
+    Ok(id)
+}
+
+// An array of texts or of objects with a text and an optional completed
+fn parse_json(body: &str) -> Result<Vec<ImportRow>, ImportGoalsError> {
+    let values = serde_json::from_str::<Vec<Value>>(body).map_err(|err| {
+        ImportGoalsError::InvalidRequestError(format!("The body is not a JSON array: {}", err))
+    })?;
+
+    let rows = values
+        .into_iter()
+        .enumerate()
+        .map(|(index, value)| match value {
+            Value::String(text) => ImportRow::new(index + 1, &text, false),
+            value => match serde_json::from_value::<ImportedGoalDto>(value) {
+                Ok(goal) => ImportRow::new(index + 1, &goal.text, goal.completed.unwrap_or(false)),
+                Err(err) => ImportRow::unreadable(
+                    index + 1,
+                    format!("Must be a text or an object with a text: {}", err),
+                ),
+            },
+        })
+        .collect();
+
+    Ok(rows)
+}
+
+// The first line has the column names, the text is in the text, title or goal column. The exports
+// of GET /api/users/me/export can be imported back
+fn parse_csv(body: &str) -> Result<Vec<ImportRow>, ImportGoalsError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(body.as_bytes());
+
+    let headers = reader
+        .headers()
+        .map_err(|err| ImportGoalsError::InvalidRequestError(err.to_string()))?
+        .iter()
+        .map(|header| header.to_lowercase())
+        .collect::<Vec<String>>();
+
+    let find_column = |names: &[&str]| {
+        headers
+            .iter()
+            .position(|header| names.contains(&header.as_str()))
+    };
+
+    let text_column = find_column(&CSV_TEXT_COLUMNS).ok_or_else(|| {
+        ImportGoalsError::InvalidRequestError(
+            "The CSV needs a text, title or goal column".to_string(),
+        )
+    })?;
+    let completed_column = find_column(&CSV_COMPLETED_COLUMNS);
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let row = match record {
+            Err(err) => {
+                let line = err.position().map(|position| position.line()).unwrap_or(0);
+                ImportRow::unreadable(line as usize, err.to_string())
+            }
+            Ok(record) => {
+                let line = record
+                    .position()
+                    .map(|position| position.line())
+                    .unwrap_or(0);
+                let completed = completed_column
+                    .and_then(|column| record.get(column))
+                    .map(ImportRow::parse_completed)
+                    .unwrap_or(false);
+                match record.get(text_column) {
+                    None => ImportRow::unreadable(line as usize, "Missing text".to_string()),
+                    Some(text) => ImportRow::new(line as usize, text, completed),
+                }
+            }
+        };
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+// Every "- [ ] text" or "- [x] text" line is a goal, the other lines are ignored
+fn parse_markdown(body: &str) -> Result<Vec<ImportRow>, ImportGoalsError> {
+    let item_regex = Regex::new(CHECKLIST_ITEM_PATTERN)
+        .map_err(|err| ImportGoalsError::DatabaseError(err.to_string()))?;
+
+    let rows = body
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            item_regex.captures(line).map(|captures| {
+                let completed = captures[1].eq_ignore_ascii_case("x");
+                ImportRow::new(index + 1, &captures[2], completed)
+            })
+        })
+        .collect::<Vec<ImportRow>>();
+
+    if rows.is_empty() {
+        return Err(ImportGoalsError::InvalidRequestError(
+            "No \"- [ ]\" checklist item found".to_string(),
+        ));
+    }
+
+    Ok(rows)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), ImportGoalsError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| ImportGoalsError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(ImportGoalsError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(ImportGoalsError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn check_can_write_goals(
+    client: &Client,
+    organization_id: &str,
+    user_id: &str,
+) -> Result<(), ImportGoalsError> {
+    let opt_member = find_organization_member(client, organization_id, user_id)
+        .await
+        .map_err(|err| ImportGoalsError::DatabaseError(err.to_string()))?;
+
+    match opt_member {
+        Some(member) if member.can_write_goals() => Ok(()),
+        Some(_) => Err(ImportGoalsError::ForbiddenError(
+            "Viewers cannot create goals in the organization".to_string(),
+        )),
+        None => Err(ImportGoalsError::ForbiddenError(
+            "The user is not a member of the organization".to_string(),
+        )),
+    }
+}
+
+async fn get_connected_client() -> Result<Client, ImportGoalsError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| ImportGoalsError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(result: Result<Vec<ImportRow>, ImportGoalsError>) -> Vec<ImportRow> {
+        match result {
+            Ok(rows) => rows,
+            Err(_) => panic!("The document could not be read"),
+        }
+    }
+
+    fn summary(rows: &[ImportRow]) -> Vec<(usize, &str, bool, Option<&str>)> {
+        rows.iter()
+            .map(|row| {
+                (
+                    row.row,
+                    row.text.as_str(),
+                    row.completed,
+                    row.error.as_deref(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reads_the_json_texts_and_objects() {
+        let rows = rows(parse_json(
+            r#"["Read a book", {"text": "Run", "completed": true}, {"title": "Swim"}, 3]"#,
+        ));
+
+        assert_eq!(rows.len(), 4);
+        assert_eq!(
+            summary(&rows[..2]),
+            vec![(1, "Read a book", false, None), (2, "Run", true, None)]
+        );
+        assert_eq!((rows[2].row, rows[3].row), (3, 4));
+        assert!(rows[2]
+            .error
+            .as_deref()
+            .unwrap()
+            .starts_with("Must be a text"));
+        assert!(rows[3]
+            .error
+            .as_deref()
+            .unwrap()
+            .starts_with("Must be a text"));
+    }
+
+    #[test]
+    fn rejects_a_json_body_that_is_not_an_array() {
+        assert!(matches!(
+            parse_json(r#"{"text": "Run"}"#),
+            Err(ImportGoalsError::InvalidRequestError(_))
+        ));
+    }
+
+    #[test]
+    fn reads_the_csv_rows_with_their_line() {
+        let rows = rows(parse_csv(
+            "done,Title\nyes,\"Read, then write\"\n,Run\n\"x\n\",\"Swim\"\nno\n",
+        ));
+
+        assert_eq!(
+            summary(&rows),
+            vec![
+                (2, "Read, then write", true, None),
+                (3, "Run", false, None),
+                (4, "Swim", true, None),
+                (6, "", false, Some("Missing text")),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_a_csv_without_a_text_column() {
+        assert!(matches!(
+            parse_csv("name,done\nRun,yes\n"),
+            Err(ImportGoalsError::InvalidRequestError(_))
+        ));
+    }
+
+    #[test]
+    fn reads_the_markdown_checklist_items() {
+        let rows = rows(parse_markdown(
+            "# Goals\n- [ ] Read a book\n  * [X] Run\nSwim\n-[ ] Not an item\n+ [x] Cook\n",
+        ));
+
+        assert_eq!(
+            summary(&rows),
+            vec![
+                (2, "Read a book", false, None),
+                (3, "Run", true, None),
+                (6, "Cook", true, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_a_markdown_without_checklist_items() {
+        assert!(matches!(
+            parse_markdown("# Goals\n- Read a book\n"),
+            Err(ImportGoalsError::InvalidRequestError(_))
+        ));
+    }
+}
@@ -1,3 +1,19 @@
 pub mod create_goal;
 pub mod get_all_goals;
 pub mod delete_goal;
+pub mod update_goal;
+pub mod habits;
+pub mod get_goal_events;
+pub mod purge_goal_events;
+pub mod get_trashed_goals;
+pub mod restore_goal;
+pub mod purge_goal;
+pub mod purge_trashed_goals;
+pub mod archive_goal;
+pub mod unarchive_goal;
+pub mod archive_completed_goals;
+pub mod notify_approaching_deadlines;
+pub mod get_goal_history;
+pub mod revert_goal;
+pub mod import_goals;
+pub mod import_from_tool;
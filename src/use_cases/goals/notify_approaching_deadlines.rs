@@ -0,0 +1,82 @@
+use tokio_postgres::Client;
+
+use crate::{
+    config::get_env_parsed,
+    data_access::{
+        goal_data_access::claim_approaching_deadlines, notification_data_access::add_notification,
+    },
+    db::establish_connection,
+    entities::notification::{Notification, DEADLINE_KIND},
+};
+
+const BATCH_SIZE: i64 = 100;
+
+pub enum NotifyApproachingDeadlinesError {
+    DatabaseError(String),
+}
+
+// Notifies the owners of the active goals due within DEADLINE_NOTICE_HOURS (default 24) and returns
+// how many were notified. Each goal is notified once per due date, moving it notifies again
+pub async fn execute() -> Result<usize, NotifyApproachingDeadlinesError> {
+    let client = get_connected_client().await?;
+    let notice_seconds = get_env_parsed("DEADLINE_NOTICE_HOURS", 24.0) * 3600.0;
+
+    client
+        .batch_execute("BEGIN")
+        .await
+        .map_err(|err| NotifyApproachingDeadlinesError::DatabaseError(err.to_string()))?;
+
+    let notified = match save_notifications(&client, notice_seconds).await {
+        Err(err) => {
+            if let Err(rollback_err) = client.batch_execute("ROLLBACK").await {
+                eprintln!("Deadline notifications rollback error: {}", rollback_err);
+            }
+            return Err(err);
+        }
+        Ok(notified) => notified,
+    };
+
+    client
+        .batch_execute("COMMIT")
+        .await
+        .map_err(|err| NotifyApproachingDeadlinesError::DatabaseError(err.to_string()))?;
+
+    Ok(notified)
+}
+
+// The goals are marked as notified with their notifications, a failure leaves them to the next run
+async fn save_notifications(
+    client: &Client,
+    notice_seconds: f64,
+) -> Result<usize, NotifyApproachingDeadlinesError> {
+    let goals = claim_approaching_deadlines(client, notice_seconds, BATCH_SIZE)
+        .await
+        .map_err(|err| NotifyApproachingDeadlinesError::DatabaseError(err.to_string()))?;
+
+    for goal in goals.iter() {
+        let due_at = match goal.get_due_at() {
+            Some(due_at) => due_at,
+            None => continue,
+        };
+        let notification = Notification::from_event(
+            &goal.get_user_id(),
+            DEADLINE_KIND,
+            Some(goal.get_id()),
+            None,
+            format!("Due {}: {}", due_at.to_rfc3339(), goal.get_text()),
+        )
+        .map_err(|err| NotifyApproachingDeadlinesError::DatabaseError(err.to_string()))?;
+        add_notification(client, &notification)
+            .await
+            .map_err(|err| NotifyApproachingDeadlinesError::DatabaseError(err.to_string()))?;
+    }
+
+    Ok(goals.len())
+}
+
+async fn get_connected_client() -> Result<Client, NotifyApproachingDeadlinesError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| NotifyApproachingDeadlinesError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
@@ -0,0 +1,114 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::{self, find_trashed_goal_by_id},
+        goal_share_data_access::find_goal_share,
+        organization_data_access::find_organization_member,
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::goal::Goal,
+};
+
+pub enum PurgeGoalError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    GoalNotFoundError(String),
+    ForbiddenError(String),
+    InvalidRequestError(String),
+}
+
+// Deletes for good a goal of the trash, with its notes, shares and comments. The deletion was
+// already announced when the goal was moved to the trash
+pub async fn execute(goal_id: String, user_id: String) -> Result<(), PurgeGoalError> {
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    Goal::validate_id(&goal_id)
+        .map_err(|err| PurgeGoalError::InvalidRequestError(err.to_string()))?;
+    let goal = find_trashed_goal(&client, &goal_id).await?;
+    check_can_purge(&client, &goal, &user_id).await?;
+
+    goal_data_access::delete_goal(&client, &goal_id)
+        .await
+        .map_err(|err| PurgeGoalError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+async fn get_connected_client() -> Result<Client, PurgeGoalError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| PurgeGoalError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), PurgeGoalError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| PurgeGoalError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(PurgeGoalError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(PurgeGoalError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn find_trashed_goal(client: &Client, goal_id: &str) -> Result<Goal, PurgeGoalError> {
+    let opt_goal = find_trashed_goal_by_id(client, goal_id)
+        .await
+        .map_err(|err| PurgeGoalError::DatabaseError(err.to_string()))?;
+
+    opt_goal.ok_or_else(|| {
+        PurgeGoalError::GoalNotFoundError(format!(
+            "Goal not found in the trash for the id: {}",
+            goal_id
+        ))
+    })
+}
+
+// Same rules as the deletion. Goals the user has no access to are reported as not found, so their
+// ids are not leaked
+async fn check_can_purge(
+    client: &Client,
+    goal: &Goal,
+    user_id: &str,
+) -> Result<(), PurgeGoalError> {
+    match goal.get_organization_id() {
+        None if goal.get_user_id() == user_id => return Ok(()),
+        None => {}
+        Some(organization_id) => {
+            let opt_member = find_organization_member(client, &organization_id, user_id)
+                .await
+                .map_err(|err| PurgeGoalError::DatabaseError(err.to_string()))?;
+            match opt_member {
+                Some(member) if member.can_write_goals() => return Ok(()),
+                Some(_) => {
+                    return Err(PurgeGoalError::ForbiddenError(
+                        "Viewers cannot delete goals of the organization".to_string(),
+                    ))
+                }
+                None => {}
+            }
+        }
+    }
+
+    let opt_share = find_goal_share(client, &goal.get_id(), user_id)
+        .await
+        .map_err(|err| PurgeGoalError::DatabaseError(err.to_string()))?;
+
+    match opt_share {
+        Some(_) => Err(PurgeGoalError::ForbiddenError(
+            "Shared goals can only be deleted by their owner".to_string(),
+        )),
+        None => Err(PurgeGoalError::GoalNotFoundError(format!(
+            "Goal not found in the trash for the id: {}",
+            goal.get_id()
+        ))),
+    }
+}
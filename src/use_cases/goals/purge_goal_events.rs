@@ -0,0 +1,29 @@
+use tokio_postgres::Client;
+
+use crate::{
+    config::get_env_parsed, data_access::goal_event_data_access::delete_goal_events_before,
+    db::establish_connection,
+};
+
+pub enum PurgeGoalEventsError {
+    DatabaseError(String),
+}
+
+// Deletes the goal events older than GOAL_EVENT_RETENTION_HOURS (default 24) and returns how many
+// were deleted. Streams cannot resume from a deleted event
+pub async fn execute() -> Result<u64, PurgeGoalEventsError> {
+    let retention_hours: f64 = get_env_parsed("GOAL_EVENT_RETENTION_HOURS", 24.0);
+
+    let client = get_connected_client().await?;
+
+    delete_goal_events_before(&client, retention_hours * 3600.0)
+        .await
+        .map_err(|err| PurgeGoalEventsError::DatabaseError(err.to_string()))
+}
+
+async fn get_connected_client() -> Result<Client, PurgeGoalEventsError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| PurgeGoalEventsError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
@@ -0,0 +1,28 @@
+use tokio_postgres::Client;
+
+use crate::{
+    config::get_env_parsed, data_access::goal_data_access::delete_trashed_goals_before,
+    db::establish_connection,
+};
+
+pub enum PurgeTrashedGoalsError {
+    DatabaseError(String),
+}
+
+// Deletes for good the goals in the trash for more than GOAL_TRASH_RETENTION_DAYS (default 30) and
+// returns how many were deleted
+pub async fn execute() -> Result<u64, PurgeTrashedGoalsError> {
+    let client = get_connected_client().await?;
+    let retention_seconds = get_env_parsed("GOAL_TRASH_RETENTION_DAYS", 30.0) * 86400.0;
+
+    delete_trashed_goals_before(&client, retention_seconds)
+        .await
+        .map_err(|err| PurgeTrashedGoalsError::DatabaseError(err.to_string()))
+}
+
+async fn get_connected_client() -> Result<Client, PurgeTrashedGoalsError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| PurgeTrashedGoalsError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
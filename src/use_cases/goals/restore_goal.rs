@@ -0,0 +1,221 @@
+use serde_json::json;
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::{self, find_goal_by_id, find_trashed_goal_by_id},
+        goal_event_data_access::add_goal_event,
+        goal_revision_data_access::add_goal_revision,
+        goal_share_data_access::find_goal_share,
+        organization_data_access::find_organization_member,
+        user_data_access::find_user_by_id,
+        webhook_data_access::enqueue_event,
+    },
+    db::establish_connection,
+    entities::{
+        goal::{Goal, GoalDto},
+        goal_revision::{GoalRevision, RESTORE_ACTION},
+        webhook::{WebhookEvent, GOAL_RESTORED_EVENT},
+    },
+};
+
+pub enum RestoreGoalError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    GoalNotFoundError(String),
+    ForbiddenError(String),
+    InvalidRequestError(String),
+}
+
+// Takes the goal out of the trash, the users who could delete it can restore it
+pub async fn execute(goal_id: String, user_id: String) -> Result<GoalDto, RestoreGoalError> {
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    Goal::validate_id(&goal_id)
+        .map_err(|err| RestoreGoalError::InvalidRequestError(err.to_string()))?;
+    let goal = find_trashed_goal(&client, &goal_id).await?;
+    check_can_restore(&client, &goal, &user_id).await?;
+    let before = GoalRevision::snapshot(&goal);
+
+    client
+        .batch_execute("BEGIN")
+        .await
+        .map_err(|err| RestoreGoalError::DatabaseError(err.to_string()))?;
+
+    let restored = match save_restore(&client, &goal, &before, &user_id).await {
+        Err(err) => {
+            if let Err(rollback_err) = client.batch_execute("ROLLBACK").await {
+                eprintln!("Goal restore rollback error: {}", rollback_err);
+            }
+            return Err(err);
+        }
+        Ok(restored) => restored,
+    };
+
+    client
+        .batch_execute("COMMIT")
+        .await
+        .map_err(|err| RestoreGoalError::DatabaseError(err.to_string()))?;
+
+    Ok(GoalDto {
+        id: restored.get_id(),
+        text: restored.get_text(),
+        user_id: restored.get_user_id(),
+        organization_id: restored.get_organization_id(),
+        permission: None,
+        note_count: restored.get_note_count(),
+        due_at: restored.get_due_at().map(|date| date.to_rfc3339()),
+        completed_at: restored.get_completed_at().map(|date| date.to_rfc3339()),
+        archived_at: restored.get_archived_at().map(|date| date.to_rfc3339()),
+        deleted_at: None,
+    })
+}
+
+// The goal, its revision and its events are written together, a failure leaves none of them.
+// Returns the restored goal
+async fn save_restore(
+    client: &Client,
+    goal: &Goal,
+    before: &str,
+    user_id: &str,
+) -> Result<Goal, RestoreGoalError> {
+    let goal_id = goal.get_id();
+    restore_goal(client, &goal_id).await?;
+
+    let restored = find_goal_by_id(client, &goal_id)
+        .await
+        .map_err(|err| RestoreGoalError::DatabaseError(err.to_string()))?
+        .ok_or_else(|| {
+            RestoreGoalError::GoalNotFoundError(format!("Goal not found for the id: {}", goal_id))
+        })?;
+    add_goal_revision(
+        client,
+        &goal_id,
+        Some(user_id),
+        RESTORE_ACTION,
+        Some(before),
+        &GoalRevision::snapshot(&restored),
+    )
+    .await
+    .map_err(|err| RestoreGoalError::DatabaseError(err.to_string()))?;
+
+    let data = json!({
+        "id": goal.get_id(),
+        "text": goal.get_text(),
+        "user_id": goal.get_user_id(),
+        "organization_id": goal.get_organization_id(),
+        "restored_by": user_id,
+    });
+    add_goal_event(
+        client,
+        &goal.get_id(),
+        &goal.get_user_id(),
+        goal.get_organization_id(),
+        GOAL_RESTORED_EVENT,
+        &data,
+    )
+    .await
+    .map_err(|err| RestoreGoalError::DatabaseError(err.to_string()))?;
+
+    enqueue_event(
+        client,
+        &goal.get_user_id(),
+        &WebhookEvent::new(GOAL_RESTORED_EVENT, data),
+    )
+    .await
+    .map_err(|err| RestoreGoalError::DatabaseError(err.to_string()))?;
+
+    Ok(restored)
+}
+
+async fn get_connected_client() -> Result<Client, RestoreGoalError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| RestoreGoalError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), RestoreGoalError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| RestoreGoalError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(RestoreGoalError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(RestoreGoalError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn find_trashed_goal(client: &Client, goal_id: &str) -> Result<Goal, RestoreGoalError> {
+    let opt_goal = find_trashed_goal_by_id(client, goal_id)
+        .await
+        .map_err(|err| RestoreGoalError::DatabaseError(err.to_string()))?;
+
+    opt_goal.ok_or_else(|| {
+        RestoreGoalError::GoalNotFoundError(format!(
+            "Goal not found in the trash for the id: {}",
+            goal_id
+        ))
+    })
+}
+
+// Same rules as the deletion. Goals the user has no access to are reported as not found, so their
+// ids are not leaked
+async fn check_can_restore(
+    client: &Client,
+    goal: &Goal,
+    user_id: &str,
+) -> Result<(), RestoreGoalError> {
+    match goal.get_organization_id() {
+        None if goal.get_user_id() == user_id => return Ok(()),
+        None => {}
+        Some(organization_id) => {
+            let opt_member = find_organization_member(client, &organization_id, user_id)
+                .await
+                .map_err(|err| RestoreGoalError::DatabaseError(err.to_string()))?;
+            match opt_member {
+                Some(member) if member.can_write_goals() => return Ok(()),
+                Some(_) => {
+                    return Err(RestoreGoalError::ForbiddenError(
+                        "Viewers cannot restore goals of the organization".to_string(),
+                    ))
+                }
+                None => {}
+            }
+        }
+    }
+
+    let opt_share = find_goal_share(client, &goal.get_id(), user_id)
+        .await
+        .map_err(|err| RestoreGoalError::DatabaseError(err.to_string()))?;
+
+    match opt_share {
+        Some(_) => Err(RestoreGoalError::ForbiddenError(
+            "Shared goals can only be restored by their owner".to_string(),
+        )),
+        None => Err(RestoreGoalError::GoalNotFoundError(format!(
+            "Goal not found in the trash for the id: {}",
+            goal.get_id()
+        ))),
+    }
+}
+
+// Restored or purged at the same time by another request
+async fn restore_goal(client: &Client, goal_id: &str) -> Result<(), RestoreGoalError> {
+    let restored = goal_data_access::restore_goal(client, goal_id)
+        .await
+        .map_err(|err| RestoreGoalError::DatabaseError(err.to_string()))?;
+
+    match restored {
+        0 => Err(RestoreGoalError::GoalNotFoundError(format!(
+            "Goal not found in the trash for the id: {}",
+            goal_id
+        ))),
+        _ => Ok(()),
+    }
+}
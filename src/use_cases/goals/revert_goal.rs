@@ -0,0 +1,220 @@
+use serde_json::json;
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::{self, find_goal_by_id, has_goal_access, has_goal_write_access},
+        goal_event_data_access::add_goal_event,
+        goal_revision_data_access::{add_goal_revision, find_goal_revision_by_id},
+        user_data_access::find_user_by_id,
+        webhook_data_access::enqueue_event,
+    },
+    db::establish_connection,
+    entities::{
+        goal::{Goal, GoalDto},
+        goal_revision::{GoalRevision, REVERT_ACTION},
+        webhook::{WebhookEvent, GOAL_UPDATED_EVENT},
+    },
+};
+
+pub enum RevertGoalError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    GoalNotFoundError(String),
+    RevisionNotFoundError(String),
+    ForbiddenError(String),
+    InvalidRequestError(String),
+}
+
+// Sets the text and the completion of the goal back to what they were once the revision was made.
+// Archiving and deletion have their own routes and are left as they are, like the due date. The
+// revert is itself a revision, it can be reverted too
+pub async fn execute(
+    goal_id: String,
+    revision_id: String,
+    user_id: String,
+) -> Result<GoalDto, RevertGoalError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| RevertGoalError::InvalidRequestError(err.to_string()))?;
+    let revision_id = GoalRevision::parse_id(&revision_id)
+        .map_err(|err| RevertGoalError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+
+    let mut goal = find_goal(&client, &goal_id).await?;
+    check_goal_write_access(&client, &goal_id, &user_id).await?;
+    let revision = find_revision(&client, &goal_id, revision_id).await?;
+
+    let before = GoalRevision::snapshot(&goal);
+    let text = revision.get_after_text().ok_or_else(|| {
+        RevertGoalError::InvalidRequestError("The revision has no text to revert to".to_string())
+    })?;
+    goal.set_text(text)
+        .map_err(|err| RevertGoalError::InvalidRequestError(err.to_string()))?;
+    goal.set_completed_at(revision.get_after_completed_at());
+
+    client
+        .batch_execute("BEGIN")
+        .await
+        .map_err(|err| RevertGoalError::DatabaseError(err.to_string()))?;
+
+    if let Err(err) = save_revert(&client, &goal, &before, revision_id, &user_id).await {
+        if let Err(rollback_err) = client.batch_execute("ROLLBACK").await {
+            eprintln!("Goal revert rollback error: {}", rollback_err);
+        }
+        return Err(err);
+    }
+
+    client
+        .batch_execute("COMMIT")
+        .await
+        .map_err(|err| RevertGoalError::DatabaseError(err.to_string()))?;
+
+    Ok(GoalDto {
+        id: goal.get_id(),
+        text: goal.get_text(),
+        user_id: goal.get_user_id(),
+        organization_id: goal.get_organization_id(),
+        permission: None,
+        note_count: goal.get_note_count(),
+        due_at: goal.get_due_at().map(|date| date.to_rfc3339()),
+        completed_at: goal.get_completed_at().map(|date| date.to_rfc3339()),
+        archived_at: goal.get_archived_at().map(|date| date.to_rfc3339()),
+        deleted_at: None,
+    })
+}
+
+// The goal, its revision and its events are written together, a failure leaves none of them
+async fn save_revert(
+    client: &Client,
+    goal: &Goal,
+    before: &str,
+    revision_id: i64,
+    user_id: &str,
+) -> Result<(), RevertGoalError> {
+    goal_data_access::update_goal(client, goal)
+        .await
+        .map_err(|err| RevertGoalError::DatabaseError(err.to_string()))?;
+
+    let after = GoalRevision::snapshot(goal);
+    add_goal_revision(
+        client,
+        &goal.get_id(),
+        Some(user_id),
+        REVERT_ACTION,
+        Some(before),
+        &after,
+    )
+    .await
+    .map_err(|err| RevertGoalError::DatabaseError(err.to_string()))?;
+
+    let data = json!({
+        "id": goal.get_id(),
+        "text": goal.get_text(),
+        "user_id": goal.get_user_id(),
+        "organization_id": goal.get_organization_id(),
+        "completed_at": goal.get_completed_at().map(|date| date.to_rfc3339()),
+        "updated_by": user_id,
+        "reverted_to": revision_id,
+    });
+    add_goal_event(
+        client,
+        &goal.get_id(),
+        &goal.get_user_id(),
+        goal.get_organization_id(),
+        GOAL_UPDATED_EVENT,
+        &data,
+    )
+    .await
+    .map_err(|err| RevertGoalError::DatabaseError(err.to_string()))?;
+    enqueue_event(
+        client,
+        &goal.get_user_id(),
+        &WebhookEvent::new(GOAL_UPDATED_EVENT, data),
+    )
+    .await
+    .map_err(|err| RevertGoalError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+async fn get_connected_client() -> Result<Client, RevertGoalError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| RevertGoalError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), RevertGoalError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| RevertGoalError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(RevertGoalError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(RevertGoalError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn find_goal(client: &Client, goal_id: &str) -> Result<Goal, RevertGoalError> {
+    let opt_goal = find_goal_by_id(client, goal_id)
+        .await
+        .map_err(|err| RevertGoalError::DatabaseError(err.to_string()))?;
+
+    opt_goal.ok_or_else(|| {
+        RevertGoalError::GoalNotFoundError(format!("Goal not found for the id: {}", goal_id))
+    })
+}
+
+async fn find_revision(
+    client: &Client,
+    goal_id: &str,
+    revision_id: i64,
+) -> Result<GoalRevision, RevertGoalError> {
+    let opt_revision = find_goal_revision_by_id(client, revision_id)
+        .await
+        .map_err(|err| RevertGoalError::DatabaseError(err.to_string()))?;
+
+    match opt_revision {
+        Some(revision) if revision.get_goal_id() == goal_id => Ok(revision),
+        _ => Err(RevertGoalError::RevisionNotFoundError(format!(
+            "Revision not found for the id: {}",
+            revision_id
+        ))),
+    }
+}
+
+// Same rules as the update: the owner, the members of the organization that can write goals and
+// the users the goal is shared with for edit
+async fn check_goal_write_access(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<(), RevertGoalError> {
+    let can_write = has_goal_write_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| RevertGoalError::DatabaseError(err.to_string()))?;
+    if can_write {
+        return Ok(());
+    }
+
+    let has_access = has_goal_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| RevertGoalError::DatabaseError(err.to_string()))?;
+
+    match has_access {
+        true => Err(RevertGoalError::ForbiddenError(
+            "The user can only view this goal".to_string(),
+        )),
+        false => Err(RevertGoalError::GoalNotFoundError(format!(
+            "Goal not found for the id: {}",
+            goal_id
+        ))),
+    }
+}
@@ -0,0 +1,211 @@
+use serde_json::json;
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::{self, find_goal_by_id},
+        goal_event_data_access::add_goal_event,
+        goal_revision_data_access::add_goal_revision,
+        goal_share_data_access::find_goal_share,
+        organization_data_access::find_organization_member,
+        user_data_access::find_user_by_id,
+        webhook_data_access::enqueue_event,
+    },
+    db::establish_connection,
+    entities::{
+        goal::{Goal, GoalDto},
+        goal_revision::{GoalRevision, UNARCHIVE_ACTION},
+        webhook::{WebhookEvent, GOAL_UNARCHIVED_EVENT},
+    },
+};
+
+pub enum UnarchiveGoalError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    GoalNotFoundError(String),
+    ForbiddenError(String),
+    InvalidRequestError(String),
+}
+
+// Brings an archived goal back to the goal lists
+pub async fn execute(goal_id: String, user_id: String) -> Result<GoalDto, UnarchiveGoalError> {
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    Goal::validate_id(&goal_id)
+        .map_err(|err| UnarchiveGoalError::InvalidRequestError(err.to_string()))?;
+    let goal = find_goal(&client, &goal_id).await?;
+    check_can_unarchive(&client, &goal, &user_id).await?;
+    let before = GoalRevision::snapshot(&goal);
+
+    client
+        .batch_execute("BEGIN")
+        .await
+        .map_err(|err| UnarchiveGoalError::DatabaseError(err.to_string()))?;
+
+    let unarchived = match save_unarchive(&client, &goal, &before, &user_id).await {
+        Err(err) => {
+            if let Err(rollback_err) = client.batch_execute("ROLLBACK").await {
+                eprintln!("Goal unarchive rollback error: {}", rollback_err);
+            }
+            return Err(err);
+        }
+        Ok(unarchived) => unarchived,
+    };
+
+    client
+        .batch_execute("COMMIT")
+        .await
+        .map_err(|err| UnarchiveGoalError::DatabaseError(err.to_string()))?;
+
+    Ok(GoalDto {
+        id: unarchived.get_id(),
+        text: unarchived.get_text(),
+        user_id: unarchived.get_user_id(),
+        organization_id: unarchived.get_organization_id(),
+        permission: None,
+        note_count: unarchived.get_note_count(),
+        due_at: unarchived.get_due_at().map(|date| date.to_rfc3339()),
+        completed_at: unarchived.get_completed_at().map(|date| date.to_rfc3339()),
+        archived_at: unarchived.get_archived_at().map(|date| date.to_rfc3339()),
+        deleted_at: None,
+    })
+}
+
+// The goal, its revision and its events are written together, a failure leaves none of them.
+// Returns the unarchived goal
+async fn save_unarchive(
+    client: &Client,
+    goal: &Goal,
+    before: &str,
+    user_id: &str,
+) -> Result<Goal, UnarchiveGoalError> {
+    unarchive_goal(client, &goal.get_id()).await?;
+
+    let unarchived = find_goal(client, &goal.get_id()).await?;
+    add_goal_revision(
+        client,
+        &goal.get_id(),
+        Some(user_id),
+        UNARCHIVE_ACTION,
+        Some(before),
+        &GoalRevision::snapshot(&unarchived),
+    )
+    .await
+    .map_err(|err| UnarchiveGoalError::DatabaseError(err.to_string()))?;
+
+    let data = json!({
+        "id": goal.get_id(),
+        "text": goal.get_text(),
+        "user_id": goal.get_user_id(),
+        "organization_id": goal.get_organization_id(),
+        "unarchived_by": user_id,
+    });
+    add_goal_event(
+        client,
+        &goal.get_id(),
+        &goal.get_user_id(),
+        goal.get_organization_id(),
+        GOAL_UNARCHIVED_EVENT,
+        &data,
+    )
+    .await
+    .map_err(|err| UnarchiveGoalError::DatabaseError(err.to_string()))?;
+
+    enqueue_event(
+        client,
+        &goal.get_user_id(),
+        &WebhookEvent::new(GOAL_UNARCHIVED_EVENT, data),
+    )
+    .await
+    .map_err(|err| UnarchiveGoalError::DatabaseError(err.to_string()))?;
+
+    Ok(unarchived)
+}
+
+async fn get_connected_client() -> Result<Client, UnarchiveGoalError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| UnarchiveGoalError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), UnarchiveGoalError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| UnarchiveGoalError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(UnarchiveGoalError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(UnarchiveGoalError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn find_goal(client: &Client, goal_id: &str) -> Result<Goal, UnarchiveGoalError> {
+    let opt_goal = find_goal_by_id(client, goal_id)
+        .await
+        .map_err(|err| UnarchiveGoalError::DatabaseError(err.to_string()))?;
+
+    opt_goal.ok_or_else(|| {
+        UnarchiveGoalError::GoalNotFoundError(format!("Goal not found for the id: {}", goal_id))
+    })
+}
+
+// Same rules as the deletion: personal goals by their owner and organization goals by the members
+// that can write them. Goals the user has no access to are reported as not found, so their ids are
+// not leaked
+async fn check_can_unarchive(
+    client: &Client,
+    goal: &Goal,
+    user_id: &str,
+) -> Result<(), UnarchiveGoalError> {
+    match goal.get_organization_id() {
+        None if goal.get_user_id() == user_id => return Ok(()),
+        None => {}
+        Some(organization_id) => {
+            let opt_member = find_organization_member(client, &organization_id, user_id)
+                .await
+                .map_err(|err| UnarchiveGoalError::DatabaseError(err.to_string()))?;
+            match opt_member {
+                Some(member) if member.can_write_goals() => return Ok(()),
+                Some(_) => {
+                    return Err(UnarchiveGoalError::ForbiddenError(
+                        "Viewers cannot unarchive goals of the organization".to_string(),
+                    ))
+                }
+                None => {}
+            }
+        }
+    }
+
+    let opt_share = find_goal_share(client, &goal.get_id(), user_id)
+        .await
+        .map_err(|err| UnarchiveGoalError::DatabaseError(err.to_string()))?;
+
+    match opt_share {
+        Some(_) => Err(UnarchiveGoalError::ForbiddenError(
+            "Shared goals can only be unarchived by their owner".to_string(),
+        )),
+        None => Err(UnarchiveGoalError::GoalNotFoundError(format!(
+            "Goal not found for the id: {}",
+            goal.get_id()
+        ))),
+    }
+}
+
+async fn unarchive_goal(client: &Client, goal_id: &str) -> Result<(), UnarchiveGoalError> {
+    let changed = goal_data_access::unarchive_goal(client, goal_id)
+        .await
+        .map_err(|err| UnarchiveGoalError::DatabaseError(err.to_string()))?;
+
+    match changed {
+        0 => Err(UnarchiveGoalError::InvalidRequestError(
+            "The goal is not archived".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,249 @@
+use serde_json::json;
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::{self, find_goal_by_id, has_goal_access, has_goal_write_access},
+        goal_event_data_access::add_goal_event,
+        goal_revision_data_access::add_goal_revision,
+        reminder_data_access::{delete_due_reminders, reschedule_due_reminders},
+        user_data_access::find_user_by_id,
+        webhook_data_access::enqueue_event,
+    },
+    db::establish_connection,
+    entities::{
+        goal::{Goal, GoalDto, UpdateGoalDto},
+        goal_revision::{GoalRevision, UPDATE_ACTION},
+        webhook::{WebhookEvent, GOAL_COMPLETED_EVENT, GOAL_UPDATED_EVENT},
+    },
+};
+
+pub enum UpdateGoalError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    GoalNotFoundError(String),
+    ForbiddenError(String),
+    InvalidRequestError(String),
+}
+
+pub async fn execute(
+    goal_id: String,
+    update_goal: UpdateGoalDto,
+    user_id: String,
+) -> Result<GoalDto, UpdateGoalError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| UpdateGoalError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+
+    let mut goal = find_goal(&client, &goal_id).await?;
+    check_goal_write_access(&client, &goal_id, &user_id).await?;
+    let before = GoalRevision::snapshot(&goal);
+    let was_completed = goal.get_completed_at().is_some();
+    let previous_due_at = goal.get_due_at();
+
+    goal.set_text(update_goal.text)
+        .map_err(|err| UpdateGoalError::InvalidRequestError(err.to_string()))?;
+    if let Some(completed) = update_goal.completed {
+        goal.set_completed(completed);
+    }
+    if let Some(due_at) = update_goal.due_at {
+        let due_at = match due_at {
+            None => None,
+            Some(due_at) => Some(
+                Goal::parse_due_at(&due_at)
+                    .map_err(|err| UpdateGoalError::InvalidRequestError(err.to_string()))?,
+            ),
+        };
+        goal.set_due_at(due_at);
+    }
+    let due_at_changed = goal.get_due_at() != previous_due_at;
+
+    client
+        .batch_execute("BEGIN")
+        .await
+        .map_err(|err| UpdateGoalError::DatabaseError(err.to_string()))?;
+
+    let saved = save_update(
+        &client,
+        &goal,
+        &before,
+        was_completed,
+        due_at_changed,
+        &user_id,
+    )
+    .await;
+    if let Err(err) = saved {
+        if let Err(rollback_err) = client.batch_execute("ROLLBACK").await {
+            eprintln!("Goal update rollback error: {}", rollback_err);
+        }
+        return Err(err);
+    }
+
+    client
+        .batch_execute("COMMIT")
+        .await
+        .map_err(|err| UpdateGoalError::DatabaseError(err.to_string()))?;
+
+    Ok(GoalDto {
+        id: goal.get_id(),
+        text: goal.get_text(),
+        user_id: goal.get_user_id(),
+        organization_id: goal.get_organization_id(),
+        permission: None,
+        note_count: goal.get_note_count(),
+        due_at: goal.get_due_at().map(|date| date.to_rfc3339()),
+        completed_at: goal.get_completed_at().map(|date| date.to_rfc3339()),
+        archived_at: goal.get_archived_at().map(|date| date.to_rfc3339()),
+        deleted_at: goal.get_deleted_at().map(|date| date.to_rfc3339()),
+    })
+}
+
+// The goal, its revision, its events and the reminders following its due date are written
+// together, a failure leaves none of them
+async fn save_update(
+    client: &Client,
+    goal: &Goal,
+    before: &str,
+    was_completed: bool,
+    due_at_changed: bool,
+    user_id: &str,
+) -> Result<(), UpdateGoalError> {
+    goal_data_access::update_goal(client, goal)
+        .await
+        .map_err(|err| UpdateGoalError::DatabaseError(err.to_string()))?;
+
+    if due_at_changed {
+        match goal.get_due_at() {
+            None => delete_due_reminders(client, &goal.get_id()).await,
+            Some(due_at) => reschedule_due_reminders(client, &goal.get_id(), due_at).await,
+        }
+        .map_err(|err| UpdateGoalError::DatabaseError(err.to_string()))?;
+    }
+
+    let after = GoalRevision::snapshot(goal);
+    add_goal_revision(
+        client,
+        &goal.get_id(),
+        Some(user_id),
+        UPDATE_ACTION,
+        Some(before),
+        &after,
+    )
+    .await
+    .map_err(|err| UpdateGoalError::DatabaseError(err.to_string()))?;
+
+    let data = json!({
+        "id": goal.get_id(),
+        "text": goal.get_text(),
+        "user_id": goal.get_user_id(),
+        "organization_id": goal.get_organization_id(),
+        "due_at": goal.get_due_at().map(|date| date.to_rfc3339()),
+        "completed_at": goal.get_completed_at().map(|date| date.to_rfc3339()),
+        "updated_by": user_id,
+    });
+    add_goal_event(
+        client,
+        &goal.get_id(),
+        &goal.get_user_id(),
+        goal.get_organization_id(),
+        GOAL_UPDATED_EVENT,
+        &data,
+    )
+    .await
+    .map_err(|err| UpdateGoalError::DatabaseError(err.to_string()))?;
+    // The webhooks of the owner receive the event, whoever edited the goal
+    enqueue_event(
+        client,
+        &goal.get_user_id(),
+        &WebhookEvent::new(GOAL_UPDATED_EVENT, data.clone()),
+    )
+    .await
+    .map_err(|err| UpdateGoalError::DatabaseError(err.to_string()))?;
+
+    if !was_completed && goal.get_completed_at().is_some() {
+        add_goal_event(
+            client,
+            &goal.get_id(),
+            &goal.get_user_id(),
+            goal.get_organization_id(),
+            GOAL_COMPLETED_EVENT,
+            &data,
+        )
+        .await
+        .map_err(|err| UpdateGoalError::DatabaseError(err.to_string()))?;
+        enqueue_event(
+            client,
+            &goal.get_user_id(),
+            &WebhookEvent::new(GOAL_COMPLETED_EVENT, data),
+        )
+        .await
+        .map_err(|err| UpdateGoalError::DatabaseError(err.to_string()))?;
+    }
+
+    Ok(())
+}
+
+async fn get_connected_client() -> Result<Client, UpdateGoalError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| UpdateGoalError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), UpdateGoalError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| UpdateGoalError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(UpdateGoalError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(UpdateGoalError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn find_goal(client: &Client, goal_id: &str) -> Result<Goal, UpdateGoalError> {
+    let opt_goal = find_goal_by_id(client, goal_id)
+        .await
+        .map_err(|err| UpdateGoalError::DatabaseError(err.to_string()))?;
+
+    opt_goal.ok_or_else(|| {
+        UpdateGoalError::GoalNotFoundError(format!("Goal not found for the id: {}", goal_id))
+    })
+}
+
+// The owner, the members of the organization that can write goals and the users the goal is
+// shared with for edit can change it. Goals the user has no access to are reported as not found,
+// so their ids are not leaked
+async fn check_goal_write_access(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<(), UpdateGoalError> {
+    let can_write = has_goal_write_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| UpdateGoalError::DatabaseError(err.to_string()))?;
+    if can_write {
+        return Ok(());
+    }
+
+    let has_access = has_goal_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| UpdateGoalError::DatabaseError(err.to_string()))?;
+
+    match has_access {
+        true => Err(UpdateGoalError::ForbiddenError(
+            "The user can only view this goal".to_string(),
+        )),
+        false => Err(UpdateGoalError::GoalNotFoundError(format!(
+            "Goal not found for the id: {}",
+            goal_id
+        ))),
+    }
+}
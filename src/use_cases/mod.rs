@@ -1,2 +1,15 @@
 pub mod users;
 pub mod goals;
+pub mod api_keys;
+pub mod admin;
+pub mod organizations;
+pub mod goal_shares;
+pub mod share_links;
+pub mod comments;
+pub mod notes;
+pub mod reminders;
+pub mod notifications;
+pub mod webhooks;
+pub mod realtime;
+pub mod sync;
+pub mod calendar;
@@ -0,0 +1,102 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::{has_goal_access, has_goal_write_access},
+        note_data_access::{add_note, find_note_by_id},
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{
+        goal::Goal,
+        note::{CreateNoteDto, Note, NoteDto},
+    },
+    use_cases::notes::get_notes::map_to_dto,
+};
+
+pub enum CreateNoteError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    GoalNotFoundError(String),
+    ForbiddenError(String),
+    InvalidRequestError(String),
+}
+
+// Users that can write the goal add notes to its journal
+pub async fn execute(
+    goal_id: String,
+    new_note: CreateNoteDto,
+    user_id: String,
+) -> Result<NoteDto, CreateNoteError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| CreateNoteError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    check_goal_write_access(&client, &goal_id, &user_id).await?;
+
+    let note = Note::from_create_note_dto(new_note, &goal_id, &user_id)
+        .map_err(|err| CreateNoteError::InvalidRequestError(err.to_string()))?;
+
+    let id = add_note(&client, &note)
+        .await
+        .map_err(|err| CreateNoteError::DatabaseError(err.to_string()))?;
+
+    let note = find_note_by_id(&client, &id)
+        .await
+        .map_err(|err| CreateNoteError::DatabaseError(err.to_string()))?
+        .ok_or_else(|| CreateNoteError::DatabaseError("Note not saved".to_string()))?;
+
+    Ok(map_to_dto(&note))
+}
+
+async fn get_connected_client() -> Result<Client, CreateNoteError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| CreateNoteError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), CreateNoteError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| CreateNoteError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(CreateNoteError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(CreateNoteError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+// Goals the user cannot see are reported as not found, goals the user can only see as forbidden
+async fn check_goal_write_access(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<(), CreateNoteError> {
+    let can_write = has_goal_write_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| CreateNoteError::DatabaseError(err.to_string()))?;
+    if can_write {
+        return Ok(());
+    }
+
+    let has_access = has_goal_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| CreateNoteError::DatabaseError(err.to_string()))?;
+
+    match has_access {
+        true => Err(CreateNoteError::ForbiddenError(
+            "The user can only view this goal".to_string(),
+        )),
+        false => Err(CreateNoteError::GoalNotFoundError(format!(
+            "Goal not found for the id: {}",
+            goal_id
+        ))),
+    }
+}
@@ -0,0 +1,97 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::has_goal_access,
+        note_data_access::{self, find_note_by_id},
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{goal::Goal, note::Note},
+};
+
+pub enum DeleteNoteError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    NoteNotFoundError(String),
+    ForbiddenError(String),
+    InvalidRequestError(String),
+}
+
+// Only the author deletes a note
+pub async fn execute(
+    goal_id: String,
+    note_id: String,
+    user_id: String,
+) -> Result<(), DeleteNoteError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| DeleteNoteError::InvalidRequestError(err.to_string()))?;
+    Note::validate_id(&note_id)
+        .map_err(|err| DeleteNoteError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    let note = find_note(&client, &goal_id, &note_id, &user_id).await?;
+
+    if note.get_user_id() != user_id {
+        return Err(DeleteNoteError::ForbiddenError(
+            "Only the author can delete a note".to_string(),
+        ));
+    }
+
+    note_data_access::delete_note(&client, &note_id)
+        .await
+        .map_err(|err| DeleteNoteError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+async fn get_connected_client() -> Result<Client, DeleteNoteError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| DeleteNoteError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), DeleteNoteError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| DeleteNoteError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(DeleteNoteError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(DeleteNoteError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+// Notes of goals the user cannot see are reported as not found
+async fn find_note(
+    client: &Client,
+    goal_id: &str,
+    note_id: &str,
+    user_id: &str,
+) -> Result<Note, DeleteNoteError> {
+    let has_access = has_goal_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| DeleteNoteError::DatabaseError(err.to_string()))?;
+
+    let opt_note = match has_access {
+        false => None,
+        true => find_note_by_id(client, note_id)
+            .await
+            .map_err(|err| DeleteNoteError::DatabaseError(err.to_string()))?,
+    };
+
+    match opt_note {
+        Some(note) if note.get_goal_id() == goal_id => Ok(note),
+        _ => Err(DeleteNoteError::NoteNotFoundError(format!(
+            "Note not found for the id: {}",
+            note_id
+        ))),
+    }
+}
@@ -0,0 +1,98 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::has_goal_access, note_data_access::find_notes,
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{
+        goal::Goal,
+        note::{Note, NoteDto, NotesQueryDto},
+    },
+};
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 200;
+
+pub enum GetNotesError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    GoalNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// Every user with access to the goal reads its notes, in chronological order
+pub async fn execute(
+    goal_id: String,
+    query: NotesQueryDto,
+    user_id: String,
+) -> Result<Vec<NoteDto>, GetNotesError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| GetNotesError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    check_goal_access(&client, &goal_id, &user_id).await?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let notes_db = find_notes(&client, &goal_id, limit, offset)
+        .await
+        .map_err(|err| GetNotesError::DatabaseError(err.to_string()))?;
+
+    Ok(notes_db.iter().map(map_to_dto).collect())
+}
+
+async fn get_connected_client() -> Result<Client, GetNotesError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| GetNotesError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), GetNotesError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| GetNotesError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(GetNotesError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(GetNotesError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn check_goal_access(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<(), GetNotesError> {
+    let has_access = has_goal_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| GetNotesError::DatabaseError(err.to_string()))?;
+
+    if !has_access {
+        return Err(GetNotesError::GoalNotFoundError(format!(
+            "Goal not found for the id: {}",
+            goal_id
+        )));
+    }
+    Ok(())
+}
+
+pub fn map_to_dto(note: &Note) -> NoteDto {
+    NoteDto {
+        id: note.get_id(),
+        goal_id: note.get_goal_id(),
+        user_id: note.get_user_id(),
+        text: note.get_text(),
+        created_at: note.get_created_at().map(|date| date.to_rfc3339()),
+        updated_at: note.get_updated_at().map(|date| date.to_rfc3339()),
+    }
+}
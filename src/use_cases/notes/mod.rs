@@ -0,0 +1,4 @@
+pub mod create_note;
+pub mod get_notes;
+pub mod update_note;
+pub mod delete_note;
@@ -0,0 +1,108 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::has_goal_access,
+        note_data_access::{find_note_by_id, update_note_text},
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{
+        goal::Goal,
+        note::{Note, NoteDto, UpdateNoteDto},
+    },
+    use_cases::notes::get_notes::map_to_dto,
+};
+
+pub enum UpdateNoteError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    NoteNotFoundError(String),
+    ForbiddenError(String),
+    InvalidRequestError(String),
+}
+
+// Only the author edits a note, while they still have access to the goal
+pub async fn execute(
+    goal_id: String,
+    note_id: String,
+    update_note: UpdateNoteDto,
+    user_id: String,
+) -> Result<NoteDto, UpdateNoteError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| UpdateNoteError::InvalidRequestError(err.to_string()))?;
+    Note::validate_id(&note_id)
+        .map_err(|err| UpdateNoteError::InvalidRequestError(err.to_string()))?;
+    Note::validate_text(&update_note.text)
+        .map_err(|err| UpdateNoteError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    let note = find_note(&client, &goal_id, &note_id, &user_id).await?;
+
+    if note.get_user_id() != user_id {
+        return Err(UpdateNoteError::ForbiddenError(
+            "Only the author can edit a note".to_string(),
+        ));
+    }
+
+    if note.get_text() != update_note.text {
+        update_note_text(&client, &note_id, &update_note.text)
+            .await
+            .map_err(|err| UpdateNoteError::DatabaseError(err.to_string()))?;
+    }
+
+    let note = find_note(&client, &goal_id, &note_id, &user_id).await?;
+
+    Ok(map_to_dto(&note))
+}
+
+async fn get_connected_client() -> Result<Client, UpdateNoteError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| UpdateNoteError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), UpdateNoteError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| UpdateNoteError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(UpdateNoteError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(UpdateNoteError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+// Notes of goals the user cannot see are reported as not found
+async fn find_note(
+    client: &Client,
+    goal_id: &str,
+    note_id: &str,
+    user_id: &str,
+) -> Result<Note, UpdateNoteError> {
+    let has_access = has_goal_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| UpdateNoteError::DatabaseError(err.to_string()))?;
+
+    let opt_note = match has_access {
+        false => None,
+        true => find_note_by_id(client, note_id)
+            .await
+            .map_err(|err| UpdateNoteError::DatabaseError(err.to_string()))?,
+    };
+
+    match opt_note {
+        Some(note) if note.get_goal_id() == goal_id => Ok(note),
+        _ => Err(UpdateNoteError::NoteNotFoundError(format!(
+            "Note not found for the id: {}",
+            note_id
+        ))),
+    }
+}
@@ -0,0 +1,63 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{notification_data_access, user_data_access::find_user_by_id},
+    db::establish_connection,
+    entities::notification::Notification,
+};
+
+pub enum DeleteNotificationError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    NotificationNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// Notifications of other users are reported as not found
+pub async fn execute(
+    notification_id: String,
+    user_id: String,
+) -> Result<(), DeleteNotificationError> {
+    Notification::validate_id(&notification_id)
+        .map_err(|err| DeleteNotificationError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+
+    let deleted =
+        notification_data_access::delete_notification(&client, &notification_id, &user_id)
+            .await
+            .map_err(|err| DeleteNotificationError::DatabaseError(err.to_string()))?;
+
+    if deleted == 0 {
+        return Err(DeleteNotificationError::NotificationNotFoundError(format!(
+            "Notification not found for the id: {}",
+            notification_id
+        )));
+    }
+
+    Ok(())
+}
+
+async fn get_connected_client() -> Result<Client, DeleteNotificationError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| DeleteNotificationError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), DeleteNotificationError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| DeleteNotificationError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(DeleteNotificationError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(DeleteNotificationError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
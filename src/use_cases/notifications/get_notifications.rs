@@ -0,0 +1,104 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        notification_data_access::{count_unread_notifications, find_notifications},
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::notification::{
+        Notification, NotificationDto, NotificationPageDto, NotificationsQueryDto,
+    },
+};
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 200;
+
+pub enum GetNotificationsError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// Newest first. One extra notification is read to know if there is a next page
+pub async fn execute(
+    query: NotificationsQueryDto,
+    user_id: String,
+) -> Result<NotificationPageDto, GetNotificationsError> {
+    let cursor = match &query.cursor {
+        None => None,
+        Some(cursor) => Some(
+            Notification::decode_cursor(cursor)
+                .map_err(|err| GetNotificationsError::InvalidRequestError(err.to_string()))?,
+        ),
+    };
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+
+    let mut notifications_db = find_notifications(
+        &client,
+        &user_id,
+        query.unread.unwrap_or(false),
+        cursor,
+        limit + 1,
+    )
+    .await
+    .map_err(|err| GetNotificationsError::DatabaseError(err.to_string()))?;
+
+    let has_next_page = notifications_db.len() as i64 > limit;
+    notifications_db.truncate(limit as usize);
+    let next_cursor = match notifications_db.last() {
+        Some(last) if has_next_page => last
+            .get_created_at()
+            .map(|created_at| Notification::encode_cursor(&created_at, &last.get_id())),
+        _ => None,
+    };
+
+    let unread_count = count_unread_notifications(&client, &user_id)
+        .await
+        .map_err(|err| GetNotificationsError::DatabaseError(err.to_string()))?;
+
+    Ok(NotificationPageDto {
+        notifications: notifications_db.iter().map(map_to_dto).collect(),
+        unread_count,
+        next_cursor,
+    })
+}
+
+async fn get_connected_client() -> Result<Client, GetNotificationsError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| GetNotificationsError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), GetNotificationsError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| GetNotificationsError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(GetNotificationsError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(GetNotificationsError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+pub fn map_to_dto(notification: &Notification) -> NotificationDto {
+    NotificationDto {
+        id: notification.get_id(),
+        kind: notification.get_kind(),
+        goal_id: notification.get_goal_id(),
+        actor_id: notification.get_actor_id(),
+        text: notification.get_text(),
+        read: notification.get_read_at().is_some(),
+        read_at: notification.get_read_at().map(|date| date.to_rfc3339()),
+        created_at: notification.get_created_at().map(|date| date.to_rfc3339()),
+    }
+}
@@ -0,0 +1,48 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        notification_data_access::count_unread_notifications, user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::notification::UnreadCountDto,
+};
+
+pub enum GetUnreadCountError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+}
+
+pub async fn execute(user_id: String) -> Result<UnreadCountDto, GetUnreadCountError> {
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+
+    let unread_count = count_unread_notifications(&client, &user_id)
+        .await
+        .map_err(|err| GetUnreadCountError::DatabaseError(err.to_string()))?;
+
+    Ok(UnreadCountDto { unread_count })
+}
+
+async fn get_connected_client() -> Result<Client, GetUnreadCountError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| GetUnreadCountError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), GetUnreadCountError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| GetUnreadCountError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(GetUnreadCountError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(GetUnreadCountError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
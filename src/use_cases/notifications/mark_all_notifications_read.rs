@@ -0,0 +1,49 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        notification_data_access::mark_all_notifications_read, user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::notification::UnreadCountDto,
+};
+
+pub enum MarkAllNotificationsReadError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+}
+
+// Returns the unread count after the update, always 0 unless a notification arrived meanwhile
+pub async fn execute(user_id: String) -> Result<UnreadCountDto, MarkAllNotificationsReadError> {
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+
+    mark_all_notifications_read(&client, &user_id)
+        .await
+        .map_err(|err| MarkAllNotificationsReadError::DatabaseError(err.to_string()))?;
+
+    Ok(UnreadCountDto { unread_count: 0 })
+}
+
+async fn get_connected_client() -> Result<Client, MarkAllNotificationsReadError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| MarkAllNotificationsReadError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), MarkAllNotificationsReadError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| MarkAllNotificationsReadError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(MarkAllNotificationsReadError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(MarkAllNotificationsReadError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
@@ -0,0 +1,65 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        notification_data_access::set_notification_read, user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::notification::Notification,
+};
+
+pub enum MarkNotificationReadError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    NotificationNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// Marks the notification as read or unread. Notifications of other users are reported as not
+// found
+pub async fn execute(
+    notification_id: String,
+    read: bool,
+    user_id: String,
+) -> Result<(), MarkNotificationReadError> {
+    Notification::validate_id(&notification_id)
+        .map_err(|err| MarkNotificationReadError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+
+    let updated = set_notification_read(&client, &notification_id, &user_id, read)
+        .await
+        .map_err(|err| MarkNotificationReadError::DatabaseError(err.to_string()))?;
+
+    if updated == 0 {
+        return Err(MarkNotificationReadError::NotificationNotFoundError(
+            format!("Notification not found for the id: {}", notification_id),
+        ));
+    }
+
+    Ok(())
+}
+
+async fn get_connected_client() -> Result<Client, MarkNotificationReadError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| MarkNotificationReadError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), MarkNotificationReadError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| MarkNotificationReadError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(MarkNotificationReadError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(MarkNotificationReadError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
@@ -0,0 +1,5 @@
+pub mod get_notifications;
+pub mod get_unread_count;
+pub mod mark_notification_read;
+pub mod mark_all_notifications_read;
+pub mod delete_notification;
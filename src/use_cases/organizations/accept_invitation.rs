@@ -0,0 +1,101 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        invitation_data_access::{
+            find_pending_invitation_by_token_hash, update_invitation_status, ACCEPTED_STATUS,
+        },
+        organization_data_access::add_organization_member,
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{
+        invitation::{Invitation, InvitationTokenDto},
+        organization::OrganizationMember,
+        user::User,
+    },
+    services::token_services::hash_token,
+};
+
+pub enum AcceptInvitationError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    InvitationNotFoundError(String),
+    ForbiddenError(String),
+}
+
+// Joins the organization with the role of the invitation. The invitation must be for the e-mail of
+// the signed in user
+pub async fn execute(
+    invitation_token: InvitationTokenDto,
+    user_id: String,
+) -> Result<(), AcceptInvitationError> {
+    let client = get_connected_client().await?;
+    let user = find_user(&client, &user_id).await?;
+    let invitation = find_invitation(&client, &invitation_token.token, &user).await?;
+
+    let member = OrganizationMember::from_db_fields(
+        &invitation.get_organization_id(),
+        &user_id,
+        &invitation.get_role(),
+    )
+    .map_err(|err| AcceptInvitationError::DatabaseError(err.to_string()))?;
+
+    add_organization_member(&client, &member)
+        .await
+        .map_err(|err| AcceptInvitationError::DatabaseError(err.to_string()))?;
+
+    update_invitation_status(&client, &invitation.get_id(), ACCEPTED_STATUS)
+        .await
+        .map_err(|err| AcceptInvitationError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+async fn get_connected_client() -> Result<Client, AcceptInvitationError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| AcceptInvitationError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<User, AcceptInvitationError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| AcceptInvitationError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(AcceptInvitationError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(AcceptInvitationError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(user) => Ok(user),
+    }
+}
+
+async fn find_invitation(
+    client: &Client,
+    token: &str,
+    user: &User,
+) -> Result<Invitation, AcceptInvitationError> {
+    let opt_invitation = find_pending_invitation_by_token_hash(client, &hash_token(token))
+        .await
+        .map_err(|err| AcceptInvitationError::DatabaseError(err.to_string()))?;
+
+    match opt_invitation {
+        None => Err(AcceptInvitationError::InvitationNotFoundError(
+            "Invitation not found or already answered".to_string(),
+        )),
+        Some(invitation) if invitation.is_expired() => Err(
+            AcceptInvitationError::InvitationNotFoundError("The invitation expired".to_string()),
+        ),
+        Some(invitation) if !invitation.is_for_email(&user.get_email()) => {
+            Err(AcceptInvitationError::ForbiddenError(
+                "The invitation is for another e-mail".to_string(),
+            ))
+        }
+        Some(invitation) => Ok(invitation),
+    }
+}
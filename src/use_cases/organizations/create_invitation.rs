@@ -0,0 +1,102 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        invitation_data_access::add_invitation, organization_data_access::find_organization_member,
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{
+        invitation::{CreateInvitationDto, CreatedInvitationDto, Invitation},
+        organization::Organization,
+    },
+    services::token_services::{generate_random_token, hash_token},
+};
+
+pub enum CreateInvitationError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    InvalidRequestError(String),
+    ForbiddenError(String),
+}
+
+// Invites an e-mail to the organization. The token is returned only here and must be shared with
+// the invited person, who accepts or declines it once signed in with that e-mail
+pub async fn execute(
+    organization_id: String,
+    new_invitation: CreateInvitationDto,
+    user_id: String,
+) -> Result<CreatedInvitationDto, CreateInvitationError> {
+    Organization::validate_id(&organization_id)
+        .map_err(|err| CreateInvitationError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+
+    let token = generate_random_token(32);
+    let invitation = Invitation::from_create_invitation_dto(
+        new_invitation,
+        &organization_id,
+        &hash_token(&token),
+    )
+    .map_err(|err| CreateInvitationError::InvalidRequestError(err.to_string()))?;
+
+    check_can_invite(&client, &organization_id, &user_id, &invitation.get_role()).await?;
+
+    let id = add_invitation(&client, &invitation, &user_id)
+        .await
+        .map_err(|err| CreateInvitationError::DatabaseError(err.to_string()))?;
+
+    Ok(CreatedInvitationDto {
+        id,
+        organization_id,
+        email: invitation.get_email(),
+        role: invitation.get_role(),
+        expires_at: invitation.get_expires_at().to_rfc3339(),
+        token,
+    })
+}
+
+async fn get_connected_client() -> Result<Client, CreateInvitationError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| CreateInvitationError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), CreateInvitationError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| CreateInvitationError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(CreateInvitationError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(CreateInvitationError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn check_can_invite(
+    client: &Client,
+    organization_id: &str,
+    user_id: &str,
+    role: &str,
+) -> Result<(), CreateInvitationError> {
+    let opt_member = find_organization_member(client, organization_id, user_id)
+        .await
+        .map_err(|err| CreateInvitationError::DatabaseError(err.to_string()))?;
+
+    match opt_member {
+        Some(member) if member.can_assign_role(role) => Ok(()),
+        Some(_) => Err(CreateInvitationError::ForbiddenError(
+            "The user is not allowed to invite with this role".to_string(),
+        )),
+        None => Err(CreateInvitationError::ForbiddenError(
+            "The user is not a member of the organization".to_string(),
+        )),
+    }
+}
@@ -0,0 +1,58 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{organization_data_access::add_organization, user_data_access::find_user_by_id},
+    db::establish_connection,
+    entities::organization::{CreateOrganizationDto, Organization, OrganizationDto, OWNER_ROLE},
+};
+
+pub enum CreateOrganizationError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// The user creating the organization becomes its owner
+pub async fn execute(
+    new_organization: CreateOrganizationDto,
+    user_id: String,
+) -> Result<OrganizationDto, CreateOrganizationError> {
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+
+    let organization = Organization::from_create_organization_dto(new_organization)
+        .map_err(|err| CreateOrganizationError::InvalidRequestError(err.to_string()))?;
+
+    let id = add_organization(&client, &organization, &user_id)
+        .await
+        .map_err(|err| CreateOrganizationError::DatabaseError(err.to_string()))?;
+
+    Ok(OrganizationDto {
+        id,
+        name: organization.get_name(),
+        role: OWNER_ROLE.to_string(),
+    })
+}
+
+async fn get_connected_client() -> Result<Client, CreateOrganizationError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| CreateOrganizationError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), CreateOrganizationError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| CreateOrganizationError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(CreateOrganizationError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(CreateOrganizationError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
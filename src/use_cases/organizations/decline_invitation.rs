@@ -0,0 +1,84 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        invitation_data_access::{
+            find_pending_invitation_by_token_hash, update_invitation_status, DECLINED_STATUS,
+        },
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{
+        invitation::{Invitation, InvitationTokenDto},
+        user::User,
+    },
+    services::token_services::hash_token,
+};
+
+pub enum DeclineInvitationError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    InvitationNotFoundError(String),
+    ForbiddenError(String),
+}
+
+pub async fn execute(
+    invitation_token: InvitationTokenDto,
+    user_id: String,
+) -> Result<(), DeclineInvitationError> {
+    let client = get_connected_client().await?;
+    let user = find_user(&client, &user_id).await?;
+    let invitation = find_invitation(&client, &invitation_token.token, &user).await?;
+
+    update_invitation_status(&client, &invitation.get_id(), DECLINED_STATUS)
+        .await
+        .map_err(|err| DeclineInvitationError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+async fn get_connected_client() -> Result<Client, DeclineInvitationError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| DeclineInvitationError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<User, DeclineInvitationError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| DeclineInvitationError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(DeclineInvitationError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(DeclineInvitationError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(user) => Ok(user),
+    }
+}
+
+// Expired invitations can still be declined, so they stop showing as pending
+async fn find_invitation(
+    client: &Client,
+    token: &str,
+    user: &User,
+) -> Result<Invitation, DeclineInvitationError> {
+    let opt_invitation = find_pending_invitation_by_token_hash(client, &hash_token(token))
+        .await
+        .map_err(|err| DeclineInvitationError::DatabaseError(err.to_string()))?;
+
+    match opt_invitation {
+        None => Err(DeclineInvitationError::InvitationNotFoundError(
+            "Invitation not found or already answered".to_string(),
+        )),
+        Some(invitation) if !invitation.is_for_email(&user.get_email()) => {
+            Err(DeclineInvitationError::ForbiddenError(
+                "The invitation is for another e-mail".to_string(),
+            ))
+        }
+        Some(invitation) => Ok(invitation),
+    }
+}
@@ -0,0 +1,91 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        organization_data_access::{find_organization_member, find_organization_members},
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::organization::{MemberDto, Organization},
+};
+
+pub enum GetMembersError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    InvalidRequestError(String),
+    ForbiddenError(String),
+}
+
+// Every member of the organization can see the other members
+pub async fn execute(
+    organization_id: String,
+    user_id: String,
+) -> Result<Vec<MemberDto>, GetMembersError> {
+    Organization::validate_id(&organization_id)
+        .map_err(|err| GetMembersError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    check_is_member(&client, &organization_id, &user_id).await?;
+
+    let members_db = find_organization_members(&client, &organization_id)
+        .await
+        .map_err(|err| GetMembersError::DatabaseError(err.to_string()))?;
+
+    let mut members_dto = Vec::new();
+    for member in members_db {
+        let opt_user = find_user_by_id(&client, &member.get_user_id())
+            .await
+            .map_err(|err| GetMembersError::DatabaseError(err.to_string()))?;
+        if let Some(user) = opt_user {
+            members_dto.push(MemberDto {
+                user_id: member.get_user_id(),
+                name: user.get_name(),
+                email: user.get_email(),
+                role: member.get_role(),
+            });
+        }
+    }
+
+    Ok(members_dto)
+}
+
+async fn get_connected_client() -> Result<Client, GetMembersError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| GetMembersError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), GetMembersError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| GetMembersError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(GetMembersError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(GetMembersError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn check_is_member(
+    client: &Client,
+    organization_id: &str,
+    user_id: &str,
+) -> Result<(), GetMembersError> {
+    let opt_member = find_organization_member(client, organization_id, user_id)
+        .await
+        .map_err(|err| GetMembersError::DatabaseError(err.to_string()))?;
+
+    match opt_member {
+        Some(_) => Ok(()),
+        None => Err(GetMembersError::ForbiddenError(
+            "The user is not a member of the organization".to_string(),
+        )),
+    }
+}
@@ -0,0 +1,57 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        organization_data_access::find_organizations_by_user, user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::organization::OrganizationDto,
+};
+
+pub enum GetOrganizationsError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+}
+
+pub async fn execute(user_id: String) -> Result<Vec<OrganizationDto>, GetOrganizationsError> {
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+
+    let organizations_db = find_organizations_by_user(&client, &user_id)
+        .await
+        .map_err(|err| GetOrganizationsError::DatabaseError(err.to_string()))?;
+
+    let mut organizations_dto = Vec::new();
+    for (organization, member) in organizations_db {
+        organizations_dto.push(OrganizationDto {
+            id: organization.get_id(),
+            name: organization.get_name(),
+            role: member.get_role(),
+        });
+    }
+
+    Ok(organizations_dto)
+}
+
+async fn get_connected_client() -> Result<Client, GetOrganizationsError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| GetOrganizationsError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), GetOrganizationsError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| GetOrganizationsError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(GetOrganizationsError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(GetOrganizationsError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
@@ -0,0 +1,8 @@
+pub mod accept_invitation;
+pub mod create_invitation;
+pub mod create_organization;
+pub mod decline_invitation;
+pub mod get_members;
+pub mod get_organizations;
+pub mod remove_member;
+pub mod update_member;
@@ -0,0 +1,121 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        organization_data_access::{
+            count_organization_owners, delete_organization_member, find_organization_member,
+        },
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{
+        organization::{Organization, OrganizationMember},
+        user::User,
+    },
+};
+
+pub enum RemoveMemberError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    MemberNotFoundError(String),
+    InvalidRequestError(String),
+    ForbiddenError(String),
+}
+
+// Removes a member from the organization. Any member can leave, removing someone else follows the
+// same rules as changing their role. The last owner can neither leave nor be removed
+pub async fn execute(
+    organization_id: String,
+    member_id: String,
+    user_id: String,
+) -> Result<(), RemoveMemberError> {
+    Organization::validate_id(&organization_id)
+        .map_err(|err| RemoveMemberError::InvalidRequestError(err.to_string()))?;
+    User::validate_id(&member_id)
+        .map_err(|err| RemoveMemberError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+
+    let actor = find_member(&client, &organization_id, &user_id)
+        .await?
+        .ok_or_else(|| {
+            RemoveMemberError::ForbiddenError(
+                "The user is not a member of the organization".to_string(),
+            )
+        })?;
+    let member = find_member(&client, &organization_id, &member_id)
+        .await?
+        .ok_or_else(|| {
+            RemoveMemberError::MemberNotFoundError(format!(
+                "Member not found for the user id: {}",
+                member_id
+            ))
+        })?;
+
+    let is_leaving = member_id == user_id;
+    if !is_leaving && !actor.can_assign_role(&member.get_role()) {
+        return Err(RemoveMemberError::ForbiddenError(
+            "The user is not allowed to remove this member".to_string(),
+        ));
+    }
+
+    if member.is_owner() {
+        check_is_not_last_owner(&client, &organization_id).await?;
+    }
+
+    delete_organization_member(&client, &organization_id, &member_id)
+        .await
+        .map_err(|err| RemoveMemberError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+async fn get_connected_client() -> Result<Client, RemoveMemberError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| RemoveMemberError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), RemoveMemberError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| RemoveMemberError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(RemoveMemberError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(RemoveMemberError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn find_member(
+    client: &Client,
+    organization_id: &str,
+    user_id: &str,
+) -> Result<Option<OrganizationMember>, RemoveMemberError> {
+    find_organization_member(client, organization_id, user_id)
+        .await
+        .map_err(|err| RemoveMemberError::DatabaseError(err.to_string()))
+}
+
+async fn check_is_not_last_owner(
+    client: &Client,
+    organization_id: &str,
+) -> Result<(), RemoveMemberError> {
+    let owners_count = count_organization_owners(client, organization_id)
+        .await
+        .map_err(|err| RemoveMemberError::DatabaseError(err.to_string()))?;
+
+    if owners_count <= 1 {
+        return Err(RemoveMemberError::ForbiddenError(
+            "The last owner of the organization cannot be removed".to_string(),
+        ));
+    }
+    Ok(())
+}
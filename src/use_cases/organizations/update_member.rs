@@ -0,0 +1,132 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        organization_data_access::{
+            count_organization_owners, find_organization_member, update_organization_member_role,
+        },
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{
+        organization::{Organization, OrganizationMember, UpdateMemberDto, OWNER_ROLE},
+        user::User,
+    },
+};
+
+pub enum UpdateMemberError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    MemberNotFoundError(String),
+    InvalidRequestError(String),
+    ForbiddenError(String),
+}
+
+// Changes the role of a member. Admins manage members and viewers, only owners manage owners and
+// admins. The last owner of an organization cannot be demoted
+pub async fn execute(
+    organization_id: String,
+    member_id: String,
+    update_member: UpdateMemberDto,
+    user_id: String,
+) -> Result<(), UpdateMemberError> {
+    validate_request(&organization_id, &member_id, &update_member)?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+
+    let actor = find_member(&client, &organization_id, &user_id)
+        .await?
+        .ok_or_else(|| {
+            UpdateMemberError::ForbiddenError(
+                "The user is not a member of the organization".to_string(),
+            )
+        })?;
+    let member = find_member(&client, &organization_id, &member_id)
+        .await?
+        .ok_or_else(|| {
+            UpdateMemberError::MemberNotFoundError(format!(
+                "Member not found for the user id: {}",
+                member_id
+            ))
+        })?;
+
+    if !actor.can_assign_role(&member.get_role()) || !actor.can_assign_role(&update_member.role) {
+        return Err(UpdateMemberError::ForbiddenError(
+            "The user is not allowed to change the role of this member".to_string(),
+        ));
+    }
+
+    if member.is_owner() && update_member.role != OWNER_ROLE {
+        check_is_not_last_owner(&client, &organization_id).await?;
+    }
+
+    update_organization_member_role(&client, &organization_id, &member_id, &update_member.role)
+        .await
+        .map_err(|err| UpdateMemberError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+fn validate_request(
+    organization_id: &str,
+    member_id: &str,
+    update_member: &UpdateMemberDto,
+) -> Result<(), UpdateMemberError> {
+    Organization::validate_id(organization_id)
+        .map_err(|err| UpdateMemberError::InvalidRequestError(err.to_string()))?;
+    User::validate_id(member_id)
+        .map_err(|err| UpdateMemberError::InvalidRequestError(err.to_string()))?;
+    OrganizationMember::validate_role(&update_member.role)
+        .map_err(|err| UpdateMemberError::InvalidRequestError(err.to_string()))?;
+    Ok(())
+}
+
+async fn get_connected_client() -> Result<Client, UpdateMemberError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| UpdateMemberError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), UpdateMemberError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| UpdateMemberError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(UpdateMemberError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(UpdateMemberError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn find_member(
+    client: &Client,
+    organization_id: &str,
+    user_id: &str,
+) -> Result<Option<OrganizationMember>, UpdateMemberError> {
+    find_organization_member(client, organization_id, user_id)
+        .await
+        .map_err(|err| UpdateMemberError::DatabaseError(err.to_string()))
+}
+
+async fn check_is_not_last_owner(
+    client: &Client,
+    organization_id: &str,
+) -> Result<(), UpdateMemberError> {
+    let owners_count = count_organization_owners(client, organization_id)
+        .await
+        .map_err(|err| UpdateMemberError::DatabaseError(err.to_string()))?;
+
+    if owners_count <= 1 {
+        return Err(UpdateMemberError::ForbiddenError(
+            "The last owner of the organization cannot be demoted".to_string(),
+        ));
+    }
+    Ok(())
+}
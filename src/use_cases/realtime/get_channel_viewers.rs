@@ -0,0 +1,42 @@
+use tokio_postgres::Client;
+
+use crate::{
+    config::get_env_parsed,
+    data_access::channel_presence_data_access::find_channel_viewers,
+    db::establish_connection,
+    entities::channel::{ChannelViewer, ViewerDto},
+};
+
+pub enum GetChannelViewersError {
+    DatabaseError(String),
+}
+
+// Presences not refreshed for PRESENCE_TTL_SECONDS (default 90) belong to connections that are gone
+pub fn get_presence_ttl_seconds() -> f64 {
+    get_env_parsed("PRESENCE_TTL_SECONDS", 90.0)
+}
+
+// Only called for the channels the connection joined, the access is checked when joining
+pub async fn execute(channel: String) -> Result<Vec<ViewerDto>, GetChannelViewersError> {
+    let client = get_connected_client().await?;
+
+    let viewers = find_channel_viewers(&client, &channel, get_presence_ttl_seconds())
+        .await
+        .map_err(|err| GetChannelViewersError::DatabaseError(err.to_string()))?;
+
+    Ok(viewers.iter().map(map_to_dto).collect())
+}
+
+async fn get_connected_client() -> Result<Client, GetChannelViewersError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| GetChannelViewersError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+pub fn map_to_dto(viewer: &ChannelViewer) -> ViewerDto {
+    ViewerDto {
+        user_id: viewer.get_user_id(),
+        name: viewer.get_name(),
+    }
+}
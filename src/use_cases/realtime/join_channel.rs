@@ -0,0 +1,110 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        channel_presence_data_access::{add_presence, find_channel_viewers},
+        goal_data_access::has_goal_access,
+        organization_data_access::find_organization_member,
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::channel::{Channel, ViewerDto, GOAL_CHANNEL, ORGANIZATION_CHANNEL},
+};
+
+use super::get_channel_viewers::{get_presence_ttl_seconds, map_to_dto};
+
+pub enum JoinChannelError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    InvalidRequestError(String),
+    ForbiddenError(String),
+    NotFoundError(String),
+}
+
+// Adds the presence of the connection to the channel and returns its viewers, the user included.
+// Goal channels need access to the goal, organization channels a membership and user channels are
+// only open to the user
+pub async fn execute(
+    user_id: String,
+    connection_id: String,
+    channel_name: String,
+) -> Result<Vec<ViewerDto>, JoinChannelError> {
+    let channel = Channel::from_name(&channel_name)
+        .map_err(|err| JoinChannelError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    check_can_join(&client, &channel, &user_id).await?;
+
+    add_presence(&client, &connection_id, &channel.get_name(), &user_id)
+        .await
+        .map_err(|err| JoinChannelError::DatabaseError(err.to_string()))?;
+
+    let viewers = find_channel_viewers(&client, &channel.get_name(), get_presence_ttl_seconds())
+        .await
+        .map_err(|err| JoinChannelError::DatabaseError(err.to_string()))?;
+
+    Ok(viewers.iter().map(map_to_dto).collect())
+}
+
+async fn get_connected_client() -> Result<Client, JoinChannelError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| JoinChannelError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), JoinChannelError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| JoinChannelError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(JoinChannelError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(JoinChannelError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+// Goals the user has no access to are reported as not found, so their ids are not leaked
+async fn check_can_join(
+    client: &Client,
+    channel: &Channel,
+    user_id: &str,
+) -> Result<(), JoinChannelError> {
+    let resource_id = channel.get_resource_id();
+
+    match channel.get_kind().as_str() {
+        GOAL_CHANNEL => {
+            let has_access = has_goal_access(client, &resource_id, user_id)
+                .await
+                .map_err(|err| JoinChannelError::DatabaseError(err.to_string()))?;
+            match has_access {
+                true => Ok(()),
+                false => Err(JoinChannelError::NotFoundError(format!(
+                    "Goal not found for the id: {}",
+                    resource_id
+                ))),
+            }
+        }
+        ORGANIZATION_CHANNEL => {
+            let opt_member = find_organization_member(client, &resource_id, user_id)
+                .await
+                .map_err(|err| JoinChannelError::DatabaseError(err.to_string()))?;
+            match opt_member {
+                Some(_) => Ok(()),
+                None => Err(JoinChannelError::ForbiddenError(
+                    "The user is not a member of the organization".to_string(),
+                )),
+            }
+        }
+        _ if resource_id == user_id => Ok(()),
+        _ => Err(JoinChannelError::ForbiddenError(
+            "Only the user can join their own channel".to_string(),
+        )),
+    }
+}
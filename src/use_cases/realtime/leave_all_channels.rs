@@ -0,0 +1,25 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::channel_presence_data_access::delete_connection_presence, db::establish_connection,
+};
+
+pub enum LeaveAllChannelsError {
+    DatabaseError(String),
+}
+
+// Called when a connection closes, returns the number of channels left
+pub async fn execute(connection_id: String) -> Result<u64, LeaveAllChannelsError> {
+    let client = get_connected_client().await?;
+
+    delete_connection_presence(&client, &connection_id)
+        .await
+        .map_err(|err| LeaveAllChannelsError::DatabaseError(err.to_string()))
+}
+
+async fn get_connected_client() -> Result<Client, LeaveAllChannelsError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| LeaveAllChannelsError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
@@ -0,0 +1,40 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::channel_presence_data_access::delete_presence, db::establish_connection,
+    entities::channel::Channel,
+};
+
+pub enum LeaveChannelError {
+    DatabaseError(String),
+    InvalidRequestError(String),
+    NotFoundError(String),
+}
+
+// Removes the presence of the connection from the channel, its other viewers are notified
+pub async fn execute(connection_id: String, channel_name: String) -> Result<(), LeaveChannelError> {
+    let channel = Channel::from_name(&channel_name)
+        .map_err(|err| LeaveChannelError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+
+    let deleted = delete_presence(&client, &connection_id, &channel.get_name())
+        .await
+        .map_err(|err| LeaveChannelError::DatabaseError(err.to_string()))?;
+
+    if deleted == 0 {
+        return Err(LeaveChannelError::NotFoundError(format!(
+            "The connection has not joined the channel: {}",
+            channel.get_name()
+        )));
+    }
+
+    Ok(())
+}
+
+async fn get_connected_client() -> Result<Client, LeaveChannelError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| LeaveChannelError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
@@ -0,0 +1,6 @@
+pub mod get_channel_viewers;
+pub mod join_channel;
+pub mod leave_channel;
+pub mod leave_all_channels;
+pub mod refresh_presence;
+pub mod purge_stale_presence;
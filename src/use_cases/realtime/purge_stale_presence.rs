@@ -0,0 +1,28 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::channel_presence_data_access::delete_stale_presence, db::establish_connection,
+};
+
+use super::get_channel_viewers::get_presence_ttl_seconds;
+
+pub enum PurgeStalePresenceError {
+    DatabaseError(String),
+}
+
+// Deletes the presences of the connections that stopped refreshing them, an instance that stopped
+// cannot remove its own. Returns how many were deleted
+pub async fn execute() -> Result<u64, PurgeStalePresenceError> {
+    let client = get_connected_client().await?;
+
+    delete_stale_presence(&client, get_presence_ttl_seconds())
+        .await
+        .map_err(|err| PurgeStalePresenceError::DatabaseError(err.to_string()))
+}
+
+async fn get_connected_client() -> Result<Client, PurgeStalePresenceError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| PurgeStalePresenceError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
@@ -0,0 +1,25 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::channel_presence_data_access::touch_connection_presence, db::establish_connection,
+};
+
+pub enum RefreshPresenceError {
+    DatabaseError(String),
+}
+
+// Keeps the presences of an open connection from expiring, returns the number of channels refreshed
+pub async fn execute(connection_id: String) -> Result<u64, RefreshPresenceError> {
+    let client = get_connected_client().await?;
+
+    touch_connection_presence(&client, &connection_id)
+        .await
+        .map_err(|err| RefreshPresenceError::DatabaseError(err.to_string()))
+}
+
+async fn get_connected_client() -> Result<Client, RefreshPresenceError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| RefreshPresenceError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
@@ -0,0 +1,103 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::{find_goal_by_id, has_goal_access},
+        reminder_data_access::{add_reminder, find_reminder_by_id},
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{
+        goal::Goal,
+        reminder::{CreateReminderDto, Reminder, ReminderDto},
+    },
+    services::outbound_url_services::check_public_url,
+    use_cases::reminders::get_reminders::map_to_dto,
+};
+
+pub enum CreateReminderError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    GoalNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// Every user with access to the goal sets reminders for themselves
+pub async fn execute(
+    goal_id: String,
+    new_reminder: CreateReminderDto,
+    user_id: String,
+) -> Result<ReminderDto, CreateReminderError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| CreateReminderError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    let goal = find_goal(&client, &goal_id, &user_id).await?;
+
+    let reminder =
+        Reminder::from_create_reminder_dto(new_reminder, &goal_id, &user_id, goal.get_due_at())
+            .map_err(|err| CreateReminderError::InvalidRequestError(err.to_string()))?;
+
+    if let Some(webhook_url) = reminder.get_webhook_url() {
+        check_public_url(&webhook_url)
+            .await
+            .map_err(CreateReminderError::InvalidRequestError)?;
+    }
+
+    let id = add_reminder(&client, &reminder)
+        .await
+        .map_err(|err| CreateReminderError::DatabaseError(err.to_string()))?;
+
+    let reminder = find_reminder_by_id(&client, &id)
+        .await
+        .map_err(|err| CreateReminderError::DatabaseError(err.to_string()))?
+        .ok_or_else(|| CreateReminderError::DatabaseError("Reminder not saved".to_string()))?;
+
+    Ok(map_to_dto(&reminder))
+}
+
+async fn get_connected_client() -> Result<Client, CreateReminderError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| CreateReminderError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), CreateReminderError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| CreateReminderError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(CreateReminderError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(CreateReminderError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+// The goal is read for its due date, the reminders relative to it are computed from it
+async fn find_goal(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<Goal, CreateReminderError> {
+    let has_access = has_goal_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| CreateReminderError::DatabaseError(err.to_string()))?;
+
+    let opt_goal = match has_access {
+        false => None,
+        true => find_goal_by_id(client, goal_id)
+            .await
+            .map_err(|err| CreateReminderError::DatabaseError(err.to_string()))?,
+    };
+
+    opt_goal.ok_or_else(|| {
+        CreateReminderError::GoalNotFoundError(format!("Goal not found for the id: {}", goal_id))
+    })
+}
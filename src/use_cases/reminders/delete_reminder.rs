@@ -0,0 +1,77 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        reminder_data_access::{self, find_reminder_by_id},
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{goal::Goal, reminder::Reminder},
+};
+
+pub enum DeleteReminderError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    ReminderNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// Only the user that set the reminder deletes it, the reminders of other users are reported as
+// not found
+pub async fn execute(
+    goal_id: String,
+    reminder_id: String,
+    user_id: String,
+) -> Result<(), DeleteReminderError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| DeleteReminderError::InvalidRequestError(err.to_string()))?;
+    Reminder::validate_id(&reminder_id)
+        .map_err(|err| DeleteReminderError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+
+    let opt_reminder = find_reminder_by_id(&client, &reminder_id)
+        .await
+        .map_err(|err| DeleteReminderError::DatabaseError(err.to_string()))?;
+
+    match opt_reminder {
+        Some(reminder)
+            if reminder.get_goal_id() == goal_id && reminder.get_user_id() == user_id => {}
+        _ => {
+            return Err(DeleteReminderError::ReminderNotFoundError(format!(
+                "Reminder not found for the id: {}",
+                reminder_id
+            )))
+        }
+    }
+
+    reminder_data_access::delete_reminder(&client, &reminder_id)
+        .await
+        .map_err(|err| DeleteReminderError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+async fn get_connected_client() -> Result<Client, DeleteReminderError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| DeleteReminderError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), DeleteReminderError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| DeleteReminderError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(DeleteReminderError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(DeleteReminderError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
@@ -0,0 +1,107 @@
+use tokio_postgres::Client;
+
+use crate::{
+    config::get_env_parsed,
+    data_access::{
+        goal_data_access::has_goal_access,
+        notification_data_access::add_notification,
+        reminder_data_access::{claim_due_reminders, mark_reminder_failed, mark_reminder_sent},
+    },
+    db::establish_connection,
+    entities::{
+        notification::{Notification, REMINDER_KIND},
+        reminder::ReminderNotification,
+    },
+    services::notifier_services::get_notifiers,
+};
+
+// A claimed reminder is locked for the other instances while it is being sent
+const LOCK_SECONDS: f64 = 300.0;
+const BASE_RETRY_SECONDS: f64 = 60.0;
+const MAX_RETRY_SECONDS: f64 = 3600.0;
+
+pub enum DispatchDueRemindersError {
+    DatabaseError(String),
+}
+
+// Sends the due reminders through their channel and returns how many were sent. A failed reminder
+// is tried again with an exponential backoff, until REMINDER_MAX_ATTEMPTS is reached
+pub async fn execute() -> Result<usize, DispatchDueRemindersError> {
+    let batch_size: i64 = get_env_parsed("REMINDER_BATCH_SIZE", 50);
+    let max_attempts: i32 = get_env_parsed("REMINDER_MAX_ATTEMPTS", 5);
+
+    let client = get_connected_client().await?;
+
+    let notifications = claim_due_reminders(&client, batch_size, LOCK_SECONDS)
+        .await
+        .map_err(|err| DispatchDueRemindersError::DatabaseError(err.to_string()))?;
+
+    let mut sent = 0;
+    for notification in notifications.iter() {
+        match send(&client, notification).await? {
+            Ok(_) => {
+                mark_reminder_sent(&client, &notification.reminder_id)
+                    .await
+                    .map_err(|err| DispatchDueRemindersError::DatabaseError(err.to_string()))?;
+                sent += 1;
+            }
+            Err(error) => {
+                let retry_in_seconds =
+                    (BASE_RETRY_SECONDS * 2f64.powi(notification.attempts)).min(MAX_RETRY_SECONDS);
+                mark_reminder_failed(
+                    &client,
+                    &notification.reminder_id,
+                    &error,
+                    max_attempts,
+                    retry_in_seconds,
+                )
+                .await
+                .map_err(|err| DispatchDueRemindersError::DatabaseError(err.to_string()))?;
+            }
+        }
+    }
+
+    Ok(sent)
+}
+
+async fn get_connected_client() -> Result<Client, DispatchDueRemindersError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| DispatchDueRemindersError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+// Users that lost access to the goal since they set the reminder are not notified. The reminder is
+// also added to the notifications of the user on its first attempt, retries do not add it again
+async fn send(
+    client: &Client,
+    notification: &ReminderNotification,
+) -> Result<Result<(), String>, DispatchDueRemindersError> {
+    let has_access = has_goal_access(client, &notification.goal_id, &notification.user_id)
+        .await
+        .map_err(|err| DispatchDueRemindersError::DatabaseError(err.to_string()))?;
+    if !has_access {
+        return Ok(Err("The user no longer has access to the goal".to_string()));
+    }
+
+    if notification.attempts == 0 {
+        let in_app_notification = Notification::from_event(
+            &notification.user_id,
+            REMINDER_KIND,
+            Some(notification.goal_id.clone()),
+            None,
+            format!("Reminder: {}", notification.goal_text),
+        )
+        .map_err(|err| DispatchDueRemindersError::DatabaseError(err.to_string()))?;
+        add_notification(client, &in_app_notification)
+            .await
+            .map_err(|err| DispatchDueRemindersError::DatabaseError(err.to_string()))?;
+    }
+
+    let notifier = match get_notifiers().get(&notification.channel) {
+        Err(error) => return Ok(Err(error)),
+        Ok(notifier) => notifier,
+    };
+
+    Ok(notifier.notify(notification).await)
+}
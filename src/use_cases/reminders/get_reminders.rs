@@ -0,0 +1,96 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::has_goal_access, reminder_data_access::find_reminders,
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{
+        goal::Goal,
+        reminder::{Reminder, ReminderDto},
+    },
+};
+
+pub enum GetRemindersError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    GoalNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// Reminders are personal, every user only sees their own reminders on the goal
+pub async fn execute(
+    goal_id: String,
+    user_id: String,
+) -> Result<Vec<ReminderDto>, GetRemindersError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| GetRemindersError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    check_goal_access(&client, &goal_id, &user_id).await?;
+
+    let reminders_db = find_reminders(&client, &goal_id, &user_id)
+        .await
+        .map_err(|err| GetRemindersError::DatabaseError(err.to_string()))?;
+
+    Ok(reminders_db.iter().map(map_to_dto).collect())
+}
+
+async fn get_connected_client() -> Result<Client, GetRemindersError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| GetRemindersError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), GetRemindersError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| GetRemindersError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(GetRemindersError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(GetRemindersError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn check_goal_access(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<(), GetRemindersError> {
+    let has_access = has_goal_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| GetRemindersError::DatabaseError(err.to_string()))?;
+
+    if !has_access {
+        return Err(GetRemindersError::GoalNotFoundError(format!(
+            "Goal not found for the id: {}",
+            goal_id
+        )));
+    }
+    Ok(())
+}
+
+pub fn map_to_dto(reminder: &Reminder) -> ReminderDto {
+    ReminderDto {
+        id: reminder.get_id(),
+        goal_id: reminder.get_goal_id(),
+        remind_at: reminder.get_local_remind_at(),
+        minutes_before_due: reminder.get_minutes_before_due(),
+        timezone: reminder.get_timezone(),
+        channel: reminder.get_channel(),
+        webhook_url: reminder.get_webhook_url(),
+        status: reminder.get_status(),
+        attempts: reminder.get_attempts(),
+        sent_at: reminder.get_sent_at().map(|date| date.to_rfc3339()),
+        last_error: reminder.get_last_error(),
+    }
+}
@@ -0,0 +1,4 @@
+pub mod create_reminder;
+pub mod get_reminders;
+pub mod delete_reminder;
+pub mod dispatch_due_reminders;
@@ -0,0 +1,96 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::find_goal_by_id, share_link_data_access::add_share_link,
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{
+        goal::Goal,
+        share_link::{CreateShareLinkDto, CreatedShareLinkDto, ShareLink},
+    },
+    services::token_services::{generate_random_token, hash_token},
+};
+
+pub enum CreateShareLinkError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    GoalNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// Only the owner of the goal can make it public. The token is returned only here
+pub async fn execute(
+    goal_id: String,
+    new_share_link: CreateShareLinkDto,
+    user_id: String,
+) -> Result<CreatedShareLinkDto, CreateShareLinkError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| CreateShareLinkError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    find_owned_goal(&client, &goal_id, &user_id).await?;
+
+    let token = generate_random_token(32);
+    let share_link = ShareLink::from_create_share_link_dto(
+        new_share_link,
+        &goal_id,
+        &user_id,
+        &hash_token(&token),
+    )
+    .map_err(|err| CreateShareLinkError::InvalidRequestError(err.to_string()))?;
+
+    let id = add_share_link(&client, &share_link)
+        .await
+        .map_err(|err| CreateShareLinkError::DatabaseError(err.to_string()))?;
+
+    Ok(CreatedShareLinkDto {
+        id,
+        goal_id,
+        expires_at: share_link.get_expires_at().map(|date| date.to_rfc3339()),
+        token,
+    })
+}
+
+async fn get_connected_client() -> Result<Client, CreateShareLinkError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| CreateShareLinkError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), CreateShareLinkError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| CreateShareLinkError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(CreateShareLinkError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(CreateShareLinkError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn find_owned_goal(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<Goal, CreateShareLinkError> {
+    let opt_goal = find_goal_by_id(client, goal_id)
+        .await
+        .map_err(|err| CreateShareLinkError::DatabaseError(err.to_string()))?;
+
+    match opt_goal {
+        Some(goal) if goal.get_user_id() == user_id => Ok(goal),
+        _ => Err(CreateShareLinkError::GoalNotFoundError(format!(
+            "Goal not found for the id: {}",
+            goal_id
+        ))),
+    }
+}
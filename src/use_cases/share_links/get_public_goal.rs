@@ -0,0 +1,62 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::find_goal_by_id, share_link_data_access::find_share_link_by_token_hash,
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::share_link::PublicGoalDto,
+    services::token_services::hash_token,
+};
+
+pub enum GetPublicGoalError {
+    DatabaseError(String),
+    NotFoundError(String),
+}
+
+const NOT_FOUND_MESSAGE: &str = "Shared goal not found";
+
+// No authentication, the token is the access. Unknown, revoked and expired links, and links of
+// disabled users, all give the same not found error
+pub async fn execute(token: String) -> Result<PublicGoalDto, GetPublicGoalError> {
+    let client = get_connected_client().await?;
+
+    let share_link = find_share_link_by_token_hash(&client, &hash_token(&token))
+        .await
+        .map_err(|err| GetPublicGoalError::DatabaseError(err.to_string()))?
+        .filter(|share_link| !share_link.is_expired())
+        .ok_or_else(|| GetPublicGoalError::NotFoundError(NOT_FOUND_MESSAGE.to_string()))?;
+
+    let goal = find_goal_by_id(&client, &share_link.get_goal_id())
+        .await
+        .map_err(|err| GetPublicGoalError::DatabaseError(err.to_string()))?
+        .ok_or_else(|| GetPublicGoalError::NotFoundError(NOT_FOUND_MESSAGE.to_string()))?;
+
+    check_owner_is_enabled(&client, &goal.get_user_id()).await?;
+
+    Ok(PublicGoalDto {
+        id: goal.get_id(),
+        text: goal.get_text(),
+    })
+}
+
+async fn get_connected_client() -> Result<Client, GetPublicGoalError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| GetPublicGoalError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn check_owner_is_enabled(client: &Client, user_id: &str) -> Result<(), GetPublicGoalError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| GetPublicGoalError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        Some(user) if !user.is_disabled() => Ok(()),
+        _ => Err(GetPublicGoalError::NotFoundError(
+            NOT_FOUND_MESSAGE.to_string(),
+        )),
+    }
+}
@@ -0,0 +1,85 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::find_goal_by_id, share_link_data_access::find_share_links,
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{goal::Goal, share_link::ShareLinkDto},
+};
+
+pub enum GetShareLinksError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    GoalNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+pub async fn execute(
+    goal_id: String,
+    user_id: String,
+) -> Result<Vec<ShareLinkDto>, GetShareLinksError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| GetShareLinksError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    find_owned_goal(&client, &goal_id, &user_id).await?;
+
+    let share_links_db = find_share_links(&client, &goal_id)
+        .await
+        .map_err(|err| GetShareLinksError::DatabaseError(err.to_string()))?;
+
+    let mut share_links_dto = Vec::new();
+    for share_link in share_links_db {
+        share_links_dto.push(ShareLinkDto {
+            id: share_link.get_id(),
+            goal_id: share_link.get_goal_id(),
+            expires_at: share_link.get_expires_at().map(|date| date.to_rfc3339()),
+        });
+    }
+
+    Ok(share_links_dto)
+}
+
+async fn get_connected_client() -> Result<Client, GetShareLinksError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| GetShareLinksError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), GetShareLinksError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| GetShareLinksError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(GetShareLinksError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(GetShareLinksError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn find_owned_goal(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<Goal, GetShareLinksError> {
+    let opt_goal = find_goal_by_id(client, goal_id)
+        .await
+        .map_err(|err| GetShareLinksError::DatabaseError(err.to_string()))?;
+
+    match opt_goal {
+        Some(goal) if goal.get_user_id() == user_id => Ok(goal),
+        _ => Err(GetShareLinksError::GoalNotFoundError(format!(
+            "Goal not found for the id: {}",
+            goal_id
+        ))),
+    }
+}
@@ -0,0 +1,4 @@
+pub mod create_share_link;
+pub mod get_share_links;
+pub mod revoke_share_link;
+pub mod get_public_goal;
@@ -0,0 +1,87 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::find_goal_by_id, share_link_data_access,
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{goal::Goal, share_link::ShareLink},
+};
+
+pub enum RevokeShareLinkError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    GoalNotFoundError(String),
+    ShareLinkNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+pub async fn execute(
+    goal_id: String,
+    share_link_id: String,
+    user_id: String,
+) -> Result<(), RevokeShareLinkError> {
+    Goal::validate_id(&goal_id)
+        .map_err(|err| RevokeShareLinkError::InvalidRequestError(err.to_string()))?;
+    ShareLink::validate_id(&share_link_id)
+        .map_err(|err| RevokeShareLinkError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    find_owned_goal(&client, &goal_id, &user_id).await?;
+
+    let revoked = share_link_data_access::revoke_share_link(&client, &share_link_id, &goal_id)
+        .await
+        .map_err(|err| RevokeShareLinkError::DatabaseError(err.to_string()))?;
+
+    if revoked == 0 {
+        return Err(RevokeShareLinkError::ShareLinkNotFoundError(format!(
+            "Share link not found for the id: {}",
+            share_link_id
+        )));
+    }
+
+    Ok(())
+}
+
+async fn get_connected_client() -> Result<Client, RevokeShareLinkError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| RevokeShareLinkError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), RevokeShareLinkError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| RevokeShareLinkError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(RevokeShareLinkError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(RevokeShareLinkError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn find_owned_goal(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<Goal, RevokeShareLinkError> {
+    let opt_goal = find_goal_by_id(client, goal_id)
+        .await
+        .map_err(|err| RevokeShareLinkError::DatabaseError(err.to_string()))?;
+
+    match opt_goal {
+        Some(goal) if goal.get_user_id() == user_id => Ok(goal),
+        _ => Err(RevokeShareLinkError::GoalNotFoundError(format!(
+            "Goal not found for the id: {}",
+            goal_id
+        ))),
+    }
+}
@@ -0,0 +1,434 @@
+use chrono::Utc;
+use serde_json::{json, Value};
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::{
+            add_goal_with_id, find_goal_by_id, has_goal_access, has_goal_delete_access,
+            has_goal_write_access, trash_goal, update_goal_completion_if_newer,
+            update_goal_text_if_newer,
+        },
+        goal_event_data_access::add_goal_event,
+        goal_revision_data_access::add_goal_revision,
+        organization_data_access::find_organization_member,
+        user_data_access::find_user_by_id,
+        webhook_data_access::enqueue_event,
+    },
+    db::establish_connection,
+    entities::{
+        goal::{CreateGoalDto, Goal},
+        goal_revision::{GoalRevision, CREATE_ACTION, DELETE_ACTION, UPDATE_ACTION},
+        sync::{
+            SyncMutation, SyncMutationDto, SyncMutationResultDto, SyncMutationResultsDto,
+            SyncMutationsDto, APPLIED_STATUS, CREATE_OPERATION, IGNORED_STATUS, REJECTED_STATUS,
+            UPDATE_OPERATION,
+        },
+        webhook::{
+            WebhookEvent, GOAL_COMPLETED_EVENT, GOAL_CREATED_EVENT, GOAL_DELETED_EVENT,
+            GOAL_UPDATED_EVENT,
+        },
+    },
+};
+
+const MAX_MUTATIONS: usize = 100;
+
+pub enum ApplyMutationsError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+enum Outcome {
+    Applied,
+    Ignored(String),
+    Rejected(String),
+}
+
+// Applies the changes a client made offline, in order, with the checks of the goal routes. The text
+// and the completion changed last win, each on its own, deletions win over edits. Every change can be sent again: a goal created twice
+// is updated, an older text and a deleted goal are ignored
+pub async fn execute(
+    mutations: SyncMutationsDto,
+    user_id: String,
+) -> Result<SyncMutationResultsDto, ApplyMutationsError> {
+    if mutations.mutations.len() > MAX_MUTATIONS {
+        return Err(ApplyMutationsError::InvalidRequestError(format!(
+            "At most {} mutations can be sent at once",
+            MAX_MUTATIONS
+        )));
+    }
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+
+    let mut results = Vec::new();
+    for mutation_dto in mutations.mutations {
+        results.push(apply_mutation(&client, mutation_dto, &user_id).await?);
+    }
+
+    Ok(SyncMutationResultsDto { results })
+}
+
+async fn get_connected_client() -> Result<Client, ApplyMutationsError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| ApplyMutationsError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), ApplyMutationsError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| ApplyMutationsError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(ApplyMutationsError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(ApplyMutationsError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn apply_mutation(
+    client: &Client,
+    mutation_dto: SyncMutationDto,
+    user_id: &str,
+) -> Result<SyncMutationResultDto, ApplyMutationsError> {
+    let goal_id = mutation_dto.goal_id.clone();
+    let operation = mutation_dto.operation.clone();
+
+    let outcome = match SyncMutation::from_sync_mutation_dto(mutation_dto) {
+        Err(err) => Outcome::Rejected(err.to_string()),
+        Ok(mutation) => save_mutation(client, &mutation, user_id).await?,
+    };
+
+    let (status, message) = match outcome {
+        Outcome::Applied => (APPLIED_STATUS, None),
+        Outcome::Ignored(message) => (IGNORED_STATUS, Some(message)),
+        Outcome::Rejected(message) => (REJECTED_STATUS, Some(message)),
+    };
+
+    Ok(SyncMutationResultDto {
+        goal_id,
+        operation,
+        status: status.to_string(),
+        message,
+    })
+}
+
+// Each mutation is its own transaction: the fields of the goal, its revision and its events are
+// written together, and the mutations applied before a failure stay applied, they can be sent again
+async fn save_mutation(
+    client: &Client,
+    mutation: &SyncMutation,
+    user_id: &str,
+) -> Result<Outcome, ApplyMutationsError> {
+    client
+        .batch_execute("BEGIN")
+        .await
+        .map_err(|err| ApplyMutationsError::DatabaseError(err.to_string()))?;
+
+    let outcome = match mutation.get_operation().as_str() {
+        CREATE_OPERATION => create_goal(client, mutation, user_id).await,
+        UPDATE_OPERATION => update_goal(client, mutation, user_id).await,
+        _ => remove_goal(client, mutation, user_id).await,
+    };
+    let outcome = match outcome {
+        Err(err) => {
+            if let Err(rollback_err) = client.batch_execute("ROLLBACK").await {
+                eprintln!("Sync mutation rollback error: {}", rollback_err);
+            }
+            return Err(err);
+        }
+        Ok(outcome) => outcome,
+    };
+
+    client
+        .batch_execute("COMMIT")
+        .await
+        .map_err(|err| ApplyMutationsError::DatabaseError(err.to_string()))?;
+
+    Ok(outcome)
+}
+
+async fn create_goal(
+    client: &Client,
+    mutation: &SyncMutation,
+    user_id: &str,
+) -> Result<Outcome, ApplyMutationsError> {
+    let text = match mutation.get_text() {
+        None => {
+            return Ok(Outcome::Rejected(
+                "text is required to create a goal".to_string(),
+            ))
+        }
+        Some(text) => text,
+    };
+    let new_goal = CreateGoalDto {
+        text,
+        organization_id: mutation.get_organization_id(),
+        due_at: None,
+    };
+    let mut goal = match Goal::from_create_goal_dto(new_goal, user_id) {
+        Err(err) => return Ok(Outcome::Rejected(err.to_string())),
+        Ok(goal) => goal,
+    };
+    if let Err(err) = goal.set_id(mutation.get_goal_id()) {
+        return Ok(Outcome::Rejected(err.to_string()));
+    }
+    goal.set_text_updated_at(Some(mutation.get_updated_at()));
+    if let Some(completed) = mutation.get_completed() {
+        goal.set_completed(completed);
+        goal.set_completion_updated_at(Some(mutation.get_updated_at()));
+    }
+
+    if let Some(organization_id) = goal.get_organization_id() {
+        let opt_member = find_organization_member(client, &organization_id, user_id)
+            .await
+            .map_err(|err| ApplyMutationsError::DatabaseError(err.to_string()))?;
+        match opt_member {
+            Some(member) if member.can_write_goals() => {}
+            Some(_) => {
+                return Ok(Outcome::Rejected(
+                    "Viewers cannot create goals in the organization".to_string(),
+                ))
+            }
+            None => {
+                return Ok(Outcome::Rejected(
+                    "The user is not a member of the organization".to_string(),
+                ))
+            }
+        }
+    }
+
+    let inserted = add_goal_with_id(client, &goal)
+        .await
+        .map_err(|err| ApplyMutationsError::DatabaseError(err.to_string()))?;
+
+    // Sent again after a lost response, or the id was used by another goal
+    if !inserted {
+        let opt_goal = find_goal_by_id(client, &goal.get_id())
+            .await
+            .map_err(|err| ApplyMutationsError::DatabaseError(err.to_string()))?;
+        return match opt_goal {
+            Some(existing) if existing.get_user_id() == user_id => {
+                update_goal(client, mutation, user_id).await
+            }
+            Some(_) => Ok(Outcome::Rejected("The goal id is already used".to_string())),
+            None => Ok(Outcome::Ignored("The goal was deleted".to_string())),
+        };
+    }
+
+    let snapshot = GoalRevision::snapshot(&goal);
+    add_revision(client, &goal, user_id, CREATE_ACTION, None, &snapshot).await?;
+
+    let data = json!({
+        "id": goal.get_id(),
+        "text": goal.get_text(),
+        "user_id": goal.get_user_id(),
+        "organization_id": goal.get_organization_id(),
+    });
+    publish_event(client, &goal, GOAL_CREATED_EVENT, data).await?;
+
+    Ok(Outcome::Applied)
+}
+
+async fn update_goal(
+    client: &Client,
+    mutation: &SyncMutation,
+    user_id: &str,
+) -> Result<Outcome, ApplyMutationsError> {
+    let text = mutation.get_text();
+    let completed = mutation.get_completed();
+    if text.is_none() && completed.is_none() {
+        return Ok(Outcome::Rejected(
+            "text or completed is required to update a goal".to_string(),
+        ));
+    }
+
+    let mut goal = match find_readable_goal(client, &mutation.get_goal_id(), user_id).await? {
+        Err(outcome) => return Ok(outcome),
+        Ok(goal) => goal,
+    };
+
+    let can_write = has_goal_write_access(client, &goal.get_id(), user_id)
+        .await
+        .map_err(|err| ApplyMutationsError::DatabaseError(err.to_string()))?;
+    if !can_write {
+        return Ok(Outcome::Rejected(
+            "The user cannot change the goal".to_string(),
+        ));
+    }
+
+    let before = GoalRevision::snapshot(&goal);
+    let was_completed = goal.get_completed_at().is_some();
+    let mut updated = 0;
+
+    if let Some(text) = text {
+        if let Err(err) = goal.set_text(text) {
+            return Ok(Outcome::Rejected(err.to_string()));
+        }
+        goal.set_text_updated_at(Some(mutation.get_updated_at()));
+        updated += update_goal_text_if_newer(client, &goal)
+            .await
+            .map_err(|err| ApplyMutationsError::DatabaseError(err.to_string()))?;
+    }
+    if let Some(completed) = completed {
+        goal.set_completed(completed);
+        goal.set_completion_updated_at(Some(mutation.get_updated_at()));
+        updated += update_goal_completion_if_newer(client, &goal)
+            .await
+            .map_err(|err| ApplyMutationsError::DatabaseError(err.to_string()))?;
+    }
+    if updated == 0 {
+        return Ok(Outcome::Ignored(
+            "The goal was changed after this change".to_string(),
+        ));
+    }
+
+    // One of the fields can have lost against a newer change, the goal is read again
+    let goal = match find_goal_by_id(client, &goal.get_id())
+        .await
+        .map_err(|err| ApplyMutationsError::DatabaseError(err.to_string()))?
+    {
+        None => return Ok(Outcome::Ignored("The goal was deleted".to_string())),
+        Some(goal) => goal,
+    };
+
+    let after = GoalRevision::snapshot(&goal);
+    add_revision(client, &goal, user_id, UPDATE_ACTION, Some(&before), &after).await?;
+
+    let data = json!({
+        "id": goal.get_id(),
+        "text": goal.get_text(),
+        "user_id": goal.get_user_id(),
+        "organization_id": goal.get_organization_id(),
+        "completed_at": goal.get_completed_at().map(|date| date.to_rfc3339()),
+        "updated_by": user_id,
+    });
+    publish_event(client, &goal, GOAL_UPDATED_EVENT, data.clone()).await?;
+    if !was_completed && goal.get_completed_at().is_some() {
+        publish_event(client, &goal, GOAL_COMPLETED_EVENT, data).await?;
+    }
+
+    Ok(Outcome::Applied)
+}
+
+// Same rules as the delete route: personal goals by their owner, organization goals by the members
+// that can write them. The goal is moved to the trash
+async fn remove_goal(
+    client: &Client,
+    mutation: &SyncMutation,
+    user_id: &str,
+) -> Result<Outcome, ApplyMutationsError> {
+    let mut goal = match find_readable_goal(client, &mutation.get_goal_id(), user_id).await? {
+        Err(outcome) => return Ok(outcome),
+        Ok(goal) => goal,
+    };
+
+    let can_delete = has_goal_delete_access(client, &goal.get_id(), user_id)
+        .await
+        .map_err(|err| ApplyMutationsError::DatabaseError(err.to_string()))?;
+    if !can_delete {
+        return Ok(Outcome::Rejected(
+            "The user cannot delete the goal".to_string(),
+        ));
+    }
+
+    let trashed = trash_goal(client, &goal.get_id())
+        .await
+        .map_err(|err| ApplyMutationsError::DatabaseError(err.to_string()))?;
+    if trashed == 0 {
+        return Ok(Outcome::Ignored("The goal was deleted".to_string()));
+    }
+
+    let before = GoalRevision::snapshot(&goal);
+    goal.set_deleted_at(Some(Utc::now()));
+    let after = GoalRevision::snapshot(&goal);
+    add_revision(client, &goal, user_id, DELETE_ACTION, Some(&before), &after).await?;
+
+    let data = json!({
+        "id": goal.get_id(),
+        "text": goal.get_text(),
+        "user_id": goal.get_user_id(),
+        "organization_id": goal.get_organization_id(),
+        "deleted_by": user_id,
+    });
+    publish_event(client, &goal, GOAL_DELETED_EVENT, data).await?;
+
+    Ok(Outcome::Applied)
+}
+
+// Missing goals were deleted, the changes made offline are ignored. Goals the user has no access to
+// are reported as not found, so their ids are not leaked
+async fn find_readable_goal(
+    client: &Client,
+    goal_id: &str,
+    user_id: &str,
+) -> Result<Result<Goal, Outcome>, ApplyMutationsError> {
+    let opt_goal = find_goal_by_id(client, goal_id)
+        .await
+        .map_err(|err| ApplyMutationsError::DatabaseError(err.to_string()))?;
+
+    let goal = match opt_goal {
+        None => return Ok(Err(Outcome::Ignored("The goal was deleted".to_string()))),
+        Some(goal) => goal,
+    };
+
+    let has_access = has_goal_access(client, goal_id, user_id)
+        .await
+        .map_err(|err| ApplyMutationsError::DatabaseError(err.to_string()))?;
+    match has_access {
+        true => Ok(Ok(goal)),
+        false => Ok(Err(Outcome::Rejected(format!(
+            "Goal not found for the id: {}",
+            goal_id
+        )))),
+    }
+}
+
+// Same events as the goal routes, for the streams, the WebSockets and the webhooks of the owner
+async fn publish_event(
+    client: &Client,
+    goal: &Goal,
+    event_type: &str,
+    data: Value,
+) -> Result<(), ApplyMutationsError> {
+    add_goal_event(
+        client,
+        &goal.get_id(),
+        &goal.get_user_id(),
+        goal.get_organization_id(),
+        event_type,
+        &data,
+    )
+    .await
+    .map_err(|err| ApplyMutationsError::DatabaseError(err.to_string()))?;
+
+    enqueue_event(
+        client,
+        &goal.get_user_id(),
+        &WebhookEvent::new(event_type, data),
+    )
+    .await
+    .map_err(|err| ApplyMutationsError::DatabaseError(err.to_string()))?;
+
+    Ok(())
+}
+
+async fn add_revision(
+    client: &Client,
+    goal: &Goal,
+    user_id: &str,
+    action: &str,
+    before: Option<&str>,
+    after: &str,
+) -> Result<(), ApplyMutationsError> {
+    add_goal_revision(client, &goal.get_id(), Some(user_id), action, before, after)
+        .await
+        .map_err(|err| ApplyMutationsError::DatabaseError(err.to_string()))
+}
@@ -0,0 +1,242 @@
+use chrono::Utc;
+use tokio_postgres::Client;
+
+use crate::{
+    config::get_env_parsed,
+    data_access::{
+        goal_data_access::{find_goal_changes, find_stable_goal_version},
+        goal_tombstone_data_access::find_goal_tombstones,
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{
+        goal::Goal,
+        sync::{
+            GoalTombstone, GoalTombstoneDto, SyncChangesDto, SyncCursor, SyncGoalDto, SyncQueryDto,
+        },
+    },
+};
+
+const DEFAULT_LIMIT: i64 = 200;
+const MAX_LIMIT: i64 = 1000;
+
+pub enum GetChangesError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// Tombstones are kept SYNC_TOMBSTONE_RETENTION_DAYS (default 30), older cursors may have missed
+// deletions
+pub fn get_tombstone_retention_seconds() -> f64 {
+    get_env_parsed("SYNC_TOMBSTONE_RETENTION_DAYS", 30.0) * 86400.0
+}
+
+// Goals the user can see that changed after the cursor and tombstones of the goals the user cannot
+// see anymore, in the order of their versions. Without cursor, or with a cursor older than the
+// tombstones, every goal is returned and the client starts again from them. Changes of transactions
+// still in progress, and the committed ones after them, wait for the next call
+pub async fn execute(
+    query: SyncQueryDto,
+    user_id: String,
+) -> Result<SyncChangesDto, GetChangesError> {
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let cursor = match query.cursor {
+        None => None,
+        Some(cursor) => Some(
+            SyncCursor::decode(&cursor)
+                .map_err(|err| GetChangesError::InvalidRequestError(err.to_string()))?,
+        ),
+    };
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+
+    let retention_seconds = get_tombstone_retention_seconds();
+    let cursor = cursor.filter(|cursor| {
+        (Utc::now() - cursor.get_issued_at()).num_seconds() as f64 <= retention_seconds
+    });
+    let reset = cursor.is_none();
+    let after_version = cursor.map(|cursor| cursor.get_version()).unwrap_or(0);
+
+    let stable_version = find_stable_goal_version(&client)
+        .await
+        .map_err(|err| GetChangesError::DatabaseError(err.to_string()))?;
+
+    // One more of each, to know if there are more changes
+    let mut goals = find_goal_changes(&client, &user_id, after_version, stable_version, limit + 1)
+        .await
+        .map_err(|err| GetChangesError::DatabaseError(err.to_string()))?;
+    let mut tombstones = match reset {
+        true => Vec::new(),
+        false => find_goal_tombstones(&client, &user_id, after_version, stable_version, limit + 1)
+            .await
+            .map_err(|err| GetChangesError::DatabaseError(err.to_string()))?,
+    };
+
+    let mut versions: Vec<i64> = goals
+        .iter()
+        .map(|goal| goal.get_version())
+        .chain(tombstones.iter().map(|tombstone| tombstone.get_version()))
+        .collect();
+    versions.sort_unstable();
+
+    let has_more = versions.len() as i64 > limit;
+    let last_version = match has_more {
+        true => versions[limit as usize - 1],
+        false => versions.last().copied().unwrap_or(after_version),
+    };
+    goals.retain(|goal| goal.get_version() <= last_version);
+    tombstones.retain(|tombstone| tombstone.get_version() <= last_version);
+
+    Ok(SyncChangesDto {
+        goals: goals.iter().map(map_to_dto).collect(),
+        tombstones: tombstones.iter().map(map_tombstone_to_dto).collect(),
+        cursor: SyncCursor::new(last_version).encode(),
+        has_more,
+        reset,
+    })
+}
+
+async fn get_connected_client() -> Result<Client, GetChangesError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| GetChangesError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), GetChangesError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| GetChangesError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(GetChangesError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(GetChangesError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+pub fn map_to_dto(goal: &Goal) -> SyncGoalDto {
+    SyncGoalDto {
+        id: goal.get_id(),
+        text: goal.get_text(),
+        user_id: goal.get_user_id(),
+        organization_id: goal.get_organization_id(),
+        version: goal.get_version(),
+        updated_at: goal.get_updated_at().map(|date| date.to_rfc3339()),
+        text_updated_at: goal.get_text_updated_at().map(|date| date.to_rfc3339()),
+        due_at: goal.get_due_at().map(|date| date.to_rfc3339()),
+        completed_at: goal.get_completed_at().map(|date| date.to_rfc3339()),
+        completion_updated_at: goal
+            .get_completion_updated_at()
+            .map(|date| date.to_rfc3339()),
+        archived_at: goal.get_archived_at().map(|date| date.to_rfc3339()),
+        deleted_at: goal.get_deleted_at().map(|date| date.to_rfc3339()),
+    }
+}
+
+fn map_tombstone_to_dto(tombstone: &GoalTombstone) -> GoalTombstoneDto {
+    GoalTombstoneDto {
+        goal_id: tombstone.get_goal_id(),
+        version: tombstone.get_version(),
+        deleted_at: tombstone.get_deleted_at().map(|date| date.to_rfc3339()),
+    }
+}
+
+// These tests need the database of db.rs with the tables of sql/create-tables.sql, they are skipped
+// when it cannot be reached
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use uuid::Uuid;
+
+    use super::*;
+
+    async fn add_test_user(client: &Client) -> String {
+        let row = client
+            .query_one(
+                "INSERT INTO users (name, email, password_hash, phone)
+                VALUES ('Sync test', $1, 'hash', '555-555-5555') RETURNING id::text",
+                &[&format!("sync-{}@example.com", Uuid::new_v4())],
+            )
+            .await
+            .unwrap();
+        row.get(0)
+    }
+
+    async fn add_test_goal(client: &Client, user_id: &str, text: &str) {
+        client
+            .execute(
+                "INSERT INTO goals (text, user_id) VALUES ($1, $2::text::uuid)",
+                &[&text, &user_id],
+            )
+            .await
+            .unwrap();
+    }
+
+    async fn sync(user_id: &str, cursor: Option<String>) -> SyncChangesDto {
+        let query = SyncQueryDto {
+            cursor,
+            limit: None,
+        };
+        let changes =
+            tokio::time::timeout(Duration::from_secs(5), execute(query, user_id.to_string()))
+                .await
+                .expect("The sync waited for the open transaction");
+        match changes {
+            Ok(changes) => changes,
+            Err(GetChangesError::DatabaseError(err_msg))
+            | Err(GetChangesError::UserNotFoundError(err_msg))
+            | Err(GetChangesError::InvalidRequestError(err_msg)) => panic!("{}", err_msg),
+        }
+    }
+
+    fn texts(changes: &SyncChangesDto) -> Vec<String> {
+        changes.goals.iter().map(|goal| goal.text.clone()).collect()
+    }
+
+    #[tokio::test]
+    async fn stops_before_open_transactions_without_waiting() {
+        let client = match establish_connection().await {
+            Err(err) => return eprintln!("Skipped, no database: {}", err),
+            Ok(client) => client,
+        };
+        let writer = establish_connection().await.unwrap();
+        let user_id = add_test_user(&client).await;
+        add_test_goal(&client, &user_id, "committed").await;
+
+        writer.batch_execute("BEGIN").await.unwrap();
+        add_test_goal(&writer, &user_id, "open").await;
+        add_test_goal(&client, &user_id, "committed after").await;
+
+        let changes = sync(&user_id, None).await;
+        assert_eq!(texts(&changes), vec!["committed"]);
+
+        // Other writes do not wait for the sync either
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            add_test_goal(&client, &user_id, "written after the sync"),
+        )
+        .await
+        .expect("The write waited for the sync");
+
+        writer.batch_execute("COMMIT").await.unwrap();
+
+        let changes = sync(&user_id, Some(changes.cursor)).await;
+        assert_eq!(
+            texts(&changes),
+            vec!["open", "committed after", "written after the sync"]
+        );
+
+        client
+            .execute("DELETE FROM users WHERE id = $1::text::uuid", &[&user_id])
+            .await
+            .unwrap();
+    }
+}
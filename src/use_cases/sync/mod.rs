@@ -0,0 +1,3 @@
+pub mod get_changes;
+pub mod apply_mutations;
+pub mod purge_goal_tombstones;
@@ -0,0 +1,29 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::goal_tombstone_data_access::delete_goal_tombstones_before,
+    db::establish_connection,
+};
+
+use super::get_changes::get_tombstone_retention_seconds;
+
+pub enum PurgeGoalTombstonesError {
+    DatabaseError(String),
+}
+
+// Deletes the tombstones older than the retention and returns how many were deleted. Clients with
+// an older cursor sync every goal again
+pub async fn execute() -> Result<u64, PurgeGoalTombstonesError> {
+    let client = get_connected_client().await?;
+
+    delete_goal_tombstones_before(&client, get_tombstone_retention_seconds())
+        .await
+        .map_err(|err| PurgeGoalTombstonesError::DatabaseError(err.to_string()))
+}
+
+async fn get_connected_client() -> Result<Client, PurgeGoalTombstonesError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| PurgeGoalTombstonesError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
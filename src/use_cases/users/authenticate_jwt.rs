@@ -0,0 +1,50 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::user_data_access::find_user_by_id,
+    db::establish_connection,
+    services::auth_services::{validate_and_get_claims_from_token, TokenClaims},
+};
+
+pub enum AuthenticateJwtError {
+    InvalidTokenError(String),
+    DatabaseError(String),
+}
+
+// Checks the JWT and that it was issued after the last password change of the user, a password
+// change or a forced reset ends the sessions opened before. Unknown and disabled users are left to
+// the use cases, they report them with their own errors
+pub async fn execute(token: &str) -> Result<TokenClaims, AuthenticateJwtError> {
+    let claims = validate_and_get_claims_from_token(token)
+        .map_err(AuthenticateJwtError::InvalidTokenError)?;
+
+    let client = get_connected_client().await?;
+    check_not_revoked(&client, &claims).await?;
+
+    Ok(claims)
+}
+
+async fn get_connected_client() -> Result<Client, AuthenticateJwtError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| AuthenticateJwtError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+pub async fn check_not_revoked(
+    client: &Client,
+    claims: &TokenClaims,
+) -> Result<(), AuthenticateJwtError> {
+    let opt_user = find_user_by_id(client, &claims.user_id)
+        .await
+        .map_err(|err| AuthenticateJwtError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        Some(user) if user.is_token_revoked(claims.issued_at) => {
+            Err(AuthenticateJwtError::InvalidTokenError(
+                "The token was issued before the last password change".to_string(),
+            ))
+        }
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,219 @@
+use chrono::Utc;
+use serde_json::json;
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        user_data_access::{find_user_by_email, update_user_password},
+        webhook_data_access::enqueue_event,
+    },
+    db::establish_connection,
+    entities::{
+        audit_event::{
+            AuditContext, AuditEvent, USER_PASSWORD_CHANGED_AUDIT_EVENT,
+            USER_PASSWORD_CHANGE_FAILED_AUDIT_EVENT,
+        },
+        user::{ChangePasswordDto, CredentialsDto, User},
+        webhook::{WebhookEvent, PASSWORD_CHANGED_EVENT},
+    },
+    services::{
+        audit_services::record_audit_event,
+        auth_services::{hash_password, match_password_and_hash},
+        password_policy_services::check_password_policy,
+        token_services::match_token_and_hash,
+    },
+};
+
+pub enum ChangePasswordError {
+    InvalidRequestError(String),
+    UserNotFound(String),
+    PasswordAndHashDontMatchError(String),
+    AccountDisabledError(String),
+    InvalidResetTokenError(String),
+    HashPasswordError(String),
+    DatabaseError(String),
+}
+
+// Uses the current credentials instead of a JWT, so it also works when a password reset is
+// required and the user cannot sign in. A forced reset also needs the reset token given by the
+// admin, the current credentials may be the stolen ones. The changes and the failed attempts are
+// recorded in the audit log
+pub async fn execute(
+    change_password: ChangePasswordDto,
+    audit: AuditContext,
+) -> Result<(), ChangePasswordError> {
+    let new_password = change_password.new_password;
+    let reset_token = change_password.reset_token;
+
+    let user = User::from_credentials_dto(CredentialsDto {
+        email: change_password.email,
+        password: change_password.password,
+    })
+    .map_err(|err| ChangePasswordError::InvalidRequestError(err.to_string()))?;
+
+    User::validate_password(&new_password)
+        .map_err(|err| ChangePasswordError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+
+    let result = change(&client, &user, &new_password, reset_token.as_deref()).await;
+    record_change(&client, &user, &result, &audit).await;
+
+    result.map(|_| ())
+}
+
+// Returns the id of the user
+async fn change(
+    client: &Client,
+    user: &User,
+    new_password: &str,
+    reset_token: Option<&str>,
+) -> Result<String, ChangePasswordError> {
+    let found_user = find_user(client, user).await?;
+
+    check_password(&user.get_password(), &found_user.get_password_hash())?;
+
+    if found_user.is_disabled() {
+        return Err(ChangePasswordError::AccountDisabledError(
+            "This account is disabled".to_string(),
+        ));
+    }
+
+    if found_user.is_password_reset_required() {
+        check_reset_token(&found_user, reset_token)?;
+    }
+
+    check_new_password(&found_user, &user.get_password(), new_password)?;
+
+    let password_hash =
+        hash_password(new_password).map_err(ChangePasswordError::HashPasswordError)?;
+
+    update_user_password(client, &found_user.get_id(), &password_hash)
+        .await
+        .map_err(|err| ChangePasswordError::DatabaseError(err.to_string()))?;
+
+    let event = WebhookEvent::new(
+        PASSWORD_CHANGED_EVENT,
+        json!({ "user_id": found_user.get_id() }),
+    );
+    enqueue_event(client, &found_user.get_id(), &event)
+        .await
+        .map_err(|err| ChangePasswordError::DatabaseError(err.to_string()))?;
+
+    Ok(found_user.get_id())
+}
+
+async fn record_change(
+    client: &Client,
+    user: &User,
+    result: &Result<String, ChangePasswordError>,
+    audit: &AuditContext,
+) {
+    let mut event = match result {
+        Ok(user_id) => {
+            let mut event = AuditEvent::from_context(USER_PASSWORD_CHANGED_AUDIT_EVENT, audit);
+            if let Err(err) = event.set_user_id(Some(user_id.clone())) {
+                eprintln!("Audit event error: {}", err);
+            }
+            event
+        }
+        Err(err) => {
+            let mut event =
+                AuditEvent::from_context(USER_PASSWORD_CHANGE_FAILED_AUDIT_EVENT, audit);
+            event.set_details(json!({ "reason": failure_reason(err) }).to_string());
+            event
+        }
+    };
+    event.set_email(Some(user.get_email()));
+    record_audit_event(client, &event).await;
+}
+
+fn failure_reason(err: &ChangePasswordError) -> &'static str {
+    match err {
+        ChangePasswordError::InvalidRequestError(_) => "invalid_request",
+        ChangePasswordError::UserNotFound(_) => "user_not_found",
+        ChangePasswordError::PasswordAndHashDontMatchError(_) => "wrong_password",
+        ChangePasswordError::AccountDisabledError(_) => "account_disabled",
+        ChangePasswordError::InvalidResetTokenError(_) => "invalid_reset_token",
+        ChangePasswordError::HashPasswordError(_) | ChangePasswordError::DatabaseError(_) => {
+            "server_error"
+        }
+    }
+}
+
+async fn get_connected_client() -> Result<Client, ChangePasswordError> {
+    let client = establish_connection().await.map_err(|err| {
+        eprintln!("Client connection error: {}", err);
+        ChangePasswordError::DatabaseError(err.to_string())
+    })?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user: &User) -> Result<User, ChangePasswordError> {
+    let email = user.get_email();
+
+    let found_user = find_user_by_email(client, &email)
+        .await
+        .map_err(|err| ChangePasswordError::DatabaseError(err.to_string()))?;
+
+    found_user.ok_or_else(|| {
+        ChangePasswordError::UserNotFound(format!("User not found for the e-mail: {}", &email))
+    })
+}
+
+fn check_password(password: &str, hash: &str) -> Result<(), ChangePasswordError> {
+    let is_match = match_password_and_hash(password, hash)
+        .map_err(ChangePasswordError::PasswordAndHashDontMatchError)?;
+
+    if !is_match {
+        return Err(ChangePasswordError::PasswordAndHashDontMatchError(
+            "Password and Hash don't match".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn check_reset_token(user: &User, reset_token: Option<&str>) -> Result<(), ChangePasswordError> {
+    let reset_token = reset_token.ok_or_else(|| {
+        ChangePasswordError::InvalidResetTokenError(
+            "A password reset is required, the reset token given by the admin is missing"
+                .to_string(),
+        )
+    })?;
+
+    let is_valid = match (
+        user.get_password_reset_token_hash(),
+        user.get_password_reset_expires_at(),
+    ) {
+        (Some(token_hash), Some(expires_at)) => {
+            expires_at > Utc::now() && match_token_and_hash(reset_token, &token_hash)
+        }
+        _ => false,
+    };
+
+    if !is_valid {
+        return Err(ChangePasswordError::InvalidResetTokenError(
+            "The reset token is invalid or expired".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn check_new_password(
+    user: &User,
+    password: &str,
+    new_password: &str,
+) -> Result<(), ChangePasswordError> {
+    if password == new_password {
+        return Err(ChangePasswordError::InvalidRequestError(
+            "The new password must be different from the current one".to_string(),
+        ));
+    }
+
+    check_password_policy(new_password, &user.get_name(), &user.get_email())
+        .map_err(|errors| ChangePasswordError::InvalidRequestError(errors.join(". ")))?;
+
+    Ok(())
+}
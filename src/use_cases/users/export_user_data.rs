@@ -0,0 +1,358 @@
+use std::{collections::HashMap, iter};
+
+use chrono::{NaiveDate, Utc};
+use serde::Serialize;
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        goal_data_access::find_all_goals,
+        habit_data_access::{find_check_ins_of_personal_goals, find_habits_of_personal_goals},
+        note_data_access::find_notes_of_personal_goals,
+        user_data_access::find_user_by_id,
+    },
+    db::establish_connection,
+    entities::{
+        goal::Goal,
+        habit::Habit,
+        note::Note,
+        user::{User, UserDto},
+        user_export::{DataFormat, ExportedGoalDto, UserExportQueryDto},
+    },
+    use_cases::{goals::habits::get_habit, notes::get_notes},
+};
+
+pub const CSV_HEADERS: [&str; 12] = [
+    "id",
+    "text",
+    "completed",
+    "completed_at",
+    "archived_at",
+    "habit_recurrence",
+    "current_streak",
+    "longest_streak",
+    "last_check_in",
+    "check_ins",
+    "note_count",
+    "notes",
+];
+
+pub enum ExportUserDataError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// The parts of the document, in order. Each goal is serialized when its part is read, so the
+// response is streamed instead of being built in memory first
+pub type ExportChunks = Box<dyn Iterator<Item = Result<String, ExportUserDataError>> + Send>;
+
+pub struct UserExport {
+    pub content_type: &'static str,
+    pub file_name: String,
+    pub chunks: ExportChunks,
+}
+
+// Notes, habits and check-ins of the goals, read with one query each
+struct GoalDetails {
+    notes: HashMap<String, Vec<Note>>,
+    habits: HashMap<String, Habit>,
+    check_ins: HashMap<String, Vec<NaiveDate>>,
+}
+
+// The profile and the personal goals of the user, archived ones included. The CSV has one row
+// per goal, the notes are joined in the last column
+pub async fn execute(
+    query: UserExportQueryDto,
+    user_id: String,
+) -> Result<UserExport, ExportUserDataError> {
+    let format = DataFormat::parse(query.format.as_deref())
+        .map_err(|err| ExportUserDataError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    let user = find_user(&client, &user_id).await?;
+
+    let mut goals = find_all_goals(&client, &user_id, false)
+        .await
+        .map_err(|err| ExportUserDataError::DatabaseError(err.to_string()))?;
+    let archived_goals = find_all_goals(&client, &user_id, true)
+        .await
+        .map_err(|err| ExportUserDataError::DatabaseError(err.to_string()))?;
+    goals.extend(archived_goals);
+
+    let mut details = find_goal_details(&client, &user_id).await?;
+    let today = Utc::now().date_naive();
+    let exported_goals = goals
+        .into_iter()
+        .map(move |goal| export_goal(&goal, &mut details, today));
+
+    let exported_at = Utc::now().to_rfc3339();
+    let profile = map_to_user_dto(&user);
+    let chunks = match format {
+        DataFormat::Json => to_json(exported_at, &profile, exported_goals)?,
+        DataFormat::Csv => to_csv(exported_goals)?,
+        DataFormat::Markdown => to_markdown(exported_at, &profile, exported_goals),
+    };
+
+    Ok(UserExport {
+        content_type: format.get_content_type(),
+        file_name: format!("goals-export.{}", format.get_extension()),
+        chunks,
+    })
+}
+
+async fn get_connected_client() -> Result<Client, ExportUserDataError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| ExportUserDataError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<User, ExportUserDataError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| ExportUserDataError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(ExportUserDataError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(ExportUserDataError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(user) => Ok(user),
+    }
+}
+
+async fn find_goal_details(
+    client: &Client,
+    user_id: &str,
+) -> Result<GoalDetails, ExportUserDataError> {
+    let notes = find_notes_of_personal_goals(client, user_id)
+        .await
+        .map_err(|err| ExportUserDataError::DatabaseError(err.to_string()))?;
+    let habits = find_habits_of_personal_goals(client, user_id)
+        .await
+        .map_err(|err| ExportUserDataError::DatabaseError(err.to_string()))?;
+    let check_ins = find_check_ins_of_personal_goals(client, user_id)
+        .await
+        .map_err(|err| ExportUserDataError::DatabaseError(err.to_string()))?;
+
+    let mut details = GoalDetails {
+        notes: HashMap::new(),
+        habits: HashMap::new(),
+        check_ins: HashMap::new(),
+    };
+    for note in notes {
+        details
+            .notes
+            .entry(note.get_goal_id())
+            .or_default()
+            .push(note);
+    }
+    for habit in habits {
+        details.habits.insert(habit.get_goal_id(), habit);
+    }
+    for (goal_id, date) in check_ins {
+        details.check_ins.entry(goal_id).or_default().push(date);
+    }
+
+    Ok(details)
+}
+
+// Each goal takes its details out of the maps, they are only kept until the goal is exported
+fn export_goal(goal: &Goal, details: &mut GoalDetails, today: NaiveDate) -> ExportedGoalDto {
+    let goal_id = goal.get_id();
+    let notes = details.notes.remove(&goal_id).unwrap_or_default();
+    let habit = details.habits.remove(&goal_id);
+    let check_ins = match habit {
+        None => Vec::new(),
+        Some(_) => details.check_ins.remove(&goal_id).unwrap_or_default(),
+    };
+
+    ExportedGoalDto {
+        id: goal_id,
+        text: goal.get_text(),
+        due_at: goal.get_due_at().map(|date| date.to_rfc3339()),
+        completed_at: goal.get_completed_at().map(|date| date.to_rfc3339()),
+        archived_at: goal.get_archived_at().map(|date| date.to_rfc3339()),
+        habit: habit
+            .as_ref()
+            .map(|habit| get_habit::map_to_dto(habit, &check_ins, today)),
+        check_ins: check_ins.iter().map(Habit::format_date).collect(),
+        notes: notes.iter().map(get_notes::map_to_dto).collect(),
+    }
+}
+
+fn map_to_user_dto(user: &User) -> UserDto {
+    UserDto {
+        id: user.get_id(),
+        name: user.get_name(),
+        email: user.get_email(),
+        phone: user.get_phone(),
+        role: user.get_role(),
+        is_disabled: user.is_disabled(),
+        password_reset_required: user.is_password_reset_required(),
+    }
+}
+
+// {"exported_at", "profile", "goals"}, one goal per line
+fn to_json(
+    exported_at: String,
+    profile: &UserDto,
+    goals: impl Iterator<Item = ExportedGoalDto> + Send + 'static,
+) -> Result<ExportChunks, ExportUserDataError> {
+    let header = format!(
+        "{{\"exported_at\":{},\"profile\":{},\"goals\":[",
+        to_json_value(&exported_at)?,
+        to_json_value(profile)?
+    );
+
+    let goals = goals.enumerate().map(|(index, goal)| {
+        let separator = if index == 0 { "\n" } else { ",\n" };
+        to_json_value(&goal).map(|goal| format!("{}{}", separator, goal))
+    });
+
+    Ok(Box::new(
+        iter::once(Ok(header))
+            .chain(goals)
+            .chain(iter::once(Ok("\n]}\n".to_string()))),
+    ))
+}
+
+fn to_json_value<T: Serialize + ?Sized>(value: &T) -> Result<String, ExportUserDataError> {
+    serde_json::to_string(value).map_err(|err| ExportUserDataError::DatabaseError(err.to_string()))
+}
+
+fn to_csv(
+    goals: impl Iterator<Item = ExportedGoalDto> + Send + 'static,
+) -> Result<ExportChunks, ExportUserDataError> {
+    let header = to_csv_record(
+        CSV_HEADERS
+            .iter()
+            .map(|header| header.to_string())
+            .collect(),
+    )?;
+
+    let rows = goals.map(|goal| {
+        let notes = goal
+            .notes
+            .iter()
+            .map(|note| note.text.clone())
+            .collect::<Vec<String>>();
+        let habit = goal.habit.as_ref();
+
+        to_csv_record(vec![
+            goal.id.clone(),
+            goal.text.clone(),
+            goal.completed_at.is_some().to_string(),
+            goal.completed_at.clone().unwrap_or_default(),
+            goal.archived_at.clone().unwrap_or_default(),
+            habit
+                .map(|habit| habit.recurrence.clone())
+                .unwrap_or_default(),
+            habit
+                .map(|habit| habit.current_streak.to_string())
+                .unwrap_or_default(),
+            habit
+                .map(|habit| habit.longest_streak.to_string())
+                .unwrap_or_default(),
+            habit
+                .and_then(|habit| habit.last_check_in.clone())
+                .unwrap_or_default(),
+            goal.check_ins.join(" "),
+            notes.len().to_string(),
+            notes.join("\n\n"),
+        ])
+    });
+
+    Ok(Box::new(iter::once(Ok(header)).chain(rows)))
+}
+
+// One line of the CSV, quoted and escaped by the csv writer
+fn to_csv_record(fields: Vec<String>) -> Result<String, ExportUserDataError> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    writer
+        .write_record(fields)
+        .map_err(|err| ExportUserDataError::DatabaseError(err.to_string()))?;
+
+    let bytes = writer
+        .into_inner()
+        .map_err(|err| ExportUserDataError::DatabaseError(err.to_string()))?;
+
+    String::from_utf8(bytes).map_err(|err| ExportUserDataError::DatabaseError(err.to_string()))
+}
+
+// The active goals come first, a title starts each section. Lines of the text of a goal are joined,
+// so each goal stays a single checklist item
+fn to_markdown(
+    exported_at: String,
+    profile: &UserDto,
+    goals: impl Iterator<Item = ExportedGoalDto> + Send + 'static,
+) -> ExportChunks {
+    let header = [
+        format!("# Goals of {}", profile.name),
+        String::new(),
+        format!("- E-mail: {}", profile.email),
+        format!("- Phone: {}", profile.phone),
+        format!("- Exported at: {}", exported_at),
+        String::new(),
+    ]
+    .join("\n");
+
+    let mut current_section = None;
+    let goals = goals.map(move |goal| {
+        let archived = goal.archived_at.is_some();
+        let mut chunk = String::new();
+        if current_section != Some(archived) {
+            current_section = Some(archived);
+            let title = if archived { "Archived goals" } else { "Goals" };
+            chunk.push_str(&format!("\n## {}\n\n", title));
+        }
+        for line in goal_to_markdown(&goal) {
+            chunk.push_str(&line);
+            chunk.push('\n');
+        }
+        Ok(chunk)
+    });
+
+    Box::new(iter::once(Ok(header)).chain(goals))
+}
+
+fn goal_to_markdown(goal: &ExportedGoalDto) -> Vec<String> {
+    let check = if goal.completed_at.is_some() {
+        "x"
+    } else {
+        " "
+    };
+    let text = goal.text.lines().collect::<Vec<&str>>().join(" ");
+    let mut lines = vec![format!("- [{}] {}", check, text)];
+
+    if let Some(completed_at) = &goal.completed_at {
+        lines.push(format!("  - Completed at: {}", completed_at));
+    }
+
+    if let Some(habit) = &goal.habit {
+        let last_check_in = habit.last_check_in.as_deref().unwrap_or("never");
+        lines.push(format!(
+            "  - Habit: {}, current streak {}, longest streak {}, last check-in {}",
+            habit.recurrence, habit.current_streak, habit.longest_streak, last_check_in
+        ));
+    }
+
+    for note in goal.notes.iter() {
+        let created_at = note.created_at.as_deref().unwrap_or_default();
+        let mut note_lines = note.text.lines();
+        lines.push(format!(
+            "  - Note ({}): {}",
+            created_at,
+            note_lines.next().unwrap_or_default()
+        ));
+        for line in note_lines {
+            lines.push(format!("    {}", line));
+        }
+    }
+
+    lines
+}
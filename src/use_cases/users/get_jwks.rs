@@ -0,0 +1,8 @@
+use crate::{entities::jwk::JwkSetDto, services::jwt_key_services::get_jwt_keys};
+
+// Public keys only, the HS512 secret is never exposed
+pub fn execute() -> JwkSetDto {
+    JwkSetDto {
+        keys: get_jwt_keys().get_jwks(),
+    }
+}
@@ -1,3 +1,7 @@
 pub mod sign_up;
 pub mod sign_in;
 pub mod verify_token;
+pub mod get_jwks;
+pub mod change_password;
+pub mod export_user_data;
+pub mod authenticate_jwt;
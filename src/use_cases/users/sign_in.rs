@@ -1,10 +1,19 @@
+use serde_json::json;
 use tokio_postgres::Client;
 
 use crate::{
-    data_access::user_data_access::find_user_by_email,
+    data_access::user_data_access::{find_user_by_email, update_user_password_hash},
     db::establish_connection,
-    entities::user::{CredentialsDto, SignedUserDto, User},
-    services::auth_services::{match_password_and_hash, generate_auth_token},
+    entities::{
+        audit_event::{
+            AuditContext, AuditEvent, USER_SIGNED_IN_AUDIT_EVENT, USER_SIGN_IN_FAILED_AUDIT_EVENT,
+        },
+        user::{CredentialsDto, SignedUserDto, User},
+    },
+    services::{
+        audit_services::record_audit_event,
+        auth_services::{generate_auth_token, hash_password, verify_password, PasswordMatch},
+    },
 };
 
 pub enum SignInError {
@@ -13,19 +22,39 @@ pub enum SignInError {
     PasswordAndHashDontMatchError(String),
     DatabaseError(String),
     GenerateJwtError(String),
+    AccountDisabledError(String),
+    PasswordResetRequiredError(String),
 }
 
-pub async fn execute(credentials: CredentialsDto) -> Result<SignedUserDto, SignInError> {
+// Every attempt with valid credentials format is recorded in the audit log, with the reason of
+// the failure
+pub async fn execute(
+    credentials: CredentialsDto,
+    audit: AuditContext,
+) -> Result<SignedUserDto, SignInError> {
     let user = User::from_credentials_dto(credentials)
         .map_err(|err| SignInError::InvalidRequestError(err.to_string()))?;
 
     let client = get_connected_client().await?;
 
-    let found_user = find_user(&client, &user).await?;
+    let result = sign_in(&client, &user).await;
+    record_sign_in(&client, &user, &result, &audit).await;
+
+    result
+}
+
+async fn sign_in(client: &Client, user: &User) -> Result<SignedUserDto, SignInError> {
+    let found_user = find_user(client, user).await?;
 
     let password = user.get_password();
     let hash = found_user.get_password_hash();
-    check_password(&password, &hash)?;
+    let needs_rehash = check_password(&password, &hash)?;
+
+    check_account(&found_user)?;
+
+    if needs_rehash {
+        rehash_password(client, &found_user, &password).await;
+    }
 
     let token = make_token(&found_user)?;
 
@@ -33,10 +62,45 @@ pub async fn execute(credentials: CredentialsDto) -> Result<SignedUserDto, SignI
         id: found_user.get_id().to_string(),
         name: found_user.get_name(),
         email: found_user.get_email(),
-        token: token.into(),
+        token,
     })
 }
 
+async fn record_sign_in(
+    client: &Client,
+    user: &User,
+    result: &Result<SignedUserDto, SignInError>,
+    audit: &AuditContext,
+) {
+    let mut event = match result {
+        Ok(signed_user) => {
+            let mut event = AuditEvent::from_context(USER_SIGNED_IN_AUDIT_EVENT, audit);
+            if let Err(err) = event.set_user_id(Some(signed_user.id.clone())) {
+                eprintln!("Audit event error: {}", err);
+            }
+            event
+        }
+        Err(err) => {
+            let mut event = AuditEvent::from_context(USER_SIGN_IN_FAILED_AUDIT_EVENT, audit);
+            event.set_details(json!({ "reason": failure_reason(err) }).to_string());
+            event
+        }
+    };
+    event.set_email(Some(user.get_email()));
+    record_audit_event(client, &event).await;
+}
+
+fn failure_reason(err: &SignInError) -> &'static str {
+    match err {
+        SignInError::InvalidRequestError(_) => "invalid_request",
+        SignInError::UserNotFound(_) => "user_not_found",
+        SignInError::PasswordAndHashDontMatchError(_) => "wrong_password",
+        SignInError::AccountDisabledError(_) => "account_disabled",
+        SignInError::PasswordResetRequiredError(_) => "password_reset_required",
+        SignInError::DatabaseError(_) | SignInError::GenerateJwtError(_) => "server_error",
+    }
+}
+
 async fn get_connected_client() -> Result<tokio_postgres::Client, SignInError> {
     // Calls up db util to get a connected client
     let client = establish_connection().await.map_err(|err| {
@@ -49,7 +113,7 @@ async fn get_connected_client() -> Result<tokio_postgres::Client, SignInError> {
 async fn find_user(client: &Client, user: &User) -> Result<User, SignInError> {
     let email = user.get_email();
 
-    let found_user = find_user_by_email(&client, &email)
+    let found_user = find_user_by_email(client, &email)
         .await
         .map_err(|err| SignInError::DatabaseError(err.to_string()))?;
 
@@ -60,24 +124,58 @@ async fn find_user(client: &Client, user: &User) -> Result<User, SignInError> {
     Ok(found_user)
 }
 
-fn check_password(password: &str, hash: &str) -> Result<(), SignInError> {
-    let is_match = match_password_and_hash(&password, &hash)
-        .map_err(|err| SignInError::PasswordAndHashDontMatchError(err))?;
+// Returns true when the password matches but the stored hash should be replaced
+fn check_password(password: &str, hash: &str) -> Result<bool, SignInError> {
+    let password_match =
+        verify_password(password, hash).map_err(SignInError::PasswordAndHashDontMatchError)?;
 
-    if !is_match {
-        return Err(SignInError::PasswordAndHashDontMatchError(
+    match password_match {
+        PasswordMatch::NoMatch => Err(SignInError::PasswordAndHashDontMatchError(
             "Password and Hash don't match".to_string(),
+        )),
+        PasswordMatch::Match => Ok(false),
+        PasswordMatch::MatchNeedsRehash => Ok(true),
+    }
+}
+
+// Only checked after the password, so the account state is not leaked to anyone with an e-mail
+fn check_account(user: &User) -> Result<(), SignInError> {
+    if user.is_disabled() {
+        return Err(SignInError::AccountDisabledError(
+            "This account is disabled".to_string(),
+        ));
+    }
+
+    if user.is_password_reset_required() {
+        return Err(SignInError::PasswordResetRequiredError(
+            "A password change is required, use POST /api/users/password".to_string(),
         ));
     }
 
     Ok(())
 }
 
+// The user is already authenticated here, so a failed rehash is logged and the sign in goes on
+async fn rehash_password(client: &Client, user: &User, password: &str) {
+    let password_hash = match hash_password(password) {
+        Err(err) => {
+            eprintln!("Rehash password error: {}", err);
+            return;
+        }
+        Ok(password_hash) => password_hash,
+    };
+
+    if let Err(err) = update_user_password_hash(client, &user.get_id(), &password_hash).await {
+        eprintln!("Rehash password error: {}", err);
+    }
+}
+
 fn make_token(user: &User) -> Result<String, SignInError>
 {
     let user_id = user.get_id();
+    let role = user.get_role();
 
-    let token = generate_auth_token(&user_id).map_err(|err| {
+    let token = generate_auth_token(&user_id, &role).map_err(|err| {
         SignInError::GenerateJwtError(err.to_string())
     })?;
 
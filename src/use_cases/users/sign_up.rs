@@ -1,8 +1,11 @@
 use crate::entities::user::{User, CreateUserDto};
+use crate::entities::audit_event::{AuditContext, AuditEvent, USER_SIGNED_UP_AUDIT_EVENT};
 use crate::db::establish_connection;
 use crate::data_access::user_data_access::{add_user, find_user_by_email, UserDataAccessError};
 use crate::errors::user_errors::InvalidUserError;
 use crate::services::auth_services::{hash_password, match_password_and_hash};
+use crate::services::password_policy_services::check_password_policy;
+use crate::services::audit_services::record_audit_event;
 
 pub enum SignUpError {
     RequestValidationError(InvalidUserError),
@@ -11,10 +14,12 @@ pub enum SignUpError {
     EmailAlreadyTakenError(String),
 }
 
-pub async fn execute(new_user: CreateUserDto) -> Result<(), SignUpError>
+pub async fn execute(new_user: CreateUserDto, audit: AuditContext) -> Result<(), SignUpError>
 {
     let mut user = dto_to_entity_user(new_user)?;
 
+    check_password_strength(&user)?;
+
     let password_hash = creates_and_validates_password_hash(&user)?;
     user.set_password_hash(password_hash).map_err(|err| SignUpError::HashPasswordError(err.to_string()))?;
 
@@ -22,11 +27,23 @@ pub async fn execute(new_user: CreateUserDto) -> Result<(), SignUpError>
 
     is_email_available(&client, &user).await?;
 
-    add_user(&client, &user).await.map_err(|err| SignUpError::DbError(err))?;
+    let user_id = add_user(&client, &user).await.map_err(SignUpError::DbError)?;
+
+    record_sign_up(&client, &user, user_id, &audit).await;
 
     Ok(())
 }
 
+async fn record_sign_up(client: &tokio_postgres::Client, user: &User, user_id: String, audit: &AuditContext)
+{
+    let mut event = AuditEvent::from_context(USER_SIGNED_UP_AUDIT_EVENT, audit);
+    if let Err(err) = event.set_user_id(Some(user_id)) {
+        eprintln!("Audit event error: {}", err);
+    }
+    event.set_email(Some(user.get_email()));
+    record_audit_event(client, &event).await;
+}
+
 fn dto_to_entity_user(new_user: CreateUserDto) -> Result<User, SignUpError>
 {
     // Creates a valid User entity from CreateUserDto
@@ -36,6 +53,15 @@ fn dto_to_entity_user(new_user: CreateUserDto) -> Result<User, SignUpError>
     Ok(user)
 }
 
+fn check_password_strength(user: &User) -> Result<(), SignUpError>
+{
+    // Every broken rule of the password policy is returned in the message
+    check_password_policy(&user.get_password(), &user.get_name(), &user.get_email()).map_err(|errors| {
+        SignUpError::RequestValidationError(InvalidUserError::new(Some(errors.join(". "))))
+    })?;
+    Ok(())
+}
+
 fn creates_and_validates_password_hash(user: &User) -> Result<String, SignUpError>
 {
     // Creates a PasswordHash and checks if it matches the password
@@ -65,10 +91,10 @@ async fn is_email_available(client: &tokio_postgres::Client, user: &User) -> Res
 {
     // Check if e-mail already in use
     let email = user.get_email();
-    let found_user = find_user_by_email(&client, &email).await.map_err(|err| {
+    let found_user = find_user_by_email(client, &email).await.map_err(|err| {
         SignUpError::DbError(err)
     })?;
-    if let Some(_) = found_user {
+    if found_user.is_some() {
         return Err(SignUpError::EmailAlreadyTakenError(
                    "E-mail already taken and cannot be used".to_string()));
     }
@@ -1,8 +1,14 @@
+use serde_json::json;
 use tokio_postgres::Client;
 
 use crate::{
-    data_access::user_data_access::find_user_by_id, db::establish_connection,
-    services::auth_services::validate_and_get_id_from_token,
+    data_access::user_data_access::find_user_by_id,
+    db::establish_connection,
+    entities::audit_event::{AuditContext, AuditEvent, USER_TOKEN_VERIFICATION_FAILED_AUDIT_EVENT},
+    services::{
+        audit_services::record_audit_event, auth_services::validate_and_get_claims_from_token,
+    },
+    use_cases::users::authenticate_jwt::{check_not_revoked, AuthenticateJwtError},
 };
 
 pub enum VerifyTokenError {
@@ -11,19 +17,63 @@ pub enum VerifyTokenError {
     UserNotFound(String),
 }
 
-pub async fn execute(token: String) -> Result<(), VerifyTokenError> {
-    let user_id = validate_and_get_id_from_token(&token)
+// The failures are recorded in the audit log, with the user when the token could be decoded
+pub async fn execute(token: String, audit: AuditContext) -> Result<(), VerifyTokenError> {
+    let client = get_connected_client().await?;
+
+    let result = verify(&client, &token).await;
+    if let Err(err) = &result {
+        record_failure(&client, &token, err, &audit).await;
+    }
+
+    result
+}
+
+async fn verify(client: &Client, token: &str) -> Result<(), VerifyTokenError> {
+    let claims = validate_and_get_claims_from_token(token)
         .map_err(|err| VerifyTokenError::DecodeTokenError(err.to_string()))?;
 
-    let client = get_connected_client().await?;
+    find_user(client, &claims.user_id).await?;
 
-    find_user(&client, &user_id).await?;
+    check_not_revoked(client, &claims)
+        .await
+        .map_err(|err| match err {
+            AuthenticateJwtError::InvalidTokenError(err_msg) => {
+                VerifyTokenError::DecodeTokenError(err_msg)
+            }
+            AuthenticateJwtError::DatabaseError(err_msg) => {
+                VerifyTokenError::DatabaseError(err_msg)
+            }
+        })?;
 
     Ok(())
 }
 
+async fn record_failure(
+    client: &Client,
+    token: &str,
+    err: &VerifyTokenError,
+    audit: &AuditContext,
+) {
+    let reason = match err {
+        VerifyTokenError::DecodeTokenError(_) => "invalid_token",
+        VerifyTokenError::UserNotFound(_) => "user_not_found",
+        VerifyTokenError::DatabaseError(_) => "server_error",
+    };
+
+    let mut event = AuditEvent::from_context(USER_TOKEN_VERIFICATION_FAILED_AUDIT_EVENT, audit);
+    let user_id = validate_and_get_claims_from_token(token)
+        .ok()
+        .map(|claims| claims.user_id);
+    if let Err(err) = event.set_user_id(user_id) {
+        eprintln!("Audit event error: {}", err);
+    }
+    event.set_details(json!({ "reason": reason }).to_string());
+    record_audit_event(client, &event).await;
+}
+
 async fn find_user(client: &Client, user_id: &str) -> Result<(), VerifyTokenError> {
-    let found_user = find_user_by_id(&client, &user_id)
+    let found_user = find_user_by_id(client, user_id)
         .await
         .map_err(|err| VerifyTokenError::DatabaseError(err.to_string()))?;
 
@@ -32,6 +82,9 @@ async fn find_user(client: &Client, user_id: &str) -> Result<(), VerifyTokenErro
             "User not found for the id: {}",
             &user_id
         ))),
+        Some(user) if user.is_disabled() => Err(VerifyTokenError::UserNotFound(
+            "The user account is disabled".to_string(),
+        )),
         Some(_user) => Ok(()),
     }
 }
@@ -0,0 +1,77 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        user_data_access::find_user_by_id,
+        webhook_data_access::{add_webhook, find_webhook_by_id},
+    },
+    db::establish_connection,
+    entities::webhook::{CreateWebhookDto, Webhook, WebhookDto},
+    services::{outbound_url_services::check_public_url, token_services::generate_random_token},
+    use_cases::webhooks::get_webhooks::map_to_dto,
+};
+
+const SECRET_BYTES: usize = 32;
+
+pub enum CreateWebhookError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// The secret is returned once, the receiver needs it to check the signatures
+pub async fn execute(
+    new_webhook: CreateWebhookDto,
+    user_id: String,
+) -> Result<WebhookDto, CreateWebhookError> {
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+
+    let webhook = Webhook::from_create_webhook_dto(
+        new_webhook,
+        &user_id,
+        generate_random_token(SECRET_BYTES),
+    )
+    .map_err(|err| CreateWebhookError::InvalidRequestError(err.to_string()))?;
+
+    check_public_url(&webhook.get_url())
+        .await
+        .map_err(CreateWebhookError::InvalidRequestError)?;
+
+    let id = add_webhook(&client, &webhook)
+        .await
+        .map_err(|err| CreateWebhookError::DatabaseError(err.to_string()))?;
+
+    let webhook = find_webhook_by_id(&client, &id)
+        .await
+        .map_err(|err| CreateWebhookError::DatabaseError(err.to_string()))?
+        .ok_or_else(|| CreateWebhookError::DatabaseError("Webhook not saved".to_string()))?;
+
+    let mut webhook_dto = map_to_dto(&webhook);
+    webhook_dto.secret = Some(webhook.get_secret());
+
+    Ok(webhook_dto)
+}
+
+async fn get_connected_client() -> Result<Client, CreateWebhookError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| CreateWebhookError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), CreateWebhookError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| CreateWebhookError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(CreateWebhookError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(CreateWebhookError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
@@ -0,0 +1,60 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{user_data_access::find_user_by_id, webhook_data_access},
+    db::establish_connection,
+    entities::webhook::Webhook,
+};
+
+pub enum DeleteWebhookError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    WebhookNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// The pending deliveries of the webhook are dropped with it. Webhooks of other users are reported
+// as not found
+pub async fn execute(webhook_id: String, user_id: String) -> Result<(), DeleteWebhookError> {
+    Webhook::validate_id(&webhook_id)
+        .map_err(|err| DeleteWebhookError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+
+    let deleted = webhook_data_access::delete_webhook(&client, &webhook_id, &user_id)
+        .await
+        .map_err(|err| DeleteWebhookError::DatabaseError(err.to_string()))?;
+
+    if deleted == 0 {
+        return Err(DeleteWebhookError::WebhookNotFoundError(format!(
+            "Webhook not found for the id: {}",
+            webhook_id
+        )));
+    }
+
+    Ok(())
+}
+
+async fn get_connected_client() -> Result<Client, DeleteWebhookError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| DeleteWebhookError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), DeleteWebhookError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| DeleteWebhookError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(DeleteWebhookError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(DeleteWebhookError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
@@ -0,0 +1,69 @@
+use tokio_postgres::Client;
+
+use crate::{
+    config::get_env_parsed,
+    data_access::webhook_data_access::{
+        claim_due_deliveries, mark_delivery_failed, mark_delivery_sent,
+    },
+    db::establish_connection,
+    services::webhook_services::deliver,
+};
+
+// A claimed delivery is locked for the other instances while it is being sent
+const LOCK_SECONDS: f64 = 300.0;
+const BASE_RETRY_SECONDS: f64 = 30.0;
+const MAX_RETRY_SECONDS: f64 = 21600.0;
+
+pub enum DispatchWebhookDeliveriesError {
+    DatabaseError(String),
+}
+
+// Sends the due deliveries and returns how many succeeded. A failed delivery is tried again with an
+// exponential backoff, until WEBHOOK_MAX_ATTEMPTS is reached
+pub async fn execute() -> Result<usize, DispatchWebhookDeliveriesError> {
+    let batch_size: i64 = get_env_parsed("WEBHOOK_BATCH_SIZE", 50);
+    let max_attempts: i32 = get_env_parsed("WEBHOOK_MAX_ATTEMPTS", 8);
+
+    let client = get_connected_client().await?;
+
+    let deliveries = claim_due_deliveries(&client, batch_size, LOCK_SECONDS)
+        .await
+        .map_err(|err| DispatchWebhookDeliveriesError::DatabaseError(err.to_string()))?;
+
+    let mut sent = 0;
+    for delivery in deliveries.iter() {
+        match deliver(delivery).await {
+            Ok(response_status) => {
+                mark_delivery_sent(&client, &delivery.delivery_id, response_status)
+                    .await
+                    .map_err(|err| {
+                        DispatchWebhookDeliveriesError::DatabaseError(err.to_string())
+                    })?;
+                sent += 1;
+            }
+            Err(failure) => {
+                let retry_in_seconds =
+                    (BASE_RETRY_SECONDS * 2f64.powi(delivery.attempts)).min(MAX_RETRY_SECONDS);
+                mark_delivery_failed(
+                    &client,
+                    &delivery.delivery_id,
+                    failure.response_status,
+                    &failure.error,
+                    max_attempts,
+                    retry_in_seconds,
+                )
+                .await
+                .map_err(|err| DispatchWebhookDeliveriesError::DatabaseError(err.to_string()))?;
+            }
+        }
+    }
+
+    Ok(sent)
+}
+
+async fn get_connected_client() -> Result<Client, DispatchWebhookDeliveriesError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| DispatchWebhookDeliveriesError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
@@ -0,0 +1,107 @@
+use serde_json::Value;
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        user_data_access::find_user_by_id,
+        webhook_data_access::{find_deliveries, find_webhook_by_id},
+    },
+    db::establish_connection,
+    entities::webhook::{Webhook, WebhookDeliveriesQueryDto, WebhookDelivery, WebhookDeliveryDto},
+};
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 200;
+
+pub enum GetWebhookDeliveriesError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    WebhookNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// Delivery log of a webhook of the user, newest first
+pub async fn execute(
+    webhook_id: String,
+    query: WebhookDeliveriesQueryDto,
+    user_id: String,
+) -> Result<Vec<WebhookDeliveryDto>, GetWebhookDeliveriesError> {
+    Webhook::validate_id(&webhook_id)
+        .map_err(|err| GetWebhookDeliveriesError::InvalidRequestError(err.to_string()))?;
+    if let Some(status) = &query.status {
+        Webhook::validate_delivery_status(status)
+            .map_err(|err| GetWebhookDeliveriesError::InvalidRequestError(err.to_string()))?;
+    }
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    find_owned_webhook(&client, &webhook_id, &user_id).await?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let deliveries_db = find_deliveries(&client, &webhook_id, query.status, limit, offset)
+        .await
+        .map_err(|err| GetWebhookDeliveriesError::DatabaseError(err.to_string()))?;
+
+    Ok(deliveries_db.iter().map(map_to_dto).collect())
+}
+
+async fn get_connected_client() -> Result<Client, GetWebhookDeliveriesError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| GetWebhookDeliveriesError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), GetWebhookDeliveriesError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| GetWebhookDeliveriesError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(GetWebhookDeliveriesError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(GetWebhookDeliveriesError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn find_owned_webhook(
+    client: &Client,
+    webhook_id: &str,
+    user_id: &str,
+) -> Result<Webhook, GetWebhookDeliveriesError> {
+    let opt_webhook = find_webhook_by_id(client, webhook_id)
+        .await
+        .map_err(|err| GetWebhookDeliveriesError::DatabaseError(err.to_string()))?;
+
+    match opt_webhook {
+        Some(webhook) if webhook.get_user_id() == user_id => Ok(webhook),
+        _ => Err(GetWebhookDeliveriesError::WebhookNotFoundError(format!(
+            "Webhook not found for the id: {}",
+            webhook_id
+        ))),
+    }
+}
+
+pub fn map_to_dto(delivery: &WebhookDelivery) -> WebhookDeliveryDto {
+    let payload = delivery.get_payload();
+    WebhookDeliveryDto {
+        id: delivery.get_id(),
+        webhook_id: delivery.get_webhook_id(),
+        event_id: delivery.get_event_id(),
+        event_type: delivery.get_event_type(),
+        payload: serde_json::from_str(&payload).unwrap_or(Value::String(payload)),
+        status: delivery.get_status(),
+        attempts: delivery.get_attempts(),
+        response_status: delivery.get_response_status(),
+        last_error: delivery.get_last_error(),
+        next_attempt_at: delivery.get_next_attempt_at().map(|date| date.to_rfc3339()),
+        delivered_at: delivery.get_delivered_at().map(|date| date.to_rfc3339()),
+        created_at: delivery.get_created_at().map(|date| date.to_rfc3339()),
+    }
+}
@@ -0,0 +1,57 @@
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{user_data_access::find_user_by_id, webhook_data_access::find_webhooks},
+    db::establish_connection,
+    entities::webhook::{Webhook, WebhookDto},
+};
+
+pub enum GetWebhooksError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+}
+
+pub async fn execute(user_id: String) -> Result<Vec<WebhookDto>, GetWebhooksError> {
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+
+    let webhooks_db = find_webhooks(&client, &user_id)
+        .await
+        .map_err(|err| GetWebhooksError::DatabaseError(err.to_string()))?;
+
+    Ok(webhooks_db.iter().map(map_to_dto).collect())
+}
+
+async fn get_connected_client() -> Result<Client, GetWebhooksError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| GetWebhooksError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), GetWebhooksError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| GetWebhooksError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(GetWebhooksError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(GetWebhooksError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+// The secret is left out
+pub fn map_to_dto(webhook: &Webhook) -> WebhookDto {
+    WebhookDto {
+        id: webhook.get_id(),
+        url: webhook.get_url(),
+        event_types: webhook.get_event_types(),
+        secret: None,
+        created_at: webhook.get_created_at().map(|date| date.to_rfc3339()),
+    }
+}
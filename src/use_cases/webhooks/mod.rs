@@ -0,0 +1,6 @@
+pub mod create_webhook;
+pub mod get_webhooks;
+pub mod delete_webhook;
+pub mod get_webhook_deliveries;
+pub mod test_webhook;
+pub mod dispatch_webhook_deliveries;
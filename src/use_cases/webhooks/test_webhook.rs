@@ -0,0 +1,86 @@
+use serde_json::json;
+use tokio_postgres::Client;
+
+use crate::{
+    data_access::{
+        user_data_access::find_user_by_id,
+        webhook_data_access::{enqueue_delivery, find_delivery_by_id, find_webhook_by_id},
+    },
+    db::establish_connection,
+    entities::webhook::{Webhook, WebhookDeliveryDto, WebhookEvent, PING_EVENT},
+    use_cases::webhooks::get_webhook_deliveries::map_to_dto,
+};
+
+pub enum TestWebhookError {
+    DatabaseError(String),
+    UserNotFoundError(String),
+    WebhookNotFoundError(String),
+    InvalidRequestError(String),
+}
+
+// Queues a ping event for the webhook. It is sent by the dispatcher like any other event, so its
+// result shows up in the delivery log
+pub async fn execute(
+    webhook_id: String,
+    user_id: String,
+) -> Result<WebhookDeliveryDto, TestWebhookError> {
+    Webhook::validate_id(&webhook_id)
+        .map_err(|err| TestWebhookError::InvalidRequestError(err.to_string()))?;
+
+    let client = get_connected_client().await?;
+    find_user(&client, &user_id).await?;
+    find_owned_webhook(&client, &webhook_id, &user_id).await?;
+
+    let event = WebhookEvent::new(PING_EVENT, json!({ "webhook_id": webhook_id }));
+    let delivery_id = enqueue_delivery(&client, &webhook_id, &event)
+        .await
+        .map_err(|err| TestWebhookError::DatabaseError(err.to_string()))?;
+
+    let delivery = find_delivery_by_id(&client, &delivery_id)
+        .await
+        .map_err(|err| TestWebhookError::DatabaseError(err.to_string()))?
+        .ok_or_else(|| TestWebhookError::DatabaseError("Delivery not saved".to_string()))?;
+
+    Ok(map_to_dto(&delivery))
+}
+
+async fn get_connected_client() -> Result<Client, TestWebhookError> {
+    let client = establish_connection()
+        .await
+        .map_err(|err| TestWebhookError::DatabaseError(err.to_string()))?;
+    Ok(client)
+}
+
+async fn find_user(client: &Client, user_id: &str) -> Result<(), TestWebhookError> {
+    let opt_user = find_user_by_id(client, user_id)
+        .await
+        .map_err(|err| TestWebhookError::DatabaseError(err.to_string()))?;
+
+    match opt_user {
+        None => Err(TestWebhookError::UserNotFoundError(
+            "User not found with the id present in the authorization headers".to_string(),
+        )),
+        Some(user) if user.is_disabled() => Err(TestWebhookError::UserNotFoundError(
+            "The user account is disabled".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn find_owned_webhook(
+    client: &Client,
+    webhook_id: &str,
+    user_id: &str,
+) -> Result<Webhook, TestWebhookError> {
+    let opt_webhook = find_webhook_by_id(client, webhook_id)
+        .await
+        .map_err(|err| TestWebhookError::DatabaseError(err.to_string()))?;
+
+    match opt_webhook {
+        Some(webhook) if webhook.get_user_id() == user_id => Ok(webhook),
+        _ => Err(TestWebhookError::WebhookNotFoundError(format!(
+            "Webhook not found for the id: {}",
+            webhook_id
+        ))),
+    }
+}
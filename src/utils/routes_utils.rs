@@ -1,6 +1,14 @@
 use actix_web::HttpRequest;
 
-use crate::services::auth_services::validate_and_get_id_from_token;
+use crate::entities::audit_event::AuditContext;
+use crate::entities::user::ADMIN_ROLE;
+use crate::services::api_key_services::is_api_key;
+use crate::services::audit_services::resolve_client_ip;
+use crate::services::auth_services::TokenClaims;
+use crate::use_cases::api_keys::authenticate_api_key::{self, AuthenticateApiKeyError};
+use crate::use_cases::users::authenticate_jwt::{self, AuthenticateJwtError};
+
+const MAX_USER_AGENT_LENGTH: usize = 512;
 
 pub fn extract_token_from_headers(req: &HttpRequest) -> Option<String> {
     match req.headers().get("authorization") {
@@ -13,20 +21,103 @@ pub fn extract_token_from_headers(req: &HttpRequest) -> Option<String> {
     }
 }
 
-pub fn extract_user_id_from_headers(req: &HttpRequest) -> Option<String> {
-    let token = match req.headers().get("authorization") {
-        None => return None,
-        Some(auth_header) => {
-            let auth_header = String::from(auth_header.to_str().unwrap());
-            let token = auth_header.split(" ").collect::<Vec<&str>>()[1].to_string();
-            token
+// JWT only
+pub async fn extract_user_id_from_headers(req: &HttpRequest) -> Option<String> {
+    let token = extract_token_from_headers(req)?;
+    let claims = authenticate_jwt_token(&token).await?;
+    Some(claims.user_id)
+}
+
+// A JWT that is valid and was not revoked by a password change
+async fn authenticate_jwt_token(token: &str) -> Option<TokenClaims> {
+    match authenticate_jwt::execute(token).await {
+        Err(AuthenticateJwtError::InvalidTokenError(_)) => None,
+        Err(AuthenticateJwtError::DatabaseError(err_msg)) => {
+            eprintln!("JWT authentication error: {}", err_msg);
+            None
         }
-    };
+        Ok(claims) => Some(claims),
+    }
+}
+
+// Accepts a JWT or an api key in the authorization headers. Api keys must have the required scope
+pub async fn extract_user_id_from_request(
+    req: &HttpRequest,
+    required_scope: &str,
+) -> Option<String> {
+    let token = extract_token_from_headers(req)?;
+    authenticate_token(token, required_scope).await
+}
 
-    let user_id = match validate_and_get_id_from_token(&token) {
-        Err(_) => return None,
-        Ok(id) => id,
+// A JWT, or an api key with the required scope
+pub async fn authenticate_token(token: String, required_scope: &str) -> Option<String> {
+    if !is_api_key(&token) {
+        return authenticate_jwt_token(&token)
+            .await
+            .map(|claims| claims.user_id);
+    }
+
+    match authenticate_api_key::execute(token, required_scope).await {
+        Err(AuthenticateApiKeyError::InvalidApiKeyError(err_msg))
+        | Err(AuthenticateApiKeyError::MissingScopeError(err_msg))
+        | Err(AuthenticateApiKeyError::DatabaseError(err_msg)) => {
+            eprintln!("Api key authentication error: {}", err_msg);
+            None
+        }
+        Ok(user_id) => Some(user_id),
+    }
+}
+
+// JWT only, with the admin role claim. The admin use cases still check the role in the database
+pub async fn extract_admin_id_from_headers(req: &HttpRequest) -> Option<String> {
+    let token = extract_token_from_headers(req)?;
+
+    match authenticate_jwt_token(&token).await {
+        Some(claims) if claims.role == ADMIN_ROLE => Some(claims.user_id),
+        _ => None,
+    }
+}
+
+// The address of the client, from the Forwarded or X-Forwarded-For headers when the server runs
+// behind trusted proxies, and its user agent
+pub fn extract_audit_context(req: &HttpRequest) -> AuditContext {
+    let ip = req
+        .peer_addr()
+        .map(|addr| resolve_client_ip(addr.ip(), &extract_forwarded_hops(req)).to_string());
+
+    let user_agent = req
+        .headers()
+        .get("user-agent")
+        .and_then(|user_agent| user_agent.to_str().ok())
+        .map(|user_agent| user_agent.chars().take(MAX_USER_AGENT_LENGTH).collect());
+
+    AuditContext { ip, user_agent }
+}
+
+// The hops of X-Forwarded-For, or the for= of Forwarded without it, leftmost first. The header can
+// be repeated, its lines are read in order
+fn extract_forwarded_hops(req: &HttpRequest) -> Vec<String> {
+    let header_values = |name: &str| {
+        req.headers()
+            .get_all(name)
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|hop| hop.trim().to_string())
+            .collect::<Vec<String>>()
     };
 
-    Some(user_id)
+    let hops = header_values("x-forwarded-for");
+    if !hops.is_empty() {
+        return hops;
+    }
+
+    header_values("forwarded")
+        .iter()
+        .filter_map(|element| {
+            element.split(';').find_map(|pair| {
+                let (name, value) = pair.trim().split_once('=')?;
+                name.eq_ignore_ascii_case("for").then(|| value.to_string())
+            })
+        })
+        .collect()
 }